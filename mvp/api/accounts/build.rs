@@ -2,6 +2,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     tonic_build::configure()
         .build_server(true)
         .compile_protos(&["proto/accounts.proto", "proto/ledger.proto"], &["proto"])?;
+    // Users service token introspection (client only)
+    tonic_build::configure()
+        .build_server(false)
+        .compile_protos(&["proto/auth.proto"], &["proto"])?;
     Ok(())
 }
-
@@ -0,0 +1,79 @@
+//! Local webhook receiver for trying out endpoint deliveries.
+//!
+//! Start the service with WEBHOOK_ALLOW_PRIVATE_NETWORKS=true, register
+//! `http://127.0.0.1:4000/webhooks` as a sandbox endpoint, then run
+//!
+//! ```bash
+//! WEBHOOK_SECRET=whsec_... cargo run --example webhook_receiver
+//! ```
+//!
+//! Every request is verified and printed. Set WEBHOOK_RECEIVER_STATUS (e.g. 500) to answer
+//! with that status instead and watch the service retry.
+
+use axum::{body::Bytes, http::HeaderMap, http::StatusCode, routing::post, Router};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// Deliveries whose timestamp is further off than this are rejected (replay protection)
+const TOLERANCE_SECS: i64 = 300;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let addr = std::env::var("WEBHOOK_RECEIVER_ADDR").unwrap_or_else(|_| "127.0.0.1:4000".to_string());
+    let app = Router::new().route("/webhooks", post(receive));
+
+    println!("Listening on http://{}/webhooks", addr);
+    let listener = tokio::net::TcpListener::bind(&addr).await?;
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+async fn receive(headers: HeaderMap, body: Bytes) -> StatusCode {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).unwrap_or_default().to_string();
+    let body = String::from_utf8_lossy(&body);
+
+    let verified = match std::env::var("WEBHOOK_SECRET") {
+        Ok(secret) => verify(&secret, &header("Rails-Webhook-Timestamp"), &header("Rails-Webhook-Signature"), &body),
+        Err(_) => Err("WEBHOOK_SECRET is not set".to_string()),
+    };
+
+    println!(
+        "{} {} ({})\n{}\n",
+        header("Rails-Webhook-Event-Type"),
+        header("Rails-Webhook-Event-Id"),
+        match &verified {
+            Ok(()) => "signature ok".to_string(),
+            Err(e) => format!("signature rejected: {}", e),
+        },
+        body
+    );
+
+    if verified.is_err() {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    std::env::var("WEBHOOK_RECEIVER_STATUS")
+        .ok()
+        .and_then(|v| v.parse::<u16>().ok())
+        .and_then(|code| StatusCode::from_u16(code).ok())
+        .unwrap_or(StatusCode::OK)
+}
+
+/// Signature = "v1=" + hex HMAC-SHA256 of "{timestamp}.{raw body}" under the endpoint secret.
+fn verify(secret: &str, timestamp: &str, signature: &str, body: &str) -> Result<(), String> {
+    let sent_at: i64 = timestamp.parse().map_err(|_| "missing or invalid timestamp".to_string())?;
+    if (chrono::Utc::now().timestamp() - sent_at).abs() > TOLERANCE_SECS {
+        return Err("timestamp outside tolerance".to_string());
+    }
+
+    let expected = signature
+        .strip_prefix("v1=")
+        .and_then(|hex_signature| hex::decode(hex_signature).ok())
+        .ok_or_else(|| "missing or malformed signature".to_string())?;
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).map_err(|e| e.to_string())?;
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    mac.verify_slice(&expected).map_err(|_| "signature mismatch".to_string())
+}
@@ -0,0 +1,36 @@
+-- Align fixed_savings_plans with the intent-only transactions schema.
+-- Amounts are stored as BIGINT minor units (same as transactions.amount) so the
+-- auto-withdraw scheduler can create transaction intents without conversion.
+-- Existing amounts are DECIMAL major units; they are scaled by 10^exponent of the plan's
+-- currency (ISO 4217, as src/models/currency.rs; 2 unless listed below).
+
+CREATE OR REPLACE FUNCTION pg_temp.minor_unit_scale(currency TEXT) RETURNS NUMERIC AS $$
+    SELECT CASE
+        WHEN currency IN ('BIF', 'CLP', 'DJF', 'GNF', 'ISK', 'JPY', 'KMF', 'KRW', 'PYG', 'RWF', 'UGX',
+                          'UYI', 'VND', 'VUV', 'XAF', 'XOF', 'XPF') THEN 1
+        WHEN currency IN ('BHD', 'IQD', 'JOD', 'KWD', 'LYD', 'OMR', 'TND') THEN 1000
+        WHEN currency IN ('CLF', 'UYW') THEN 10000
+        ELSE 100
+    END;
+$$ LANGUAGE sql IMMUTABLE;
+
+ALTER TABLE fixed_savings_plans
+    ALTER COLUMN initial_amount TYPE BIGINT
+        USING ROUND(initial_amount * pg_temp.minor_unit_scale(currency))::BIGINT,
+    ALTER COLUMN current_balance TYPE BIGINT
+        USING ROUND(current_balance * pg_temp.minor_unit_scale(currency))::BIGINT,
+    ALTER COLUMN monthly_withdraw_amount TYPE BIGINT
+        USING ROUND(monthly_withdraw_amount * pg_temp.minor_unit_scale(currency))::BIGINT;
+
+ALTER TABLE fixed_savings_plans
+    DROP CONSTRAINT IF EXISTS fixed_savings_plans_current_balance_check;
+
+ALTER TABLE fixed_savings_plans
+    ADD CONSTRAINT fixed_savings_plans_current_balance_check
+        CHECK (current_balance >= 0);
+
+-- Scheduler query pattern:
+-- WHERE plan_type = 'auto_withdraw' AND status = 'active' AND next_withdraw_date <= $1
+CREATE INDEX IF NOT EXISTS idx_fixed_savings_plans_auto_withdraw_due
+    ON fixed_savings_plans(next_withdraw_date)
+    WHERE plan_type = 'auto_withdraw' AND status = 'active';
@@ -0,0 +1,62 @@
+-- Interest accrual for saving accounts.
+-- Rates are configured per organization/environment/currency with balance tiers.
+-- Daily accruals are stored with fractional minor units and posted monthly as
+-- 'interest' transactions from the organization's SYSTEM_INTEREST_EXPENSE account.
+
+CREATE TABLE IF NOT EXISTS interest_rate_configs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id UUID NOT NULL,
+    environment VARCHAR(20) NOT NULL CHECK (environment IN ('sandbox', 'production')),
+    currency VARCHAR(3) NOT NULL,
+    interest_method VARCHAR(20) NOT NULL CHECK (interest_method IN ('simple', 'compound')),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_interest_rate_configs_org_env_currency
+    ON interest_rate_configs(organization_id, environment, currency);
+
+-- Tier applies its annual_rate to the whole balance when balance >= min_balance
+-- (highest matching min_balance wins). annual_rate is a fraction, e.g. 0.045000 = 4.5%.
+CREATE TABLE IF NOT EXISTS interest_rate_tiers (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    config_id UUID NOT NULL REFERENCES interest_rate_configs(id) ON DELETE CASCADE,
+    min_balance BIGINT NOT NULL CHECK (min_balance >= 0),
+    annual_rate NUMERIC(9, 6) NOT NULL CHECK (annual_rate >= 0)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_interest_rate_tiers_config_min_balance
+    ON interest_rate_tiers(config_id, min_balance);
+
+CREATE TABLE IF NOT EXISTS interest_accruals (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id UUID NOT NULL,
+    environment VARCHAR(20) NOT NULL CHECK (environment IN ('sandbox', 'production')),
+    account_id UUID NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    accrual_date DATE NOT NULL,
+    balance BIGINT NOT NULL,
+    annual_rate NUMERIC(9, 6) NOT NULL,
+    interest_method VARCHAR(20) NOT NULL CHECK (interest_method IN ('simple', 'compound')),
+    accrued_amount NUMERIC(28, 10) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    transaction_id UUID,
+    posted_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- One accrual per account per day (makes the accrual job idempotent)
+CREATE UNIQUE INDEX IF NOT EXISTS idx_interest_accruals_account_date
+    ON interest_accruals(account_id, accrual_date);
+
+-- Monthly posting query pattern: WHERE posted_at IS NULL AND accrual_date < $1
+CREATE INDEX IF NOT EXISTS idx_interest_accruals_unposted
+    ON interest_accruals(account_id, accrual_date)
+    WHERE posted_at IS NULL;
+
+-- Allow the new 'interest' transaction kind
+ALTER TABLE transactions
+    DROP CONSTRAINT IF EXISTS transactions_transaction_kind_check;
+
+ALTER TABLE transactions
+    ADD CONSTRAINT transactions_transaction_kind_check
+        CHECK (transaction_kind IN ('deposit', 'withdraw', 'transfer', 'interest'));
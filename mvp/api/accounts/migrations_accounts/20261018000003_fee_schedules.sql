@@ -0,0 +1,67 @@
+-- Fee schedules applied to money movement.
+-- A schedule matches on (organization, environment, transaction_kind, account_type);
+-- NULL account_type matches every account type. fee = flat_amount + amount * percentage,
+-- clamped to [min_amount, max_amount]. All amounts are BIGINT minor units.
+
+CREATE TABLE IF NOT EXISTS fee_schedules (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id UUID NOT NULL,
+    environment VARCHAR(20) NOT NULL CHECK (environment IN ('sandbox', 'production')),
+    name VARCHAR(100) NOT NULL,
+    transaction_kind VARCHAR(20) NOT NULL CHECK (transaction_kind IN ('deposit', 'withdraw', 'transfer')),
+    account_type VARCHAR(20) CHECK (account_type IN ('checking', 'saving')),
+    flat_amount BIGINT NOT NULL DEFAULT 0 CHECK (flat_amount >= 0),
+    percentage NUMERIC(9, 6) NOT NULL DEFAULT 0 CHECK (percentage >= 0 AND percentage <= 1),
+    min_amount BIGINT CHECK (min_amount >= 0),
+    max_amount BIGINT CHECK (max_amount >= 0),
+    -- Account credited with the fee; NULL routes to the Ledger SYSTEM_FEE_INCOME account
+    revenue_account_id UUID REFERENCES accounts(id),
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK (min_amount IS NULL OR max_amount IS NULL OR min_amount <= max_amount)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_fee_schedules_org_env_name
+    ON fee_schedules(organization_id, environment, name)
+    WHERE active;
+
+-- Intent creation query pattern: WHERE organization_id = $1 AND environment = $2 AND transaction_kind = $3 AND active
+CREATE INDEX IF NOT EXISTS idx_fee_schedules_org_env_kind
+    ON fee_schedules(organization_id, environment, transaction_kind)
+    WHERE active;
+
+-- Fees charged on a transaction intent. Each fee is posted to the Ledger as its own
+-- leg (payer -> revenue account) once the parent transaction is posted.
+CREATE TABLE IF NOT EXISTS transaction_fees (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    transaction_id UUID NOT NULL REFERENCES transactions(id) ON DELETE CASCADE,
+    fee_schedule_id UUID NOT NULL REFERENCES fee_schedules(id),
+    organization_id UUID NOT NULL,
+    environment VARCHAR(20) NOT NULL CHECK (environment IN ('sandbox', 'production')),
+    name VARCHAR(100) NOT NULL,
+    payer_account_id UUID NOT NULL,
+    revenue_account_id UUID,
+    amount BIGINT NOT NULL CHECK (amount > 0),
+    currency VARCHAR(3) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'posted', 'failed')),
+    failure_reason TEXT,
+    idempotency_key VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- One fee per schedule per transaction (idempotent replays don't charge twice)
+CREATE UNIQUE INDEX IF NOT EXISTS idx_transaction_fees_transaction_schedule
+    ON transaction_fees(transaction_id, fee_schedule_id);
+
+CREATE INDEX IF NOT EXISTS idx_transaction_fees_pending_created_at
+    ON transaction_fees(status, created_at)
+    WHERE status = 'pending';
+
+CREATE INDEX IF NOT EXISTS idx_transaction_fees_payer_account_id
+    ON transaction_fees(payer_account_id);
+
+CREATE INDEX IF NOT EXISTS idx_transaction_fees_revenue_account_id
+    ON transaction_fees(revenue_account_id)
+    WHERE revenue_account_id IS NOT NULL;
@@ -0,0 +1,59 @@
+-- Cross-currency transfers.
+-- Rates are quoted as units of quote_currency per one unit of base_currency (major units).
+-- A cross-currency transfer debits `amount` in `currency` from the source account and
+-- credits `destination_amount` in `destination_currency` to the destination account; the
+-- Ledger posting goes through per-currency SYSTEM_FX_CLEARING accounts.
+
+-- Reference rates for the database-backed FxRateProvider
+CREATE TABLE IF NOT EXISTS fx_rates (
+    base_currency VARCHAR(3) NOT NULL,
+    quote_currency VARCHAR(3) NOT NULL,
+    rate NUMERIC(20, 10) NOT NULL CHECK (rate > 0),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (base_currency, quote_currency),
+    CHECK (base_currency <> quote_currency)
+);
+
+-- Quotes handed to clients. A quote is honoured until expires_at; locking it extends
+-- expires_at to the lock window. A quote can be used by exactly one transfer.
+CREATE TABLE IF NOT EXISTS fx_quotes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id UUID NOT NULL,
+    environment VARCHAR(20) NOT NULL CHECK (environment IN ('sandbox', 'production')),
+    source_currency VARCHAR(3) NOT NULL,
+    destination_currency VARCHAR(3) NOT NULL,
+    rate NUMERIC(20, 10) NOT NULL CHECK (rate > 0),
+    source_amount BIGINT NOT NULL CHECK (source_amount > 0),
+    destination_amount BIGINT NOT NULL CHECK (destination_amount > 0),
+    status VARCHAR(20) NOT NULL DEFAULT 'open' CHECK (status IN ('open', 'locked', 'used')),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    locked_at TIMESTAMP WITH TIME ZONE,
+    transaction_id UUID REFERENCES transactions(id),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK (source_currency <> destination_currency),
+    CHECK ((status = 'used') = (transaction_id IS NOT NULL))
+);
+
+CREATE INDEX IF NOT EXISTS idx_fx_quotes_org_env_created_at
+    ON fx_quotes(organization_id, environment, created_at DESC);
+
+ALTER TABLE transactions
+    ADD COLUMN IF NOT EXISTS destination_amount BIGINT,
+    ADD COLUMN IF NOT EXISTS destination_currency VARCHAR(3),
+    ADD COLUMN IF NOT EXISTS fx_rate NUMERIC(20, 10),
+    ADD COLUMN IF NOT EXISTS fx_quote_id UUID REFERENCES fx_quotes(id);
+
+-- FX details are all-or-nothing and only apply to transfers between different currencies
+ALTER TABLE transactions DROP CONSTRAINT IF EXISTS transactions_fx_details_check;
+ALTER TABLE transactions
+    ADD CONSTRAINT transactions_fx_details_check CHECK (
+        (destination_amount IS NULL AND destination_currency IS NULL AND fx_rate IS NULL AND fx_quote_id IS NULL)
+        OR (
+            transaction_kind = 'transfer'
+            AND destination_amount > 0
+            AND destination_currency IS NOT NULL
+            AND destination_currency <> currency
+            AND fx_rate > 0
+        )
+    );
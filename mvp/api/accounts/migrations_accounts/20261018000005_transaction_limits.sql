@@ -0,0 +1,42 @@
+-- Velocity and amount limits on outgoing money movement (withdrawals and transfers out).
+-- Scope is derived from which of account_id / account_type is set:
+--   account_id set            -> one account
+--   account_type set          -> every account of that type in the organization
+--   neither                   -> the whole organization
+-- NULL limit columns are not enforced. NULL transaction_kind covers withdraw and transfer.
+
+CREATE TABLE IF NOT EXISTS transaction_limits (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id UUID NOT NULL,
+    environment VARCHAR(20) NOT NULL CHECK (environment IN ('sandbox', 'production')),
+    account_type VARCHAR(20) CHECK (account_type IN ('checking', 'saving')),
+    account_id UUID REFERENCES accounts(id) ON DELETE CASCADE,
+    transaction_kind VARCHAR(20) CHECK (transaction_kind IN ('withdraw', 'transfer')),
+    currency VARCHAR(3) NOT NULL,
+    max_amount_per_transaction BIGINT CHECK (max_amount_per_transaction >= 0),
+    daily_amount BIGINT CHECK (daily_amount >= 0),
+    monthly_amount BIGINT CHECK (monthly_amount >= 0),
+    daily_count BIGINT CHECK (daily_count >= 0),
+    monthly_count BIGINT CHECK (monthly_count >= 0),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK (account_id IS NULL OR account_type IS NULL)
+);
+
+-- One limit row per scope/kind/currency
+CREATE UNIQUE INDEX IF NOT EXISTS idx_transaction_limits_scope
+    ON transaction_limits(
+        organization_id,
+        environment,
+        currency,
+        COALESCE(account_type, ''),
+        COALESCE(account_id, '00000000-0000-0000-0000-000000000000'::uuid),
+        COALESCE(transaction_kind, '')
+    );
+
+-- Usage queries: outgoing intents per account / organization within a window
+CREATE INDEX IF NOT EXISTS idx_transactions_from_account_created_at
+    ON transactions(from_account_id, created_at);
+
+CREATE INDEX IF NOT EXISTS idx_transactions_org_env_created_at
+    ON transactions(organization_id, environment, created_at);
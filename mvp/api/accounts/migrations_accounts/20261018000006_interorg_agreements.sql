@@ -0,0 +1,49 @@
+-- Inter-organization transfer agreements.
+-- An agreement lets accounts of organization_id (the payer) transfer to one account of
+-- another organization (counterparty_account_id). Transfers are posted to the Ledger as one
+-- leg per organization through its SYSTEM_INTERORG_CLEARING account.
+
+CREATE TABLE IF NOT EXISTS interorg_agreements (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    environment VARCHAR(20) NOT NULL CHECK (environment IN ('sandbox', 'production')),
+    organization_id UUID NOT NULL,
+    counterparty_organization_id UUID NOT NULL,
+    counterparty_account_id UUID NOT NULL REFERENCES accounts(id),
+    -- Optional limits (minor units of the counterparty account currency)
+    max_amount_per_transfer BIGINT CHECK (max_amount_per_transfer > 0),
+    daily_amount BIGINT CHECK (daily_amount > 0),
+    status VARCHAR(20) NOT NULL DEFAULT 'active' CHECK (status IN ('active', 'revoked')),
+    revoked_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK (organization_id <> counterparty_organization_id)
+);
+
+-- At most one active agreement per payer organization and counterparty account
+CREATE UNIQUE INDEX IF NOT EXISTS idx_interorg_agreements_active_counterparty
+    ON interorg_agreements(environment, organization_id, counterparty_account_id)
+    WHERE status = 'active';
+
+CREATE INDEX IF NOT EXISTS idx_interorg_agreements_counterparty_org
+    ON interorg_agreements(environment, counterparty_organization_id);
+
+ALTER TABLE transactions
+    ADD COLUMN IF NOT EXISTS counterparty_organization_id UUID,
+    ADD COLUMN IF NOT EXISTS interorg_agreement_id UUID REFERENCES interorg_agreements(id);
+
+ALTER TABLE transactions DROP CONSTRAINT IF EXISTS transactions_interorg_check;
+ALTER TABLE transactions
+    ADD CONSTRAINT transactions_interorg_check CHECK (
+        (counterparty_organization_id IS NULL AND interorg_agreement_id IS NULL)
+        OR (
+            transaction_kind = 'transfer'
+            AND counterparty_organization_id IS NOT NULL
+            AND interorg_agreement_id IS NOT NULL
+            AND counterparty_organization_id <> organization_id
+        )
+    );
+
+-- Counterparty organizations list incoming inter-org transfers
+CREATE INDEX IF NOT EXISTS idx_transactions_counterparty_org
+    ON transactions(counterparty_organization_id, created_at)
+    WHERE counterparty_organization_id IS NOT NULL;
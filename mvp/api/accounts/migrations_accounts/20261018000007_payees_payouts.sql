@@ -0,0 +1,59 @@
+-- External payees (bank accounts outside the platform) and outbound payouts to them.
+-- A payout debits the account with a withdraw intent (account -> SYSTEM_CASH_CONTROL),
+-- is submitted to a payment rail, and either settles or is returned. Returns credit the
+-- account back with a deposit intent (SYSTEM_CASH_CONTROL -> account).
+
+CREATE TABLE IF NOT EXISTS payees (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    environment VARCHAR(20) NOT NULL CHECK (environment IN ('sandbox', 'production')),
+    organization_id UUID NOT NULL,
+    -- Account owner the payee belongs to (accounts.user_id)
+    user_id UUID NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    bank_name VARCHAR(255),
+    routing_number VARCHAR(9) NOT NULL CHECK (routing_number ~ '^[0-9]{9}$'),
+    account_number VARCHAR(17) NOT NULL CHECK (account_number ~ '^[0-9]{4,17}$'),
+    bank_account_type VARCHAR(20) NOT NULL CHECK (bank_account_type IN ('checking', 'savings')),
+    status VARCHAR(20) NOT NULL DEFAULT 'active' CHECK (status IN ('active', 'inactive')),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_payees_owner
+    ON payees(environment, organization_id, user_id);
+
+CREATE TABLE IF NOT EXISTS payouts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    environment VARCHAR(20) NOT NULL CHECK (environment IN ('sandbox', 'production')),
+    organization_id UUID NOT NULL,
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    payee_id UUID NOT NULL REFERENCES payees(id),
+    -- Withdraw intent that debited the account (one payout per intent)
+    transaction_id UUID NOT NULL UNIQUE REFERENCES transactions(id),
+    -- Deposit intent that credited the account back when the payout was returned
+    return_transaction_id UUID UNIQUE REFERENCES transactions(id),
+    amount BIGINT NOT NULL CHECK (amount > 0),
+    currency VARCHAR(3) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'submitted', 'settled', 'returned')),
+    rail VARCHAR(50) NOT NULL,
+    rail_reference VARCHAR(255),
+    return_code VARCHAR(10),
+    return_reason TEXT,
+    -- Last submission error while the payout is pending
+    failure_reason TEXT,
+    submitted_at TIMESTAMP WITH TIME ZONE,
+    settled_at TIMESTAMP WITH TIME ZONE,
+    returned_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK ((status = 'returned') = (return_transaction_id IS NOT NULL))
+);
+
+CREATE INDEX IF NOT EXISTS idx_payouts_account
+    ON payouts(account_id, created_at);
+
+-- Payout worker: payouts still moving through the rail
+CREATE INDEX IF NOT EXISTS idx_payouts_in_flight
+    ON payouts(status, created_at)
+    WHERE status IN ('pending', 'submitted');
@@ -0,0 +1,40 @@
+-- NACHA ACH files of outbound payouts (PAYOUT_RAIL=nacha).
+-- Each settlement window, posted payouts still pending submission are written to one file per
+-- environment (one PPD credit batch per organization) and marked submitted. The withdraw
+-- intent of each payout records the file and the entry's trace number, which NACHA return
+-- files reference to identify the original entry.
+
+CREATE TABLE IF NOT EXISTS nacha_files (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    environment VARCHAR(20) NOT NULL CHECK (environment IN ('sandbox', 'production')),
+    file_name VARCHAR(255) NOT NULL,
+    file_path TEXT NOT NULL,
+    file_creation_date DATE NOT NULL,
+    file_id_modifier CHAR(1) NOT NULL,
+    -- Payouts created up to this instant were eligible for the file
+    window_end TIMESTAMP WITH TIME ZONE NOT NULL,
+    batch_count INTEGER NOT NULL,
+    entry_count INTEGER NOT NULL,
+    entry_hash BIGINT NOT NULL,
+    total_credit_amount BIGINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (environment, file_creation_date, file_id_modifier)
+);
+
+-- 7-digit sequence part of entry trace numbers (ODFI routing prefix + sequence)
+CREATE SEQUENCE IF NOT EXISTS nacha_trace_seq MINVALUE 1 MAXVALUE 9999999 CYCLE;
+
+ALTER TABLE transactions
+    ADD COLUMN IF NOT EXISTS nacha_file_id UUID REFERENCES nacha_files(id),
+    ADD COLUMN IF NOT EXISTS nacha_trace_number VARCHAR(15);
+
+ALTER TABLE transactions DROP CONSTRAINT IF EXISTS transactions_nacha_check;
+ALTER TABLE transactions
+    ADD CONSTRAINT transactions_nacha_check CHECK (
+        (nacha_file_id IS NULL) = (nacha_trace_number IS NULL)
+    );
+
+-- Return files are matched back to transactions by trace number
+CREATE INDEX IF NOT EXISTS idx_transactions_nacha_trace_number
+    ON transactions(nacha_trace_number)
+    WHERE nacha_trace_number IS NOT NULL;
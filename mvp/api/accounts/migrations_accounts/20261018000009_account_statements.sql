@@ -0,0 +1,33 @@
+-- Periodic account statements. Monthly statements are stored once the month has ended and
+-- never change afterwards: opening/closing balances, every posted movement with its running
+-- balance (lines) and totals by kind are frozen at generation time.
+
+CREATE TABLE IF NOT EXISTS account_statements (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    organization_id UUID,
+    environment VARCHAR(20) NOT NULL CHECK (environment IN ('sandbox', 'production')),
+    period_start DATE NOT NULL,
+    period_end DATE NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    opening_balance BIGINT NOT NULL,
+    closing_balance BIGINT NOT NULL,
+    total_credits BIGINT NOT NULL,
+    total_debits BIGINT NOT NULL,
+    totals JSONB NOT NULL,
+    lines JSONB NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (account_id, period_start, period_end),
+    CHECK (period_start <= period_end)
+);
+
+CREATE OR REPLACE FUNCTION account_statements_immutable() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'account statements are immutable';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS account_statements_no_update ON account_statements;
+CREATE TRIGGER account_statements_no_update
+    BEFORE UPDATE OR DELETE ON account_statements
+    FOR EACH ROW EXECUTE FUNCTION account_statements_immutable();
@@ -0,0 +1,18 @@
+-- Indexes backing the transaction listing filters (date range, status, kind, amount range,
+-- counterparty account, idempotency key prefix) on account and organization listings.
+
+-- Account listings: either side of the transaction, newest first
+-- (from_account_id, created_at) exists since 20261018000005
+CREATE INDEX IF NOT EXISTS idx_transactions_to_account_created_at
+    ON transactions(to_account_id, created_at);
+
+-- Organization listings filtered by kind or amount
+CREATE INDEX IF NOT EXISTS idx_transactions_org_kind_created_at
+    ON transactions(organization_id, transaction_kind, created_at);
+
+CREATE INDEX IF NOT EXISTS idx_transactions_org_amount
+    ON transactions(organization_id, amount);
+
+-- Idempotency key prefix search (LIKE 'prefix%')
+CREATE INDEX IF NOT EXISTS idx_transactions_org_idempotency_key_pattern
+    ON transactions(organization_id, idempotency_key varchar_pattern_ops);
@@ -0,0 +1,18 @@
+-- Keyset (cursor) pagination seeks on (created_at, id) within each listing scope.
+
+CREATE INDEX IF NOT EXISTS idx_accounts_user_env_created_at_id
+    ON accounts(user_id, environment, created_at, id);
+
+CREATE INDEX IF NOT EXISTS idx_accounts_org_env_created_at_id
+    ON accounts(organization_id, environment, created_at, id);
+
+CREATE INDEX IF NOT EXISTS idx_accounts_admin_env_created_at_id
+    ON accounts(admin_user_id, environment, created_at, id)
+    WHERE admin_user_id IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_transactions_org_created_at_id
+    ON transactions(organization_id, created_at, id);
+
+CREATE INDEX IF NOT EXISTS idx_transactions_counterparty_org_created_at_id
+    ON transactions(counterparty_organization_id, created_at, id)
+    WHERE counterparty_organization_id IS NOT NULL;
@@ -0,0 +1,160 @@
+-- Outbound webhooks: per-organization endpoints, an event outbox filled by triggers on
+-- transactions and accounts, and per-endpoint deliveries with an attempt log.
+
+CREATE TABLE IF NOT EXISTS webhook_endpoints (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id UUID NOT NULL,
+    environment VARCHAR(20) NOT NULL CHECK (environment IN ('sandbox', 'production')),
+    url TEXT NOT NULL,
+    description TEXT,
+    -- HMAC-SHA256 signing key; never returned after creation
+    secret VARCHAR(100) NOT NULL,
+    -- Subscribed event types; empty = all events
+    event_types TEXT[] NOT NULL DEFAULT '{}',
+    status VARCHAR(20) NOT NULL DEFAULT 'active' CHECK (status IN ('active', 'disabled')),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_endpoints_org_env
+    ON webhook_endpoints(organization_id, environment)
+    WHERE status = 'active';
+
+CREATE TABLE IF NOT EXISTS webhook_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id UUID NOT NULL,
+    environment VARCHAR(20) NOT NULL,
+    event_type VARCHAR(50) NOT NULL,
+    payload JSONB NOT NULL,
+    -- Set once deliveries have been created for the subscribed endpoints
+    fanned_out_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_events_pending_fan_out
+    ON webhook_events(created_at)
+    WHERE fanned_out_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    endpoint_id UUID NOT NULL REFERENCES webhook_endpoints(id),
+    event_id UUID NOT NULL REFERENCES webhook_events(id),
+    status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'succeeded', 'failed')),
+    attempt_count INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    last_attempt_at TIMESTAMP WITH TIME ZONE,
+    last_response_status INTEGER,
+    last_error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (endpoint_id, event_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_due
+    ON webhook_deliveries(next_attempt_at)
+    WHERE status = 'pending';
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_endpoint_created_at
+    ON webhook_deliveries(endpoint_id, created_at);
+
+CREATE TABLE IF NOT EXISTS webhook_delivery_attempts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    delivery_id UUID NOT NULL REFERENCES webhook_deliveries(id),
+    attempt_number INTEGER NOT NULL,
+    response_status INTEGER,
+    error TEXT,
+    duration_ms BIGINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_delivery_attempts_delivery
+    ON webhook_delivery_attempts(delivery_id, attempt_number);
+
+-- Events are recorded in the same database transaction as the change they describe.
+
+CREATE OR REPLACE FUNCTION webhook_transaction_payload(t transactions) RETURNS JSONB AS $$
+    SELECT jsonb_build_object(
+        'id', t.id,
+        'organization_id', t.organization_id,
+        'from_account_id', t.from_account_id,
+        'to_account_id', t.to_account_id,
+        'amount', t.amount,
+        'currency', t.currency,
+        'destination_amount', t.destination_amount,
+        'destination_currency', t.destination_currency,
+        'transaction_kind', t.transaction_kind,
+        'status', t.status,
+        'failure_reason', t.failure_reason,
+        'idempotency_key', t.idempotency_key,
+        'counterparty_organization_id', t.counterparty_organization_id,
+        'created_at', t.created_at,
+        'updated_at', t.updated_at
+    );
+$$ LANGUAGE sql STABLE;
+
+CREATE OR REPLACE FUNCTION webhook_record_transaction_event() RETURNS trigger AS $$
+DECLARE
+    event_type VARCHAR(50);
+BEGIN
+    IF NEW.environment IS NULL THEN
+        RETURN NEW;
+    END IF;
+
+    IF TG_OP = 'INSERT' THEN
+        event_type := 'transaction.created';
+    ELSIF NEW.status IS DISTINCT FROM OLD.status AND NEW.status IN ('posted', 'failed') THEN
+        event_type := 'transaction.' || NEW.status;
+    ELSE
+        RETURN NEW;
+    END IF;
+
+    INSERT INTO webhook_events (organization_id, environment, event_type, payload)
+    VALUES (NEW.organization_id, NEW.environment, event_type, webhook_transaction_payload(NEW));
+
+    -- Inter-organization transfers are also reported to the receiving organization
+    IF NEW.counterparty_organization_id IS NOT NULL
+       AND NEW.counterparty_organization_id <> NEW.organization_id THEN
+        INSERT INTO webhook_events (organization_id, environment, event_type, payload)
+        VALUES (NEW.counterparty_organization_id, NEW.environment, event_type, webhook_transaction_payload(NEW));
+    END IF;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS transactions_webhook_events ON transactions;
+CREATE TRIGGER transactions_webhook_events
+    AFTER INSERT OR UPDATE OF status ON transactions
+    FOR EACH ROW EXECUTE FUNCTION webhook_record_transaction_event();
+
+CREATE OR REPLACE FUNCTION webhook_record_account_event() RETURNS trigger AS $$
+BEGIN
+    IF NEW.organization_id IS NULL OR NEW.environment IS NULL
+       OR NEW.status IS NOT DISTINCT FROM OLD.status THEN
+        RETURN NEW;
+    END IF;
+
+    INSERT INTO webhook_events (organization_id, environment, event_type, payload)
+    VALUES (
+        NEW.organization_id,
+        NEW.environment,
+        'account.status_changed',
+        jsonb_build_object(
+            'id', NEW.id,
+            'account_number', NEW.account_number,
+            'organization_id', NEW.organization_id,
+            'user_id', NEW.user_id,
+            'previous_status', OLD.status,
+            'status', NEW.status,
+            'updated_at', NEW.updated_at
+        )
+    );
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS accounts_webhook_events ON accounts;
+CREATE TRIGGER accounts_webhook_events
+    AFTER UPDATE OF status ON accounts
+    FOR EACH ROW EXECUTE FUNCTION webhook_record_account_event();
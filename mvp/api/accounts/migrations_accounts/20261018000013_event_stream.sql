@@ -0,0 +1,24 @@
+-- Live event stream: the webhook event outbox doubles as the persisted event log. A
+-- per-row sequence gives stream clients a resumable position (SSE Last-Event-ID), and
+-- every new event is announced on the rails_events channel so open streams wake up.
+
+ALTER TABLE webhook_events ADD COLUMN IF NOT EXISTS sequence BIGSERIAL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_webhook_events_sequence
+    ON webhook_events(sequence);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_events_org_env_sequence
+    ON webhook_events(organization_id, environment, sequence);
+
+-- Payload: "<organization_id>:<environment>", so listeners can skip other tenants' events
+CREATE OR REPLACE FUNCTION webhook_events_notify() RETURNS trigger AS $$
+BEGIN
+    PERFORM pg_notify('rails_events', NEW.organization_id::text || ':' || NEW.environment);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS webhook_events_notify ON webhook_events;
+CREATE TRIGGER webhook_events_notify
+    AFTER INSERT ON webhook_events
+    FOR EACH ROW EXECUTE FUNCTION webhook_events_notify();
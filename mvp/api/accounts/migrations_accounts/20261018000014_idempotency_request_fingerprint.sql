@@ -0,0 +1,16 @@
+-- Fingerprint of the request an idempotency key was first used for, so a replay with a
+-- different amount, accounts, kind or quote is rejected instead of returning the original.
+-- SHA-256 (hex) of "kind|from_account_id|to_account_id|amount|currency|fx_quote_id".
+
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS request_fingerprint VARCHAR(64);
+
+UPDATE transactions
+SET request_fingerprint = encode(
+    sha256(convert_to(
+        transaction_kind || '|' || from_account_id::text || '|' || to_account_id::text || '|'
+            || amount::text || '|' || currency || '|' || COALESCE(fx_quote_id::text, ''),
+        'UTF8'
+    )),
+    'hex'
+)
+WHERE request_fingerprint IS NULL;
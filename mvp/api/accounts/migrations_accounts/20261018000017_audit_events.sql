@@ -0,0 +1,49 @@
+-- Audit trail of mutating API calls: who (user, API key or internal service) did what to
+-- which record, with the record before and the response after, the correlation id and the
+-- client IP. Rows are never changed or removed; the triggers below reject it.
+
+CREATE TABLE IF NOT EXISTS audit_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- NULL for operator calls spanning organizations (NACHA files and returns)
+    organization_id UUID,
+    environment VARCHAR(20) CHECK (environment IN ('sandbox', 'production')),
+    actor_type VARCHAR(20) NOT NULL CHECK (actor_type IN ('user', 'api_key', 'internal_service')),
+    actor_id UUID,
+    action VARCHAR(100) NOT NULL,
+    target_type VARCHAR(50) NOT NULL,
+    target_id UUID,
+    before JSONB,
+    after JSONB,
+    -- HTTP status of the call; failed calls are recorded too
+    status_code INTEGER NOT NULL,
+    correlation_id VARCHAR(255),
+    ip_address VARCHAR(45),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_events_org_env_created
+    ON audit_events(organization_id, environment, created_at DESC, id DESC);
+
+CREATE INDEX IF NOT EXISTS idx_audit_events_target
+    ON audit_events(target_id, created_at DESC)
+    WHERE target_id IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_audit_events_actor
+    ON audit_events(actor_id, created_at DESC)
+    WHERE actor_id IS NOT NULL;
+
+CREATE OR REPLACE FUNCTION audit_events_append_only() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_events is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS audit_events_no_update ON audit_events;
+CREATE TRIGGER audit_events_no_update
+    BEFORE UPDATE OR DELETE ON audit_events
+    FOR EACH ROW EXECUTE FUNCTION audit_events_append_only();
+
+DROP TRIGGER IF EXISTS audit_events_no_truncate ON audit_events;
+CREATE TRIGGER audit_events_no_truncate
+    BEFORE TRUNCATE ON audit_events
+    FOR EACH STATEMENT EXECUTE FUNCTION audit_events_append_only();
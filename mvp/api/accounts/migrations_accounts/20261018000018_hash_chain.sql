@@ -0,0 +1,173 @@
+-- Tamper evidence: every new transaction intent and audit event is linked into a hash chain
+-- per organization and table. A record's hash is SHA-256 over the previous record's hash
+-- followed by the record's canonical content, so editing, removing or inserting a record
+-- breaks every later link. Operator audit events (no organization) are chained under the nil
+-- UUID. Records created before this migration are not chained.
+--
+-- Canonical content is the concatenation of the immutable fields below, each encoded as
+-- "<byte length>:<text>" or "-" for NULL, timestamps as UTC "YYYY-MM-DDTHH:MM:SS.ffffffZ".
+-- The verifier (src/hash_chain.rs) recomputes it independently; both must stay in step.
+
+ALTER TABLE transactions
+    ADD COLUMN IF NOT EXISTS chain_seq BIGINT,
+    ADD COLUMN IF NOT EXISTS prev_hash CHAR(64),
+    ADD COLUMN IF NOT EXISTS record_hash CHAR(64);
+
+ALTER TABLE audit_events
+    ADD COLUMN IF NOT EXISTS chain_seq BIGINT,
+    ADD COLUMN IF NOT EXISTS prev_hash CHAR(64),
+    ADD COLUMN IF NOT EXISTS record_hash CHAR(64);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_transactions_chain
+    ON transactions(organization_id, chain_seq)
+    WHERE chain_seq IS NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_audit_events_chain
+    ON audit_events((COALESCE(organization_id, '00000000-0000-0000-0000-000000000000'::uuid)), chain_seq)
+    WHERE chain_seq IS NOT NULL;
+
+-- Last link of each chain; locked while a record is appended, so links are assigned in
+-- commit order per organization.
+CREATE TABLE IF NOT EXISTS hash_chain_heads (
+    organization_id UUID NOT NULL,
+    chain VARCHAR(20) NOT NULL CHECK (chain IN ('transactions', 'audit_events')),
+    last_seq BIGINT NOT NULL,
+    last_hash CHAR(64) NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (organization_id, chain)
+);
+
+CREATE OR REPLACE FUNCTION hash_chain_field(value TEXT) RETURNS TEXT AS $$
+    SELECT CASE WHEN value IS NULL THEN '-' ELSE octet_length(value)::text || ':' || value END;
+$$ LANGUAGE sql IMMUTABLE;
+
+CREATE OR REPLACE FUNCTION hash_chain_timestamp(value TIMESTAMP WITH TIME ZONE) RETURNS TEXT AS $$
+    SELECT to_char(value AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS.US"Z"');
+$$ LANGUAGE sql IMMUTABLE;
+
+-- Links a record into its chain: sets chain_seq, prev_hash and record_hash.
+CREATE OR REPLACE FUNCTION hash_chain_append(
+    p_organization_id UUID,
+    p_chain TEXT,
+    p_canonical TEXT,
+    OUT seq BIGINT,
+    OUT prev_hash TEXT,
+    OUT record_hash TEXT
+) AS $$
+BEGIN
+    INSERT INTO hash_chain_heads (organization_id, chain, last_seq, last_hash)
+    VALUES (p_organization_id, p_chain, 0, repeat('0', 64))
+    ON CONFLICT (organization_id, chain) DO NOTHING;
+
+    SELECT h.last_seq + 1, h.last_hash INTO seq, prev_hash
+    FROM hash_chain_heads h
+    WHERE h.organization_id = p_organization_id AND h.chain = p_chain
+    FOR UPDATE;
+
+    record_hash := encode(sha256(convert_to(prev_hash || p_canonical, 'UTF8')), 'hex');
+
+    UPDATE hash_chain_heads
+    SET last_seq = seq, last_hash = record_hash, updated_at = NOW()
+    WHERE organization_id = p_organization_id AND chain = p_chain;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION transactions_hash_chain() RETURNS trigger AS $$
+DECLARE
+    link RECORD;
+BEGIN
+    SELECT * INTO link FROM hash_chain_append(
+        NEW.organization_id,
+        'transactions',
+        hash_chain_field(NEW.id::text)
+            || hash_chain_field(NEW.organization_id::text)
+            || hash_chain_field(NEW.environment)
+            || hash_chain_field(NEW.transaction_kind)
+            || hash_chain_field(NEW.from_account_id::text)
+            || hash_chain_field(NEW.to_account_id::text)
+            || hash_chain_field(NEW.amount::text)
+            || hash_chain_field(NEW.currency)
+            || hash_chain_field(NEW.destination_amount::text)
+            || hash_chain_field(NEW.destination_currency)
+            || hash_chain_field(NEW.fx_rate::text)
+            || hash_chain_field(NEW.fx_quote_id::text)
+            || hash_chain_field(NEW.counterparty_organization_id::text)
+            || hash_chain_field(NEW.interorg_agreement_id::text)
+            || hash_chain_field(NEW.idempotency_key)
+            || hash_chain_field(hash_chain_timestamp(NEW.created_at))
+    );
+
+    NEW.chain_seq := link.seq;
+    NEW.prev_hash := link.prev_hash;
+    NEW.record_hash := link.record_hash;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS transactions_hash_chain ON transactions;
+CREATE TRIGGER transactions_hash_chain
+    BEFORE INSERT ON transactions
+    FOR EACH ROW EXECUTE FUNCTION transactions_hash_chain();
+
+CREATE OR REPLACE FUNCTION audit_events_hash_chain() RETURNS trigger AS $$
+DECLARE
+    link RECORD;
+BEGIN
+    SELECT * INTO link FROM hash_chain_append(
+        COALESCE(NEW.organization_id, '00000000-0000-0000-0000-000000000000'::uuid),
+        'audit_events',
+        hash_chain_field(NEW.id::text)
+            || hash_chain_field(NEW.organization_id::text)
+            || hash_chain_field(NEW.environment)
+            || hash_chain_field(NEW.actor_type)
+            || hash_chain_field(NEW.actor_id::text)
+            || hash_chain_field(NEW.action)
+            || hash_chain_field(NEW.target_type)
+            || hash_chain_field(NEW.target_id::text)
+            || hash_chain_field(NEW.before::text)
+            || hash_chain_field(NEW.after::text)
+            || hash_chain_field(NEW.status_code::text)
+            || hash_chain_field(NEW.correlation_id)
+            || hash_chain_field(NEW.ip_address)
+            || hash_chain_field(hash_chain_timestamp(NEW.created_at))
+    );
+
+    NEW.chain_seq := link.seq;
+    NEW.prev_hash := link.prev_hash;
+    NEW.record_hash := link.record_hash;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS audit_events_hash_chain ON audit_events;
+CREATE TRIGGER audit_events_hash_chain
+    BEFORE INSERT ON audit_events
+    FOR EACH ROW EXECUTE FUNCTION audit_events_hash_chain();
+
+-- Daily signed statement of each chain's head, for external attestation. The signature is
+-- Ed25519 over the message built in src/hash_chain.rs (checkpoint_message).
+CREATE TABLE IF NOT EXISTS hash_chain_checkpoints (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id UUID NOT NULL,
+    chain VARCHAR(20) NOT NULL CHECK (chain IN ('transactions', 'audit_events')),
+    -- Head as of the end of this UTC day
+    checkpoint_date DATE NOT NULL,
+    chain_seq BIGINT NOT NULL,
+    record_hash CHAR(64) NOT NULL,
+    -- Base64 Ed25519 signature and the public key it verifies with
+    signature TEXT NOT NULL,
+    public_key TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (organization_id, chain, checkpoint_date)
+);
+
+CREATE OR REPLACE FUNCTION hash_chain_checkpoints_append_only() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'hash_chain_checkpoints is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS hash_chain_checkpoints_no_update ON hash_chain_checkpoints;
+CREATE TRIGGER hash_chain_checkpoints_no_update
+    BEFORE UPDATE OR DELETE ON hash_chain_checkpoints
+    FOR EACH ROW EXECUTE FUNCTION hash_chain_checkpoints_append_only();
@@ -0,0 +1,22 @@
+-- Account lifecycle: every status change, with the reason given and who made it.
+-- Suspending and closing require a reason code; reactivating takes an optional one.
+CREATE TABLE IF NOT EXISTS account_status_history (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    organization_id UUID NOT NULL,
+    environment VARCHAR(20) NOT NULL CHECK (environment IN ('sandbox', 'production')),
+    from_status VARCHAR(20) NOT NULL CHECK (from_status IN ('active', 'suspended', 'closed')),
+    to_status VARCHAR(20) NOT NULL CHECK (to_status IN ('active', 'suspended', 'closed')),
+    reason VARCHAR(40) CHECK (
+        reason IN ('customer_request', 'fraud_suspected', 'compliance_hold', 'dormant', 'charged_off', 'other')
+    ),
+    note TEXT,
+    -- user or api_key; actor_id is NULL for a user token without a user
+    actor_type VARCHAR(20) NOT NULL CHECK (actor_type IN ('user', 'api_key')),
+    actor_id UUID,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK (to_status = 'active' OR reason IS NOT NULL)
+);
+
+CREATE INDEX IF NOT EXISTS idx_account_status_history_account
+    ON account_status_history(account_id, created_at DESC, id DESC);
@@ -0,0 +1,55 @@
+-- Account closure workflow: settle or cancel the account's pending intents, sweep the residual
+-- Ledger balance to another account or a payee, then close the account. Each step is
+-- recorded here so an interrupted closure resumes where it stopped.
+CREATE TABLE IF NOT EXISTS account_closures (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- Closed is final, so an account has at most one closure
+    account_id UUID NOT NULL UNIQUE REFERENCES accounts(id),
+    organization_id UUID NOT NULL,
+    environment VARCHAR(20) NOT NULL CHECK (environment IN ('sandbox', 'production')),
+    status VARCHAR(20) NOT NULL DEFAULT 'in_progress' CHECK (status IN ('in_progress', 'completed')),
+    reason VARCHAR(40) NOT NULL CHECK (
+        reason IN ('customer_request', 'fraud_suspected', 'compliance_hold', 'dormant', 'charged_off', 'other')
+    ),
+    note TEXT,
+    actor_type VARCHAR(20) NOT NULL CHECK (actor_type IN ('user', 'api_key')),
+    actor_id UUID,
+    -- Sweep destination: an account of the organization, or a payee of the account owner
+    sweep_to_account_id UUID REFERENCES accounts(id),
+    payee_id UUID REFERENCES payees(id),
+    currency VARCHAR(3) NOT NULL,
+    -- Sweep intents use the idempotency key 'account-closure:<id>:<attempt>'; the attempt
+    -- moves on when a sweep fails or funds arrive after a sweep posted
+    sweep_attempt INT NOT NULL DEFAULT 0,
+    sweep_transaction_id UUID REFERENCES transactions(id),
+    -- Pending intents posted to the Ledger, and those the Ledger rejected (marked failed)
+    settled_intents INT NOT NULL DEFAULT 0,
+    cancelled_intents INT NOT NULL DEFAULT 0,
+    -- Why the closure is waiting, while in progress
+    last_error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMP WITH TIME ZONE,
+    CHECK (sweep_to_account_id IS NULL OR payee_id IS NULL),
+    CHECK ((status = 'completed') = (completed_at IS NOT NULL))
+);
+
+CREATE INDEX IF NOT EXISTS idx_account_closures_in_progress
+    ON account_closures(updated_at)
+    WHERE status = 'in_progress';
+
+-- Accounts being closed by the workflow are 'closing': intents need an active account, so none
+-- are created while the balance is swept, and only the workflow moves the account on to 'closed'.
+ALTER TABLE accounts DROP CONSTRAINT IF EXISTS accounts_status_check;
+ALTER TABLE accounts
+    ADD CONSTRAINT accounts_status_check CHECK (status IN ('active', 'suspended', 'closing', 'closed'));
+
+ALTER TABLE account_status_history DROP CONSTRAINT IF EXISTS account_status_history_from_status_check;
+ALTER TABLE account_status_history
+    ADD CONSTRAINT account_status_history_from_status_check
+        CHECK (from_status IN ('active', 'suspended', 'closing', 'closed'));
+
+ALTER TABLE account_status_history DROP CONSTRAINT IF EXISTS account_status_history_to_status_check;
+ALTER TABLE account_status_history
+    ADD CONSTRAINT account_status_history_to_status_check
+        CHECK (to_status IN ('active', 'suspended', 'closing', 'closed'));
@@ -0,0 +1,24 @@
+-- Client-defined fields: a nickname and metadata on accounts; a description, reference and
+-- metadata on transaction intents. Metadata is a JSON object of string values (at most 50
+-- keys of up to 40 characters, values up to 500 characters; checked by the API) and can be
+-- filtered on with containment (metadata @> '{"order_id": "123"}').
+ALTER TABLE accounts
+    ADD COLUMN IF NOT EXISTS nickname VARCHAR(100),
+    ADD COLUMN IF NOT EXISTS metadata JSONB NOT NULL DEFAULT '{}'::jsonb
+        CHECK (jsonb_typeof(metadata) = 'object');
+
+ALTER TABLE transactions
+    ADD COLUMN IF NOT EXISTS description VARCHAR(500),
+    ADD COLUMN IF NOT EXISTS reference VARCHAR(100),
+    ADD COLUMN IF NOT EXISTS metadata JSONB NOT NULL DEFAULT '{}'::jsonb
+        CHECK (jsonb_typeof(metadata) = 'object');
+
+CREATE INDEX IF NOT EXISTS idx_accounts_metadata
+    ON accounts USING GIN (metadata jsonb_path_ops);
+
+CREATE INDEX IF NOT EXISTS idx_transactions_metadata
+    ON transactions USING GIN (metadata jsonb_path_ops);
+
+CREATE INDEX IF NOT EXISTS idx_transactions_reference
+    ON transactions(organization_id, reference)
+    WHERE reference IS NOT NULL;
@@ -0,0 +1,16 @@
+-- Fee schedule amounts (flat, min, max) are minor units of one currency, so a schedule only
+-- applies to intents in that currency. Schedules created before this column were written for
+-- the default account currency.
+ALTER TABLE fee_schedules
+    ADD COLUMN IF NOT EXISTS currency VARCHAR(3);
+
+UPDATE fee_schedules SET currency = 'USD' WHERE currency IS NULL;
+
+ALTER TABLE fee_schedules
+    ALTER COLUMN currency SET NOT NULL;
+
+-- Intent creation query pattern: ... AND transaction_kind = $3 AND currency = $5 AND active
+DROP INDEX IF EXISTS idx_fee_schedules_org_env_kind;
+CREATE INDEX IF NOT EXISTS idx_fee_schedules_org_env_kind_currency
+    ON fee_schedules(organization_id, environment, transaction_kind, currency)
+    WHERE active;
@@ -0,0 +1,38 @@
+-- Inter-organization agreements need consent from both organizations. The payer creates a
+-- pending agreement naming a counterparty account; it becomes active when the organization
+-- owning that account accepts it. The payer is not told whether the account exists or who
+-- owns it, so counterparty_organization_id is only known once the agreement is accepted and
+-- counterparty_account_id may name an account that does not exist.
+
+ALTER TABLE interorg_agreements
+    ADD COLUMN IF NOT EXISTS accepted_at TIMESTAMP WITH TIME ZONE;
+
+-- Agreements created before acceptance existed were active from creation
+UPDATE interorg_agreements SET accepted_at = created_at WHERE accepted_at IS NULL AND status = 'active';
+
+ALTER TABLE interorg_agreements DROP CONSTRAINT IF EXISTS interorg_agreements_counterparty_account_id_fkey;
+ALTER TABLE interorg_agreements ALTER COLUMN counterparty_organization_id DROP NOT NULL;
+
+ALTER TABLE interorg_agreements DROP CONSTRAINT IF EXISTS interorg_agreements_status_check;
+ALTER TABLE interorg_agreements
+    ADD CONSTRAINT interorg_agreements_status_check CHECK (status IN ('pending', 'active', 'revoked'));
+ALTER TABLE interorg_agreements ALTER COLUMN status SET DEFAULT 'pending';
+
+ALTER TABLE interorg_agreements DROP CONSTRAINT IF EXISTS interorg_agreements_accepted_check;
+ALTER TABLE interorg_agreements
+    ADD CONSTRAINT interorg_agreements_accepted_check CHECK (
+        status = 'pending'
+        OR status = 'revoked'
+        OR (counterparty_organization_id IS NOT NULL AND accepted_at IS NOT NULL)
+    );
+
+-- At most one pending or active agreement per payer organization and counterparty account
+DROP INDEX IF EXISTS idx_interorg_agreements_active_counterparty;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_interorg_agreements_open_counterparty
+    ON interorg_agreements(environment, organization_id, counterparty_account_id)
+    WHERE status IN ('pending', 'active');
+
+-- Counterparty organizations list pending agreements naming their accounts
+CREATE INDEX IF NOT EXISTS idx_interorg_agreements_pending_account
+    ON interorg_agreements(environment, counterparty_account_id)
+    WHERE status = 'pending';
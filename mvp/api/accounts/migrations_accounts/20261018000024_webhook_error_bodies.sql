@@ -0,0 +1,9 @@
+-- Delivery logs used to keep up to 500 bytes of a failed response's body ("HTTP 500: ...").
+-- Receivers' bodies can hold anything their network exposes, so only the status is kept.
+UPDATE webhook_delivery_attempts
+SET error = split_part(error, ':', 1)
+WHERE error ~ '^HTTP [0-9]{3}: ';
+
+UPDATE webhook_deliveries
+SET last_error = split_part(last_error, ':', 1)
+WHERE last_error ~ '^HTTP [0-9]{3}: ';
@@ -0,0 +1,48 @@
+-- Stream positions came from a global BIGSERIAL, taken when the event row was inserted. An
+-- event committed after a later-numbered one could be skipped by a client that had already
+-- streamed past its position. Positions are now assigned per organization and environment
+-- from a counter row that stays locked until the inserting transaction ends (like
+-- hash_chain_heads), so the events of one stream become visible in position order.
+
+CREATE TABLE IF NOT EXISTS event_stream_heads (
+    organization_id UUID NOT NULL,
+    environment VARCHAR(20) NOT NULL,
+    last_sequence BIGINT NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (organization_id, environment)
+);
+
+-- Existing positions are kept, so clients still resume from their last event id
+INSERT INTO event_stream_heads (organization_id, environment, last_sequence)
+SELECT organization_id, environment, MAX(sequence)
+FROM webhook_events
+GROUP BY organization_id, environment
+ON CONFLICT (organization_id, environment) DO NOTHING;
+
+ALTER TABLE webhook_events ALTER COLUMN sequence DROP DEFAULT;
+DROP SEQUENCE IF EXISTS webhook_events_sequence_seq;
+
+DROP INDEX IF EXISTS idx_webhook_events_sequence;
+DROP INDEX IF EXISTS idx_webhook_events_org_env_sequence;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_webhook_events_org_env_sequence
+    ON webhook_events(organization_id, environment, sequence);
+
+CREATE OR REPLACE FUNCTION webhook_events_assign_sequence() RETURNS trigger AS $$
+BEGIN
+    INSERT INTO event_stream_heads (organization_id, environment, last_sequence)
+    VALUES (NEW.organization_id, NEW.environment, 0)
+    ON CONFLICT (organization_id, environment) DO NOTHING;
+
+    UPDATE event_stream_heads
+    SET last_sequence = last_sequence + 1, updated_at = NOW()
+    WHERE organization_id = NEW.organization_id AND environment = NEW.environment
+    RETURNING last_sequence INTO NEW.sequence;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS webhook_events_assign_sequence ON webhook_events;
+CREATE TRIGGER webhook_events_assign_sequence
+    BEFORE INSERT ON webhook_events
+    FOR EACH ROW EXECUTE FUNCTION webhook_events_assign_sequence();
@@ -0,0 +1,29 @@
+-- Auto-withdraw plans the scheduler cannot pay out (no positive monthly amount, or no active
+-- checking account of the owner to pay into) are suspended with the reason instead of being
+-- retried on every run. Setting a suspended plan back to 'active' resumes it; installments
+-- due meanwhile are caught up from next_withdraw_date.
+
+ALTER TABLE fixed_savings_plans
+    ADD COLUMN IF NOT EXISTS suspended_reason TEXT;
+
+ALTER TABLE fixed_savings_plans DROP CONSTRAINT IF EXISTS fixed_savings_plans_status_check;
+ALTER TABLE fixed_savings_plans
+    ADD CONSTRAINT fixed_savings_plans_status_check
+        CHECK (status IN ('active', 'suspended', 'completed', 'cancelled'));
+
+UPDATE fixed_savings_plans
+SET status = 'suspended',
+    suspended_reason = 'monthly_withdraw_amount and next_withdraw_date are required',
+    updated_at = NOW()
+WHERE plan_type = 'auto_withdraw'
+  AND status = 'active'
+  AND (monthly_withdraw_amount IS NULL OR monthly_withdraw_amount <= 0 OR next_withdraw_date IS NULL);
+
+-- Active auto-withdraw plans always have an installment to pay
+ALTER TABLE fixed_savings_plans DROP CONSTRAINT IF EXISTS fixed_savings_plans_auto_withdraw_check;
+ALTER TABLE fixed_savings_plans
+    ADD CONSTRAINT fixed_savings_plans_auto_withdraw_check CHECK (
+        plan_type <> 'auto_withdraw'
+        OR status <> 'active'
+        OR (monthly_withdraw_amount > 0 AND next_withdraw_date IS NOT NULL)
+    );
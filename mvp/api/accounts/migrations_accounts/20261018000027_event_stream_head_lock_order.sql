@@ -0,0 +1,69 @@
+-- An inter-organization transfer records an event for the payer and then for the receiving
+-- organization, and each insert locks that organization's event_stream_heads row until
+-- commit. Two transfers in opposite directions took the two rows in opposite order and could
+-- deadlock. Both rows are now locked up front, in organization_id order.
+
+-- Locks the stream heads of the organizations in organization_id order, creating missing
+-- ones (also in that order) after the existing ones are held.
+CREATE OR REPLACE FUNCTION event_stream_lock_heads(env VARCHAR, organization_ids UUID[]) RETURNS void AS $$
+BEGIN
+    PERFORM 1 FROM event_stream_heads
+    WHERE environment = env AND organization_id = ANY(organization_ids)
+    ORDER BY organization_id
+    FOR UPDATE;
+
+    INSERT INTO event_stream_heads (organization_id, environment, last_sequence)
+    SELECT id, env, 0
+    FROM unnest(organization_ids) AS ids(id)
+    WHERE NOT EXISTS (
+        SELECT 1 FROM event_stream_heads h WHERE h.organization_id = ids.id AND h.environment = env
+    )
+    ORDER BY id
+    ON CONFLICT (organization_id, environment) DO NOTHING;
+
+    PERFORM 1 FROM event_stream_heads
+    WHERE environment = env AND organization_id = ANY(organization_ids)
+    ORDER BY organization_id
+    FOR UPDATE;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION webhook_record_transaction_event() RETURNS trigger AS $$
+DECLARE
+    event_type VARCHAR(50);
+    cross_organization BOOLEAN;
+BEGIN
+    IF NEW.environment IS NULL THEN
+        RETURN NEW;
+    END IF;
+
+    IF TG_OP = 'INSERT' THEN
+        event_type := 'transaction.created';
+    ELSIF NEW.status IS DISTINCT FROM OLD.status AND NEW.status IN ('posted', 'failed') THEN
+        event_type := 'transaction.' || NEW.status;
+    ELSE
+        RETURN NEW;
+    END IF;
+
+    cross_organization := NEW.counterparty_organization_id IS NOT NULL
+        AND NEW.counterparty_organization_id <> NEW.organization_id;
+
+    IF cross_organization THEN
+        PERFORM event_stream_lock_heads(
+            NEW.environment,
+            ARRAY[NEW.organization_id, NEW.counterparty_organization_id]
+        );
+    END IF;
+
+    INSERT INTO webhook_events (organization_id, environment, event_type, payload)
+    VALUES (NEW.organization_id, NEW.environment, event_type, webhook_transaction_payload(NEW));
+
+    -- Inter-organization transfers are also reported to the receiving organization
+    IF cross_organization THEN
+        INSERT INTO webhook_events (organization_id, environment, event_type, payload)
+        VALUES (NEW.counterparty_organization_id, NEW.environment, event_type, webhook_transaction_payload(NEW));
+    END IF;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts, http::HeaderMap};
+use jsonwebtoken::{decode, DecodingKey, Validation};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tonic::transport::Endpoint;
+use uuid::Uuid;
+
+use crate::environment::{Environment, EnvironmentMode};
+use crate::errors::AppError;
+use crate::grpc::users_proto::{auth_service_client::AuthServiceClient, introspect_request::Credential, IntrospectRequest};
+use crate::routes::api::AppState;
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+pub const INTERNAL_SERVICE_TOKEN_HEADER: &str = "x-internal-service-token";
+
+/// Introspection results kept at most this many; the cache is cleared when full
+const MAX_CACHED_CREDENTIALS: usize = 10_000;
+
+/// Secret the users service signs with when its JWT_SECRET is unset; accepted for local
+/// development only, behind ALLOW_DEV_JWT_SECRET=true
+const DEV_JWT_SECRET: &str = "dev_secret";
+
+/// Caller authenticated with a users-service access token or API key. Everything it can
+/// see or change belongs to its business (the `organization_id` of accounts-api data).
+#[derive(Clone, Debug)]
+pub struct AuthContext {
+    pub business_id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub api_key_id: Option<Uuid>,
+    /// Environment the credential is bound to, when it is bound to one
+    pub environment: Option<Environment>,
+}
+
+impl AuthContext {
+    /// Organization a request acts on: the caller's own. An `organization_id` given in the
+    /// query or body may be omitted, but naming another organization is rejected.
+    pub fn organization(&self, requested: Option<Uuid>) -> Result<Uuid, AppError> {
+        match requested {
+            Some(organization_id) if organization_id != self.business_id => Err(AppError::Forbidden(
+                "organization_id does not belong to the authenticated business".to_string(),
+            )),
+            _ => Ok(self.business_id),
+        }
+    }
+
+    /// Who a change is attributed to: the API key, else the user.
+    pub fn actor(&self) -> (&'static str, Option<Uuid>) {
+        match (self.api_key_id, self.user_id) {
+            (Some(api_key_id), _) => ("api_key", Some(api_key_id)),
+            (None, user_id) => ("user", user_id),
+        }
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for AuthContext {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        // Middleware may already have authenticated the request
+        if let Some(auth) = parts.extensions.get::<AuthContext>() {
+            return Ok(auth.clone());
+        }
+
+        let auth = state.authenticator.authenticate(&parts.headers).await?;
+        parts.extensions.insert(auth.clone());
+        Ok(auth)
+    }
+}
+
+/// Caller of operator endpoints that span all organizations (NACHA files and returns).
+/// Requires an `X-Internal-Service-Token` listed in INTERNAL_SERVICE_TOKEN_ALLOWLIST; with
+/// no allowlist configured these endpoints are closed.
+pub struct InternalCaller;
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for InternalCaller {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let provided = header(&parts.headers, INTERNAL_SERVICE_TOKEN_HEADER)
+            .ok_or_else(|| AppError::Unauthorized("X-Internal-Service-Token header is required".to_string()))?;
+
+        let allowlist = std::env::var("INTERNAL_SERVICE_TOKEN_ALLOWLIST").unwrap_or_default();
+        if allowlist.split(',').map(str::trim).any(|token| !token.is_empty() && token == provided) {
+            Ok(Self)
+        } else {
+            Err(AppError::Forbidden("unrecognized internal service token".to_string()))
+        }
+    }
+}
+
+/// How access tokens are verified (AUTH_MODE): `local` checks the signature with the shared
+/// JWT_SECRET, `introspect` asks the users service, which also rejects deactivated users.
+#[derive(Clone, Copy, PartialEq)]
+enum AuthMode {
+    Local,
+    Introspect,
+}
+
+#[derive(Debug, Deserialize)]
+struct JwtClaims {
+    sub: String,
+    business_id: Option<String>,
+    /// "sandbox" or "production"; tokens issued before the claim was added lack it
+    env_type: Option<String>,
+}
+
+/// Verifies caller credentials. API keys are always introspected over gRPC (USERS_GRPC_URL),
+/// as only the users service holds their hashes. Introspection results are cached for
+/// AUTH_CACHE_SECS, so a revoked key keeps working for at most that long.
+#[derive(Clone)]
+pub struct Authenticator {
+    mode: AuthMode,
+    pub environment_mode: EnvironmentMode,
+    jwt_secret: String,
+    users_grpc_url: String,
+    timeout: Duration,
+    cache_ttl: Duration,
+    cache: Arc<Mutex<HashMap<String, (Instant, AuthContext)>>>,
+}
+
+impl Authenticator {
+    pub fn from_env() -> Result<Self, AppError> {
+        let mode = match std::env::var("AUTH_MODE").unwrap_or_else(|_| "local".to_string()).as_str() {
+            "local" => AuthMode::Local,
+            "introspect" => AuthMode::Introspect,
+            other => return Err(AppError::Internal(format!("unknown AUTH_MODE: {}", other))),
+        };
+
+        // Tokens signed with a guessable secret could be forged for any business
+        let jwt_secret = match std::env::var("JWT_SECRET").ok().filter(|v| !v.trim().is_empty()) {
+            Some(secret) => secret,
+            None if mode == AuthMode::Introspect => String::new(),
+            None if std::env::var("ALLOW_DEV_JWT_SECRET").is_ok_and(|v| v == "true") => {
+                tracing::warn!("JWT_SECRET is not set; verifying access tokens with the development secret");
+                DEV_JWT_SECRET.to_string()
+            }
+            None => {
+                return Err(AppError::Internal(
+                    "JWT_SECRET must be set when AUTH_MODE=local (ALLOW_DEV_JWT_SECRET=true uses the development secret)"
+                        .to_string(),
+                ))
+            }
+        };
+
+        let cache_secs = std::env::var("AUTH_CACHE_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(30);
+
+        Ok(Self {
+            mode,
+            environment_mode: EnvironmentMode::from_env()?,
+            jwt_secret,
+            users_grpc_url: std::env::var("USERS_GRPC_URL").unwrap_or_else(|_| "http://127.0.0.1:50051".to_string()),
+            timeout: Duration::from_secs(5),
+            cache_ttl: Duration::from_secs(cache_secs),
+            cache: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    /// `X-API-Key` takes precedence over `Authorization: Bearer <access token>`.
+    pub async fn authenticate(&self, headers: &HeaderMap) -> Result<AuthContext, AppError> {
+        if let Some(api_key) = header(headers, API_KEY_HEADER) {
+            return self.introspect_cached(Credential::ApiKey(api_key)).await;
+        }
+
+        let token = header(headers, "authorization")
+            .and_then(|value| value.strip_prefix("Bearer ").map(|token| token.trim().to_string()))
+            .filter(|token| !token.is_empty())
+            .ok_or_else(|| {
+                AppError::Unauthorized("a Bearer access token or X-API-Key header is required".to_string())
+            })?;
+
+        match self.mode {
+            AuthMode::Local => self.decode_local(&token),
+            AuthMode::Introspect => self.introspect_cached(Credential::AccessToken(token)).await,
+        }
+    }
+
+    fn decode_local(&self, token: &str) -> Result<AuthContext, AppError> {
+        let claims = decode::<JwtClaims>(
+            token,
+            &DecodingKey::from_secret(self.jwt_secret.as_bytes()),
+            &Validation::default(),
+        )
+        .map_err(|e| AppError::Unauthorized(format!("invalid access token: {}", e)))?
+        .claims;
+
+        let user_id = Uuid::parse_str(&claims.sub)
+            .map_err(|_| AppError::Unauthorized("invalid access token: sub is not a user id".to_string()))?;
+        let business_id = claims
+            .business_id
+            .as_deref()
+            .and_then(|id| Uuid::parse_str(id).ok())
+            .ok_or_else(|| AppError::Unauthorized("invalid access token: missing business_id".to_string()))?;
+
+        Ok(AuthContext {
+            business_id,
+            user_id: Some(user_id),
+            api_key_id: None,
+            environment: claims.env_type.as_deref().and_then(Environment::parse),
+        })
+    }
+
+    async fn introspect_cached(&self, credential: Credential) -> Result<AuthContext, AppError> {
+        let cache_key = match &credential {
+            Credential::AccessToken(token) => format!("token:{}", hex::encode(Sha256::digest(token))),
+            Credential::ApiKey(key) => format!("key:{}", hex::encode(Sha256::digest(key))),
+        };
+
+        if let Some((cached_at, auth)) = self.cache.lock().expect("auth cache lock poisoned").get(&cache_key) {
+            if cached_at.elapsed() < self.cache_ttl {
+                return Ok(auth.clone());
+            }
+        }
+
+        // Only valid credentials are cached; rejected ones are introspected every time
+        let auth = self.introspect(credential).await?;
+
+        let mut cache = self.cache.lock().expect("auth cache lock poisoned");
+        if cache.len() >= MAX_CACHED_CREDENTIALS {
+            cache.clear();
+        }
+        cache.insert(cache_key, (Instant::now(), auth.clone()));
+        Ok(auth)
+    }
+
+    async fn introspect(&self, credential: Credential) -> Result<AuthContext, AppError> {
+        let channel = Endpoint::from_shared(self.users_grpc_url.clone())
+            .map_err(|e| AppError::Internal(format!("invalid USERS_GRPC_URL: {}", e)))?
+            .connect_timeout(self.timeout)
+            .timeout(self.timeout)
+            .connect()
+            .await
+            .map_err(|e| AppError::Internal(format!("users gRPC connect failed: {}", e)))?;
+
+        let mut client = AuthServiceClient::new(channel);
+
+        let resp = client
+            .introspect(tonic::Request::new(IntrospectRequest {
+                credential: Some(credential),
+            }))
+            .await
+            .map_err(|e| AppError::Internal(format!("users gRPC introspection failed: {}", e)))?
+            .into_inner();
+
+        if !resp.active {
+            return Err(AppError::Unauthorized("invalid, expired or revoked credentials".to_string()));
+        }
+
+        let business_id = Uuid::parse_str(&resp.business_id)
+            .map_err(|_| AppError::Internal("users service returned an invalid business_id".to_string()))?;
+
+        Ok(AuthContext {
+            business_id,
+            user_id: Uuid::parse_str(&resp.user_id).ok(),
+            api_key_id: Uuid::parse_str(&resp.api_key_id).ok(),
+            environment: Environment::parse(&resp.environment),
+        })
+    }
+}
+
+#[cfg(test)]
+impl Authenticator {
+    /// Verifies access tokens locally with `jwt_secret`, without reading the environment.
+    pub fn local(jwt_secret: &str, environment_mode: EnvironmentMode) -> Self {
+        Self {
+            mode: AuthMode::Local,
+            environment_mode,
+            jwt_secret: jwt_secret.to_string(),
+            users_grpc_url: String::new(),
+            timeout: Duration::from_secs(5),
+            cache_ttl: Duration::from_secs(0),
+            cache: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+fn header(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
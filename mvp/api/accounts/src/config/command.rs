@@ -0,0 +1,58 @@
+use uuid::Uuid;
+
+const USAGE: &str = "usage: accounts-api [verify-chain [organization_id]]";
+
+/// What the binary was started to do: serve the APIs (no arguments) or run a one-off command.
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Serve,
+    /// Recompute the hash chains of one organization or all of them, print the reports and
+    /// exit non-zero if any chain is broken. Runs without migrating the database.
+    VerifyChain { organization_id: Option<Uuid> },
+}
+
+impl Command {
+    pub fn from_args() -> Result<Self, String> {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let args: Vec<String> = args.into_iter().collect();
+        match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+            [] => Ok(Command::Serve),
+            ["verify-chain"] => Ok(Command::VerifyChain { organization_id: None }),
+            ["verify-chain", organization_id] => Uuid::parse_str(organization_id)
+                .map(|id| Command::VerifyChain { organization_id: Some(id) })
+                .map_err(|_| format!("invalid organization_id: {}\n{}", organization_id, USAGE)),
+            _ => Err(USAGE.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        Command::parse(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn parses_serve_and_verify_chain() {
+        let organization_id = Uuid::new_v4();
+
+        assert_eq!(parse(&[]).unwrap(), Command::Serve);
+        assert_eq!(parse(&["verify-chain"]).unwrap(), Command::VerifyChain { organization_id: None });
+        assert_eq!(
+            parse(&["verify-chain", &organization_id.to_string()]).unwrap(),
+            Command::VerifyChain { organization_id: Some(organization_id) }
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_commands_and_arguments() {
+        assert!(parse(&["verify-chain", "not-a-uuid"]).is_err());
+        assert!(parse(&["verify-chain", &Uuid::nil().to_string(), "extra"]).is_err());
+        assert!(parse(&["migrate"]).is_err());
+    }
+}
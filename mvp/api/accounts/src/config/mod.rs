@@ -1,3 +1,5 @@
+pub mod command;
 pub mod settings;
 
+pub use command::Command;
 pub use settings::Settings;
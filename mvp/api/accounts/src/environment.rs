@@ -0,0 +1,155 @@
+use std::fmt;
+
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts, http::HeaderMap};
+
+use crate::auth::AuthContext;
+use crate::errors::AppError;
+use crate::routes::api::AppState;
+
+pub const ENVIRONMENT_HEADER: &str = "x-environment";
+
+/// Sandbox or production data set a request acts on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Environment {
+    Sandbox,
+    Production,
+}
+
+impl Environment {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Environment::Sandbox => "sandbox",
+            Environment::Production => "production",
+        }
+    }
+
+    /// Case-insensitive; `None` for anything but "sandbox" or "production".
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "sandbox" => Some(Environment::Sandbox),
+            "production" => Some(Environment::Production),
+            _ => None,
+        }
+    }
+
+    /// Environment named by the X-Environment header, if the header is present.
+    fn from_header(headers: &HeaderMap) -> Result<Option<Self>, AppError> {
+        let Some(value) = headers.get(ENVIRONMENT_HEADER) else {
+            return Ok(None);
+        };
+
+        value
+            .to_str()
+            .ok()
+            .and_then(Self::parse)
+            .map(Some)
+            .ok_or_else(|| AppError::Validation("X-Environment must be 'sandbox' or 'production'".to_string()))
+    }
+}
+
+impl fmt::Display for Environment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How a request without an environment is handled (ENVIRONMENT_MODE): `lenient` falls back
+/// to sandbox (never production), `strict` rejects it with 400.
+#[derive(Clone, Copy, PartialEq)]
+pub enum EnvironmentMode {
+    Lenient,
+    Strict,
+}
+
+impl EnvironmentMode {
+    pub fn from_env() -> Result<Self, AppError> {
+        match std::env::var("ENVIRONMENT_MODE").unwrap_or_else(|_| "lenient".to_string()).as_str() {
+            "lenient" => Ok(EnvironmentMode::Lenient),
+            "strict" => Ok(EnvironmentMode::Strict),
+            other => Err(AppError::Internal(format!("unknown ENVIRONMENT_MODE: {}", other))),
+        }
+    }
+
+    /// The credential's environment wins; an X-Environment header naming another one is
+    /// rejected. Credentials not bound to an environment (API keys without one, tokens
+    /// verified locally that predate the `env_type` claim) use the header.
+    pub fn resolve(&self, credential: Option<Environment>, headers: &HeaderMap) -> Result<Environment, AppError> {
+        let requested = Environment::from_header(headers)?;
+
+        match (credential, requested) {
+            (Some(credential), Some(requested)) if credential != requested => Err(AppError::Validation(format!(
+                "X-Environment '{}' conflicts with the credential's environment '{}'",
+                requested, credential
+            ))),
+            (Some(environment), _) | (None, Some(environment)) => Ok(environment),
+            (None, None) if *self == EnvironmentMode::Strict => Err(AppError::Validation(
+                "X-Environment header is required ('sandbox' or 'production')".to_string(),
+            )),
+            (None, None) => {
+                tracing::warn!("Missing X-Environment header, defaulting to sandbox");
+                Ok(Environment::Sandbox)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for Environment {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        if let Some(environment) = parts.extensions.get::<Environment>() {
+            return Ok(*environment);
+        }
+
+        let auth = AuthContext::from_request_parts(parts, state).await?;
+        let environment = state.authenticator.environment_mode.resolve(auth.environment, &parts.headers)?;
+        parts.extensions.insert(environment);
+        Ok(environment)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn headers(environment: Option<&'static str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(environment) = environment {
+            headers.insert(ENVIRONMENT_HEADER, HeaderValue::from_static(environment));
+        }
+        headers
+    }
+
+    #[test]
+    fn the_credential_environment_wins_and_conflicting_headers_are_rejected() {
+        for mode in [EnvironmentMode::Lenient, EnvironmentMode::Strict] {
+            let production = Some(Environment::Production);
+            assert_eq!(mode.resolve(production, &headers(None)).unwrap(), Environment::Production);
+            assert_eq!(mode.resolve(production, &headers(Some("Production"))).unwrap(), Environment::Production);
+            assert!(matches!(mode.resolve(production, &headers(Some("sandbox"))), Err(AppError::Validation(_))));
+        }
+    }
+
+    #[test]
+    fn unbound_credentials_use_the_header() {
+        for mode in [EnvironmentMode::Lenient, EnvironmentMode::Strict] {
+            assert_eq!(mode.resolve(None, &headers(Some("production"))).unwrap(), Environment::Production);
+            assert_eq!(mode.resolve(None, &headers(Some(" SANDBOX "))).unwrap(), Environment::Sandbox);
+            assert!(matches!(mode.resolve(None, &headers(Some("staging"))), Err(AppError::Validation(_))));
+        }
+    }
+
+    #[test]
+    fn a_missing_environment_is_sandbox_when_lenient_and_rejected_when_strict() {
+        assert_eq!(EnvironmentMode::Lenient.resolve(None, &headers(None)).unwrap(), Environment::Sandbox);
+
+        let error = EnvironmentMode::Strict.resolve(None, &headers(None)).unwrap_err();
+        assert!(matches!(error, AppError::Validation(_)));
+        assert_eq!(
+            axum::response::IntoResponse::into_response(error).status(),
+            axum::http::StatusCode::BAD_REQUEST
+        );
+    }
+}
@@ -6,6 +6,8 @@ use axum::{
 use serde_json::json;
 use thiserror::Error;
 
+use crate::models::LimitViolation;
+
 #[derive(Error, Debug)]
 pub enum AppError {
     #[error("Database error: {0}")]
@@ -28,6 +30,24 @@ pub enum AppError {
 
     #[error("Internal server error: {0}")]
     Internal(String),
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Idempotency conflict: {0}")]
+    IdempotencyConflict(String),
+
+    #[error("Rate limited: {0}")]
+    RateLimited(String),
+
+    #[error("Transaction limit exceeded: {}", .0.limit_type.as_str())]
+    LimitExceeded(Box<LimitViolation>),
 }
 
 impl IntoResponse for AppError {
@@ -44,6 +64,19 @@ impl IntoResponse for AppError {
             AppError::BusinessLogic(_) => (StatusCode::BAD_REQUEST, self.to_string(), false),
             AppError::AccountNotActive => (StatusCode::BAD_REQUEST, self.to_string(), false),
             AppError::InvalidAccountType => (StatusCode::BAD_REQUEST, self.to_string(), false),
+            AppError::Unauthorized(_) => (StatusCode::UNAUTHORIZED, self.to_string(), false),
+            AppError::Forbidden(_) => (StatusCode::FORBIDDEN, self.to_string(), false),
+            AppError::Conflict(_) => (StatusCode::CONFLICT, self.to_string(), false),
+            AppError::IdempotencyConflict(_) => (StatusCode::UNPROCESSABLE_ENTITY, self.to_string(), false),
+            AppError::RateLimited(_) => (StatusCode::TOO_MANY_REQUESTS, self.to_string(), false),
+            AppError::LimitExceeded(ref violation) => {
+                let body = Json(json!({
+                    "error": self.to_string(),
+                    "status": StatusCode::UNPROCESSABLE_ENTITY.as_u16(),
+                    "limit": violation
+                }));
+                return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+            }
             AppError::Internal(ref e) => {
                 tracing::error!("Internal error: {}", e);
                 // Always report internal errors
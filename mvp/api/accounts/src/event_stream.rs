@@ -0,0 +1,61 @@
+use sqlx::postgres::PgListener;
+use sqlx::PgPool;
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Postgres channel new event log entries are announced on (see the event_stream migration)
+const CHANNEL: &str = "rails_events";
+
+/// Fans `rails_events` notifications out to open event streams. Notices only carry
+/// "<organization_id>:<environment>"; streams read the events themselves from the log.
+#[derive(Clone)]
+pub struct EventNotifier {
+    sender: broadcast::Sender<String>,
+}
+
+impl EventNotifier {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(1024);
+        Self { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<String> {
+        self.sender.subscribe()
+    }
+
+    /// Notice payload for events of this organization and environment.
+    pub fn key(organization_id: Uuid, environment: &str) -> String {
+        format!("{}:{}", organization_id, environment)
+    }
+}
+
+/// Background listener relaying database notifications to the notifier; reconnects after
+/// failures. Streams also re-read the log periodically, so a missed notice only delays events.
+pub async fn run(pool: PgPool, notifier: EventNotifier) {
+    loop {
+        match PgListener::connect_with(&pool).await {
+            Ok(mut listener) => match listener.listen(CHANNEL).await {
+                Ok(()) => {
+                    info!(channel = CHANNEL, "Event stream listener started");
+                    loop {
+                        match listener.recv().await {
+                            Ok(notification) => {
+                                // No receivers just means no stream is open
+                                let _ = notifier.sender.send(notification.payload().to_string());
+                            }
+                            Err(e) => {
+                                warn!(error = %e, "event_stream_listener_failed");
+                                break;
+                            }
+                        }
+                    }
+                }
+                Err(e) => warn!(error = %e, "event_stream_listen_failed"),
+            },
+            Err(e) => warn!(error = %e, "event_stream_listener_connect_failed"),
+        }
+
+        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+    }
+}
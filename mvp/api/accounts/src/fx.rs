@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+
+use crate::errors::AppError;
+use crate::repositories::FxRepository;
+
+/// Source of reference FX rates used to price quotes and cross-currency transfers.
+pub trait FxRateProvider {
+    /// Units of `quote_currency` per one unit of `base_currency` (major units).
+    async fn get_rate(&self, base_currency: &str, quote_currency: &str) -> Result<Decimal, AppError>;
+}
+
+/// Rates loaded once at startup from a JSON file of the form
+/// `{"USD": {"EUR": "0.92", "GBP": "0.79"}}`. Inverse pairs are derived when missing.
+#[derive(Clone)]
+pub struct FileFxRateProvider {
+    rates: Arc<HashMap<(String, String), Decimal>>,
+}
+
+impl FileFxRateProvider {
+    pub fn load(path: &str) -> Result<Self, AppError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| AppError::Internal(format!("failed to read FX rates file {}: {}", path, e)))?;
+        let table: HashMap<String, HashMap<String, Decimal>> = serde_json::from_str(&contents)
+            .map_err(|e| AppError::Internal(format!("invalid FX rates file {}: {}", path, e)))?;
+
+        let mut rates = HashMap::new();
+        for (base, quotes) in table {
+            for (quote, rate) in quotes {
+                if rate <= Decimal::ZERO {
+                    return Err(AppError::Internal(format!(
+                        "invalid FX rate {}/{} in {}: must be positive",
+                        base, quote, path
+                    )));
+                }
+                rates.insert((base.to_uppercase(), quote.to_uppercase()), rate);
+            }
+        }
+
+        Ok(Self { rates: Arc::new(rates) })
+    }
+}
+
+impl FxRateProvider for FileFxRateProvider {
+    async fn get_rate(&self, base_currency: &str, quote_currency: &str) -> Result<Decimal, AppError> {
+        if let Some(rate) = self.rates.get(&(base_currency.to_string(), quote_currency.to_string())) {
+            return Ok(*rate);
+        }
+
+        self.rates
+            .get(&(quote_currency.to_string(), base_currency.to_string()))
+            .map(|inverse| (Decimal::ONE / inverse).round_dp(10))
+            .ok_or_else(|| unavailable(base_currency, quote_currency))
+    }
+}
+
+/// Rates read from the `fx_rates` table on every request.
+#[derive(Clone)]
+pub struct DbFxRateProvider {
+    pool: PgPool,
+}
+
+impl DbFxRateProvider {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl FxRateProvider for DbFxRateProvider {
+    async fn get_rate(&self, base_currency: &str, quote_currency: &str) -> Result<Decimal, AppError> {
+        FxRepository::find_rate(&self.pool, base_currency, quote_currency)
+            .await?
+            .ok_or_else(|| unavailable(base_currency, quote_currency))
+    }
+}
+
+/// Provider selected at startup (FX_RATE_PROVIDER=database|file, FX_RATES_FILE=path).
+#[derive(Clone)]
+pub enum FxRates {
+    Database(DbFxRateProvider),
+    File(FileFxRateProvider),
+}
+
+impl FxRates {
+    pub fn from_env(pool: PgPool) -> Result<Self, AppError> {
+        let provider = std::env::var("FX_RATE_PROVIDER").unwrap_or_else(|_| "database".to_string());
+
+        match provider.as_str() {
+            "database" => Ok(Self::Database(DbFxRateProvider::new(pool))),
+            "file" => {
+                let path = std::env::var("FX_RATES_FILE").map_err(|_| {
+                    AppError::Internal("FX_RATES_FILE must be set when FX_RATE_PROVIDER=file".to_string())
+                })?;
+                Ok(Self::File(FileFxRateProvider::load(&path)?))
+            }
+            other => Err(AppError::Internal(format!("unknown FX_RATE_PROVIDER: {}", other))),
+        }
+    }
+}
+
+impl FxRateProvider for FxRates {
+    async fn get_rate(&self, base_currency: &str, quote_currency: &str) -> Result<Decimal, AppError> {
+        match self {
+            Self::Database(provider) => provider.get_rate(base_currency, quote_currency).await,
+            Self::File(provider) => provider.get_rate(base_currency, quote_currency).await,
+        }
+    }
+}
+
+fn unavailable(base_currency: &str, quote_currency: &str) -> AppError {
+    AppError::BusinessLogic(format!("no FX rate available for {}/{}", base_currency, quote_currency))
+}
@@ -1,5 +1,5 @@
 use crate::errors::AppError;
-use crate::models::{AccountType, CreateAccountRequest};
+use crate::models::{AccountType, CreateAccountRequest, Currency, Metadata};
 use crate::services::AccountService;
 use sqlx::PgPool;
 use std::str::FromStr;
@@ -53,7 +53,11 @@ impl AccountsService for AccountsGrpcService {
             _ => AccountType::Checking,
         };
 
-        let currency = if req.currency.is_empty() { "USD" } else { req.currency.as_str() };
+        let currency = if req.currency.is_empty() {
+            Currency::USD
+        } else {
+            Currency::parse(&req.currency).map_err(map_app_error)?
+        };
 
         let admin_user_id = if req.admin_user_id.is_empty() {
             return Err(Status::invalid_argument("admin_user_id is required for customer accounts"));
@@ -65,10 +69,12 @@ impl AccountsService for AccountsGrpcService {
         let create_req = CreateAccountRequest {
             account_type,
             user_id,
-            currency: currency.to_string(),
+            currency,
             organization_id: Some(org_id),
             environment: Some(environment.to_string()),
             admin_user_id: Some(admin_user_id),
+            nickname: None,
+            metadata: Metadata::new(),
         };
 
         let account = AccountService::create_account(&self.pool, create_req)
@@ -97,6 +103,7 @@ fn map_app_error(err: AppError) -> Status {
         AppError::NotFound(msg) => Status::not_found(msg),
         AppError::Validation(msg) => Status::invalid_argument(msg),
         AppError::BusinessLogic(msg) => Status::failed_precondition(msg),
+        AppError::LimitExceeded(_) => Status::resource_exhausted(err.to_string()),
         other => Status::internal(other.to_string()),
     }
 }
@@ -7,3 +7,7 @@ pub mod proto {
 pub mod ledger_proto {
     tonic::include_proto!("rails.ledger.v1");
 }
+
+pub mod users_proto {
+    tonic::include_proto!("rails.users.v1");
+}
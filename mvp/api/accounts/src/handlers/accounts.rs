@@ -5,25 +5,26 @@ use axum::{
 };
 use serde::Deserialize;
 use uuid::Uuid;
+use crate::auth::AuthContext;
+use crate::environment::Environment;
 use crate::errors::AppError;
-use crate::models::{AccountResponse, CreateAccountRequest, UpdateAccountRequest, PaginatedAccountsResponse};
+use crate::middleware::idempotency::IDEMPOTENT_REPLAYED_HEADER;
+use crate::models::{
+    metadata_filter, AccountResponse, AccountStatusChange, AmountInput, CloseAccountRequest, CreateAccountRequest,
+    PaginatedAccountsResponse, TransactionDetails, UpdateAccountRequest,
+};
 use crate::routes::api::AppState;
-use crate::services::AccountService;
+use crate::services::{AccountService, FeeService};
+use crate::utils::pagination::PageRequest;
 
-/// Extract and validate environment from X-Environment header
-/// Defaults to "sandbox" if missing or invalid (safety: never defaults to production)
-pub(crate) fn extract_environment(headers: &HeaderMap) -> String {
-    let env = headers
-        .get("x-environment")
-        .and_then(|v: &axum::http::HeaderValue| v.to_str().ok())
-        .map(|s: &str| s.trim().to_lowercase())
-        .filter(|s: &String| s == "sandbox" || s == "production")
-        .unwrap_or_else(|| {
-            tracing::warn!("Missing or invalid X-Environment header, defaulting to sandbox");
-            "sandbox".to_string()
-        });
-    
-    env
+/// `Idempotent-Replayed: true` when the Idempotency-Key had already been used and the
+/// original intent is returned.
+pub(crate) fn idempotency_headers(replayed: bool) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if replayed {
+        headers.insert(IDEMPOTENT_REPLAYED_HEADER, axum::http::HeaderValue::from_static("true"));
+    }
+    headers
 }
 
 #[derive(Deserialize)]
@@ -33,19 +34,26 @@ pub struct ListAccountsQuery {
     pub admin_user_id: Option<Uuid>,
     pub page: Option<u32>,
     pub per_page: Option<u32>,
+    /// Opaque cursor from a previous response (takes precedence over `page`)
+    pub cursor: Option<String>,
 }
 
 pub async fn create_account(
     State(state): State<AppState>,
-    headers: HeaderMap,
+    auth: AuthContext,
+    environment: Environment,
     Json(request): Json<CreateAccountRequest>,
 ) -> Result<(StatusCode, Json<AccountResponse>), AppError> {
-    // Extract environment from header (defaults to sandbox if missing)
-    let environment = extract_environment(&headers);
-    
-    // Override request environment with header value (header takes precedence)
+    // The body may repeat the request's environment but not name another one
     let mut request = request;
-    request.environment = Some(environment);
+    if request.environment.as_deref().is_some_and(|env| Environment::parse(env) != Some(environment)) {
+        return Err(AppError::Validation(format!(
+            "environment in the body does not match the request environment '{}'",
+            environment
+        )));
+    }
+    request.environment = Some(environment.to_string());
+    request.organization_id = Some(auth.organization(request.organization_id)?);
     
     // Account number is auto-generated, no validation needed
     let account = AccountService::create_account(&state.pool, request).await?;
@@ -54,79 +62,125 @@ pub async fn create_account(
 
 pub async fn get_account(
     State(state): State<AppState>,
-    headers: HeaderMap,
+    auth: AuthContext,
+    environment: Environment,
     Path(id): Path<Uuid>,
 ) -> Result<Json<AccountResponse>, AppError> {
-    let environment = extract_environment(&headers);
-    let account = AccountService::get_account(&state.pool, id, &environment).await?;
+    let account = AccountService::get_account(&state.pool, id, auth.business_id, environment.as_str()).await?;
     Ok(Json(account.into()))
 }
 
 pub async fn list_accounts(
     State(state): State<AppState>,
-    headers: HeaderMap,
+    auth: AuthContext,
+    environment: Environment,
     Query(query): Query<ListAccountsQuery>,
+    Query(params): Query<Vec<(String, String)>>,
 ) -> Result<Json<PaginatedAccountsResponse>, AppError> {
-    // Extract environment from header (defaults to sandbox if missing)
-    let environment = extract_environment(&headers);
-    
     // Parse and validate pagination params with defaults
-    let page = query.page.unwrap_or(1).max(1);
-    let per_page = query.per_page.unwrap_or(10).min(100).max(1);
+    let page = PageRequest::from_query(query.page, query.per_page, query.cursor.as_deref())?;
+    // metadata[<key>]=<value> parameters narrow any of the listings below
+    let metadata = metadata_filter(&params)?;
 
-    // Support three filtering options:
+    // Always limited to the caller's organization. Support three filtering options:
     // 1. user_id: Get accounts owned by a specific user
-    // 2. organization_id: Get all accounts in an organization (for admins)
-    // 3. admin_user_id: Get accounts managed by an admin (customer accounts)
+    // 2. admin_user_id: Get accounts managed by an admin (customer accounts)
+    // 3. neither: Get all accounts in the organization (for admins)
+    let organization_id = auth.organization(query.organization_id)?;
     let result = if let Some(user_id) = query.user_id {
-        AccountService::get_accounts_by_user_paginated(&state.pool, user_id, &environment, page, per_page).await?
-    } else if let Some(organization_id) = query.organization_id {
-        AccountService::get_accounts_by_organization_paginated(&state.pool, organization_id, &environment, page, per_page).await?
+        AccountService::get_accounts_by_user_paginated(
+            &state.pool,
+            organization_id,
+            user_id,
+            environment.as_str(),
+            &metadata,
+            &page,
+        )
+        .await?
     } else if let Some(admin_user_id) = query.admin_user_id {
-        AccountService::get_accounts_by_admin_paginated(&state.pool, admin_user_id, &environment, page, per_page).await?
+        AccountService::get_accounts_by_admin_paginated(
+            &state.pool,
+            organization_id,
+            admin_user_id,
+            environment.as_str(),
+            &metadata,
+            &page,
+        )
+        .await?
     } else {
-        return Err(AppError::Validation(
-            "One of user_id, organization_id, or admin_user_id query parameter is required".to_string()
-        ));
+        AccountService::get_accounts_by_organization_paginated(
+            &state.pool,
+            organization_id,
+            environment.as_str(),
+            &metadata,
+            &page,
+        )
+        .await?
     };
 
     Ok(Json(result))
 }
 
-pub async fn update_account_status(
+pub async fn update_account(
     State(state): State<AppState>,
-    headers: HeaderMap,
+    auth: AuthContext,
+    environment: Environment,
     Path(id): Path<Uuid>,
     Json(request): Json<UpdateAccountRequest>,
 ) -> Result<Json<AccountResponse>, AppError> {
-    let environment = extract_environment(&headers);
-    
-    let status = request.status.ok_or_else(|| {
-        AppError::Validation("status field is required".to_string())
-    })?;
-
-    let account = AccountService::update_account_status(&state.pool, id, &environment, status).await?;
+    let account = AccountService::update_account(
+        &state.pool,
+        &state.ledger_grpc,
+        id,
+        auth.business_id,
+        environment.as_str(),
+        request,
+        auth.actor(),
+    )
+    .await?;
     Ok(Json(account.into()))
 }
 
 pub async fn close_account(
     State(state): State<AppState>,
-    headers: HeaderMap,
+    auth: AuthContext,
+    environment: Environment,
     Path(id): Path<Uuid>,
+    Json(request): Json<CloseAccountRequest>,
 ) -> Result<Json<AccountResponse>, AppError> {
-    let environment = extract_environment(&headers);
-    let account = AccountService::close_account(&state.pool, id, &environment).await?;
+    let account = AccountService::close_account(
+        &state.pool,
+        &state.ledger_grpc,
+        id,
+        auth.business_id,
+        environment.as_str(),
+        request.reason,
+        request.note,
+        auth.actor(),
+    )
+    .await?;
     Ok(Json(account.into()))
 }
 
+/// Status changes of the account, newest first, with their reasons.
+pub async fn list_account_status_history(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<AccountStatusChange>>, AppError> {
+    let history = AccountService::get_status_history(&state.pool, id, auth.business_id, environment.as_str()).await?;
+    Ok(Json(history))
+}
+
 pub async fn deposit(
     State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
     Path(id): Path<Uuid>,
     headers: HeaderMap,
     Json(request): Json<crate::handlers::accounts::DepositRequest>,
-) -> Result<(StatusCode, Json<serde_json::Value>), AppError> {
-    let environment = extract_environment(&headers);
-    
+) -> Result<(StatusCode, HeaderMap, Json<serde_json::Value>), AppError> {
     let idempotency_key = headers
         .get("Idempotency-Key")
         .and_then(|v: &axum::http::HeaderValue| v.to_str().ok())
@@ -134,10 +188,6 @@ pub async fn deposit(
         .filter(|s: &String| !s.is_empty())
         .ok_or_else(|| AppError::Validation("Idempotency-Key header is required".to_string()))?;
 
-    if request.amount <= 0 {
-        return Err(AppError::Validation("Amount must be greater than zero".to_string()));
-    }
-
     let correlation_id = headers
         .get("x-correlation-id")
         .and_then(|v: &axum::http::HeaderValue| v.to_str().ok())
@@ -147,31 +197,37 @@ pub async fn deposit(
     let (account, transaction) = AccountService::deposit_with_idempotency(
         &state.pool,
         id,
-        &environment,
-        request.amount,
+        auth.business_id,
+        environment.as_str(),
+        &request.amount,
         &idempotency_key,
+        request.details,
         &state.ledger_grpc,
         correlation_id,
     )
     .await?;
 
+    let replayed = transaction.replayed;
+    let transaction = FeeService::with_fees(&state.pool, transaction).await?;
+
     Ok((
         StatusCode::OK,
+        idempotency_headers(replayed),
         Json(serde_json::json!({
             "account": AccountResponse::from(account),
-            "transaction": crate::models::TransactionResponse::from(transaction)
+            "transaction": transaction
         })),
     ))
 }
 
 pub async fn withdraw(
     State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
     Path(id): Path<Uuid>,
     headers: HeaderMap,
     Json(request): Json<crate::handlers::accounts::WithdrawRequest>,
-) -> Result<(StatusCode, Json<serde_json::Value>), AppError> {
-    let environment = extract_environment(&headers);
-    
+) -> Result<(StatusCode, HeaderMap, Json<serde_json::Value>), AppError> {
     let idempotency_key = headers
         .get("Idempotency-Key")
         .and_then(|v: &axum::http::HeaderValue| v.to_str().ok())
@@ -179,10 +235,6 @@ pub async fn withdraw(
         .filter(|s: &String| !s.is_empty())
         .ok_or_else(|| AppError::Validation("Idempotency-Key header is required".to_string()))?;
 
-    if request.amount <= 0 {
-        return Err(AppError::Validation("Amount must be greater than zero".to_string()));
-    }
-
     let correlation_id = headers
         .get("x-correlation-id")
         .and_then(|v: &axum::http::HeaderValue| v.to_str().ok())
@@ -192,31 +244,37 @@ pub async fn withdraw(
     let (account, transaction) = AccountService::withdraw_with_idempotency(
         &state.pool,
         id,
-        &environment,
-        request.amount,
+        auth.business_id,
+        environment.as_str(),
+        &request.amount,
         &idempotency_key,
+        request.details,
         &state.ledger_grpc,
         correlation_id,
     )
     .await?;
 
+    let replayed = transaction.replayed;
+    let transaction = FeeService::with_fees(&state.pool, transaction).await?;
+
     Ok((
         StatusCode::OK,
+        idempotency_headers(replayed),
         Json(serde_json::json!({
             "account": AccountResponse::from(account),
-            "transaction": crate::models::TransactionResponse::from(transaction)
+            "transaction": transaction
         })),
     ))
 }
 
 pub async fn transfer(
     State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
     Path(from_id): Path<Uuid>,
     headers: HeaderMap,
     Json(request): Json<crate::handlers::accounts::TransferRequest>,
-) -> Result<(StatusCode, Json<serde_json::Value>), AppError> {
-    let environment = extract_environment(&headers);
-    
+) -> Result<(StatusCode, HeaderMap, Json<serde_json::Value>), AppError> {
     let idempotency_key = headers
         .get("Idempotency-Key")
         .and_then(|v: &axum::http::HeaderValue| v.to_str().ok())
@@ -224,10 +282,6 @@ pub async fn transfer(
         .filter(|s: &String| !s.is_empty())
         .ok_or_else(|| AppError::Validation("Idempotency-Key header is required".to_string()))?;
 
-    if request.amount <= 0 {
-        return Err(AppError::Validation("Amount must be greater than zero".to_string()));
-    }
-
     let correlation_id = headers
         .get("x-correlation-id")
         .and_then(|v: &axum::http::HeaderValue| v.to_str().ok())
@@ -237,43 +291,60 @@ pub async fn transfer(
     let (from_account, to_account, transaction) = AccountService::transfer_with_idempotency(
         &state.pool,
         from_id,
-        &environment,
+        auth.business_id,
+        environment.as_str(),
         request.to_account_id,
-        request.amount,
+        &request.amount,
         &idempotency_key,
+        request.details,
         &state.ledger_grpc,
+        &state.fx_rates,
+        request.fx_quote_id,
         correlation_id,
     )
     .await?;
 
+    let replayed = transaction.replayed;
+    let transaction = FeeService::with_fees(&state.pool, transaction).await?;
+
     Ok((
         StatusCode::OK,
+        idempotency_headers(replayed),
         Json(serde_json::json!({
             "from_account": AccountResponse::from(from_account),
             "to_account": AccountResponse::from(to_account),
-            "transaction": crate::models::TransactionResponse::from(transaction)
+            "transaction": transaction
         })),
     ))
 }
 
 #[derive(Deserialize)]
 pub struct DepositRequest {
-    pub amount: i64,
-    #[allow(dead_code)]
-    pub description: Option<String>, // Reserved for future use
+    /// Minor units (1234) or a decimal string in the account currency ("12.34")
+    pub amount: AmountInput,
+    /// Description, reference and metadata stored with the intent
+    #[serde(flatten)]
+    pub details: TransactionDetails,
 }
 
 #[derive(Deserialize)]
 pub struct WithdrawRequest {
-    pub amount: i64,
-    #[allow(dead_code)]
-    pub description: Option<String>, // Reserved for future use
+    /// Minor units (1234) or a decimal string in the account currency ("12.34")
+    pub amount: AmountInput,
+    /// Description, reference and metadata stored with the intent
+    #[serde(flatten)]
+    pub details: TransactionDetails,
 }
 
 #[derive(Deserialize)]
 pub struct TransferRequest {
     pub to_account_id: Uuid,
-    pub amount: i64,
-    #[allow(dead_code)]
-    pub description: Option<String>, // Reserved for future use
+    /// Minor units (1234) or a decimal string in the source account currency ("12.34")
+    pub amount: AmountInput,
+    /// Locked FX quote to use when the destination account holds a different currency
+    #[serde(default)]
+    pub fx_quote_id: Option<Uuid>,
+    /// Description, reference and metadata stored with the intent
+    #[serde(flatten)]
+    pub details: TransactionDetails,
 }
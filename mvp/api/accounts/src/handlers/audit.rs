@@ -0,0 +1,134 @@
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::auth::AuthContext;
+use crate::environment::Environment;
+use crate::errors::AppError;
+use crate::models::{AuditEventFilter, AuditExportFormat, PaginatedAuditEventsResponse};
+use crate::routes::api::AppState;
+use crate::services::AuditService;
+use crate::utils::pagination::PageRequest;
+
+#[derive(Deserialize)]
+pub struct ListAuditEventsQuery {
+    pub organization_id: Option<Uuid>,
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+    /// Opaque cursor from a previous response (takes precedence over `page`)
+    pub cursor: Option<String>,
+    /// `user`, `api_key` or `internal_service`
+    pub actor_type: Option<String>,
+    pub actor_id: Option<Uuid>,
+    /// e.g. `account.close`
+    pub action: Option<String>,
+    /// e.g. `account`
+    pub target_type: Option<String>,
+    pub target_id: Option<Uuid>,
+    pub correlation_id: Option<String>,
+    /// Created at or after (RFC 3339)
+    pub from: Option<DateTime<Utc>>,
+    /// Created before (RFC 3339)
+    pub to: Option<DateTime<Utc>>,
+    /// Export only: `csv` (default) or `jsonl`
+    pub format: Option<String>,
+}
+
+impl ListAuditEventsQuery {
+    fn filter(&self) -> Result<AuditEventFilter, AppError> {
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            if from >= to {
+                return Err(AppError::Validation("from must be before to".to_string()));
+            }
+        }
+
+        let actor_type = match self.actor_type.as_deref().map(|s| s.trim().to_lowercase()) {
+            None => None,
+            Some(actor_type) if ["user", "api_key", "internal_service"].contains(&actor_type.as_str()) => {
+                Some(actor_type)
+            }
+            Some(other) => {
+                return Err(AppError::Validation(format!(
+                    "invalid actor_type '{}' (expected user, api_key or internal_service)",
+                    other
+                )))
+            }
+        };
+
+        let text = |value: &Option<String>| value.as_deref().map(str::trim).filter(|s| !s.is_empty()).map(str::to_string);
+
+        Ok(AuditEventFilter {
+            actor_type,
+            actor_id: self.actor_id,
+            action: text(&self.action),
+            target_type: text(&self.target_type),
+            target_id: self.target_id,
+            correlation_id: text(&self.correlation_id),
+            from: self.from,
+            to: self.to,
+        })
+    }
+
+    fn format(&self) -> Result<AuditExportFormat, AppError> {
+        match self.format.as_deref().map(|s| s.trim().to_lowercase()).as_deref() {
+            None | Some("csv") => Ok(AuditExportFormat::Csv),
+            Some("jsonl") => Ok(AuditExportFormat::Jsonl),
+            Some(other) => Err(AppError::Validation(format!(
+                "invalid format '{}' (expected csv or jsonl)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Audit trail of the organization, newest first.
+pub async fn list_audit_events(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Query(query): Query<ListAuditEventsQuery>,
+) -> Result<Json<PaginatedAuditEventsResponse>, AppError> {
+    let organization_id = auth.organization(query.organization_id)?;
+    let page = PageRequest::from_query(query.page, query.per_page, query.cursor.as_deref())?;
+    let filter = query.filter()?;
+
+    let (data, pagination) =
+        AuditService::get_events_paginated(&state.pool, organization_id, environment.as_str(), &filter, &page).await?;
+    Ok(Json(PaginatedAuditEventsResponse { data, pagination }))
+}
+
+/// Matching audit events as a file, oldest first, for compliance archives.
+pub async fn export_audit_events(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Query(query): Query<ListAuditEventsQuery>,
+) -> Result<Response, AppError> {
+    let organization_id = auth.organization(query.organization_id)?;
+    let filter = query.filter()?;
+    let format = query.format()?;
+
+    let contents = AuditService::export(&state.pool, organization_id, environment.as_str(), &filter, format).await?;
+    let file_name = format!(
+        "audit-events-{}-{}-{}.{}",
+        organization_id,
+        environment,
+        Utc::now().format("%Y%m%dT%H%M%SZ"),
+        format.extension()
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", file_name)),
+        ],
+        contents,
+    )
+        .into_response())
+}
@@ -0,0 +1,54 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use uuid::Uuid;
+
+use crate::auth::AuthContext;
+use crate::environment::Environment;
+use crate::errors::AppError;
+use crate::models::{AccountClosureReceipt, AccountClosureRequest, ClosureStatus};
+use crate::routes::api::AppState;
+use crate::services::AccountClosureService;
+
+/// Closes the account, sweeping its remaining funds to `sweep_to_account_id` or `payee_id`.
+/// 200 with the receipt once the account is closed; 202 while the closure waits (for the
+/// Ledger, payouts in flight or the sweep to post) and resumes in the background. Repeating
+/// the call resumes the same closure.
+pub async fn close_account_with_sweep(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Path(id): Path<Uuid>,
+    Json(request): Json<AccountClosureRequest>,
+) -> Result<(StatusCode, Json<AccountClosureReceipt>), AppError> {
+    let receipt = AccountClosureService::close(
+        &state.pool,
+        &state.ledger_grpc,
+        &state.payout_rail,
+        id,
+        auth.business_id,
+        environment.as_str(),
+        request,
+        auth.actor(),
+    )
+    .await?;
+
+    let status = match receipt.status {
+        ClosureStatus::Completed => StatusCode::OK,
+        ClosureStatus::InProgress => StatusCode::ACCEPTED,
+    };
+    Ok((status, Json(receipt)))
+}
+
+/// Receipt of the account's closure.
+pub async fn get_account_closure(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Path(id): Path<Uuid>,
+) -> Result<Json<AccountClosureReceipt>, AppError> {
+    let receipt = AccountClosureService::get_closure(&state.pool, id, auth.business_id, environment.as_str()).await?;
+    Ok(Json(receipt))
+}
@@ -0,0 +1,77 @@
+use axum::{
+    extract::{Query, State},
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures_util::{Stream, StreamExt};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::auth::AuthContext;
+use crate::environment::Environment;
+use crate::errors::AppError;
+use crate::models::EventFilter;
+use crate::routes::api::AppState;
+use crate::services::EventStreamService;
+
+#[derive(Deserialize)]
+pub struct EventStreamQuery {
+    pub organization_id: Option<Uuid>,
+    pub account_id: Option<Uuid>,
+    /// Comma-separated event types (default: all)
+    pub event_types: Option<String>,
+}
+
+/// Server-Sent Events stream of the organization's transaction and account events. Each
+/// event's id is its log position; reconnecting with `Last-Event-ID` replays what was missed.
+pub async fn stream_events(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    headers: HeaderMap,
+    Query(query): Query<EventStreamQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, AppError> {
+    let organization_id = auth.organization(query.organization_id)?;
+
+    let last_event_id = headers
+        .get("last-event-id")
+        .map(|v| {
+            v.to_str()
+                .ok()
+                .and_then(|s| s.trim().parse::<i64>().ok())
+                .filter(|id| *id >= 0)
+                .ok_or_else(|| AppError::Validation("Last-Event-ID must be an event id from this stream".to_string()))
+        })
+        .transpose()?;
+
+    let filter = EventFilter {
+        account_id: query.account_id,
+        event_types: query
+            .event_types
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(|t| t.trim().to_lowercase())
+            .filter(|t| !t.is_empty())
+            .collect(),
+    };
+
+    let events = EventStreamService::subscribe(
+        &state.pool,
+        &state.event_notifier,
+        organization_id,
+        environment.as_str(),
+        filter,
+        last_event_id,
+    )
+    .await?;
+
+    let stream = events.map(|event| {
+        Event::default()
+            .id(event.sequence.to_string())
+            .event(event.event_type.clone())
+            .json_data(&event)
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
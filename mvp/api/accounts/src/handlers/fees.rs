@@ -0,0 +1,52 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::auth::AuthContext;
+use crate::environment::Environment;
+use crate::errors::AppError;
+use crate::models::{CreateFeeScheduleRequest, FeeSchedule};
+use crate::routes::api::AppState;
+use crate::services::FeeService;
+
+#[derive(Deserialize)]
+pub struct ListFeeSchedulesQuery {
+    pub organization_id: Option<Uuid>,
+}
+
+pub async fn create_fee_schedule(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Json(request): Json<CreateFeeScheduleRequest>,
+) -> Result<(StatusCode, Json<FeeSchedule>), AppError> {
+    auth.organization(Some(request.organization_id))?;
+    let schedule = FeeService::create_schedule(&state.pool, environment.as_str(), request).await?;
+    Ok((StatusCode::CREATED, Json(schedule)))
+}
+
+pub async fn list_fee_schedules(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Query(query): Query<ListFeeSchedulesQuery>,
+) -> Result<Json<Vec<FeeSchedule>>, AppError> {
+    let organization_id = auth.organization(query.organization_id)?;
+
+    let schedules = FeeService::get_schedules(&state.pool, organization_id, environment.as_str()).await?;
+    Ok(Json(schedules))
+}
+
+pub async fn deactivate_fee_schedule(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Path(id): Path<Uuid>,
+) -> Result<Json<FeeSchedule>, AppError> {
+    let schedule = FeeService::deactivate_schedule(&state.pool, id, auth.business_id, environment.as_str()).await?;
+    Ok(Json(schedule))
+}
@@ -0,0 +1,44 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use uuid::Uuid;
+
+use crate::auth::AuthContext;
+use crate::environment::Environment;
+use crate::errors::AppError;
+use crate::models::{CreateFxQuoteRequest, FxQuote};
+use crate::routes::api::AppState;
+use crate::services::FxService;
+
+pub async fn create_fx_quote(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Json(request): Json<CreateFxQuoteRequest>,
+) -> Result<(StatusCode, Json<FxQuote>), AppError> {
+    auth.organization(Some(request.organization_id))?;
+    let quote = FxService::create_quote(&state.pool, &state.fx_rates, environment.as_str(), request).await?;
+    Ok((StatusCode::CREATED, Json(quote)))
+}
+
+pub async fn get_fx_quote(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Path(id): Path<Uuid>,
+) -> Result<Json<FxQuote>, AppError> {
+    let quote = FxService::get_quote(&state.pool, id, auth.business_id, environment.as_str()).await?;
+    Ok(Json(quote))
+}
+
+pub async fn lock_fx_quote(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Path(id): Path<Uuid>,
+) -> Result<Json<FxQuote>, AppError> {
+    let quote = FxService::lock_quote(&state.pool, id, auth.business_id, environment.as_str()).await?;
+    Ok(Json(quote))
+}
@@ -0,0 +1,61 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::{Duration, NaiveDate, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::auth::AuthContext;
+use crate::errors::AppError;
+use crate::models::{ChainCheckpoint, ChainVerificationReport};
+use crate::routes::api::AppState;
+use crate::services::HashChainService;
+
+/// Longest checkpoint range served in one call
+const MAX_CHECKPOINT_RANGE_DAYS: i64 = 366;
+
+#[derive(Deserialize)]
+pub struct HashChainQuery {
+    pub organization_id: Option<Uuid>,
+    /// Checkpoints only: first day (YYYY-MM-DD, default 30 days before `to`)
+    pub from: Option<NaiveDate>,
+    /// Checkpoints only: last day (YYYY-MM-DD, default today)
+    pub to: Option<NaiveDate>,
+}
+
+/// Recomputes the organization's transaction and audit event hash chains and reports the
+/// first break of each. Chains span both environments.
+pub async fn verify_hash_chain(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    Query(query): Query<HashChainQuery>,
+) -> Result<Json<ChainVerificationReport>, AppError> {
+    let organization_id = auth.organization(query.organization_id)?;
+    let report = HashChainService::verify(&state.pool, organization_id).await?;
+    Ok(Json(report))
+}
+
+/// Signed daily checkpoints of the organization's chains, for external attestation.
+pub async fn list_hash_chain_checkpoints(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    Query(query): Query<HashChainQuery>,
+) -> Result<Json<Vec<ChainCheckpoint>>, AppError> {
+    let organization_id = auth.organization(query.organization_id)?;
+    let to = query.to.unwrap_or_else(|| Utc::now().date_naive());
+    let from = query.from.unwrap_or(to - Duration::days(30));
+
+    if from > to {
+        return Err(AppError::Validation("from must not be after to".to_string()));
+    }
+    if (to - from).num_days() >= MAX_CHECKPOINT_RANGE_DAYS {
+        return Err(AppError::Validation(format!(
+            "date range must not exceed {} days",
+            MAX_CHECKPOINT_RANGE_DAYS
+        )));
+    }
+
+    let checkpoints = HashChainService::get_checkpoints(&state.pool, organization_id, from, to).await?;
+    Ok(Json(checkpoints))
+}
@@ -0,0 +1,76 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::auth::AuthContext;
+use crate::environment::Environment;
+use crate::errors::AppError;
+use crate::models::{InterestRateConfig, PaginatedInterestAccrualsResponse, UpsertInterestRateConfigRequest};
+use crate::routes::api::AppState;
+use crate::services::InterestService;
+
+#[derive(Deserialize)]
+pub struct ListInterestRateConfigsQuery {
+    pub organization_id: Option<Uuid>,
+}
+
+#[derive(Deserialize)]
+pub struct ListInterestAccrualsQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+}
+
+pub async fn upsert_interest_rate_config(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Json(request): Json<UpsertInterestRateConfigRequest>,
+) -> Result<Json<InterestRateConfig>, AppError> {
+    auth.organization(Some(request.organization_id))?;
+    let config = InterestService::set_rate_config(&state.pool, environment.as_str(), request).await?;
+    Ok(Json(config))
+}
+
+pub async fn list_interest_rate_configs(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Query(query): Query<ListInterestRateConfigsQuery>,
+) -> Result<Json<Vec<InterestRateConfig>>, AppError> {
+    let organization_id = auth.organization(query.organization_id)?;
+
+    let configs = InterestService::get_rate_configs(&state.pool, organization_id, environment.as_str()).await?;
+    Ok(Json(configs))
+}
+
+pub async fn list_account_interest_accruals(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Path(account_id): Path<Uuid>,
+    Query(query): Query<ListInterestAccrualsQuery>,
+) -> Result<Json<PaginatedInterestAccrualsResponse>, AppError> {
+    // Parse and validate pagination params with defaults
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(31).clamp(1, 366);
+
+    let result = InterestService::get_account_accruals(
+        &state.pool,
+        account_id,
+        auth.business_id,
+        environment.as_str(),
+        query.from,
+        query.to,
+        page,
+        per_page,
+    )
+    .await?;
+
+    Ok(Json(result))
+}
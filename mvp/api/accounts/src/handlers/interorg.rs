@@ -0,0 +1,62 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::auth::AuthContext;
+use crate::environment::Environment;
+use crate::errors::AppError;
+use crate::models::{CreateInterorgAgreementRequest, InterorgAgreement};
+use crate::routes::api::AppState;
+use crate::services::InterorgService;
+
+#[derive(Deserialize)]
+pub struct ListInterorgAgreementsQuery {
+    pub organization_id: Option<Uuid>,
+}
+
+pub async fn create_interorg_agreement(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Json(request): Json<CreateInterorgAgreementRequest>,
+) -> Result<(StatusCode, Json<InterorgAgreement>), AppError> {
+    auth.organization(Some(request.organization_id))?;
+    let agreement = InterorgService::create_agreement(&state.pool, environment.as_str(), request).await?;
+    Ok((StatusCode::CREATED, Json(agreement)))
+}
+
+pub async fn list_interorg_agreements(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Query(query): Query<ListInterorgAgreementsQuery>,
+) -> Result<Json<Vec<InterorgAgreement>>, AppError> {
+    let organization_id = auth.organization(query.organization_id)?;
+
+    let agreements = InterorgService::get_agreements(&state.pool, organization_id, environment.as_str()).await?;
+    Ok(Json(agreements))
+}
+
+pub async fn accept_interorg_agreement(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Path(id): Path<Uuid>,
+) -> Result<Json<InterorgAgreement>, AppError> {
+    let agreement = InterorgService::accept_agreement(&state.pool, id, auth.business_id, environment.as_str()).await?;
+    Ok(Json(agreement))
+}
+
+pub async fn revoke_interorg_agreement(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Path(id): Path<Uuid>,
+) -> Result<Json<InterorgAgreement>, AppError> {
+    let agreement = InterorgService::revoke_agreement(&state.pool, id, auth.business_id, environment.as_str()).await?;
+    Ok(Json(agreement))
+}
@@ -0,0 +1,77 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::auth::AuthContext;
+use crate::environment::Environment;
+use crate::errors::AppError;
+use crate::routes::api::AppState;
+use crate::services::Iso20022Service;
+
+#[derive(Deserialize)]
+pub struct DateRangeQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+impl DateRangeQuery {
+    fn range(&self) -> Result<(NaiveDate, NaiveDate), AppError> {
+        match (self.from, self.to) {
+            (Some(from), Some(to)) => Ok((from, to)),
+            _ => Err(AppError::Validation(
+                "from and to query parameters (YYYY-MM-DD) are required".to_string(),
+            )),
+        }
+    }
+}
+
+pub async fn download_camt053(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Path(account_id): Path<Uuid>,
+    Query(query): Query<DateRangeQuery>,
+) -> Result<Response, AppError> {
+    let (from, to) = query.range()?;
+
+    let xml = Iso20022Service::camt053(
+        &state.pool,
+        &state.ledger_grpc,
+        account_id,
+        auth.business_id,
+        environment.as_str(),
+        from,
+        to,
+    )
+    .await?;
+    Ok(xml_attachment(format!("camt053-{}-{}-{}.xml", account_id, from, to), xml))
+}
+
+pub async fn download_pain001(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Path(account_id): Path<Uuid>,
+    Query(query): Query<DateRangeQuery>,
+) -> Result<Response, AppError> {
+    let (from, to) = query.range()?;
+
+    let xml = Iso20022Service::pain001(&state.pool, account_id, auth.business_id, environment.as_str(), from, to).await?;
+    Ok(xml_attachment(format!("pain001-{}-{}-{}.xml", account_id, from, to), xml))
+}
+
+fn xml_attachment(file_name: String, xml: String) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, "application/xml; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", file_name)),
+        ],
+        xml,
+    )
+        .into_response()
+}
@@ -0,0 +1,52 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::auth::AuthContext;
+use crate::environment::Environment;
+use crate::errors::AppError;
+use crate::models::{TransactionLimit, UpsertTransactionLimitRequest};
+use crate::routes::api::AppState;
+use crate::services::LimitService;
+
+#[derive(Deserialize)]
+pub struct ListTransactionLimitsQuery {
+    pub organization_id: Option<Uuid>,
+}
+
+pub async fn upsert_transaction_limit(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Json(request): Json<UpsertTransactionLimitRequest>,
+) -> Result<Json<TransactionLimit>, AppError> {
+    auth.organization(Some(request.organization_id))?;
+    let limit = LimitService::set_limit(&state.pool, environment.as_str(), request).await?;
+    Ok(Json(limit))
+}
+
+pub async fn list_transaction_limits(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Query(query): Query<ListTransactionLimitsQuery>,
+) -> Result<Json<Vec<TransactionLimit>>, AppError> {
+    let organization_id = auth.organization(query.organization_id)?;
+
+    let limits = LimitService::get_limits(&state.pool, organization_id, environment.as_str()).await?;
+    Ok(Json(limits))
+}
+
+pub async fn delete_transaction_limit(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    LimitService::delete_limit(&state.pool, id, auth.business_id, environment.as_str()).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
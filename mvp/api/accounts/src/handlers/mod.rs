@@ -1,3 +1,17 @@
 pub mod accounts;
+pub mod closures;
 pub mod transactions;
 pub mod health;
+pub mod fees;
+pub mod interest;
+pub mod fx;
+pub mod limits;
+pub mod interorg;
+pub mod payouts;
+pub mod nacha;
+pub mod iso20022;
+pub mod statements;
+pub mod webhooks;
+pub mod events;
+pub mod audit;
+pub mod hash_chain;
@@ -0,0 +1,75 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use uuid::Uuid;
+
+use crate::auth::InternalCaller;
+use crate::environment::Environment;
+use crate::errors::AppError;
+use crate::models::{NachaFile, NachaReturnsResult};
+use crate::routes::api::AppState;
+use crate::services::NachaService;
+
+/// Operator requests carry no tenant credential, so the environment is the X-Environment header.
+fn operator_environment(state: &AppState, headers: &HeaderMap) -> Result<Environment, AppError> {
+    state.authenticator.environment_mode.resolve(None, headers)
+}
+
+/// Close the current settlement window now. 204 when no payouts are ready.
+pub async fn generate_nacha_file(
+    State(state): State<AppState>,
+    _caller: InternalCaller,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let environment = operator_environment(&state, &headers)?;
+    let rail = state.payout_rail.nacha()?;
+
+    match NachaService::generate_file(&state.pool, rail, environment.as_str()).await? {
+        Some(file) => Ok((StatusCode::CREATED, Json(file)).into_response()),
+        None => Ok(StatusCode::NO_CONTENT.into_response()),
+    }
+}
+
+pub async fn list_nacha_files(
+    State(state): State<AppState>,
+    _caller: InternalCaller,
+    headers: HeaderMap,
+) -> Result<Json<Vec<NachaFile>>, AppError> {
+    let environment = operator_environment(&state, &headers)?;
+    let files = NachaService::get_files(&state.pool, environment.as_str()).await?;
+    Ok(Json(files))
+}
+
+pub async fn download_nacha_file(
+    State(state): State<AppState>,
+    _caller: InternalCaller,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    let environment = operator_environment(&state, &headers)?;
+    let (file, contents) = NachaService::get_file_contents(&state.pool, id, environment.as_str()).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/plain; charset=us-ascii".to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", file.file_name)),
+        ],
+        contents,
+    )
+        .into_response())
+}
+
+/// Upload a NACHA return file (raw text body).
+pub async fn process_nacha_returns(
+    State(state): State<AppState>,
+    _caller: InternalCaller,
+    headers: HeaderMap,
+    body: String,
+) -> Result<Json<NachaReturnsResult>, AppError> {
+    let environment = operator_environment(&state, &headers)?;
+    let result = NachaService::process_return_file(&state.pool, &state.ledger_grpc, environment.as_str(), &body).await?;
+    Ok(Json(result))
+}
@@ -0,0 +1,107 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::auth::AuthContext;
+use crate::environment::Environment;
+use crate::errors::AppError;
+use crate::handlers::accounts::idempotency_headers;
+use crate::models::{CreatePayeeRequest, CreatePayoutRequest, Payout, PayeeResponse, PayoutResponse};
+use crate::routes::api::AppState;
+use crate::services::{FeeService, PayoutService};
+
+#[derive(Deserialize)]
+pub struct ListPayeesQuery {
+    pub organization_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+}
+
+pub async fn create_payee(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Json(request): Json<CreatePayeeRequest>,
+) -> Result<(StatusCode, Json<PayeeResponse>), AppError> {
+    auth.organization(Some(request.organization_id))?;
+    let payee = PayoutService::create_payee(&state.pool, environment.as_str(), request).await?;
+    Ok((StatusCode::CREATED, Json(PayeeResponse::from(payee))))
+}
+
+pub async fn list_payees(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Query(query): Query<ListPayeesQuery>,
+) -> Result<Json<Vec<PayeeResponse>>, AppError> {
+    let organization_id = auth.organization(query.organization_id)?;
+
+    let payees = PayoutService::get_payees(&state.pool, organization_id, query.user_id, environment.as_str()).await?;
+    Ok(Json(payees.into_iter().map(PayeeResponse::from).collect()))
+}
+
+pub async fn deactivate_payee(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Path(id): Path<Uuid>,
+) -> Result<Json<PayeeResponse>, AppError> {
+    let payee = PayoutService::deactivate_payee(&state.pool, id, auth.business_id, environment.as_str()).await?;
+    Ok(Json(PayeeResponse::from(payee)))
+}
+
+pub async fn create_payout(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Path(account_id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(request): Json<CreatePayoutRequest>,
+) -> Result<(StatusCode, HeaderMap, Json<PayoutResponse>), AppError> {
+    let idempotency_key = headers
+        .get("Idempotency-Key")
+        .and_then(|v: &axum::http::HeaderValue| v.to_str().ok())
+        .map(|s: &str| s.trim().to_string())
+        .filter(|s: &String| !s.is_empty())
+        .ok_or_else(|| AppError::Validation("Idempotency-Key header is required".to_string()))?;
+
+    let (payout, transaction) = PayoutService::create_payout(
+        &state.pool,
+        &state.ledger_grpc,
+        &state.payout_rail,
+        account_id,
+        auth.business_id,
+        environment.as_str(),
+        request,
+        &idempotency_key,
+    )
+    .await?;
+
+    let replayed = transaction.replayed;
+    let transaction = FeeService::with_fees(&state.pool, transaction).await?;
+
+    Ok((StatusCode::CREATED, idempotency_headers(replayed), Json(PayoutResponse { payout, transaction })))
+}
+
+pub async fn list_account_payouts(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Path(account_id): Path<Uuid>,
+) -> Result<Json<Vec<Payout>>, AppError> {
+    let payouts = PayoutService::get_account_payouts(&state.pool, account_id, auth.business_id, environment.as_str()).await?;
+    Ok(Json(payouts))
+}
+
+pub async fn get_payout(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Payout>, AppError> {
+    let payout = PayoutService::get_payout(&state.pool, id, auth.business_id, environment.as_str()).await?;
+    Ok(Json(payout))
+}
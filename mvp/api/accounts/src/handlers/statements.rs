@@ -0,0 +1,95 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::auth::AuthContext;
+use crate::environment::Environment;
+use crate::errors::AppError;
+use crate::models::{AccountStatement, StatementSummary};
+use crate::routes::api::AppState;
+use crate::services::StatementService;
+
+#[derive(Deserialize)]
+pub struct StatementQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    /// `json` (default) or `csv`
+    pub format: Option<String>,
+}
+
+/// Stored (completed month) statements of the account.
+pub async fn list_account_statements(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Path(account_id): Path<Uuid>,
+) -> Result<Json<Vec<StatementSummary>>, AppError> {
+    let statements = StatementService::list_statements(&state.pool, account_id, auth.business_id, environment.as_str()).await?;
+    Ok(Json(statements))
+}
+
+/// Monthly statement; `period` is `YYYY-MM`.
+pub async fn get_monthly_statement(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Path((account_id, period)): Path<(Uuid, String)>,
+    Query(query): Query<StatementQuery>,
+) -> Result<Response, AppError> {
+    let statement =
+        StatementService::get_monthly_statement(&state.pool, account_id, auth.business_id, environment.as_str(), &period)
+            .await?;
+    statement_response(statement, query.format.as_deref())
+}
+
+/// Statement for any period (`from`/`to`, inclusive).
+pub async fn get_statement(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Path(account_id): Path<Uuid>,
+    Query(query): Query<StatementQuery>,
+) -> Result<Response, AppError> {
+    let (from, to) = match (query.from, query.to) {
+        (Some(from), Some(to)) => (from, to),
+        _ => {
+            return Err(AppError::Validation(
+                "from and to query parameters (YYYY-MM-DD) are required".to_string(),
+            ))
+        }
+    };
+
+    let statement =
+        StatementService::get_statement(&state.pool, account_id, auth.business_id, environment.as_str(), from, to).await?;
+    statement_response(statement, query.format.as_deref())
+}
+
+fn statement_response(statement: AccountStatement, format: Option<&str>) -> Result<Response, AppError> {
+    match format.unwrap_or("json") {
+        "json" => Ok(Json(statement).into_response()),
+        "csv" => {
+            let file_name = format!(
+                "statement-{}-{}-{}.csv",
+                statement.account_id, statement.period_start, statement.period_end
+            );
+            Ok((
+                [
+                    (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                    (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", file_name)),
+                ],
+                statement.to_csv(),
+            )
+                .into_response())
+        }
+        other => Err(AppError::Validation(format!(
+            "unsupported statement format '{}' (expected json or csv)",
+            other
+        ))),
+    }
+}
@@ -3,29 +3,21 @@ use axum::{
     http::{HeaderMap, StatusCode},
     Json,
 };
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use uuid::Uuid;
 
+use crate::auth::AuthContext;
+use crate::environment::Environment;
 use crate::errors::AppError;
-use crate::models::{CreateTransactionRequest, TransactionResponse, PaginatedTransactionsResponse};
+use crate::handlers::accounts::idempotency_headers;
+use crate::models::{
+    metadata_filter, CreateTransactionRequest, PaginatedTransactionsResponse, SortOrder, TransactionFilter, TransactionKind,
+    TransactionResponse, TransactionStatus,
+};
 use crate::routes::api::AppState;
-use crate::services::TransactionService;
-
-/// Extract and validate environment from X-Environment header
-/// Defaults to "sandbox" if missing or invalid (safety: never defaults to production)
-fn extract_environment(headers: &HeaderMap) -> String {
-    let env = headers
-        .get("x-environment")
-        .and_then(|v| v.to_str().ok())
-        .map(|s| s.trim().to_lowercase())
-        .filter(|s| s == "sandbox" || s == "production")
-        .unwrap_or_else(|| {
-            tracing::warn!("Missing or invalid X-Environment header, defaulting to sandbox");
-            "sandbox".to_string()
-        });
-    
-    env
-}
+use crate::services::{FeeService, TransactionService};
+use crate::utils::pagination::PageRequest;
 
 #[derive(Deserialize)]
 pub struct ListTransactionsQuery {
@@ -33,25 +25,121 @@ pub struct ListTransactionsQuery {
     pub organization_id: Option<Uuid>,
     pub page: Option<u32>,
     pub per_page: Option<u32>,
+    /// Opaque cursor from a previous response (takes precedence over `page`)
+    pub cursor: Option<String>,
+    /// Created at or after (RFC 3339)
+    pub from: Option<DateTime<Utc>>,
+    /// Created before (RFC 3339)
+    pub to: Option<DateTime<Utc>>,
+    pub status: Option<String>,
+    pub kind: Option<String>,
+    pub min_amount: Option<i64>,
+    pub max_amount: Option<i64>,
+    pub counterparty_account_id: Option<Uuid>,
+    pub idempotency_key_prefix: Option<String>,
+    pub reference: Option<String>,
+    /// `asc` or `desc` (default) by creation time
+    pub order: Option<String>,
+}
+
+impl ListTransactionsQuery {
+    /// `params` are the raw query parameters, for the `metadata[<key>]` filters.
+    fn filter(&self, params: &[(String, String)]) -> Result<TransactionFilter, AppError> {
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            if from >= to {
+                return Err(AppError::Validation("from must be before to".to_string()));
+            }
+        }
+        if self.min_amount.is_some_and(|a| a < 0) || self.max_amount.is_some_and(|a| a < 0) {
+            return Err(AppError::Validation("min_amount and max_amount must not be negative".to_string()));
+        }
+        if let (Some(min), Some(max)) = (self.min_amount, self.max_amount) {
+            if min > max {
+                return Err(AppError::Validation("min_amount must not exceed max_amount".to_string()));
+            }
+        }
+
+        let status = match self.status.as_deref().map(|s| s.trim().to_lowercase()).as_deref() {
+            None => None,
+            Some("pending") => Some(TransactionStatus::Pending),
+            Some("posted") => Some(TransactionStatus::Posted),
+            Some("failed") => Some(TransactionStatus::Failed),
+            Some(other) => {
+                return Err(AppError::Validation(format!(
+                    "invalid status '{}' (expected pending, posted or failed)",
+                    other
+                )))
+            }
+        };
+
+        let kind = match self.kind.as_deref().map(|s| s.trim().to_lowercase()).as_deref() {
+            None => None,
+            Some("deposit") => Some(TransactionKind::Deposit),
+            Some("withdraw") => Some(TransactionKind::Withdraw),
+            Some("transfer") => Some(TransactionKind::Transfer),
+            Some("interest") => Some(TransactionKind::Interest),
+            Some(other) => {
+                return Err(AppError::Validation(format!(
+                    "invalid kind '{}' (expected deposit, withdraw, transfer or interest)",
+                    other
+                )))
+            }
+        };
+
+        let order = match self.order.as_deref().map(|s| s.trim().to_lowercase()).as_deref() {
+            None | Some("desc") => SortOrder::Desc,
+            Some("asc") => SortOrder::Asc,
+            Some(other) => {
+                return Err(AppError::Validation(format!(
+                    "invalid order '{}' (expected asc or desc)",
+                    other
+                )))
+            }
+        };
+
+        Ok(TransactionFilter {
+            from: self.from,
+            to: self.to,
+            status,
+            kind,
+            min_amount: self.min_amount,
+            max_amount: self.max_amount,
+            counterparty_account_id: self.counterparty_account_id,
+            idempotency_key_prefix: self
+                .idempotency_key_prefix
+                .as_deref()
+                .map(str::trim)
+                .filter(|p| !p.is_empty())
+                .map(str::to_string),
+            reference: self
+                .reference
+                .as_deref()
+                .map(str::trim)
+                .filter(|r| !r.is_empty())
+                .map(str::to_string),
+            metadata: metadata_filter(params)?,
+            order,
+        })
+    }
 }
 
 pub async fn get_transaction(
     State(state): State<AppState>,
-    headers: HeaderMap,
+    auth: AuthContext,
+    environment: Environment,
     Path(id): Path<Uuid>,
 ) -> Result<Json<TransactionResponse>, AppError> {
-    let environment = extract_environment(&headers);
-    let transaction = TransactionService::get_transaction(&state.pool, id, &environment).await?;
-    Ok(Json(transaction.into()))
+    let transaction = TransactionService::get_transaction(&state.pool, id, auth.business_id, environment.as_str()).await?;
+    Ok(Json(FeeService::with_fees(&state.pool, transaction).await?))
 }
 
 pub async fn create_transaction(
     State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
     headers: HeaderMap,
     Json(request): Json<CreateTransactionRequest>,
-) -> Result<(StatusCode, Json<TransactionResponse>), AppError> {
-    let environment = extract_environment(&headers);
-    
+) -> Result<(StatusCode, HeaderMap, Json<TransactionResponse>), AppError> {
     let idempotency_key = headers
         .get("Idempotency-Key")
         .and_then(|v| v.to_str().ok())
@@ -59,54 +147,65 @@ pub async fn create_transaction(
         .filter(|s| !s.is_empty())
         .ok_or_else(|| AppError::Validation("Idempotency-Key header is required".to_string()))?;
 
-    let transaction =
-        TransactionService::create_transaction(&state.pool, request, &environment, &idempotency_key).await?;
-    Ok((StatusCode::CREATED, Json(transaction.into())))
+    let transaction = TransactionService::create_transaction(
+        &state.pool,
+        auth.business_id,
+        request,
+        environment.as_str(),
+        &idempotency_key,
+        &state.fx_rates,
+    )
+    .await?;
+    let replayed = transaction.replayed;
+    let transaction = FeeService::with_fees(&state.pool, transaction).await?;
+    Ok((StatusCode::CREATED, idempotency_headers(replayed), Json(transaction)))
 }
 
 pub async fn list_account_transactions(
     State(state): State<AppState>,
-    headers: HeaderMap,
+    auth: AuthContext,
+    environment: Environment,
     Path(account_id): Path<Uuid>,
     Query(query): Query<ListTransactionsQuery>,
+    Query(params): Query<Vec<(String, String)>>,
 ) -> Result<Json<Vec<TransactionResponse>>, AppError> {
-    let environment = extract_environment(&headers);
+    let filter = query.filter(&params)?;
     
     let transactions = TransactionService::get_account_transactions(
         &state.pool,
         account_id,
-        &environment,
+        auth.business_id,
+        environment.as_str(),
+        &filter,
         query.limit,
     ).await?;
 
-    Ok(Json(transactions.into_iter().map(Into::into).collect()))
+    Ok(Json(FeeService::with_fees_many(&state.pool, transactions).await?))
 }
 
 pub async fn list_transactions(
     State(state): State<AppState>,
-    headers: HeaderMap,
+    auth: AuthContext,
+    environment: Environment,
     Query(query): Query<ListTransactionsQuery>,
+    Query(params): Query<Vec<(String, String)>>,
 ) -> Result<Json<PaginatedTransactionsResponse>, AppError> {
-    let environment = extract_environment(&headers);
-    
-    let organization_id = query.organization_id.ok_or_else(|| {
-        AppError::Validation("organization_id query parameter is required".to_string())
-    })?;
+    let organization_id = auth.organization(query.organization_id)?;
     
     // Parse and validate pagination params with defaults
-    let page = query.page.unwrap_or(1).max(1);
-    let per_page = query.per_page.unwrap_or(10).min(100).max(1);
+    let page = PageRequest::from_query(query.page, query.per_page, query.cursor.as_deref())?;
+    let filter = query.filter(&params)?;
     
     let (transactions, pagination) = TransactionService::get_transactions_by_organization_paginated(
         &state.pool,
         organization_id,
-        &environment,
-        page,
-        per_page,
+        environment.as_str(),
+        &filter,
+        &page,
     ).await?;
     
     Ok(Json(PaginatedTransactionsResponse {
-        data: transactions.into_iter().map(Into::into).collect(),
+        data: FeeService::with_fees_many(&state.pool, transactions).await?,
         pagination,
     }))
 }
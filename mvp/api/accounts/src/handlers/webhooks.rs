@@ -0,0 +1,88 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::auth::AuthContext;
+use crate::environment::Environment;
+use crate::errors::AppError;
+use crate::models::{
+    CreateWebhookEndpointRequest, CreatedWebhookEndpoint, WebhookDelivery, WebhookDeliveryDetail, WebhookEndpoint,
+};
+use crate::routes::api::AppState;
+use crate::services::WebhookService;
+
+#[derive(Deserialize)]
+pub struct ListWebhookEndpointsQuery {
+    pub organization_id: Option<Uuid>,
+}
+
+/// Register an endpoint. The signing secret is only returned in this response.
+pub async fn create_webhook_endpoint(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Json(request): Json<CreateWebhookEndpointRequest>,
+) -> Result<(StatusCode, Json<CreatedWebhookEndpoint>), AppError> {
+    auth.organization(Some(request.organization_id))?;
+    let endpoint = WebhookService::create_endpoint(&state.pool, environment.as_str(), request).await?;
+    Ok((StatusCode::CREATED, Json(endpoint)))
+}
+
+pub async fn list_webhook_endpoints(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Query(query): Query<ListWebhookEndpointsQuery>,
+) -> Result<Json<Vec<WebhookEndpoint>>, AppError> {
+    let organization_id = auth.organization(query.organization_id)?;
+
+    let endpoints = WebhookService::get_endpoints(&state.pool, organization_id, environment.as_str()).await?;
+    Ok(Json(endpoints))
+}
+
+pub async fn disable_webhook_endpoint(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Path(id): Path<Uuid>,
+) -> Result<Json<WebhookEndpoint>, AppError> {
+    let endpoint = WebhookService::disable_endpoint(&state.pool, id, auth.business_id, environment.as_str()).await?;
+    Ok(Json(endpoint))
+}
+
+/// Delivery log of an endpoint (most recent 100).
+pub async fn list_webhook_deliveries(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<WebhookDelivery>>, AppError> {
+    let deliveries = WebhookService::get_deliveries(&state.pool, id, auth.business_id, environment.as_str()).await?;
+    Ok(Json(deliveries))
+}
+
+pub async fn get_webhook_delivery(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Path(id): Path<Uuid>,
+) -> Result<Json<WebhookDeliveryDetail>, AppError> {
+    let delivery = WebhookService::get_delivery(&state.pool, id, auth.business_id, environment.as_str()).await?;
+    Ok(Json(delivery))
+}
+
+/// Send a delivery again immediately and return it with its attempt log.
+pub async fn redeliver_webhook(
+    State(state): State<AppState>,
+    auth: AuthContext,
+    environment: Environment,
+    Path(id): Path<Uuid>,
+) -> Result<Json<WebhookDeliveryDetail>, AppError> {
+    let delivery =
+        WebhookService::redeliver(&state.pool, &state.webhook_client, id, auth.business_id, environment.as_str()).await?;
+    Ok(Json(delivery))
+}
@@ -0,0 +1,199 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{DateTime, NaiveDate, Utc};
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::errors::AppError;
+
+/// `prev_hash` of the first record of a chain
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Records linked into a hash chain per organization.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Chain {
+    Transactions,
+    AuditEvents,
+}
+
+impl Chain {
+    pub const ALL: [Chain; 2] = [Chain::Transactions, Chain::AuditEvents];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Chain::Transactions => "transactions",
+            Chain::AuditEvents => "audit_events",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|chain| chain.as_str() == value)
+    }
+}
+
+/// Canonical content of a record: each field as `<byte length>:<text>`, or `-` for NULL.
+/// Mirrors `hash_chain_field` in the hash chain migration; the field order is per table.
+#[derive(Default)]
+pub struct Canonical(String);
+
+impl Canonical {
+    pub fn field(mut self, value: Option<&str>) -> Self {
+        match value {
+            Some(value) => {
+                self.0.push_str(&value.len().to_string());
+                self.0.push(':');
+                self.0.push_str(value);
+            }
+            None => self.0.push('-'),
+        }
+        self
+    }
+
+    pub fn uuid(self, value: Option<Uuid>) -> Self {
+        self.field(value.map(|v| v.to_string()).as_deref())
+    }
+
+    pub fn int(self, value: Option<i64>) -> Self {
+        self.field(value.map(|v| v.to_string()).as_deref())
+    }
+
+    /// UTC with microseconds, as `hash_chain_timestamp`.
+    pub fn timestamp(self, value: DateTime<Utc>) -> Self {
+        self.field(Some(&value.format("%Y-%m-%dT%H:%M:%S%.6fZ").to_string()))
+    }
+
+    /// Hash linking this record after `prev_hash`: hex SHA-256 of `prev_hash || content`.
+    pub fn link(&self, prev_hash: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(prev_hash.as_bytes());
+        hasher.update(self.0.as_bytes());
+        hex::encode(hasher.finalize())
+    }
+}
+
+/// Text a checkpoint signature covers; external parties rebuild it from the exported fields.
+pub fn checkpoint_message(
+    organization_id: Uuid,
+    chain: Chain,
+    date: NaiveDate,
+    chain_seq: i64,
+    record_hash: &str,
+) -> String {
+    format!(
+        "rails-hash-chain-checkpoint/v1\norganization_id={}\nchain={}\ndate={}\nsequence={}\nhash={}",
+        organization_id,
+        chain.as_str(),
+        date,
+        chain_seq,
+        record_hash
+    )
+}
+
+/// Ed25519 key signing daily checkpoints (CHECKPOINT_SIGNING_KEY: base64 of a 32-byte seed).
+pub struct CheckpointSigner {
+    key_pair: Ed25519KeyPair,
+}
+
+impl CheckpointSigner {
+    /// `None` when no key is configured; checkpoints are then not created.
+    pub fn from_env() -> Result<Option<Self>, AppError> {
+        let Some(encoded) = std::env::var("CHECKPOINT_SIGNING_KEY").ok().filter(|v| !v.trim().is_empty()) else {
+            return Ok(None);
+        };
+
+        let seed = STANDARD
+            .decode(encoded.trim())
+            .map_err(|_| AppError::Internal("CHECKPOINT_SIGNING_KEY is not valid base64".to_string()))?;
+        let key_pair = Ed25519KeyPair::from_seed_unchecked(&seed)
+            .map_err(|_| AppError::Internal("CHECKPOINT_SIGNING_KEY must be a 32-byte Ed25519 seed".to_string()))?;
+
+        Ok(Some(Self { key_pair }))
+    }
+
+    /// Base64 public key verifying this signer's signatures
+    pub fn public_key(&self) -> String {
+        STANDARD.encode(self.key_pair.public_key().as_ref())
+    }
+
+    /// Base64 signature of `message`
+    pub fn sign(&self, message: &str) -> String {
+        STANDARD.encode(self.key_pair.sign(message.as_bytes()).as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn record() -> Canonical {
+        Canonical::default()
+            .field(Some("héllo"))
+            .field(None)
+            .uuid(Some(Uuid::parse_str("6f9619ff-8b86-d011-b42d-00cf4fc964ff").unwrap()))
+            .int(Some(-150))
+            .timestamp(Utc.with_ymd_and_hms(2026, 10, 16, 14, 5, 0).unwrap() + chrono::Duration::microseconds(7))
+    }
+
+    #[test]
+    fn fields_are_length_prefixed_in_bytes() {
+        assert_eq!(
+            record().0,
+            "6:héllo-36:6f9619ff-8b86-d011-b42d-00cf4fc964ff4:-15027:2026-10-16T14:05:00.000007Z"
+        );
+    }
+
+    #[test]
+    fn null_and_empty_fields_differ() {
+        assert_eq!(Canonical::default().field(None).0, "-");
+        assert_eq!(Canonical::default().field(Some("")).0, "0:");
+        assert_eq!(Canonical::default().uuid(None).int(None).0, "--");
+    }
+
+    #[test]
+    fn length_prefixes_keep_field_boundaries() {
+        let split_early = Canonical::default().field(Some("ab")).field(Some("c"));
+        let split_late = Canonical::default().field(Some("a")).field(Some("bc"));
+
+        assert_ne!(split_early.link(GENESIS_HASH), split_late.link(GENESIS_HASH));
+    }
+
+    #[test]
+    fn link_matches_the_database_functions() {
+        // sha256(repeat('0', 64) || hash_chain_field(...) ...) for the same fields in Postgres
+        assert_eq!(
+            record().link(GENESIS_HASH),
+            "6f169ce05249d8ba47218c8f03d07a7fd0d5c907c0da0477e69e955987bd6322"
+        );
+        assert_ne!(record().link(GENESIS_HASH), record().link(&"f".repeat(64)));
+    }
+
+    #[test]
+    fn chain_names_round_trip() {
+        for chain in Chain::ALL {
+            assert_eq!(Chain::parse(chain.as_str()), Some(chain));
+        }
+        assert_eq!(Chain::parse("accounts"), None);
+    }
+
+    #[test]
+    fn checkpoint_signatures_verify_with_the_public_key() {
+        let signer = CheckpointSigner {
+            key_pair: Ed25519KeyPair::from_seed_unchecked(&[7u8; 32]).unwrap(),
+        };
+        let message = checkpoint_message(
+            Uuid::nil(),
+            Chain::Transactions,
+            NaiveDate::from_ymd_opt(2026, 10, 16).unwrap(),
+            42,
+            GENESIS_HASH,
+        );
+
+        let public_key = STANDARD.decode(signer.public_key()).unwrap();
+        let signature = STANDARD.decode(signer.sign(&message)).unwrap();
+        ring::signature::UnparsedPublicKey::new(&ring::signature::ED25519, public_key)
+            .verify(message.as_bytes(), &signature)
+            .unwrap();
+    }
+}
@@ -0,0 +1,716 @@
+//! ISO 20022 XML messages: camt.053.001.02 (bank-to-customer statement) and
+//! pain.001.001.03 (customer credit transfer initiation). Element order follows the
+//! published XSDs; amounts are absolute values with the sign carried by CdtDbtInd.
+
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+
+use crate::models::{AccountMovement, Currency, MovementKind};
+
+pub const CAMT_053_NAMESPACE: &str = "urn:iso:std:iso:20022:tech:xsd:camt.053.001.02";
+pub const PAIN_001_NAMESPACE: &str = "urn:iso:std:iso:20022:tech:xsd:pain.001.001.03";
+
+/// One end-of-day statement (`Stmt`) of an account.
+#[derive(Debug, Clone)]
+pub struct CamtStatement {
+    pub id: String,
+    pub sequence_number: i64,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub account_number: String,
+    pub currency: Currency,
+    pub opening_balance: i64,
+    pub closing_balance: i64,
+    pub entries: Vec<AccountMovement>,
+}
+
+/// One payment information block (`PmtInf`): payments from a debtor account executed on one date.
+#[derive(Debug, Clone)]
+pub struct PainPaymentInfo {
+    pub id: String,
+    pub requested_execution_date: NaiveDate,
+    pub debtor_id: String,
+    pub debtor_account_number: String,
+    pub currency: Currency,
+    pub transfers: Vec<PainCreditTransfer>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PainCreditTransfer {
+    pub end_to_end_id: String,
+    pub amount: i64,
+    pub creditor_name: String,
+    /// US ABA routing number of the creditor's bank
+    pub creditor_routing_number: String,
+    pub creditor_account_number: String,
+    pub remittance_information: String,
+}
+
+pub fn camt053(message_id: &str, created_at: DateTime<Utc>, statements: &[CamtStatement]) -> String {
+    let mut xml = XmlWriter::new(CAMT_053_NAMESPACE);
+    xml.open("BkToCstmrStmt");
+
+    xml.open("GrpHdr");
+    xml.leaf("MsgId", &max_text(message_id, 35));
+    xml.leaf("CreDtTm", &date_time(created_at));
+    xml.close("GrpHdr");
+
+    for statement in statements {
+        let currency = statement.currency;
+        xml.open("Stmt");
+        xml.leaf("Id", &max_text(&statement.id, 35));
+        xml.leaf("ElctrncSeqNb", &statement.sequence_number.to_string());
+        xml.leaf("CreDtTm", &date_time(created_at));
+        xml.open("FrToDt");
+        xml.leaf("FrDtTm", &date_time(statement.from));
+        xml.leaf("ToDtTm", &date_time(statement.to));
+        xml.close("FrToDt");
+
+        xml.open("Acct");
+        xml.open("Id");
+        xml.open("Othr");
+        xml.leaf("Id", &max_text(&statement.account_number, 34));
+        xml.close("Othr");
+        xml.close("Id");
+        xml.leaf("Ccy", currency.code());
+        xml.close("Acct");
+
+        balance(&mut xml, "OPBD", statement.opening_balance, currency, statement.from.date_naive());
+        balance(&mut xml, "CLBD", statement.closing_balance, currency, statement.from.date_naive());
+
+        let credits: Vec<i64> = statement.entries.iter().map(|e| e.amount).filter(|a| *a > 0).collect();
+        let debits: Vec<i64> = statement.entries.iter().map(|e| e.amount).filter(|a| *a < 0).collect();
+        let net: i64 = statement.entries.iter().map(|e| e.amount).sum();
+
+        xml.open("TxsSummry");
+        xml.open("TtlNtries");
+        xml.leaf("NbOfNtries", &statement.entries.len().to_string());
+        xml.leaf(
+            "Sum",
+            &currency.format_amount(statement.entries.iter().map(|e| e.amount.abs()).sum()),
+        );
+        xml.leaf("TtlNetNtryAmt", &currency.format_amount(net.abs()));
+        xml.leaf("CdtDbtInd", credit_debit(net));
+        xml.close("TtlNtries");
+        xml.open("TtlCdtNtries");
+        xml.leaf("NbOfNtries", &credits.len().to_string());
+        xml.leaf("Sum", &currency.format_amount(credits.iter().sum()));
+        xml.close("TtlCdtNtries");
+        xml.open("TtlDbtNtries");
+        xml.leaf("NbOfNtries", &debits.len().to_string());
+        xml.leaf("Sum", &currency.format_amount(-debits.iter().sum::<i64>()));
+        xml.close("TtlDbtNtries");
+        xml.close("TxsSummry");
+
+        for entry in &statement.entries {
+            xml.open("Ntry");
+            xml.leaf("NtryRef", &entry.id.simple().to_string());
+            xml.leaf_with_attr("Amt", "Ccy", currency.code(), &currency.format_amount(entry.amount.abs()));
+            xml.leaf("CdtDbtInd", credit_debit(entry.amount));
+            xml.leaf("Sts", "BOOK");
+            xml.open("BookgDt");
+            xml.leaf("DtTm", &date_time(entry.booked_at));
+            xml.close("BookgDt");
+            xml.open("ValDt");
+            xml.leaf("Dt", &entry.booked_at.date_naive().to_string());
+            xml.close("ValDt");
+            xml.leaf("AcctSvcrRef", &entry.transaction_id.simple().to_string());
+            xml.open("BkTxCd");
+            xml.open("Prtry");
+            xml.leaf("Cd", movement_code(entry.kind));
+            xml.close("Prtry");
+            xml.close("BkTxCd");
+            xml.open("NtryDtls");
+            xml.open("TxDtls");
+            xml.open("Refs");
+            xml.leaf("EndToEndId", &max_text(&entry.reference, 35));
+            xml.close("Refs");
+            xml.close("TxDtls");
+            xml.close("NtryDtls");
+            xml.close("Ntry");
+        }
+
+        xml.close("Stmt");
+    }
+
+    xml.close("BkToCstmrStmt");
+    xml.finish()
+}
+
+pub fn pain001(
+    message_id: &str,
+    created_at: DateTime<Utc>,
+    initiating_party: &str,
+    payment_infos: &[PainPaymentInfo],
+) -> String {
+    let mut xml = XmlWriter::new(PAIN_001_NAMESPACE);
+    xml.open("CstmrCdtTrfInitn");
+
+    let transaction_count: usize = payment_infos.iter().map(|p| p.transfers.len()).sum();
+    // Control sums are in major units; each block has a single currency
+    let control_sum = |info: &PainPaymentInfo| {
+        info.transfers
+            .iter()
+            .map(|t| rust_decimal::Decimal::new(t.amount, info.currency.exponent()))
+            .sum::<rust_decimal::Decimal>()
+    };
+
+    xml.open("GrpHdr");
+    xml.leaf("MsgId", &max_text(message_id, 35));
+    xml.leaf("CreDtTm", &date_time(created_at));
+    xml.leaf("NbOfTxs", &transaction_count.to_string());
+    xml.leaf(
+        "CtrlSum",
+        &payment_infos.iter().map(control_sum).sum::<rust_decimal::Decimal>().to_string(),
+    );
+    xml.open("InitgPty");
+    xml.leaf("Nm", &max_text(initiating_party, 140));
+    xml.close("InitgPty");
+    xml.close("GrpHdr");
+
+    for info in payment_infos {
+        let currency = info.currency;
+        xml.open("PmtInf");
+        xml.leaf("PmtInfId", &max_text(&info.id, 35));
+        xml.leaf("PmtMtd", "TRF");
+        xml.leaf("NbOfTxs", &info.transfers.len().to_string());
+        xml.leaf("CtrlSum", &control_sum(info).to_string());
+        xml.leaf("ReqdExctnDt", &info.requested_execution_date.to_string());
+
+        xml.open("Dbtr");
+        xml.open("Id");
+        xml.open("OrgId");
+        xml.open("Othr");
+        xml.leaf("Id", &max_text(&info.debtor_id, 35));
+        xml.close("Othr");
+        xml.close("OrgId");
+        xml.close("Id");
+        xml.close("Dbtr");
+
+        xml.open("DbtrAcct");
+        xml.open("Id");
+        xml.open("Othr");
+        xml.leaf("Id", &max_text(&info.debtor_account_number, 34));
+        xml.close("Othr");
+        xml.close("Id");
+        xml.leaf("Ccy", currency.code());
+        xml.close("DbtrAcct");
+
+        xml.open("DbtrAgt");
+        xml.open("FinInstnId");
+        xml.open("Othr");
+        xml.leaf("Id", "NOTPROVIDED");
+        xml.close("Othr");
+        xml.close("FinInstnId");
+        xml.close("DbtrAgt");
+
+        for transfer in &info.transfers {
+            xml.open("CdtTrfTxInf");
+            xml.open("PmtId");
+            xml.leaf("EndToEndId", &max_text(&transfer.end_to_end_id, 35));
+            xml.close("PmtId");
+            xml.open("Amt");
+            xml.leaf_with_attr("InstdAmt", "Ccy", currency.code(), &currency.format_amount(transfer.amount));
+            xml.close("Amt");
+
+            xml.open("CdtrAgt");
+            xml.open("FinInstnId");
+            xml.open("ClrSysMmbId");
+            xml.open("ClrSysId");
+            xml.leaf("Cd", "USABA");
+            xml.close("ClrSysId");
+            xml.leaf("MmbId", &transfer.creditor_routing_number);
+            xml.close("ClrSysMmbId");
+            xml.close("FinInstnId");
+            xml.close("CdtrAgt");
+
+            xml.open("Cdtr");
+            xml.leaf("Nm", &max_text(&transfer.creditor_name, 140));
+            xml.close("Cdtr");
+
+            xml.open("CdtrAcct");
+            xml.open("Id");
+            xml.open("Othr");
+            xml.leaf("Id", &max_text(&transfer.creditor_account_number, 34));
+            xml.close("Othr");
+            xml.close("Id");
+            xml.close("CdtrAcct");
+
+            xml.open("RmtInf");
+            xml.leaf("Ustrd", &max_text(&transfer.remittance_information, 140));
+            xml.close("RmtInf");
+            xml.close("CdtTrfTxInf");
+        }
+
+        xml.close("PmtInf");
+    }
+
+    xml.close("CstmrCdtTrfInitn");
+    xml.finish()
+}
+
+fn balance(xml: &mut XmlWriter, code: &str, amount: i64, currency: Currency, date: NaiveDate) {
+    xml.open("Bal");
+    xml.open("Tp");
+    xml.open("CdOrPrtry");
+    xml.leaf("Cd", code);
+    xml.close("CdOrPrtry");
+    xml.close("Tp");
+    xml.leaf_with_attr("Amt", "Ccy", currency.code(), &currency.format_amount(amount.abs()));
+    xml.leaf("CdtDbtInd", credit_debit(amount));
+    xml.open("Dt");
+    xml.leaf("Dt", &date.to_string());
+    xml.close("Dt");
+    xml.close("Bal");
+}
+
+/// Zero amounts are reported as credits.
+fn credit_debit(amount: i64) -> &'static str {
+    if amount < 0 {
+        "DBIT"
+    } else {
+        "CRDT"
+    }
+}
+
+fn movement_code(kind: MovementKind) -> &'static str {
+    match kind {
+        MovementKind::Deposit => "DEPOSIT",
+        MovementKind::Withdraw => "WITHDRAW",
+        MovementKind::Transfer => "TRANSFER",
+        MovementKind::Interest => "INTEREST",
+        MovementKind::Fee => "FEE",
+    }
+}
+
+fn date_time(value: DateTime<Utc>) -> String {
+    value.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Truncate to an ISO 20022 MaxNText length (characters, not bytes).
+fn max_text(value: &str, max: usize) -> String {
+    value.chars().take(max).collect()
+}
+
+/// Minimal indenting XML writer; text and attribute values are escaped.
+struct XmlWriter {
+    out: String,
+    depth: usize,
+}
+
+impl XmlWriter {
+    fn new(namespace: &str) -> Self {
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str(&format!("<Document xmlns=\"{}\">\n", namespace));
+        Self { out, depth: 1 }
+    }
+
+    fn indent(&mut self) {
+        for _ in 0..self.depth {
+            self.out.push_str("  ");
+        }
+    }
+
+    fn open(&mut self, tag: &str) {
+        self.indent();
+        self.out.push_str(&format!("<{}>\n", tag));
+        self.depth += 1;
+    }
+
+    fn close(&mut self, tag: &str) {
+        self.depth -= 1;
+        self.indent();
+        self.out.push_str(&format!("</{}>\n", tag));
+    }
+
+    fn leaf(&mut self, tag: &str, text: &str) {
+        self.indent();
+        self.out.push_str(&format!("<{}>{}</{}>\n", tag, escape(text), tag));
+    }
+
+    fn leaf_with_attr(&mut self, tag: &str, attr: &str, value: &str, text: &str) {
+        self.indent();
+        self.out
+            .push_str(&format!("<{} {}=\"{}\">{}</{}>\n", tag, attr, escape(value), escape(text), tag));
+    }
+
+    fn finish(mut self) -> String {
+        self.out.push_str("</Document>\n");
+        self.out
+    }
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters are not allowed in XML 1.0 text
+            c if c.is_control() && c != '\n' && c != '\t' => escaped.push(' '),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::path::Path;
+    use std::process::{Command, Stdio};
+
+    use chrono::TimeZone;
+    use uuid::Uuid;
+
+    use super::*;
+
+    /// Element tree of a document written by `XmlWriter` (no comments, CDATA or mixed content).
+    #[derive(Debug)]
+    struct Element {
+        name: String,
+        attributes: Vec<(String, String)>,
+        text: String,
+        children: Vec<Element>,
+    }
+
+    impl Element {
+        fn all(&self, name: &str) -> Vec<&Element> {
+            self.children.iter().filter(|c| c.name == name).collect()
+        }
+
+        /// First element along a `/`-separated path of child names.
+        fn at(&self, path: &str) -> &Element {
+            path.split('/').fold(self, |element, name| {
+                element
+                    .children
+                    .iter()
+                    .find(|c| c.name == name)
+                    .unwrap_or_else(|| panic!("no <{}> in <{}>", name, element.name))
+            })
+        }
+
+        fn text_at(&self, path: &str) -> &str {
+            &self.at(path).text
+        }
+
+        fn attribute(&self, name: &str) -> &str {
+            &self.attributes.iter().find(|(n, _)| n == name).unwrap().1
+        }
+    }
+
+    fn parse(xml: &str) -> Element {
+        let body = xml.strip_prefix("<?xml").map(|rest| &rest[rest.find("?>").unwrap() + 2..]).unwrap();
+        let (document, rest) = parse_element(body.trim_start());
+        assert!(rest.trim().is_empty(), "trailing content: {}", rest);
+        document
+    }
+
+    fn parse_element(input: &str) -> (Element, &str) {
+        let input = input.strip_prefix('<').expect("start tag");
+        let tag_end = input.find('>').unwrap();
+        let mut parts = input[..tag_end].split_whitespace();
+        let name = parts.next().unwrap().to_string();
+        let attributes = parts
+            .map(|attribute| {
+                let (name, value) = attribute.split_once('=').unwrap();
+                (name.to_string(), unescape(value.trim_matches('"')))
+            })
+            .collect();
+
+        let mut element = Element { name, attributes, text: String::new(), children: Vec::new() };
+        let mut rest = &input[tag_end + 1..];
+        loop {
+            let next = rest.find('<').unwrap();
+            if let Some(close) = rest[next..].strip_prefix("</") {
+                let close_end = close.find('>').unwrap();
+                assert_eq!(&close[..close_end], element.name);
+                if element.children.is_empty() {
+                    element.text = unescape(&rest[..next]);
+                }
+                return (element, &close[close_end + 1..]);
+            }
+            let (child, after) = parse_element(&rest[next..]);
+            element.children.push(child);
+            rest = after;
+        }
+    }
+
+    fn unescape(value: &str) -> String {
+        value
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&")
+    }
+
+    /// Validate against the vendored schema with xmllint (libxml2), which must be installed.
+    fn assert_schema_valid(xml: &str, schema: &str) {
+        let schema = Path::new(env!("CARGO_MANIFEST_DIR")).join("schemas/iso20022").join(schema);
+        let child = Command::new("xmllint")
+            .arg("--noout")
+            .arg("--schema")
+            .arg(&schema)
+            .arg("-")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn();
+        let mut child = child.expect("xmllint (libxml2) must be installed to run the schema tests");
+
+        child.stdin.take().unwrap().write_all(xml.as_bytes()).unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(
+            output.status.success(),
+            "document is not valid against {}:\n{}\n{}",
+            schema.display(),
+            String::from_utf8_lossy(&output.stderr),
+            xml
+        );
+    }
+
+    /// Signed minor units of an amount element and the CdtDbtInd next to it.
+    fn signed_amount(amount: &str, indicator: &str, currency: Currency) -> i64 {
+        let amount = currency.parse_amount(amount).unwrap();
+        match indicator {
+            "CRDT" => amount,
+            "DBIT" => -amount,
+            other => panic!("unexpected CdtDbtInd {}", other),
+        }
+    }
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, day, hour, minute, 7).unwrap()
+    }
+
+    fn movement(kind: MovementKind, amount: i64, reference: &str, booked_at: DateTime<Utc>) -> AccountMovement {
+        AccountMovement {
+            id: Uuid::new_v4(),
+            transaction_id: Uuid::new_v4(),
+            kind,
+            amount,
+            currency: "USD".to_string(),
+            reference: reference.to_string(),
+            counterparty_account_id: None,
+            booked_at,
+        }
+    }
+
+    fn statement(currency: Currency, opening_balance: i64, entries: Vec<AccountMovement>) -> CamtStatement {
+        let closing_balance = opening_balance + entries.iter().map(|e| e.amount).sum::<i64>();
+        CamtStatement {
+            id: "123456789012-20261015".to_string(),
+            sequence_number: 739_903,
+            from: Utc.with_ymd_and_hms(2026, 10, 15, 0, 0, 0).unwrap(),
+            to: Utc.with_ymd_and_hms(2026, 10, 15, 23, 59, 59).unwrap(),
+            account_number: "123456789012".to_string(),
+            currency,
+            opening_balance,
+            closing_balance,
+            entries,
+        }
+    }
+
+    fn statements() -> Vec<CamtStatement> {
+        let usd = Currency::USD;
+        vec![
+            statement(
+                usd,
+                -1_000,
+                vec![
+                    movement(MovementKind::Deposit, 150_000, "dep-1", at(15, 9, 30)),
+                    movement(MovementKind::Withdraw, -2_550, "wd <&> 'quoted'", at(15, 12, 0)),
+                    movement(MovementKind::Fee, -99, "fee-1", at(15, 12, 0)),
+                    movement(MovementKind::Interest, 1, &"r".repeat(60), at(15, 23, 59)),
+                ],
+            ),
+            statement(Currency::from_code("KWD").unwrap(), 1_234, vec![]),
+            statement(
+                Currency::from_code("JPY").unwrap(),
+                0,
+                vec![movement(MovementKind::Transfer, -5_000, "xfer-1", at(15, 8, 0))],
+            ),
+        ]
+    }
+
+    fn payment_infos() -> Vec<PainPaymentInfo> {
+        let transfer = |end_to_end_id: &str, amount: i64, creditor_name: &str| PainCreditTransfer {
+            end_to_end_id: end_to_end_id.to_string(),
+            amount,
+            creditor_name: creditor_name.to_string(),
+            creditor_routing_number: "021000021".to_string(),
+            creditor_account_number: "000123456789".to_string(),
+            remittance_information: format!("PAYOUT {}", end_to_end_id),
+        };
+
+        vec![
+            PainPaymentInfo {
+                id: "123456789012-20261015".to_string(),
+                requested_execution_date: NaiveDate::from_ymd_opt(2026, 10, 15).unwrap(),
+                debtor_id: Uuid::nil().simple().to_string(),
+                debtor_account_number: "123456789012".to_string(),
+                currency: Currency::USD,
+                transfers: vec![
+                    transfer("e2e-1", 1_050, "Jane Doe"),
+                    transfer("e2e-2", 99_999_999, &"Zoë & Sons <Ltd> ".repeat(12)),
+                ],
+            },
+            PainPaymentInfo {
+                id: "123456789012-20261016".to_string(),
+                requested_execution_date: NaiveDate::from_ymd_opt(2026, 10, 16).unwrap(),
+                debtor_id: Uuid::nil().simple().to_string(),
+                debtor_account_number: "123456789012".to_string(),
+                currency: Currency::USD,
+                transfers: vec![transfer("e2e-3", 1, "Bob")],
+            },
+        ]
+    }
+
+    #[test]
+    #[ignore = "needs xmllint (libxml2): cargo test -- --ignored"]
+    fn camt053_is_valid_against_the_schema() {
+        let xml = camt053(&"M".repeat(40), at(16, 6, 0), &statements());
+        assert_schema_valid(&xml, "camt.053.001.02.xsd");
+    }
+
+    #[test]
+    #[ignore = "needs xmllint (libxml2): cargo test -- --ignored"]
+    fn pain001_is_valid_against_the_schema() {
+        let xml = pain001(&"M".repeat(40), at(16, 6, 0), &"Initiating Party ".repeat(10), &payment_infos());
+        assert_schema_valid(&xml, "pain.001.001.03.xsd");
+    }
+
+    #[test]
+    fn camt053_round_trips_balances_entries_and_dates() {
+        let statements = statements();
+        let created_at = at(16, 6, 0);
+        let document = parse(&camt053("CAMT053-1", created_at, &statements));
+        assert_eq!(document.attribute("xmlns"), CAMT_053_NAMESPACE);
+
+        let message = document.at("BkToCstmrStmt");
+        assert_eq!(message.text_at("GrpHdr/MsgId"), "CAMT053-1");
+        assert_eq!(DateTime::parse_from_rfc3339(message.text_at("GrpHdr/CreDtTm")).unwrap(), created_at);
+
+        let parsed = message.all("Stmt");
+        assert_eq!(parsed.len(), statements.len());
+        for (stmt, expected) in parsed.into_iter().zip(&statements) {
+            let currency = expected.currency;
+            assert_eq!(stmt.text_at("Acct/Ccy"), currency.code());
+            assert_eq!(DateTime::parse_from_rfc3339(stmt.text_at("FrToDt/FrDtTm")).unwrap(), expected.from);
+            assert_eq!(DateTime::parse_from_rfc3339(stmt.text_at("FrToDt/ToDtTm")).unwrap(), expected.to);
+
+            let balances: Vec<(String, i64, NaiveDate)> = stmt
+                .all("Bal")
+                .into_iter()
+                .map(|bal| {
+                    assert_eq!(bal.at("Amt").attribute("Ccy"), currency.code());
+                    (
+                        bal.text_at("Tp/CdOrPrtry/Cd").to_string(),
+                        signed_amount(bal.text_at("Amt"), bal.text_at("CdtDbtInd"), currency),
+                        bal.text_at("Dt/Dt").parse().unwrap(),
+                    )
+                })
+                .collect();
+            let day = expected.from.date_naive();
+            assert_eq!(
+                balances,
+                vec![
+                    ("OPBD".to_string(), expected.opening_balance, day),
+                    ("CLBD".to_string(), expected.closing_balance, day),
+                ]
+            );
+
+            let entries = stmt.all("Ntry");
+            assert_eq!(entries.len(), expected.entries.len());
+            for (ntry, movement) in entries.into_iter().zip(&expected.entries) {
+                assert_eq!(signed_amount(ntry.text_at("Amt"), ntry.text_at("CdtDbtInd"), currency), movement.amount);
+                assert_eq!(DateTime::parse_from_rfc3339(ntry.text_at("BookgDt/DtTm")).unwrap(), movement.booked_at);
+                assert_eq!(ntry.text_at("ValDt/Dt").parse::<NaiveDate>().unwrap(), movement.booked_at.date_naive());
+                assert_eq!(ntry.text_at("AcctSvcrRef"), movement.transaction_id.simple().to_string());
+                assert_eq!(ntry.text_at("BkTxCd/Prtry/Cd"), movement_code(movement.kind));
+                assert_eq!(ntry.text_at("NtryDtls/TxDtls/Refs/EndToEndId"), max_text(&movement.reference, 35));
+            }
+
+            let net: i64 = expected.entries.iter().map(|e| e.amount).sum();
+            let summary = stmt.at("TxsSummry");
+            assert_eq!(summary.text_at("TtlNtries/NbOfNtries"), expected.entries.len().to_string());
+            let net_amount = summary.text_at("TtlNtries/TtlNetNtryAmt");
+            assert_eq!(signed_amount(net_amount, summary.text_at("TtlNtries/CdtDbtInd"), currency), net);
+            let credits: i64 = expected.entries.iter().map(|e| e.amount).filter(|a| *a > 0).sum();
+            assert_eq!(currency.parse_amount(summary.text_at("TtlCdtNtries/Sum")).unwrap(), credits);
+            assert_eq!(currency.parse_amount(summary.text_at("TtlDbtNtries/Sum")).unwrap(), credits - net);
+        }
+    }
+
+    #[test]
+    fn pain001_round_trips_amounts_control_sums_and_dates() {
+        let payment_infos = payment_infos();
+        let created_at = at(16, 6, 0);
+        let document = parse(&pain001("PAIN001-1", created_at, "Example Payments", &payment_infos));
+        assert_eq!(document.attribute("xmlns"), PAIN_001_NAMESPACE);
+
+        let message = document.at("CstmrCdtTrfInitn");
+        let all_transfers: Vec<&PainCreditTransfer> = payment_infos.iter().flat_map(|p| &p.transfers).collect();
+        assert_eq!(DateTime::parse_from_rfc3339(message.text_at("GrpHdr/CreDtTm")).unwrap(), created_at);
+        assert_eq!(message.text_at("GrpHdr/NbOfTxs"), all_transfers.len().to_string());
+        assert_eq!(
+            Currency::USD.parse_amount(message.text_at("GrpHdr/CtrlSum")).unwrap(),
+            all_transfers.iter().map(|t| t.amount).sum::<i64>()
+        );
+
+        for (block, expected) in message.all("PmtInf").into_iter().zip(&payment_infos) {
+            assert_eq!(block.text_at("PmtInfId"), expected.id);
+            assert_eq!(block.text_at("ReqdExctnDt").parse::<NaiveDate>().unwrap(), expected.requested_execution_date);
+            assert_eq!(block.text_at("NbOfTxs"), expected.transfers.len().to_string());
+            assert_eq!(
+                expected.currency.parse_amount(block.text_at("CtrlSum")).unwrap(),
+                expected.transfers.iter().map(|t| t.amount).sum::<i64>()
+            );
+
+            for (transaction, transfer) in block.all("CdtTrfTxInf").into_iter().zip(&expected.transfers) {
+                let amount = transaction.at("Amt/InstdAmt");
+                assert_eq!(amount.attribute("Ccy"), expected.currency.code());
+                assert_eq!(expected.currency.parse_amount(&amount.text).unwrap(), transfer.amount);
+                assert_eq!(transaction.text_at("PmtId/EndToEndId"), transfer.end_to_end_id);
+                assert_eq!(
+                    transaction.text_at("CdtrAgt/FinInstnId/ClrSysMmbId/MmbId"),
+                    transfer.creditor_routing_number
+                );
+                assert_eq!(transaction.text_at("CdtrAcct/Id/Othr/Id"), transfer.creditor_account_number);
+                assert_eq!(transaction.text_at("Cdtr/Nm"), max_text(&transfer.creditor_name, 140));
+            }
+        }
+    }
+
+    #[test]
+    fn long_text_is_truncated_by_characters_and_escaped() {
+        let message_id = "é".repeat(36);
+        let name = "Zoë & Sons <Ltd> ".repeat(12);
+        let document = parse(&pain001(&message_id, at(16, 6, 0), &name, &payment_infos()));
+        let header = document.at("CstmrCdtTrfInitn/GrpHdr");
+
+        assert_eq!(header.text_at("MsgId"), "é".repeat(35));
+        assert_eq!(header.text_at("InitgPty/Nm").chars().count(), 140);
+        assert!(name.starts_with(header.text_at("InitgPty/Nm")));
+
+        assert_eq!(max_text("abc", 35), "abc");
+        assert_eq!(max_text("ab€cd", 3), "ab€");
+        assert_eq!(escape("a\u{1}b\tc"), "a b\tc");
+    }
+
+    #[test]
+    fn zero_and_negative_balances_carry_the_sign_in_the_indicator() {
+        let document = parse(&camt053("CAMT053-1", at(16, 6, 0), &[statement(Currency::USD, 0, vec![])]));
+        let stmt = document.at("BkToCstmrStmt/Stmt");
+        assert_eq!(stmt.text_at("Bal/Amt"), "0.00");
+        assert_eq!(stmt.text_at("Bal/CdtDbtInd"), "CRDT");
+
+        let document = parse(&camt053("CAMT053-1", at(16, 6, 0), &[statement(Currency::USD, -5, vec![])]));
+        let stmt = document.at("BkToCstmrStmt/Stmt");
+        assert_eq!(stmt.text_at("Bal/Amt"), "0.05");
+        assert_eq!(stmt.text_at("Bal/CdtDbtInd"), "DBIT");
+    }
+}
@@ -2,7 +2,7 @@ use std::time::Duration;
 
 use crate::errors::AppError;
 use crate::grpc::ledger_proto::{
-    ledger_service_client::LedgerServiceClient, Environment, PostTransactionRequest,
+    ledger_service_client::LedgerServiceClient, Environment, GetAccountBalanceRequest, PostTransactionRequest,
 };
 use tonic::transport::Endpoint;
 
@@ -99,5 +99,48 @@ impl LedgerGrpc {
             )))
         }
     }
-}
 
+    /// Current Ledger balance (minor units) of an external account in one currency.
+    pub async fn get_account_balance(
+        &self,
+        organization_id: uuid::Uuid,
+        environment: &str,
+        external_account_id: String,
+        currency: String,
+    ) -> Result<i64, AppError> {
+        let env = Self::env_to_proto(environment)?;
+
+        let channel = Endpoint::from_shared(self.endpoint.clone())
+            .map_err(|e| AppError::Internal(format!("invalid LEDGER_GRPC_URL: {}", e)))?
+            .connect_timeout(self.timeout)
+            .timeout(self.timeout)
+            .connect()
+            .await
+            .map_err(|e| AppError::Internal(format!("ledger gRPC connect failed: {}", e)))?;
+
+        let mut client = LedgerServiceClient::new(channel);
+
+        let req = GetAccountBalanceRequest {
+            organization_id: organization_id.to_string(),
+            environment: env,
+            external_account_id,
+            currency,
+        };
+
+        let resp = tokio::time::timeout(
+            self.timeout,
+            client.get_account_balance(tonic::Request::new(req))
+        )
+        .await
+        .map_err(|_| AppError::Internal("ledger gRPC balance timeout expired".to_string()))?
+        .map_err(|e| AppError::Internal(format!("ledger gRPC balance failed: {}", e)))?
+        .into_inner();
+
+        resp.balance
+            .trim()
+            .parse::<rust_decimal::Decimal>()
+            .ok()
+            .and_then(|balance| i64::try_from(balance.trunc()).ok())
+            .ok_or_else(|| AppError::Internal(format!("ledger returned an invalid balance: {}", resp.balance)))
+    }
+}
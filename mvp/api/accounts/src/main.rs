@@ -1,14 +1,26 @@
+mod auth;
 mod config;
+mod environment;
 mod errors;
+mod event_stream;
+mod fx;
 mod grpc;
 mod handlers;
+mod hash_chain;
+mod iso20022;
 mod ledger;
 mod ledger_grpc;
+mod middleware;
 mod models;
+mod nacha;
+mod payout_rail;
 mod repositories;
 mod routes;
 mod services;
+#[cfg(test)]
+mod testing;
 mod utils;
+mod webhook;
 
 use axum::serve;
 use sqlx::postgres::PgPoolOptions;
@@ -17,7 +29,7 @@ use tokio::net::TcpListener;
 use tracing::info;
 use tracing_subscriber::prelude::*;
 
-use config::Settings;
+use config::{Command, Settings};
 use routes::create_router;
 use crate::ledger_grpc::LedgerGrpc;
 
@@ -28,6 +40,7 @@ use tonic::transport::Server;
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Load configuration
+    let command = Command::from_args()?;
     let settings = Settings::from_env()?;
 
     // Initialize Sentry before tracing to capture all errors
@@ -72,6 +85,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     info!("Connected to database");
 
+    // One-off commands only read; they must not migrate the database they inspect
+    if let Command::VerifyChain { organization_id } = command {
+        return verify_chain(&pool, organization_id).await;
+    }
+
     // Run migrations
     let mut migrator = sqlx::migrate!("./migrations_accounts");
     migrator.set_ignore_missing(true);
@@ -93,8 +111,38 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Ledger gRPC client wrapper (lazy connect per call)
     let ledger_grpc = LedgerGrpc::new(settings.ledger_grpc_url.clone());
 
+    // FX rate source for cross-currency transfers (FX_RATE_PROVIDER=database|file)
+    let fx_rates = crate::fx::FxRates::from_env(pool.clone())?;
+
+    // Payment rail for outbound payouts (PAYOUT_RAIL=simulated)
+    let payout_rail = crate::payout_rail::PayoutRails::from_env()?;
+
+    // HTTP client for outbound webhook deliveries
+    let webhook_client = crate::webhook::WebhookClient::from_env()?;
+
+    // Relays new event log entries to open event streams
+    let event_notifier = crate::event_stream::EventNotifier::new();
+
+    // Caller authentication: users-service access tokens and API keys (AUTH_MODE, USERS_GRPC_URL)
+    let authenticator = crate::auth::Authenticator::from_env()?;
+
+    // Per-caller token buckets (RATE_LIMIT_*_PER_MINUTE, overridden per organization)
+    let rate_limiter = crate::middleware::rate_limit::RateLimiter::from_env();
+
+    // Signs daily hash chain checkpoints (CHECKPOINT_SIGNING_KEY; none are created without it)
+    let checkpoint_signer = crate::hash_chain::CheckpointSigner::from_env()?;
+
     // Create router with Ledger gRPC config
-    let app = create_router(pool.clone(), ledger_grpc.clone());
+    let app = create_router(
+        pool.clone(),
+        authenticator,
+        ledger_grpc.clone(),
+        fx_rates,
+        payout_rail.clone(),
+        webhook_client.clone(),
+        event_notifier.clone(),
+        rate_limiter.clone(),
+    );
 
     let grpc_addr = SocketAddr::from(([0, 0, 0, 0], settings.grpc_port));
     let grpc_service = AccountsGrpcService::new(pool.clone());
@@ -106,6 +154,83 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         crate::services::transaction_retry::run(retry_pool, retry_ledger).await;
     });
 
+    // Background scheduler: auto-withdraw fixed savings plan installments
+    let savings_pool = pool.clone();
+    let savings_ledger = ledger_grpc.clone();
+    tokio::spawn(async move {
+        crate::services::savings_withdraw::run(savings_pool, savings_ledger).await;
+    });
+
+    // Background job: daily interest accrual and monthly posting for saving accounts
+    let interest_pool = pool.clone();
+    let interest_ledger = ledger_grpc.clone();
+    tokio::spawn(async move {
+        crate::services::interest_accrual::run(interest_pool, interest_ledger).await;
+    });
+
+    // Background worker: submit payouts to the rail and track settlement/returns
+    let payout_pool = pool.clone();
+    let payout_ledger = ledger_grpc.clone();
+    let payout_rail_worker = payout_rail.clone();
+    tokio::spawn(async move {
+        crate::services::payout_worker::run(payout_pool, payout_ledger, payout_rail_worker).await;
+    });
+
+    // Background job: resume account closures waiting on the Ledger, payouts or their sweep
+    let closure_pool = pool.clone();
+    let closure_ledger = ledger_grpc.clone();
+    let closure_payout_rail = payout_rail.clone();
+    tokio::spawn(async move {
+        crate::services::account_closure_service::run(closure_pool, closure_ledger, closure_payout_rail).await;
+    });
+
+    // Background job: store last month's statements once the month has ended
+    let statement_pool = pool.clone();
+    tokio::spawn(async move {
+        crate::services::statement_service::run(statement_pool).await;
+    });
+
+    // Background job: NACHA file per settlement window (PAYOUT_RAIL=nacha only)
+    let nacha_pool = pool.clone();
+    tokio::spawn(async move {
+        crate::services::nacha_service::run(nacha_pool, payout_rail).await;
+    });
+
+    // Background job: webhook fan-out and delivery with retries
+    let webhook_pool = pool.clone();
+    tokio::spawn(async move {
+        crate::services::webhook_service::run(webhook_pool, webhook_client).await;
+    });
+
+    // Background listener: database event notifications for live event streams
+    let event_stream_pool = pool.clone();
+    tokio::spawn(async move {
+        crate::event_stream::run(event_stream_pool, event_notifier).await;
+    });
+
+    // Background job: delete expired idempotency keys
+    let idempotency_pool = pool.clone();
+    tokio::spawn(async move {
+        crate::middleware::idempotency::run(idempotency_pool).await;
+    });
+
+    // Background job: reload per-organization rate limits and drop idle buckets
+    let rate_limit_pool = pool.clone();
+    tokio::spawn(async move {
+        crate::middleware::rate_limit::run(rate_limit_pool, rate_limiter).await;
+    });
+
+    // Background job: signed daily checkpoints of the hash chains
+    match checkpoint_signer {
+        Some(signer) => {
+            let checkpoint_pool = pool.clone();
+            tokio::spawn(async move {
+                crate::services::hash_chain_service::run(checkpoint_pool, signer).await;
+            });
+        }
+        None => info!("CHECKPOINT_SIGNING_KEY not configured, skipping hash chain checkpoints"),
+    }
+
     // Start server
     let addr = SocketAddr::from(([0, 0, 0, 0], settings.port));
     info!("Server starting on {}", addr);
@@ -114,7 +239,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let listener = TcpListener::bind(addr).await?;
 
     let http_task = async move {
-        serve(listener, app.into_make_service())
+        serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
             .await
             .map_err(|e| anyhow::anyhow!("HTTP server error: {}", e))
     };
@@ -131,3 +256,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// `accounts-api verify-chain [organization_id]`: print the chain reports as JSON and exit
+/// non-zero if any chain is broken.
+async fn verify_chain(
+    pool: &sqlx::PgPool,
+    organization_id: Option<uuid::Uuid>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let reports = match organization_id {
+        Some(organization_id) => vec![crate::services::HashChainService::verify(pool, organization_id).await?],
+        None => crate::services::HashChainService::verify_all(pool).await?,
+    };
+    println!("{}", serde_json::to_string_pretty(&reports)?);
+    if reports.iter().any(|report| !report.valid) {
+        std::process::exit(1);
+    }
+    Ok(())
+}
@@ -0,0 +1,190 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::{FromRequestParts, MatchedPath, RawPathParams, Request, State},
+    http::{header, Method},
+    middleware::Next,
+    response::Response,
+};
+use tracing::error;
+use uuid::Uuid;
+
+use crate::auth::{AuthContext, InternalCaller};
+use crate::environment::Environment;
+use crate::middleware::idempotency::IDEMPOTENT_REPLAYED_HEADER;
+use crate::models::{AccountResponse, NewAuditEvent};
+use crate::repositories::AccountRepository;
+use crate::routes::api::AppState;
+use crate::services::AuditService;
+
+/// Response bodies above this are recorded without `after` (matches axum's default body limit)
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Response fields never written to the audit trail
+const REDACTED_FIELDS: [&str; 1] = ["secret"];
+
+/// Audit action and target type of a mutating route (route relative to /api/v1).
+fn action(method: &Method, route: &str) -> Option<(&'static str, &'static str)> {
+    let action = match (method.as_str(), route) {
+        ("POST", "/accounts") => ("account.create", "account"),
+        ("PATCH", "/accounts/:id") => ("account.update", "account"),
+        ("DELETE", "/accounts/:id") => ("account.close", "account"),
+        ("POST", "/accounts/:id/close") => ("account.close_with_sweep", "account"),
+        ("POST", "/accounts/:id/deposit") => ("account.deposit", "account"),
+        ("POST", "/accounts/:id/withdraw") => ("account.withdraw", "account"),
+        ("POST", "/accounts/:id/transfer") => ("account.transfer", "account"),
+        ("POST", "/accounts/:id/payouts") => ("account.payout", "account"),
+        ("POST", "/transactions") => ("transaction.create", "transaction"),
+        ("POST", "/payees") => ("payee.create", "payee"),
+        ("DELETE", "/payees/:id") => ("payee.deactivate", "payee"),
+        ("PUT", "/interest-rate-configs") => ("interest_rate_config.upsert", "interest_rate_config"),
+        ("POST", "/fee-schedules") => ("fee_schedule.create", "fee_schedule"),
+        ("DELETE", "/fee-schedules/:id") => ("fee_schedule.deactivate", "fee_schedule"),
+        ("PUT", "/transaction-limits") => ("transaction_limit.upsert", "transaction_limit"),
+        ("DELETE", "/transaction-limits/:id") => ("transaction_limit.delete", "transaction_limit"),
+        ("POST", "/interorg-agreements") => ("interorg_agreement.create", "interorg_agreement"),
+        ("DELETE", "/interorg-agreements/:id") => ("interorg_agreement.revoke", "interorg_agreement"),
+        ("POST", "/interorg-agreements/:id/accept") => ("interorg_agreement.accept", "interorg_agreement"),
+        ("POST", "/webhook-endpoints") => ("webhook_endpoint.create", "webhook_endpoint"),
+        ("DELETE", "/webhook-endpoints/:id") => ("webhook_endpoint.disable", "webhook_endpoint"),
+        ("POST", "/webhook-deliveries/:id/redeliver") => ("webhook_delivery.redeliver", "webhook_delivery"),
+        ("POST", "/fx/quotes") => ("fx_quote.create", "fx_quote"),
+        ("POST", "/fx/quotes/:id/lock") => ("fx_quote.lock", "fx_quote"),
+        ("POST", "/nacha/files") => ("nacha_file.generate", "nacha_file"),
+        ("POST", "/nacha/returns") => ("nacha_returns.process", "nacha_return_file"),
+        _ => return None,
+    };
+    Some(action)
+}
+
+/// Records every mutating call of an authenticated caller in `audit_events`, whatever its
+/// outcome: the actor, the action, the target (the record named in the path, or the one
+/// created), the account's state before the call for account targets, the response body of
+/// a successful call, the correlation id and the client IP. Idempotent replays are not
+/// recorded again. Must run inside the rate limiter, which authenticates the caller.
+pub async fn audit_middleware(State(state): State<AppState>, req: Request, next: Next) -> Response {
+    if !matches!(*req.method(), Method::POST | Method::PUT | Method::PATCH | Method::DELETE) {
+        return next.run(req).await;
+    }
+
+    let (mut parts, body) = req.into_parts();
+
+    let route = parts
+        .extensions
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().trim_start_matches("/api/v1").to_string())
+        .unwrap_or_else(|| parts.uri.path().to_string());
+    let (action, target_type) = match action(&parts.method, &route) {
+        Some((action, target_type)) => (action.to_string(), target_type.to_string()),
+        None => (format!("{} {}", parts.method, route), "unknown".to_string()),
+    };
+
+    let auth = parts.extensions.get::<AuthContext>().cloned();
+    let (actor_type, actor_id, organization_id, environment) = match &auth {
+        Some(auth) => {
+            let environment = Environment::from_request_parts(&mut parts, &state).await.ok();
+            let (actor_type, actor_id) = auth.actor();
+            (actor_type, actor_id, Some(auth.business_id), environment)
+        }
+        None => {
+            if InternalCaller::from_request_parts(&mut parts, &state).await.is_err() {
+                // Unauthenticated; the handler rejects the call
+                return next.run(Request::from_parts(parts, body)).await;
+            }
+            let environment = state.authenticator.environment_mode.resolve(None, &parts.headers).ok();
+            ("internal_service", None, None, environment)
+        }
+    };
+
+    let path_target = RawPathParams::from_request_parts(&mut parts, &state)
+        .await
+        .ok()
+        .and_then(|params| {
+            params
+                .iter()
+                .find(|(name, _)| *name == "id" || *name == "account_id")
+                .and_then(|(_, value)| Uuid::parse_str(value).ok())
+        });
+
+    let before = match (target_type.as_str(), path_target, organization_id, environment) {
+        ("account", Some(id), Some(organization_id), Some(environment)) => {
+            AccountRepository::find_by_id_for_organization(&state.pool, id, organization_id, environment.as_str())
+                .await
+                .ok()
+                .and_then(|account| serde_json::to_value(AccountResponse::from(account)).ok())
+        }
+        _ => None,
+    };
+
+    let correlation_id = parts
+        .headers
+        .get("x-correlation-id")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string());
+    let ip_address = state.rate_limiter.client_ip(&parts).map(|ip| ip.to_string());
+
+    let response = next.run(Request::from_parts(parts, body)).await;
+
+    if response.headers().get(IDEMPOTENT_REPLAYED_HEADER).is_some_and(|v| v == "true") {
+        return response;
+    }
+
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+
+    let (response, after) = if response.status().is_success() && is_json {
+        let (response_parts, response_body) = response.into_parts();
+        match to_bytes(response_body, MAX_BODY_BYTES).await {
+            Ok(bytes) => {
+                let after = serde_json::from_slice::<serde_json::Value>(&bytes).ok().map(redact);
+                (Response::from_parts(response_parts, Body::from(bytes)), after)
+            }
+            Err(_) => (Response::from_parts(response_parts, Body::empty()), None),
+        }
+    } else {
+        (response, None)
+    };
+
+    let target_id = path_target.or_else(|| {
+        after
+            .as_ref()
+            .and_then(|after| after.get("id"))
+            .and_then(|id| id.as_str())
+            .and_then(|id| Uuid::parse_str(id).ok())
+    });
+
+    let event = NewAuditEvent {
+        organization_id,
+        environment: environment.map(|environment| environment.to_string()),
+        actor_type,
+        actor_id,
+        action,
+        target_type,
+        target_id,
+        before,
+        after,
+        status_code: i32::from(response.status().as_u16()),
+        correlation_id,
+        ip_address,
+    };
+
+    if let Err(e) = AuditService::record(&state.pool, &event).await {
+        error!(error = %e, action = %event.action, "audit_event_record_failed");
+        sentry::capture_message(&format!("audit event not recorded: {}", e), sentry::Level::Error);
+    }
+
+    response
+}
+
+fn redact(mut value: serde_json::Value) -> serde_json::Value {
+    if let Some(object) = value.as_object_mut() {
+        for field in REDACTED_FIELDS {
+            if let Some(redacted) = object.get_mut(field) {
+                *redacted = serde_json::Value::String("[redacted]".to_string());
+            }
+        }
+    }
+    value
+}
@@ -0,0 +1,293 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::{FromRequestParts, Request, State},
+    http::{header, HeaderValue, Method, StatusCode, Uri},
+    middleware::Next,
+    response::Response,
+};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+use crate::auth::AuthContext;
+use crate::environment::Environment;
+use crate::errors::AppError;
+use crate::models::StoredResponse;
+use crate::repositories::IdempotencyRepository;
+use crate::routes::api::AppState;
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// Request and response bodies above this are not handled (matches axum's default body limit)
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Idempotency-Key support for mutating endpoints. The first request with a key runs and its
+/// response is stored for IDEMPOTENCY_KEY_TTL_SECS; retries get the stored response with
+/// `Idempotent-Replayed: true`. A retry while the first request is still running gets 409,
+/// a key reused for a different request 422. 5xx responses are not stored, so the request
+/// can be retried with the same key. Requests without the header are passed through.
+pub async fn idempotency_middleware(
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    if !matches!(*req.method(), Method::POST | Method::PUT | Method::PATCH | Method::DELETE) {
+        return Ok(next.run(req).await);
+    }
+
+    let Some(key) = req
+        .headers()
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+    else {
+        return Ok(next.run(req).await);
+    };
+
+    if key.len() > 255 {
+        return Err(AppError::Validation("Idempotency-Key must be at most 255 characters".to_string()));
+    }
+
+    let (mut parts, body) = req.into_parts();
+    let auth = AuthContext::from_request_parts(&mut parts, &state).await?;
+    let environment = Environment::from_request_parts(&mut parts, &state).await?;
+    let tenant = tenant(&auth, environment);
+    let body = to_bytes(body, MAX_BODY_BYTES)
+        .await
+        .map_err(|_| AppError::Validation("request body is too large".to_string()))?;
+    let fingerprint = request_fingerprint(&parts.method, &parts.uri, &body);
+
+    let acquired = IdempotencyRepository::acquire(
+        &state.pool,
+        &tenant,
+        &key,
+        &fingerprint,
+        env_secs("IDEMPOTENCY_LOCK_SECS", 60),
+        env_secs("IDEMPOTENCY_KEY_TTL_SECS", 24 * 3600),
+    )
+    .await?;
+
+    if !acquired {
+        let record = IdempotencyRepository::find(&state.pool, &tenant, &key).await?;
+        return match record {
+            Some(record) if record.request_fingerprint != fingerprint => Err(AppError::IdempotencyConflict(format!(
+                "Idempotency-Key '{}' was already used for a different request",
+                key
+            ))),
+            Some(record) => match record.response {
+                Some(stored) => {
+                    info!(idempotency_key = %key, status = stored.status, "idempotent_replay");
+                    Ok(replay(stored))
+                }
+                None => Err(AppError::Conflict(
+                    "a request with this Idempotency-Key is still being processed".to_string(),
+                )),
+            },
+            // Expired or released between the two queries
+            None => Err(AppError::Conflict(
+                "a request with this Idempotency-Key is being retried; try again".to_string(),
+            )),
+        };
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+
+    if response.status().is_server_error() {
+        if let Err(e) = IdempotencyRepository::release(&state.pool, &tenant, &key).await {
+            warn!(idempotency_key = %key, error = %e, "idempotency_key_release_failed");
+        }
+        return Ok(response);
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = to_bytes(body, MAX_BODY_BYTES)
+        .await
+        .map_err(|e| AppError::Internal(format!("failed to read response body: {}", e)))?;
+
+    let stored = StoredResponse {
+        status: parts.status.as_u16(),
+        content_type: parts
+            .headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string),
+        body: body.to_vec(),
+    };
+
+    // The request has taken effect; a failure to store it only means a retry runs it again
+    // once the lock expires
+    if let Err(e) = IdempotencyRepository::complete(&state.pool, &tenant, &key, &stored).await {
+        warn!(idempotency_key = %key, error = %e, "idempotency_response_store_failed");
+    }
+
+    Ok(Response::from_parts(parts, Body::from(body)))
+}
+
+/// Scope of idempotency keys: the caller's business and environment.
+fn tenant(auth: &AuthContext, environment: Environment) -> String {
+    format!("accounts:{}:{}", auth.business_id, environment)
+}
+
+/// SHA-256 (hex) of method, path and query, and body.
+fn request_fingerprint(method: &Method, uri: &Uri, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_str().as_bytes());
+    hasher.update(b"\n");
+    hasher.update(uri.path_and_query().map(|p| p.as_str()).unwrap_or_default().as_bytes());
+    hasher.update(b"\n");
+    hasher.update(body);
+    hex::encode(hasher.finalize())
+}
+
+fn replay(stored: StoredResponse) -> Response {
+    let mut response = Response::new(Body::from(stored.body));
+    *response.status_mut() = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+    if let Some(content_type) = stored.content_type.and_then(|v| HeaderValue::from_str(&v).ok()) {
+        response.headers_mut().insert(header::CONTENT_TYPE, content_type);
+    }
+    response
+        .headers_mut()
+        .insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
+
+fn env_secs(name: &str, default: i64) -> i64 {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(default)
+}
+
+/// Background job deleting expired idempotency keys (hourly).
+pub async fn run(pool: PgPool) {
+    info!("Idempotency key cleanup started");
+
+    loop {
+        match IdempotencyRepository::purge_expired(&pool).await {
+            Ok(0) => {}
+            Ok(deleted) => info!(deleted, "idempotency_keys_purged"),
+            Err(e) => warn!(error = %e, "idempotency_key_purge_failed"),
+        }
+
+        tokio::time::sleep(std::time::Duration::from_secs(3600)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use axum::{middleware::from_fn_with_state, routing::post, Router};
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::testing;
+
+    /// Answers with the status in `x-respond-with` (201 by default) and counts the requests
+    /// that reach it.
+    fn app(state: AppState, calls: Arc<AtomicUsize>) -> Router {
+        let handler = move |headers: axum::http::HeaderMap| async move {
+            let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+            let status = headers
+                .get("x-respond-with")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u16>().ok())
+                .unwrap_or(201);
+            (StatusCode::from_u16(status).unwrap(), format!("call {}", call))
+        };
+
+        Router::new()
+            .route("/things", post(handler))
+            .route_layer(from_fn_with_state(state.clone(), idempotency_middleware))
+            .with_state(state)
+    }
+
+    fn auth(business_id: Uuid) -> AuthContext {
+        AuthContext { business_id, user_id: None, api_key_id: None, environment: None }
+    }
+
+    fn request(business_id: Uuid, key: &str, body: &'static str, respond_with: Option<u16>) -> Request {
+        let mut builder = Request::builder()
+            .method(Method::POST)
+            .uri("/things")
+            .header(IDEMPOTENCY_KEY_HEADER, key)
+            .extension(auth(business_id))
+            .extension(Environment::Sandbox);
+        if let Some(status) = respond_with {
+            builder = builder.header("x-respond-with", status.to_string());
+        }
+        builder.body(Body::from(body)).unwrap()
+    }
+
+    async fn send(app: &Router, request: Request) -> (StatusCode, Option<HeaderValue>, String) {
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let replayed = response.headers().get(IDEMPOTENT_REPLAYED_HEADER).cloned();
+        let body = to_bytes(response.into_body(), MAX_BODY_BYTES).await.unwrap();
+        (status, replayed, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[sqlx::test(migrations = "./migrations_accounts")]
+    #[ignore = "needs Postgres: DATABASE_URL=... cargo test -- --ignored"]
+    async fn replays_the_stored_response_and_rejects_a_different_request(pool: PgPool) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(testing::state(pool), calls.clone());
+        let business_id = Uuid::new_v4();
+
+        let first = send(&app, request(business_id, "key-1", "{\"a\":1}", None)).await;
+        assert_eq!(first, (StatusCode::CREATED, None, "call 1".to_string()));
+
+        let replay = send(&app, request(business_id, "key-1", "{\"a\":1}", None)).await;
+        assert_eq!(replay, (StatusCode::CREATED, Some(HeaderValue::from_static("true")), "call 1".to_string()));
+
+        let (status, _, _) = send(&app, request(business_id, "key-1", "{\"a\":2}", None)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        // Keys are scoped to the business
+        let (status, replayed, _) = send(&app, request(Uuid::new_v4(), "key-1", "{\"a\":2}", None)).await;
+        assert_eq!((status, replayed), (StatusCode::CREATED, None));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[sqlx::test(migrations = "./migrations_accounts")]
+    #[ignore = "needs Postgres: DATABASE_URL=... cargo test -- --ignored"]
+    async fn a_request_in_flight_holds_the_key(pool: PgPool) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(testing::state(pool.clone()), calls.clone());
+        let business_id = Uuid::new_v4();
+
+        // Another instance is still running the first request with this key
+        let fingerprint = request_fingerprint(&Method::POST, &"/things".parse().unwrap(), b"{\"a\":1}");
+        let tenant = tenant(&auth(business_id), Environment::Sandbox);
+        assert!(IdempotencyRepository::acquire(&pool, &tenant, "key-1", &fingerprint, 60, 3600).await.unwrap());
+
+        let (status, _, _) = send(&app, request(business_id, "key-1", "{\"a\":1}", None)).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[sqlx::test(migrations = "./migrations_accounts")]
+    #[ignore = "needs Postgres: DATABASE_URL=... cargo test -- --ignored"]
+    async fn server_errors_release_the_key(pool: PgPool) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(testing::state(pool), calls.clone());
+        let business_id = Uuid::new_v4();
+
+        let (status, _, _) = send(&app, request(business_id, "key-1", "{\"a\":1}", Some(503))).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+
+        let retry = send(&app, request(business_id, "key-1", "{\"a\":1}", None)).await;
+        assert_eq!(retry, (StatusCode::CREATED, None, "call 2".to_string()));
+
+        // Client errors are stored like successes
+        let (status, _, _) = send(&app, request(business_id, "key-2", "{\"a\":1}", Some(400))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, replayed, _) = send(&app, request(business_id, "key-2", "{\"a\":1}", None)).await;
+        assert_eq!((status, replayed), (StatusCode::BAD_REQUEST, Some(HeaderValue::from_static("true"))));
+    }
+}
@@ -1,2 +1,6 @@
-// Middleware module - placeholder for future middleware
+// Middleware module
 // Examples: logging, authentication, rate limiting, etc.
+
+pub mod idempotency;
+pub mod audit;
+pub mod rate_limit;
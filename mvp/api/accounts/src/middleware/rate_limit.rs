@@ -0,0 +1,599 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{ConnectInfo, FromRequestParts, Request, State},
+    http::{header, request::Parts, HeaderMap, HeaderValue, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sqlx::PgPool;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::auth::AuthContext;
+use crate::errors::AppError;
+use crate::repositories::RateLimitRepository;
+use crate::routes::api::AppState;
+
+/// How often organization overrides are reloaded and idle buckets dropped
+const RELOAD_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Most buckets kept between reloads. Requests failing authentication get a bucket per client
+/// IP, so without a cap a flood from many addresses grows the map until the next reload.
+const MAX_BUCKETS: usize = 100_000;
+
+/// Requests that share a budget. Safe requests (GET, HEAD) always count as reads.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RouteClass {
+    Read,
+    Write,
+    MoneyMovement,
+}
+
+impl RouteClass {
+    const ALL: [RouteClass; 3] = [RouteClass::Read, RouteClass::Write, RouteClass::MoneyMovement];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RouteClass::Read => "read",
+            RouteClass::Write => "write",
+            RouteClass::MoneyMovement => "money_movement",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|class| class.as_str() == value)
+    }
+
+    /// Default requests per minute (RATE_LIMIT_<CLASS>_PER_MINUTE); the burst is the same
+    fn default_budget(&self) -> Budget {
+        let (name, default) = match self {
+            RouteClass::Read => ("RATE_LIMIT_READ_PER_MINUTE", 600),
+            RouteClass::Write => ("RATE_LIMIT_WRITE_PER_MINUTE", 120),
+            RouteClass::MoneyMovement => ("RATE_LIMIT_MONEY_MOVEMENT_PER_MINUTE", 60),
+        };
+        let per_minute = std::env::var(name)
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(default);
+
+        Budget {
+            per_minute,
+            burst: per_minute,
+        }
+    }
+}
+
+/// Token bucket shape: holds up to `burst` requests, refilled at `per_minute`.
+#[derive(Clone, Copy, Debug)]
+struct Budget {
+    per_minute: u32,
+    burst: u32,
+}
+
+impl Budget {
+    fn refill_per_sec(&self) -> f64 {
+        f64::from(self.per_minute) / 60.0
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+    budget: Budget,
+}
+
+impl Bucket {
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.budget.refill_per_sec()).min(f64::from(self.budget.burst));
+        self.refilled_at = now;
+    }
+
+    fn is_full(&self, now: Instant) -> bool {
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens + elapsed * self.budget.refill_per_sec() >= f64::from(self.budget.burst)
+    }
+}
+
+/// Result of taking a request from a bucket, reported in `RateLimit-*` headers.
+struct Outcome {
+    allowed: bool,
+    budget: Budget,
+    remaining: u32,
+    /// Seconds until the bucket is full again
+    reset_secs: u64,
+    /// Seconds until the next request is allowed (rejected requests only)
+    retry_after_secs: u64,
+}
+
+impl Outcome {
+    fn apply(&self, headers: &mut HeaderMap) {
+        headers.insert("ratelimit-limit", HeaderValue::from(self.budget.burst));
+        headers.insert("ratelimit-remaining", HeaderValue::from(self.remaining));
+        headers.insert("ratelimit-reset", HeaderValue::from(self.reset_secs));
+        if let Ok(policy) = HeaderValue::from_str(&format!(
+            "{};w=60;burst={}",
+            self.budget.per_minute, self.budget.burst
+        )) {
+            headers.insert("ratelimit-policy", policy);
+        }
+        if !self.allowed {
+            headers.insert(header::RETRY_AFTER, HeaderValue::from(self.retry_after_secs));
+        }
+    }
+}
+
+/// In-memory token buckets, one per caller and route class, so limits apply per instance.
+/// Budgets come from RATE_LIMIT_*_PER_MINUTE unless the organization has a row in
+/// `rate_limits`.
+#[derive(Clone)]
+pub struct RateLimiter {
+    defaults: HashMap<RouteClass, Budget>,
+    overrides: Arc<RwLock<HashMap<(Uuid, RouteClass), Budget>>>,
+    buckets: Arc<Mutex<HashMap<String, Bucket>>>,
+    /// Proxies in front of the service that append to X-Forwarded-For (TRUSTED_PROXY_HOPS)
+    trusted_proxy_hops: usize,
+}
+
+impl RateLimiter {
+    pub fn from_env() -> Self {
+        Self {
+            defaults: RouteClass::ALL.into_iter().map(|class| (class, class.default_budget())).collect(),
+            overrides: Arc::new(RwLock::new(HashMap::new())),
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+            trusted_proxy_hops: std::env::var("TRUSTED_PROXY_HOPS")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(0),
+        }
+    }
+
+    fn budget(&self, organization_id: Option<Uuid>, class: RouteClass) -> Budget {
+        organization_id
+            .and_then(|id| {
+                self.overrides
+                    .read()
+                    .expect("rate limit overrides lock poisoned")
+                    .get(&(id, class))
+                    .copied()
+            })
+            .unwrap_or(self.defaults[&class])
+    }
+
+    /// Takes a request from every bucket, or from none if any of them is empty. The outcome
+    /// reported is the tightest one: the emptiest bucket, or the longest wait.
+    fn take(&self, limits: &[(String, Budget)]) -> Outcome {
+        self.take_at(limits, Instant::now())
+    }
+
+    fn take_at(&self, limits: &[(String, Budget)], now: Instant) -> Outcome {
+        let mut buckets = self.buckets.lock().expect("rate limit buckets lock poisoned");
+        if buckets.len() >= MAX_BUCKETS && limits.iter().any(|(key, _)| !buckets.contains_key(key)) {
+            make_room(&mut buckets, now);
+        }
+        for (key, budget) in limits {
+            let bucket = buckets.entry(key.clone()).or_insert_with(|| Bucket {
+                tokens: f64::from(budget.burst),
+                refilled_at: now,
+                budget: *budget,
+            });
+            bucket.budget = *budget;
+            bucket.refill(now);
+        }
+
+        let allowed = limits.iter().all(|(key, _)| buckets[key].tokens >= 1.0);
+        limits
+            .iter()
+            .map(|(key, budget)| {
+                let bucket = buckets.get_mut(key).expect("rate limit bucket created above");
+                if allowed {
+                    bucket.tokens -= 1.0;
+                }
+
+                let rate = budget.refill_per_sec();
+                Outcome {
+                    allowed,
+                    budget: *budget,
+                    remaining: bucket.tokens.floor() as u32,
+                    reset_secs: ((f64::from(budget.burst) - bucket.tokens) / rate).ceil() as u64,
+                    retry_after_secs: ((1.0 - bucket.tokens) / rate).ceil().max(1.0) as u64,
+                }
+            })
+            .reduce(|tightest, outcome| {
+                let tighter = if allowed {
+                    outcome.remaining < tightest.remaining
+                } else {
+                    outcome.retry_after_secs > tightest.retry_after_secs
+                };
+                if tighter {
+                    outcome
+                } else {
+                    tightest
+                }
+            })
+            .expect("at least one rate limit")
+    }
+
+    /// The peer address, or with TRUSTED_PROXY_HOPS set, the address the outermost trusted
+    /// proxy saw (entries further left in X-Forwarded-For can be forged by the client).
+    pub(crate) fn client_ip(&self, parts: &Parts) -> Option<IpAddr> {
+        if self.trusted_proxy_hops > 0 {
+            let forwarded: Vec<&str> = parts
+                .headers
+                .get_all("x-forwarded-for")
+                .iter()
+                .filter_map(|v| v.to_str().ok())
+                .flat_map(|v| v.split(','))
+                .map(str::trim)
+                .collect();
+            if let Some(ip) = forwarded
+                .len()
+                .checked_sub(self.trusted_proxy_hops)
+                .and_then(|i| forwarded[i].parse().ok())
+            {
+                return Some(ip);
+            }
+        }
+
+        parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip())
+    }
+
+    async fn reload(&self, pool: &PgPool) -> Result<(), AppError> {
+        let overrides: HashMap<(Uuid, RouteClass), Budget> = RateLimitRepository::find_all(pool)
+            .await?
+            .into_iter()
+            .filter_map(|policy| {
+                let class = RouteClass::parse(&policy.route_class)?;
+                let budget = Budget {
+                    per_minute: u32::try_from(policy.requests_per_minute).ok()?,
+                    burst: u32::try_from(policy.burst).ok()?,
+                };
+                Some(((policy.organization_id, class), budget))
+            })
+            .collect();
+
+        *self.overrides.write().expect("rate limit overrides lock poisoned") = overrides;
+        Ok(())
+    }
+
+    /// Drop buckets that have refilled completely; they are recreated full when needed.
+    fn evict_full(&self) {
+        let now = Instant::now();
+        self.buckets
+            .lock()
+            .expect("rate limit buckets lock poisoned")
+            .retain(|_, bucket| !bucket.is_full(now));
+    }
+}
+
+/// Drop buckets that have refilled completely and, if the map is still at MAX_BUCKETS, the
+/// tenth that has gone longest without a request, so a full map is not scanned on every
+/// new caller.
+fn make_room(buckets: &mut HashMap<String, Bucket>, now: Instant) {
+    buckets.retain(|_, bucket| !bucket.is_full(now));
+    if buckets.len() < MAX_BUCKETS {
+        return;
+    }
+
+    let mut last_used: Vec<Instant> = buckets.values().map(|bucket| bucket.refilled_at).collect();
+    let (_, cutoff, _) = last_used.select_nth_unstable(MAX_BUCKETS / 10);
+    let cutoff = *cutoff;
+    buckets.retain(|_, bucket| bucket.refilled_at > cutoff);
+}
+
+/// Token-bucket rate limiting. Authenticated requests are counted per API key or user (per
+/// organization for credentials that carry neither), within the organization's budget for
+/// the route class; requests failing authentication are counted per client IP. Every
+/// response carries `RateLimit-*` headers; a request over budget gets 429 with `Retry-After`.
+pub async fn rate_limit_middleware(
+    State((state, class)): State<(AppState, RouteClass)>,
+    req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let class = if matches!(*req.method(), Method::GET | Method::HEAD) {
+        RouteClass::Read
+    } else {
+        class
+    };
+
+    let (mut parts, body) = req.into_parts();
+    let limiter = &state.rate_limiter;
+    let auth = AuthContext::from_request_parts(&mut parts, &state).await;
+
+    let (caller, organization_id) = match &auth {
+        Ok(auth) => match (auth.api_key_id, auth.user_id) {
+            (Some(api_key_id), _) => (format!("key:{}", api_key_id), Some(auth.business_id)),
+            (None, Some(user_id)) => (format!("user:{}", user_id), Some(auth.business_id)),
+            (None, None) => (format!("org:{}", auth.business_id), Some(auth.business_id)),
+        },
+        Err(_) => match limiter.client_ip(&parts) {
+            Some(ip) => (format!("ip:{}", ip), None),
+            None => ("ip:unknown".to_string(), None),
+        },
+    };
+
+    // Each caller gets the organization's budget, and all callers of the organization share
+    // it too, so more API keys or users do not raise the organization's limit
+    let budget = limiter.budget(organization_id, class);
+    let mut limits = vec![(format!("{}:{}", class.as_str(), caller), budget)];
+    if let Some(organization_id) = organization_id {
+        let organization = format!("{}:org:{}", class.as_str(), organization_id);
+        if limits[0].0 != organization {
+            limits.push((organization, budget));
+        }
+    }
+    let outcome = limiter.take(&limits);
+
+    if !outcome.allowed {
+        warn!(caller = %caller, route_class = class.as_str(), "rate_limited");
+        let mut response = AppError::RateLimited(format!(
+            "too many {} requests; retry in {} seconds",
+            class.as_str().replace('_', " "),
+            outcome.retry_after_secs
+        ))
+        .into_response();
+        outcome.apply(response.headers_mut());
+        return Ok(response);
+    }
+
+    let mut response = match auth {
+        Ok(_) => next.run(Request::from_parts(parts, body)).await,
+        Err(e) => e.into_response(),
+    };
+    outcome.apply(response.headers_mut());
+    Ok(response)
+}
+
+/// Background job reloading organization overrides and dropping idle buckets (every minute).
+pub async fn run(pool: PgPool, limiter: RateLimiter) {
+    info!("Rate limit policy reload started");
+
+    loop {
+        if let Err(e) = limiter.reload(&pool).await {
+            warn!(error = %e, "rate_limit_policy_reload_failed");
+        }
+        limiter.evict_full();
+
+        tokio::time::sleep(RELOAD_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, middleware::from_fn_with_state, routing::post, Router};
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::testing;
+
+    fn limiter(trusted_proxy_hops: usize) -> RateLimiter {
+        RateLimiter {
+            defaults: RouteClass::ALL
+                .into_iter()
+                .map(|class| (class, Budget { per_minute: 60, burst: 2 }))
+                .collect(),
+            overrides: Arc::new(RwLock::new(HashMap::new())),
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+            trusted_proxy_hops,
+        }
+    }
+
+    fn parts(forwarded_for: &[&str]) -> Parts {
+        let mut builder = Request::builder().extension(ConnectInfo(SocketAddr::from(([10, 0, 0, 9], 4000))));
+        for value in forwarded_for {
+            builder = builder.header("x-forwarded-for", *value);
+        }
+        builder.body(()).unwrap().into_parts().0
+    }
+
+    #[test]
+    fn tokens_refill_at_the_budget_rate() {
+        let limiter = limiter(0);
+        let budget = Budget { per_minute: 60, burst: 2 };
+        let start = Instant::now();
+
+        assert!(limiter.take_at(&[("k".into(), budget)], start).allowed);
+        let second = limiter.take_at(&[("k".into(), budget)], start);
+        assert!(second.allowed);
+        assert_eq!(second.remaining, 0);
+        assert_eq!(second.reset_secs, 2);
+        assert!(!limiter.take_at(&[("k".into(), budget)], start).allowed);
+
+        // One token per second; the bucket never holds more than the burst
+        assert!(limiter.take_at(&[("k".into(), budget)], start + Duration::from_secs(1)).allowed);
+        assert!(!limiter.take_at(&[("k".into(), budget)], start + Duration::from_secs(1)).allowed);
+        let later = limiter.take_at(&[("k".into(), budget)], start + Duration::from_secs(600));
+        assert!(later.allowed);
+        assert_eq!(later.remaining, 1);
+
+        // Other callers have their own bucket
+        assert!(limiter.take_at(&[("other".into(), budget)], start).allowed);
+    }
+
+    #[test]
+    fn rejected_requests_carry_retry_after() {
+        let limiter = limiter(0);
+        let budget = Budget { per_minute: 6, burst: 1 };
+        let start = Instant::now();
+
+        let allowed = limiter.take_at(&[("k".into(), budget)], start);
+        let mut headers = HeaderMap::new();
+        allowed.apply(&mut headers);
+        assert_eq!(headers["ratelimit-limit"], "1");
+        assert_eq!(headers["ratelimit-remaining"], "0");
+        assert_eq!(headers["ratelimit-policy"], "6;w=60;burst=1");
+        assert!(headers.get(header::RETRY_AFTER).is_none());
+
+        let rejected = limiter.take_at(&[("k".into(), budget)], start + Duration::from_secs(4));
+        assert!(!rejected.allowed);
+        let mut headers = HeaderMap::new();
+        rejected.apply(&mut headers);
+        assert_eq!(headers[header::RETRY_AFTER], "6");
+        assert_eq!(headers["ratelimit-reset"], "6");
+    }
+
+    #[test]
+    fn organization_overrides_replace_the_default_budget() {
+        let limiter = limiter(0);
+        let organization_id = Uuid::new_v4();
+        limiter
+            .overrides
+            .write()
+            .unwrap()
+            .insert((organization_id, RouteClass::Write), Budget { per_minute: 1000, burst: 50 });
+
+        assert_eq!(limiter.budget(Some(organization_id), RouteClass::Write).burst, 50);
+        assert_eq!(limiter.budget(Some(organization_id), RouteClass::Read).burst, 2);
+        assert_eq!(limiter.budget(Some(Uuid::new_v4()), RouteClass::Write).burst, 2);
+        assert_eq!(limiter.budget(None, RouteClass::Write).burst, 2);
+    }
+
+    #[test]
+    fn forwarded_for_is_ignored_without_trusted_proxies() {
+        let ip = limiter(0).client_ip(&parts(&["203.0.113.7"]));
+        assert_eq!(ip, Some(IpAddr::from([10, 0, 0, 9])));
+    }
+
+    #[test]
+    fn forged_forwarded_for_entries_are_skipped() {
+        // The client sent "198.51.100.1"; the single trusted proxy appended the address it saw
+        let one_hop = limiter(1);
+        assert_eq!(
+            one_hop.client_ip(&parts(&["198.51.100.1, 203.0.113.7"])),
+            Some(IpAddr::from([203, 0, 113, 7]))
+        );
+        assert_eq!(
+            one_hop.client_ip(&parts(&["198.51.100.1", "203.0.113.7"])),
+            Some(IpAddr::from([203, 0, 113, 7]))
+        );
+
+        let two_hops = limiter(2);
+        assert_eq!(
+            two_hops.client_ip(&parts(&["198.51.100.1, 203.0.113.7, 10.0.0.2"])),
+            Some(IpAddr::from([203, 0, 113, 7]))
+        );
+
+        // Fewer entries than trusted hops, or garbage, falls back to the peer address
+        assert_eq!(two_hops.client_ip(&parts(&["203.0.113.7"])), Some(IpAddr::from([10, 0, 0, 9])));
+        assert_eq!(one_hop.client_ip(&parts(&["not-an-ip"])), Some(IpAddr::from([10, 0, 0, 9])));
+        assert_eq!(one_hop.client_ip(&parts(&[])), Some(IpAddr::from([10, 0, 0, 9])));
+    }
+
+    #[test]
+    fn buckets_are_capped() {
+        let limiter = limiter(0);
+        let budget = Budget { per_minute: 60, burst: 2 };
+        let start = Instant::now();
+        {
+            let mut buckets = limiter.buckets.lock().unwrap();
+            for i in 0..MAX_BUCKETS {
+                let refilled_at = start + Duration::from_millis(i as u64);
+                buckets.insert(format!("ip:{}", i), Bucket { tokens: 0.0, refilled_at, budget });
+            }
+        }
+
+        let now = start + Duration::from_millis(MAX_BUCKETS as u64);
+        assert!(limiter.take_at(&[("ip:new".into(), budget)], now).allowed);
+
+        let buckets = limiter.buckets.lock().unwrap();
+        assert!(buckets.len() <= MAX_BUCKETS);
+        assert!(buckets.contains_key("ip:new"));
+        assert!(!buckets.contains_key("ip:0"));
+        assert!(buckets.contains_key(&format!("ip:{}", MAX_BUCKETS - 1)));
+    }
+
+    #[test]
+    fn callers_share_the_organization_budget() {
+        let limiter = limiter(0);
+        let budget = Budget { per_minute: 60, burst: 2 };
+        let start = Instant::now();
+        let limits = |caller: &str| [(caller.to_string(), budget), ("org".to_string(), budget)];
+
+        assert!(limiter.take_at(&limits("key:1"), start).allowed);
+        assert!(limiter.take_at(&limits("key:2"), start).allowed);
+
+        // key:1 has a token left but the organization has none; nothing is taken
+        let rejected = limiter.take_at(&limits("key:1"), start);
+        assert!(!rejected.allowed);
+        assert_eq!(rejected.retry_after_secs, 1);
+
+        let allowed = limiter.take_at(&limits("key:1"), start + Duration::from_secs(1));
+        assert!(allowed.allowed);
+        assert_eq!(allowed.remaining, 0);
+        let buckets = limiter.buckets.lock().unwrap();
+        assert_eq!(buckets["key:1"].tokens, 1.0);
+        assert_eq!(buckets["key:2"].tokens, 1.0);
+    }
+
+    /// Write route answering "ok" behind the rate limiter (2 requests per class and caller).
+    fn app(pool: PgPool) -> Router {
+        let mut state = testing::state(pool);
+        state.rate_limiter = limiter(0);
+        Router::new()
+            .route("/things", post(|| async { "ok" }))
+            .route_layer(from_fn_with_state((state.clone(), RouteClass::Write), rate_limit_middleware))
+            .with_state(state)
+    }
+
+    #[sqlx::test(migrations = "./migrations_accounts")]
+    #[ignore = "needs Postgres: DATABASE_URL=... cargo test -- --ignored"]
+    async fn api_keys_of_an_organization_share_its_budget(pool: PgPool) {
+        let app = app(pool);
+        let request = |business_id: Uuid, api_key_id: Uuid| {
+            let auth = AuthContext { business_id, user_id: None, api_key_id: Some(api_key_id), environment: None };
+            Request::builder().method(Method::POST).uri("/things").extension(auth).body(Body::empty()).unwrap()
+        };
+        let organization_id = Uuid::new_v4();
+        let (first_key, second_key) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let response = app.clone().oneshot(request(organization_id, first_key)).await.unwrap();
+        assert_eq!(response.status(), 200);
+        let response = app.clone().oneshot(request(organization_id, second_key)).await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.headers()["ratelimit-remaining"], "0");
+
+        // A new key of the same organization gets nothing more
+        let response = app.clone().oneshot(request(organization_id, Uuid::new_v4())).await.unwrap();
+        assert_eq!(response.status(), 429);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "1");
+
+        // Other organizations have their own budget
+        let response = app.clone().oneshot(request(Uuid::new_v4(), first_key)).await.unwrap();
+        assert_eq!(response.status(), 200);
+    }
+
+    #[sqlx::test(migrations = "./migrations_accounts")]
+    #[ignore = "needs Postgres: DATABASE_URL=... cargo test -- --ignored"]
+    async fn unauthenticated_requests_are_limited_per_client_ip(pool: PgPool) {
+        let app = app(pool);
+        let request = |ip: [u8; 4]| {
+            Request::builder()
+                .method(Method::POST)
+                .uri("/things")
+                .extension(ConnectInfo(SocketAddr::from((ip, 4000))))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        for remaining in ["1", "0"] {
+            let response = app.clone().oneshot(request([203, 0, 113, 7])).await.unwrap();
+            assert_eq!(response.status(), 401);
+            assert_eq!(response.headers()["ratelimit-remaining"], remaining);
+        }
+
+        let response = app.clone().oneshot(request([203, 0, 113, 7])).await.unwrap();
+        assert_eq!(response.status(), 429);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "1");
+
+        let response = app.clone().oneshot(request([203, 0, 113, 8])).await.unwrap();
+        assert_eq!(response.status(), 401);
+    }
+}
@@ -3,6 +3,10 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use crate::errors::AppError;
+use crate::models::{Currency, Metadata};
+use crate::utils::pagination::edge_cursors;
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Account {
     pub id: Uuid,
@@ -20,12 +24,29 @@ pub struct Account {
     pub user_role: Option<String>,
     pub currency: Option<String>,
     pub status: Option<AccountStatus>,
+    /// Display name chosen by the client
+    pub nickname: Option<String>,
+    #[serde(default)]
+    #[sqlx(json)]
+    pub metadata: Metadata,
     #[serde(rename = "created_at")]
     pub created_at: Option<DateTime<Utc>>,
     #[serde(rename = "updated_at")]
     pub updated_at: Option<DateTime<Utc>>,
 }
 
+impl Account {
+    /// Account currency; legacy rows without one are USD.
+    pub fn currency(&self) -> Result<Currency, AppError> {
+        match self.currency.as_deref() {
+            Some(code) => Currency::from_code(code).ok_or_else(|| {
+                AppError::Internal(format!("account {} has unsupported currency {}", self.id, code))
+            }),
+            None => Ok(Currency::USD),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "varchar", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
@@ -39,35 +60,166 @@ pub enum AccountType {
 pub enum AccountStatus {
     Active,
     Suspended,
+    /// Being closed by the closure workflow: no new intents while the balance is swept
+    Closing,
     Closed,
 }
 
+impl AccountStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountStatus::Active => "active",
+            AccountStatus::Suspended => "suspended",
+            AccountStatus::Closing => "closing",
+            AccountStatus::Closed => "closed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "active" => Some(AccountStatus::Active),
+            "suspended" => Some(AccountStatus::Suspended),
+            "closing" => Some(AccountStatus::Closing),
+            "closed" => Some(AccountStatus::Closed),
+            _ => None,
+        }
+    }
+
+    /// Lifecycle transitions: active and suspended move between each other, to closing (a
+    /// closure started) or to closed; closing only moves on to closed, which is final.
+    pub fn can_transition_to(self, next: AccountStatus) -> bool {
+        matches!(
+            (self, next),
+            (AccountStatus::Active, AccountStatus::Suspended)
+                | (AccountStatus::Suspended, AccountStatus::Active)
+                | (AccountStatus::Active, AccountStatus::Closing)
+                | (AccountStatus::Suspended, AccountStatus::Closing)
+                | (AccountStatus::Active, AccountStatus::Closed)
+                | (AccountStatus::Suspended, AccountStatus::Closed)
+                | (AccountStatus::Closing, AccountStatus::Closed)
+        )
+    }
+
+    /// Suspending and closing must say why.
+    pub fn requires_reason(self) -> bool {
+        matches!(self, AccountStatus::Suspended | AccountStatus::Closing | AccountStatus::Closed)
+    }
+}
+
+/// Reason code of a status change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusReason {
+    CustomerRequest,
+    FraudSuspected,
+    ComplianceHold,
+    Dormant,
+    ChargedOff,
+    Other,
+}
+
+impl StatusReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatusReason::CustomerRequest => "customer_request",
+            StatusReason::FraudSuspected => "fraud_suspected",
+            StatusReason::ComplianceHold => "compliance_hold",
+            StatusReason::Dormant => "dormant",
+            StatusReason::ChargedOff => "charged_off",
+            StatusReason::Other => "other",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        [
+            StatusReason::CustomerRequest,
+            StatusReason::FraudSuspected,
+            StatusReason::ComplianceHold,
+            StatusReason::Dormant,
+            StatusReason::ChargedOff,
+            StatusReason::Other,
+        ]
+        .into_iter()
+        .find(|reason| reason.as_str() == value)
+    }
+}
+
+/// One entry of an account's status history.
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountStatusChange {
+    pub id: Uuid,
+    #[serde(rename = "account_id")]
+    pub account_id: Uuid,
+    #[serde(rename = "from_status")]
+    pub from_status: AccountStatus,
+    #[serde(rename = "to_status")]
+    pub to_status: AccountStatus,
+    pub reason: Option<StatusReason>,
+    pub note: Option<String>,
+    /// `user` or `api_key`
+    #[serde(rename = "actor_type")]
+    pub actor_type: String,
+    #[serde(rename = "actor_id")]
+    pub actor_id: Option<Uuid>,
+    #[serde(rename = "created_at")]
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CreateAccountRequest {
     // account_number is auto-generated, not provided by user
     pub account_type: AccountType,
     #[serde(default)]
     pub organization_id: Option<Uuid>,
-    #[serde(default = "default_environment")]
+    #[serde(default)]
     pub environment: Option<String>,
     pub user_id: Uuid,
     #[serde(default = "default_currency")]
-    pub currency: String,
+    pub currency: Currency,
     #[serde(default)]
     pub admin_user_id: Option<Uuid>,  // Required for customer accounts
+    #[serde(default)]
+    pub nickname: Option<String>,
+    #[serde(default)]
+    pub metadata: Metadata,
 }
 
-fn default_currency() -> String {
-    "USD".to_string()
+fn default_currency() -> Currency {
+    Currency::USD
 }
 
-fn default_environment() -> Option<String> {
-    Some("sandbox".to_string())
+/// A status change to apply and record.
+#[derive(Debug, Clone)]
+pub struct NewAccountStatusChange {
+    pub from_status: AccountStatus,
+    pub to_status: AccountStatus,
+    pub reason: Option<StatusReason>,
+    pub note: Option<String>,
+    pub actor_type: String,
+    pub actor_id: Option<Uuid>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateAccountRequest {
     pub status: Option<AccountStatus>,
+    /// Required when suspending or closing
+    #[serde(default)]
+    pub reason: Option<StatusReason>,
+    #[serde(default)]
+    pub note: Option<String>,
+    /// New nickname; an empty one removes it
+    #[serde(default)]
+    pub nickname: Option<String>,
+    /// Replaces the account's metadata
+    #[serde(default)]
+    pub metadata: Option<Metadata>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CloseAccountRequest {
+    pub reason: StatusReason,
+    #[serde(default)]
+    pub note: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -87,6 +239,8 @@ pub struct AccountResponse {
     pub user_role: Option<String>,
     pub currency: String,
     pub status: AccountStatus,
+    pub nickname: Option<String>,
+    pub metadata: Metadata,
     #[serde(rename = "created_at")]
     pub created_at: Option<DateTime<Utc>>,
     #[serde(rename = "updated_at")]
@@ -104,20 +258,63 @@ impl From<Account> for AccountResponse {
                 user_id: account.user_id,
                 admin_user_id: account.admin_user_id,
                 user_role: account.user_role,
-                currency: account.currency.clone().unwrap_or_else(|| "USD".to_string()),
+                currency: account.currency.clone().unwrap_or_else(|| Currency::USD.code().to_string()),
                 status: account.status.unwrap_or(AccountStatus::Active),
+            nickname: account.nickname,
+            metadata: account.metadata,
             created_at: account.created_at,
             updated_at: account.updated_at,
         }
     }
 }
 
+/// Page-number fields are set for `page` requests; cursor requests skip the count and
+/// only carry cursors. Opaque cursors are returned in both modes.
 #[derive(Debug, Serialize)]
 pub struct PaginationMeta {
-    pub page: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
     pub per_page: u32,
-    pub total_count: i64,
-    pub total_pages: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_count: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_pages: Option<u32>,
+    pub next_cursor: Option<String>,
+    pub prev_cursor: Option<String>,
+}
+
+impl PaginationMeta {
+    /// Page-number metadata, with cursors around the page's rows.
+    pub fn page<T>(
+        rows: &[T],
+        page: u32,
+        per_page: u32,
+        total_count: i64,
+        key: impl Fn(&T) -> (DateTime<Utc>, Uuid),
+    ) -> PaginationMeta {
+        let total_pages = ((total_count as f64) / (per_page as f64)).ceil() as u32;
+        let (next_cursor, prev_cursor) = edge_cursors(rows, page < total_pages, page > 1, key);
+
+        PaginationMeta {
+            page: Some(page),
+            per_page,
+            total_count: Some(total_count),
+            total_pages: Some(total_pages),
+            next_cursor,
+            prev_cursor,
+        }
+    }
+
+    pub fn cursor(per_page: u32, next_cursor: Option<String>, prev_cursor: Option<String>) -> PaginationMeta {
+        PaginationMeta {
+            page: None,
+            per_page,
+            total_count: None,
+            total_pages: None,
+            next_cursor,
+            prev_cursor,
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -125,3 +322,61 @@ pub struct PaginatedAccountsResponse {
     pub data: Vec<AccountResponse>,
     pub pagination: PaginationMeta,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [AccountStatus; 4] =
+        [AccountStatus::Active, AccountStatus::Suspended, AccountStatus::Closing, AccountStatus::Closed];
+
+    #[test]
+    fn active_and_suspended_move_between_each_other_and_to_closing_or_closed() {
+        use AccountStatus::*;
+
+        let allowed: Vec<(AccountStatus, AccountStatus)> = ALL
+            .into_iter()
+            .flat_map(|from| ALL.into_iter().map(move |to| (from, to)))
+            .filter(|(from, to)| from.can_transition_to(*to))
+            .collect();
+
+        assert_eq!(
+            allowed,
+            vec![
+                (Active, Suspended),
+                (Active, Closing),
+                (Active, Closed),
+                (Suspended, Active),
+                (Suspended, Closing),
+                (Suspended, Closed),
+                (Closing, Closed),
+            ]
+        );
+    }
+
+    #[test]
+    fn closed_is_final_and_no_status_transitions_to_itself() {
+        for status in ALL {
+            assert!(!AccountStatus::Closed.can_transition_to(status));
+            assert!(!status.can_transition_to(status));
+        }
+    }
+
+    #[test]
+    fn suspending_and_closing_require_a_reason() {
+        assert!(!AccountStatus::Active.requires_reason());
+        assert!(AccountStatus::Suspended.requires_reason());
+        assert!(AccountStatus::Closing.requires_reason());
+        assert!(AccountStatus::Closed.requires_reason());
+    }
+
+    #[test]
+    fn statuses_and_reasons_parse_their_names() {
+        for status in ALL {
+            assert_eq!(AccountStatus::parse(status.as_str()), Some(status));
+        }
+        assert_eq!(AccountStatus::parse("Active"), None);
+        assert_eq!(StatusReason::parse("fraud_suspected"), Some(StatusReason::FraudSuspected));
+        assert_eq!(StatusReason::parse("fraud"), None);
+    }
+}
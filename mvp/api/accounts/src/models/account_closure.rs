@@ -0,0 +1,119 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::{PayoutStatus, StatusReason, TransactionStatus};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClosureStatus {
+    /// Settling pending intents or sweeping the balance
+    InProgress,
+    /// Balance swept and the account closed
+    Completed,
+}
+
+impl ClosureStatus {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "in_progress" => Some(ClosureStatus::InProgress),
+            "completed" => Some(ClosureStatus::Completed),
+            _ => None,
+        }
+    }
+}
+
+/// Stored state of an account closure.
+#[derive(Debug, Clone)]
+pub struct AccountClosure {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub organization_id: Uuid,
+    pub environment: String,
+    pub status: ClosureStatus,
+    pub reason: StatusReason,
+    pub note: Option<String>,
+    pub actor_type: String,
+    pub actor_id: Option<Uuid>,
+    pub sweep_to_account_id: Option<Uuid>,
+    pub payee_id: Option<Uuid>,
+    pub currency: String,
+    pub sweep_attempt: i32,
+    pub sweep_transaction_id: Option<Uuid>,
+    pub settled_intents: i32,
+    pub cancelled_intents: i32,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl AccountClosure {
+    /// Idempotency key of the sweep intent of `attempt`
+    pub fn sweep_key(&self, attempt: i32) -> String {
+        format!("{}{}", self.sweep_key_prefix(), attempt)
+    }
+
+    pub fn sweep_key_prefix(&self) -> String {
+        format!("account-closure:{}:", self.id)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountClosureRequest {
+    pub reason: StatusReason,
+    #[serde(default)]
+    pub note: Option<String>,
+    /// Account of the same organization, environment and currency receiving the balance
+    #[serde(default, rename = "sweep_to_account_id")]
+    pub sweep_to_account_id: Option<Uuid>,
+    /// Payee of the account owner paid the balance out to
+    #[serde(default, rename = "payee_id")]
+    pub payee_id: Option<Uuid>,
+}
+
+/// Intent moving (part of) the residual balance out of a closing account.
+#[derive(Debug, Clone, Serialize)]
+pub struct ClosureSweep {
+    #[serde(rename = "transaction_id")]
+    pub transaction_id: Uuid,
+    pub amount: i64,
+    pub status: TransactionStatus,
+    /// Payout carrying the funds to the payee (payee sweeps only)
+    #[serde(rename = "payout_id")]
+    pub payout_id: Option<Uuid>,
+    #[serde(rename = "payout_status")]
+    pub payout_status: Option<PayoutStatus>,
+    #[serde(rename = "created_at")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Closure receipt: what was settled, cancelled and swept, and whether the account is closed.
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountClosureReceipt {
+    pub id: Uuid,
+    #[serde(rename = "account_id")]
+    pub account_id: Uuid,
+    pub status: ClosureStatus,
+    pub reason: StatusReason,
+    pub note: Option<String>,
+    #[serde(rename = "sweep_to_account_id")]
+    pub sweep_to_account_id: Option<Uuid>,
+    #[serde(rename = "payee_id")]
+    pub payee_id: Option<Uuid>,
+    /// Posted sweeps, in minor units of `currency`
+    #[serde(rename = "swept_amount")]
+    pub swept_amount: i64,
+    pub currency: String,
+    pub sweeps: Vec<ClosureSweep>,
+    #[serde(rename = "settled_intents")]
+    pub settled_intents: i32,
+    #[serde(rename = "cancelled_intents")]
+    pub cancelled_intents: i32,
+    /// Why the closure is waiting (in progress only)
+    #[serde(rename = "last_error")]
+    pub last_error: Option<String>,
+    #[serde(rename = "created_at")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "completed_at")]
+    pub completed_at: Option<DateTime<Utc>>,
+}
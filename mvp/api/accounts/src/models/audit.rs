@@ -0,0 +1,96 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::models::account::PaginationMeta;
+
+/// A recorded mutating API call. `before` is the target's state ahead of the call (for
+/// account targets), `after` the response body of a successful call.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEvent {
+    pub id: Uuid,
+    #[serde(rename = "organization_id")]
+    pub organization_id: Option<Uuid>,
+    pub environment: Option<String>,
+    #[serde(rename = "actor_type")]
+    pub actor_type: String,
+    #[serde(rename = "actor_id")]
+    pub actor_id: Option<Uuid>,
+    pub action: String,
+    #[serde(rename = "target_type")]
+    pub target_type: String,
+    #[serde(rename = "target_id")]
+    pub target_id: Option<Uuid>,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    #[serde(rename = "status_code")]
+    pub status_code: i32,
+    #[serde(rename = "correlation_id")]
+    pub correlation_id: Option<String>,
+    #[serde(rename = "ip_address")]
+    pub ip_address: Option<String>,
+    #[serde(rename = "created_at")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewAuditEvent {
+    pub organization_id: Option<Uuid>,
+    pub environment: Option<String>,
+    pub actor_type: &'static str,
+    pub actor_id: Option<Uuid>,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: Option<Uuid>,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    pub status_code: i32,
+    pub correlation_id: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+/// Narrows an audit listing or export; every field is optional.
+#[derive(Debug, Clone, Default)]
+pub struct AuditEventFilter {
+    pub actor_type: Option<String>,
+    pub actor_id: Option<Uuid>,
+    pub action: Option<String>,
+    pub target_type: Option<String>,
+    pub target_id: Option<Uuid>,
+    pub correlation_id: Option<String>,
+    /// Created at or after
+    pub from: Option<DateTime<Utc>>,
+    /// Created before
+    pub to: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PaginatedAuditEventsResponse {
+    pub data: Vec<AuditEvent>,
+    pub pagination: PaginationMeta,
+}
+
+/// File format of an audit export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditExportFormat {
+    /// One row per event; `before` and `after` as JSON text
+    Csv,
+    /// One JSON event per line
+    Jsonl,
+}
+
+impl AuditExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            AuditExportFormat::Csv => "text/csv; charset=utf-8",
+            AuditExportFormat::Jsonl => "application/x-ndjson",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            AuditExportFormat::Csv => "csv",
+            AuditExportFormat::Jsonl => "jsonl",
+        }
+    }
+}
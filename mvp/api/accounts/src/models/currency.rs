@@ -0,0 +1,192 @@
+use std::fmt;
+use std::str::FromStr;
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::errors::AppError;
+
+/// Active ISO 4217 currency codes and their minor-unit exponents.
+/// Precious metals, bond units and testing codes (XAU, XDR, XTS, ...) are not accepted.
+const ISO_4217: &[(&str, u32)] = &[
+    ("AED", 2), ("AFN", 2), ("ALL", 2), ("AMD", 2), ("ANG", 2), ("AOA", 2), ("ARS", 2), ("AUD", 2),
+    ("AWG", 2), ("AZN", 2), ("BAM", 2), ("BBD", 2), ("BDT", 2), ("BGN", 2), ("BHD", 3), ("BIF", 0),
+    ("BMD", 2), ("BND", 2), ("BOB", 2), ("BRL", 2), ("BSD", 2), ("BTN", 2), ("BWP", 2), ("BYN", 2),
+    ("BZD", 2), ("CAD", 2), ("CDF", 2), ("CHF", 2), ("CLF", 4), ("CLP", 0), ("CNY", 2), ("COP", 2),
+    ("CRC", 2), ("CUP", 2), ("CVE", 2), ("CZK", 2), ("DJF", 0), ("DKK", 2), ("DOP", 2), ("DZD", 2),
+    ("EGP", 2), ("ERN", 2), ("ETB", 2), ("EUR", 2), ("FJD", 2), ("FKP", 2), ("GBP", 2), ("GEL", 2),
+    ("GHS", 2), ("GIP", 2), ("GMD", 2), ("GNF", 0), ("GTQ", 2), ("GYD", 2), ("HKD", 2), ("HNL", 2),
+    ("HTG", 2), ("HUF", 2), ("IDR", 2), ("ILS", 2), ("INR", 2), ("IQD", 3), ("IRR", 2), ("ISK", 0),
+    ("JMD", 2), ("JOD", 3), ("JPY", 0), ("KES", 2), ("KGS", 2), ("KHR", 2), ("KMF", 0), ("KPW", 2),
+    ("KRW", 0), ("KWD", 3), ("KYD", 2), ("KZT", 2), ("LAK", 2), ("LBP", 2), ("LKR", 2), ("LRD", 2),
+    ("LSL", 2), ("LYD", 3), ("MAD", 2), ("MDL", 2), ("MGA", 2), ("MKD", 2), ("MMK", 2), ("MNT", 2),
+    ("MOP", 2), ("MRU", 2), ("MUR", 2), ("MVR", 2), ("MWK", 2), ("MXN", 2), ("MYR", 2), ("MZN", 2),
+    ("NAD", 2), ("NGN", 2), ("NIO", 2), ("NOK", 2), ("NPR", 2), ("NZD", 2), ("OMR", 3), ("PAB", 2),
+    ("PEN", 2), ("PGK", 2), ("PHP", 2), ("PKR", 2), ("PLN", 2), ("PYG", 0), ("QAR", 2), ("RON", 2),
+    ("RSD", 2), ("RUB", 2), ("RWF", 0), ("SAR", 2), ("SBD", 2), ("SCR", 2), ("SDG", 2), ("SEK", 2),
+    ("SGD", 2), ("SHP", 2), ("SLE", 2), ("SOS", 2), ("SRD", 2), ("SSP", 2), ("STN", 2), ("SVC", 2),
+    ("SYP", 2), ("SZL", 2), ("THB", 2), ("TJS", 2), ("TMT", 2), ("TND", 3), ("TOP", 2), ("TRY", 2),
+    ("TTD", 2), ("TWD", 2), ("TZS", 2), ("UAH", 2), ("UGX", 0), ("USD", 2), ("UYI", 0), ("UYU", 2),
+    ("UYW", 4), ("UZS", 2), ("VED", 2), ("VES", 2), ("VND", 0), ("VUV", 0), ("WST", 2), ("XAF", 0),
+    ("XCD", 2), ("XOF", 0), ("XPF", 0), ("YER", 2), ("ZAR", 2), ("ZMW", 2), ("ZWG", 2),
+];
+
+/// ISO 4217 currency with its minor-unit exponent (USD = 2, JPY = 0, KWD = 3).
+/// Serialized as the three-letter code; deserializing rejects unknown codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Currency {
+    code: &'static str,
+    exponent: u32,
+}
+
+impl Currency {
+    /// Default for account creation and legacy rows without a currency
+    pub const USD: Currency = Currency { code: "USD", exponent: 2 };
+
+    pub fn from_code(code: &str) -> Option<Currency> {
+        let code = code.trim().to_ascii_uppercase();
+        ISO_4217
+            .iter()
+            .find(|(c, _)| *c == code)
+            .map(|(code, exponent)| Currency { code, exponent: *exponent })
+    }
+
+    pub fn parse(code: &str) -> Result<Currency, AppError> {
+        Self::from_code(code)
+            .ok_or_else(|| AppError::Validation(format!("unsupported currency: {}", code.trim())))
+    }
+
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    /// Number of minor-unit digits
+    pub fn exponent(&self) -> u32 {
+        self.exponent
+    }
+
+    /// Minor units as a decimal string in major units, e.g. 1234 USD -> "12.34", 1234 JPY -> "1234"
+    pub fn format_amount(&self, minor_units: i64) -> String {
+        Decimal::new(minor_units, self.exponent).to_string()
+    }
+
+    /// Decimal string in major units to minor units, e.g. "12.34" USD -> 1234.
+    /// More fractional digits than the currency allows is an error (no implicit rounding).
+    pub fn parse_amount(&self, amount: &str) -> Result<i64, AppError> {
+        let value = Decimal::from_str(amount.trim())
+            .map_err(|_| AppError::Validation(format!("invalid amount: {}", amount)))?;
+
+        if value.normalize().scale() > self.exponent {
+            return Err(AppError::Validation(format!(
+                "amount {} has more than {} decimal places for {}",
+                amount, self.exponent, self.code
+            )));
+        }
+
+        value
+            .checked_mul(Decimal::from(10i64.pow(self.exponent)))
+            .and_then(|minor_units| minor_units.to_i64())
+            .ok_or_else(|| AppError::Validation("amount is out of range".to_string()))
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code)
+    }
+}
+
+impl Serialize for Currency {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code)
+    }
+}
+
+impl<'de> Deserialize<'de> for Currency {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        Currency::from_code(&code)
+            .ok_or_else(|| serde::de::Error::custom(format!("unsupported currency: {}", code)))
+    }
+}
+
+/// Request amount: an integer in minor units (`1234`) or a decimal string in
+/// major units (`"12.34"`), interpreted in the currency of the account it applies to.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum AmountInput {
+    MinorUnits(i64),
+    Decimal(String),
+}
+
+impl AmountInput {
+    pub fn to_minor_units(&self, currency: Currency) -> Result<i64, AppError> {
+        match self {
+            AmountInput::MinorUnits(amount) => Ok(*amount),
+            AmountInput::Decimal(amount) => currency.parse_amount(amount),
+        }
+    }
+}
+
+/// Decimal string for a stored amount; None if the stored currency code is not ISO 4217.
+pub fn format_amount(currency_code: &str, minor_units: i64) -> Option<String> {
+    Currency::from_code(currency_code).map(|currency| currency.format_amount(minor_units))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn currency(code: &str) -> Currency {
+        Currency::from_code(code).unwrap()
+    }
+
+    #[test]
+    fn parses_major_units_by_exponent() {
+        assert_eq!(currency("USD").parse_amount("12.34").unwrap(), 1234);
+        assert_eq!(currency("USD").parse_amount(" 12.3 ").unwrap(), 1230);
+        assert_eq!(currency("USD").parse_amount("12.3400").unwrap(), 1234);
+        assert_eq!(currency("JPY").parse_amount("1234").unwrap(), 1234);
+        assert_eq!(currency("KWD").parse_amount("1.234").unwrap(), 1234);
+        assert_eq!(currency("USD").parse_amount("-12.34").unwrap(), -1234);
+    }
+
+    #[test]
+    fn formats_minor_units_by_exponent() {
+        assert_eq!(currency("USD").format_amount(1234), "12.34");
+        assert_eq!(currency("USD").format_amount(-5), "-0.05");
+        assert_eq!(currency("JPY").format_amount(1234), "1234");
+        assert_eq!(currency("KWD").format_amount(1234), "1.234");
+        assert_eq!(format_amount("usd", 1234).as_deref(), Some("12.34"));
+        assert_eq!(format_amount("XXY", 1234), None);
+    }
+
+    #[test]
+    fn rejects_more_decimal_places_than_the_currency_allows() {
+        assert!(matches!(currency("USD").parse_amount("1.234"), Err(AppError::Validation(_))));
+        assert!(matches!(currency("JPY").parse_amount("1.5"), Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn rejects_malformed_amounts() {
+        assert!(matches!(currency("USD").parse_amount("12,34"), Err(AppError::Validation(_))));
+        assert!(matches!(currency("USD").parse_amount(""), Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn rejects_out_of_range_amounts_without_panicking() {
+        let amounts = [
+            "79228162514264337593543950335",
+            "-79228162514264337593543950335",
+            "92233720368547758.08",
+        ];
+        for amount in amounts {
+            match currency("USD").parse_amount(amount) {
+                Err(AppError::Validation(message)) => assert_eq!(message, "amount is out of range"),
+                other => panic!("{} parsed as {:?}", amount, other.map_err(|e| e.to_string())),
+            }
+        }
+        assert_eq!(currency("USD").parse_amount("92233720368547758.07").unwrap(), i64::MAX);
+    }
+}
@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// An entry of the organization event log, as pushed on the live event stream.
+/// `sequence` is the stream position (SSE event id) clients resume from, numbered per
+/// organization and environment in commit order.
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamEvent {
+    pub sequence: i64,
+    pub id: Uuid,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    #[serde(rename = "created_at")]
+    pub created_at: DateTime<Utc>,
+    pub data: serde_json::Value,
+}
+
+/// Narrows a stream to one account (either side of a transaction, or the account itself)
+/// and/or to some event types (empty = all).
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    pub account_id: Option<Uuid>,
+    pub event_types: Vec<String>,
+}
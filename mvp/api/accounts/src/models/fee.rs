@@ -0,0 +1,125 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::{AccountType, Currency, TransactionKind, TransactionStatus};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FeeSchedule {
+    pub id: Uuid,
+    #[serde(rename = "organization_id")]
+    pub organization_id: Uuid,
+    pub environment: String,
+    pub name: String,
+    #[serde(rename = "transaction_kind")]
+    pub transaction_kind: TransactionKind,
+    /// None applies to every account type
+    #[serde(rename = "account_type")]
+    pub account_type: Option<AccountType>,
+    /// Currency of the amounts; the schedule applies to intents in this currency only
+    pub currency: String,
+    #[serde(rename = "flat_amount")]
+    pub flat_amount: i64,
+    /// Fraction of the transaction amount, e.g. "0.015" = 1.5%
+    #[serde(with = "rust_decimal::serde::str")]
+    pub percentage: Decimal,
+    #[serde(rename = "min_amount")]
+    pub min_amount: Option<i64>,
+    #[serde(rename = "max_amount")]
+    pub max_amount: Option<i64>,
+    /// None routes fees to the Ledger SYSTEM_FEE_INCOME account
+    #[serde(rename = "revenue_account_id")]
+    pub revenue_account_id: Option<Uuid>,
+    pub active: bool,
+    #[serde(rename = "created_at")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "updated_at")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl FeeSchedule {
+    /// Fee for a transaction amount (minor units): flat + percentage (rounded half-even),
+    /// clamped to the configured min/max.
+    pub fn calculate(&self, amount: i64) -> i64 {
+        let variable = (Decimal::from(amount) * self.percentage)
+            .round_dp_with_strategy(0, RoundingStrategy::MidpointNearestEven)
+            .to_i64()
+            .unwrap_or(0);
+
+        let mut fee = self.flat_amount.saturating_add(variable);
+        if let Some(min) = self.min_amount {
+            fee = fee.max(min);
+        }
+        if let Some(max) = self.max_amount {
+            fee = fee.min(max);
+        }
+
+        fee.max(0)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateFeeScheduleRequest {
+    #[serde(rename = "organization_id")]
+    pub organization_id: Uuid,
+    pub name: String,
+    #[serde(rename = "transaction_kind")]
+    pub transaction_kind: TransactionKind,
+    #[serde(default)]
+    #[serde(rename = "account_type")]
+    pub account_type: Option<AccountType>,
+    pub currency: Currency,
+    #[serde(default)]
+    #[serde(rename = "flat_amount")]
+    pub flat_amount: i64,
+    #[serde(default, with = "rust_decimal::serde::str_option")]
+    pub percentage: Option<Decimal>,
+    #[serde(default)]
+    #[serde(rename = "min_amount")]
+    pub min_amount: Option<i64>,
+    #[serde(default)]
+    #[serde(rename = "max_amount")]
+    pub max_amount: Option<i64>,
+    #[serde(default)]
+    #[serde(rename = "revenue_account_id")]
+    pub revenue_account_id: Option<Uuid>,
+}
+
+/// Fee charged on a transaction intent, posted to the Ledger as its own leg.
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionFee {
+    pub id: Uuid,
+    #[serde(rename = "transaction_id")]
+    pub transaction_id: Uuid,
+    #[serde(rename = "fee_schedule_id")]
+    pub fee_schedule_id: Uuid,
+    #[serde(skip)]
+    pub organization_id: Uuid,
+    #[serde(skip)]
+    pub environment: String,
+    pub name: String,
+    #[serde(rename = "payer_account_id")]
+    pub payer_account_id: Uuid,
+    #[serde(rename = "revenue_account_id")]
+    pub revenue_account_id: Option<Uuid>,
+    pub amount: i64,
+    pub currency: String,
+    pub status: TransactionStatus,
+    #[serde(rename = "failure_reason")]
+    pub failure_reason: Option<String>,
+    #[serde(skip)]
+    pub idempotency_key: String,
+    #[serde(rename = "created_at")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl TransactionFee {
+    /// Ledger external account credited with the fee.
+    pub fn revenue_external_account_id(&self) -> String {
+        self.revenue_account_id
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| "SYSTEM_FEE_INCOME".to_string())
+    }
+}
@@ -0,0 +1,50 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Fixed savings plan attached to a saving account.
+/// Amounts are minor units (same convention as `Transaction::amount`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixedSavingsPlan {
+    pub id: Uuid,
+    #[serde(rename = "account_id")]
+    pub account_id: Uuid,
+    #[serde(rename = "plan_type")]
+    pub plan_type: FixedSavingsPlanType,
+    #[serde(rename = "initial_amount")]
+    pub initial_amount: i64,
+    #[serde(rename = "current_balance")]
+    pub current_balance: i64,
+    pub currency: String,
+    #[serde(rename = "monthly_withdraw_amount")]
+    pub monthly_withdraw_amount: Option<i64>,
+    #[serde(rename = "next_withdraw_date")]
+    pub next_withdraw_date: Option<NaiveDate>,
+    #[serde(rename = "unlock_date")]
+    pub unlock_date: Option<NaiveDate>,
+    pub status: FixedSavingsPlanStatus,
+    /// Why the scheduler stopped paying out the plan (suspended plans only)
+    #[serde(rename = "suspended_reason")]
+    pub suspended_reason: Option<String>,
+    #[serde(rename = "created_at")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "updated_at")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FixedSavingsPlanType {
+    AutoWithdraw,
+    DateLocked,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FixedSavingsPlanStatus {
+    Active,
+    /// Auto-withdraw plan the scheduler cannot pay out; set back to active to resume
+    Suspended,
+    Completed,
+    Cancelled,
+}
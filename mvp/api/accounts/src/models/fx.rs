@@ -0,0 +1,68 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::{AmountInput, Currency};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FxQuoteStatus {
+    Open,
+    Locked,
+    Used,
+}
+
+/// Price for converting `source_amount` into `destination_currency`, honoured until `expires_at`.
+#[derive(Debug, Clone, Serialize)]
+pub struct FxQuote {
+    pub id: Uuid,
+    #[serde(rename = "organization_id")]
+    pub organization_id: Uuid,
+    pub environment: String,
+    #[serde(rename = "source_currency")]
+    pub source_currency: String,
+    #[serde(rename = "destination_currency")]
+    pub destination_currency: String,
+    /// Units of destination_currency per unit of source_currency
+    #[serde(with = "rust_decimal::serde::str")]
+    pub rate: Decimal,
+    #[serde(rename = "source_amount")]
+    pub source_amount: i64,
+    #[serde(rename = "destination_amount")]
+    pub destination_amount: i64,
+    pub status: FxQuoteStatus,
+    #[serde(rename = "expires_at")]
+    pub expires_at: DateTime<Utc>,
+    #[serde(rename = "locked_at")]
+    pub locked_at: Option<DateTime<Utc>>,
+    #[serde(rename = "transaction_id")]
+    pub transaction_id: Option<Uuid>,
+    #[serde(rename = "created_at")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateFxQuoteRequest {
+    #[serde(rename = "organization_id")]
+    pub organization_id: Uuid,
+    #[serde(rename = "source_currency")]
+    pub source_currency: Currency,
+    #[serde(rename = "destination_currency")]
+    pub destination_currency: Currency,
+    /// Minor units (1234) or a decimal string in major units ("12.34") of source_currency
+    #[serde(rename = "source_amount")]
+    pub source_amount: AmountInput,
+}
+
+/// Amounts and rate applied to a cross-currency transfer intent.
+#[derive(Debug, Clone)]
+pub struct FxConversion {
+    pub source_currency: Currency,
+    pub source_amount: i64,
+    pub rate: Decimal,
+    pub destination_currency: Currency,
+    pub destination_amount: i64,
+    /// Set when the conversion came from a client quote rather than the live rate
+    pub quote_id: Option<Uuid>,
+}
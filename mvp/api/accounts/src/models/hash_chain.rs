@@ -0,0 +1,62 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// First place a chain fails verification.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainBreak {
+    /// Position of the offending record (or of the missing one)
+    #[serde(rename = "chain_seq")]
+    pub chain_seq: i64,
+    #[serde(rename = "record_id")]
+    pub record_id: Option<Uuid>,
+    /// `sequence_gap` (records removed), `prev_hash_mismatch` (records removed or inserted),
+    /// `hash_mismatch` (record edited) or `head_mismatch` (latest records removed)
+    pub reason: &'static str,
+    pub expected: String,
+    pub actual: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainVerification {
+    pub chain: &'static str,
+    /// Records verified, up to the first break
+    pub records: i64,
+    #[serde(rename = "head_seq")]
+    pub head_seq: i64,
+    #[serde(rename = "head_hash")]
+    pub head_hash: String,
+    pub valid: bool,
+    #[serde(rename = "first_break")]
+    pub first_break: Option<ChainBreak>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainVerificationReport {
+    #[serde(rename = "organization_id")]
+    pub organization_id: Uuid,
+    pub valid: bool,
+    pub chains: Vec<ChainVerification>,
+    #[serde(rename = "verified_at")]
+    pub verified_at: DateTime<Utc>,
+}
+
+/// Signed statement of a chain's head at the end of a UTC day. The signature is Ed25519
+/// over `message`, verifiable with `public_key` alone.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainCheckpoint {
+    #[serde(rename = "organization_id")]
+    pub organization_id: Uuid,
+    pub chain: String,
+    pub date: NaiveDate,
+    #[serde(rename = "chain_seq")]
+    pub chain_seq: i64,
+    #[serde(rename = "record_hash")]
+    pub record_hash: String,
+    pub message: String,
+    pub signature: String,
+    #[serde(rename = "public_key")]
+    pub public_key: String,
+    #[serde(rename = "created_at")]
+    pub created_at: DateTime<Utc>,
+}
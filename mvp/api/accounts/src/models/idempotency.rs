@@ -0,0 +1,14 @@
+/// A stored Idempotency-Key: the request it was first used for and, once that request
+/// has finished, its response.
+#[derive(Debug, Clone)]
+pub struct IdempotencyRecord {
+    pub request_fingerprint: String,
+    pub response: Option<StoredResponse>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
@@ -0,0 +1,176 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::Currency;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InterestMethod {
+    /// Daily interest on the end-of-day balance only
+    Simple,
+    /// Daily interest on the end-of-day balance plus interest accrued but not yet posted
+    Compound,
+}
+
+impl InterestMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InterestMethod::Simple => "simple",
+            InterestMethod::Compound => "compound",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterestRateTier {
+    /// Minimum end-of-day balance (minor units) for this tier to apply
+    #[serde(rename = "min_balance")]
+    pub min_balance: i64,
+    /// Annual rate as a fraction, e.g. "0.045" = 4.5%
+    #[serde(rename = "annual_rate", with = "rust_decimal::serde::str")]
+    pub annual_rate: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InterestRateConfig {
+    pub id: Uuid,
+    #[serde(rename = "organization_id")]
+    pub organization_id: Uuid,
+    pub environment: String,
+    pub currency: String,
+    #[serde(rename = "interest_method")]
+    pub interest_method: InterestMethod,
+    pub tiers: Vec<InterestRateTier>,
+    #[serde(rename = "created_at")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "updated_at")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl InterestRateConfig {
+    /// Annual rate for a balance: the highest tier whose min_balance the balance reaches.
+    pub fn rate_for_balance(&self, balance: i64) -> Decimal {
+        self.tiers
+            .iter()
+            .filter(|tier| balance >= tier.min_balance)
+            .max_by_key(|tier| tier.min_balance)
+            .map(|tier| tier.annual_rate)
+            .unwrap_or(Decimal::ZERO)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpsertInterestRateConfigRequest {
+    #[serde(rename = "organization_id")]
+    pub organization_id: Uuid,
+    pub currency: Currency,
+    #[serde(rename = "interest_method")]
+    pub interest_method: InterestMethod,
+    pub tiers: Vec<InterestRateTier>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InterestAccrual {
+    pub id: Uuid,
+    #[serde(rename = "organization_id")]
+    pub organization_id: Uuid,
+    pub environment: String,
+    #[serde(rename = "account_id")]
+    pub account_id: Uuid,
+    #[serde(rename = "accrual_date")]
+    pub accrual_date: NaiveDate,
+    /// End-of-day balance (minor units) the accrual was computed from
+    pub balance: i64,
+    #[serde(rename = "annual_rate", with = "rust_decimal::serde::str")]
+    pub annual_rate: Decimal,
+    #[serde(rename = "interest_method")]
+    pub interest_method: InterestMethod,
+    /// Accrued interest in fractional minor units
+    #[serde(rename = "accrued_amount", with = "rust_decimal::serde::str")]
+    pub accrued_amount: Decimal,
+    pub currency: String,
+    #[serde(rename = "transaction_id")]
+    pub transaction_id: Option<Uuid>,
+    #[serde(rename = "posted_at")]
+    pub posted_at: Option<DateTime<Utc>>,
+    #[serde(rename = "created_at")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PaginatedInterestAccrualsResponse {
+    pub data: Vec<InterestAccrual>,
+    pub pagination: crate::models::account::PaginationMeta,
+}
+
+/// Saving account eligible for daily accrual, with the last day already accrued.
+#[derive(Debug, Clone)]
+pub struct InterestAccrualCandidate {
+    pub account_id: Uuid,
+    pub organization_id: Uuid,
+    pub environment: String,
+    pub currency: String,
+    /// First day interest may accrue (later of account creation and rate configuration)
+    pub start_date: NaiveDate,
+    pub last_accrual_date: Option<NaiveDate>,
+}
+
+/// Unposted accruals of one account for one calendar month.
+#[derive(Debug, Clone)]
+pub struct InterestPostingPeriod {
+    pub account_id: Uuid,
+    pub organization_id: Uuid,
+    pub environment: String,
+    pub currency: String,
+    pub period_start: NaiveDate,
+    pub accrued_total: Decimal,
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn config(interest_method: InterestMethod, tiers: &[(i64, Decimal)]) -> InterestRateConfig {
+        InterestRateConfig {
+            id: Uuid::new_v4(),
+            organization_id: Uuid::new_v4(),
+            environment: "sandbox".to_string(),
+            currency: "USD".to_string(),
+            interest_method,
+            tiers: tiers
+                .iter()
+                .map(|&(min_balance, annual_rate)| InterestRateTier { min_balance, annual_rate })
+                .collect(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn the_highest_tier_reached_applies_to_the_whole_balance() {
+        // Tiers are matched by min_balance, whatever order they are stored in
+        let config = config(
+            InterestMethod::Simple,
+            &[(1_000_000, dec!(0.04)), (100_000, dec!(0.02)), (10_000_000, dec!(0.05))],
+        );
+
+        assert_eq!(config.rate_for_balance(99_999), Decimal::ZERO);
+        assert_eq!(config.rate_for_balance(100_000), dec!(0.02));
+        assert_eq!(config.rate_for_balance(999_999), dec!(0.02));
+        assert_eq!(config.rate_for_balance(1_000_000), dec!(0.04));
+        assert_eq!(config.rate_for_balance(50_000_000), dec!(0.05));
+        assert_eq!(config.rate_for_balance(-1), Decimal::ZERO);
+    }
+
+    #[test]
+    fn a_zero_tier_covers_every_non_negative_balance() {
+        let config = config(InterestMethod::Simple, &[(0, dec!(0.01))]);
+        assert_eq!(config.rate_for_balance(0), dec!(0.01));
+        assert_eq!(config.rate_for_balance(-100), Decimal::ZERO);
+        assert_eq!(self::config(InterestMethod::Simple, &[]).rate_for_balance(1_000), Decimal::ZERO);
+    }
+}
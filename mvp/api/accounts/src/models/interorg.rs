@@ -0,0 +1,56 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InterorgAgreementStatus {
+    /// Waiting for the counterparty organization to accept
+    Pending,
+    Active,
+    Revoked,
+}
+
+/// Consent from `organization_id` for its accounts to transfer funds to
+/// `counterparty_account_id`, which belongs to another organization. Transfers are only
+/// allowed once that organization has accepted the agreement.
+#[derive(Debug, Clone, Serialize)]
+pub struct InterorgAgreement {
+    pub id: Uuid,
+    pub environment: String,
+    #[serde(rename = "organization_id")]
+    pub organization_id: Uuid,
+    /// Set when the counterparty organization accepts
+    #[serde(rename = "counterparty_organization_id")]
+    pub counterparty_organization_id: Option<Uuid>,
+    #[serde(rename = "counterparty_account_id")]
+    pub counterparty_account_id: Uuid,
+    #[serde(rename = "max_amount_per_transfer")]
+    pub max_amount_per_transfer: Option<i64>,
+    #[serde(rename = "daily_amount")]
+    pub daily_amount: Option<i64>,
+    pub status: InterorgAgreementStatus,
+    #[serde(rename = "accepted_at")]
+    pub accepted_at: Option<DateTime<Utc>>,
+    #[serde(rename = "revoked_at")]
+    pub revoked_at: Option<DateTime<Utc>>,
+    #[serde(rename = "created_at")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "updated_at")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateInterorgAgreementRequest {
+    /// Organization granting consent (the payer)
+    #[serde(rename = "organization_id")]
+    pub organization_id: Uuid,
+    #[serde(rename = "counterparty_account_id")]
+    pub counterparty_account_id: Uuid,
+    #[serde(default)]
+    #[serde(rename = "max_amount_per_transfer")]
+    pub max_amount_per_transfer: Option<i64>,
+    #[serde(default)]
+    #[serde(rename = "daily_amount")]
+    pub daily_amount: Option<i64>,
+}
@@ -0,0 +1,123 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::{AccountType, Currency, TransactionKind};
+
+/// What a limit applies to. The most specific limits don't replace broader ones:
+/// every matching limit (account, account type and organization) is enforced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitScope {
+    Organization,
+    AccountType,
+    Account,
+}
+
+/// Limits on outgoing money movement (withdrawals and transfers out) in one currency.
+/// Daily windows reset at 00:00 UTC, monthly windows on the 1st at 00:00 UTC.
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionLimit {
+    pub id: Uuid,
+    #[serde(rename = "organization_id")]
+    pub organization_id: Uuid,
+    pub environment: String,
+    pub scope: LimitScope,
+    #[serde(rename = "account_type")]
+    pub account_type: Option<AccountType>,
+    #[serde(rename = "account_id")]
+    pub account_id: Option<Uuid>,
+    /// None applies to both withdrawals and transfers
+    #[serde(rename = "transaction_kind")]
+    pub transaction_kind: Option<TransactionKind>,
+    pub currency: String,
+    #[serde(rename = "max_amount_per_transaction")]
+    pub max_amount_per_transaction: Option<i64>,
+    #[serde(rename = "daily_amount")]
+    pub daily_amount: Option<i64>,
+    #[serde(rename = "monthly_amount")]
+    pub monthly_amount: Option<i64>,
+    #[serde(rename = "daily_count")]
+    pub daily_count: Option<i64>,
+    #[serde(rename = "monthly_count")]
+    pub monthly_count: Option<i64>,
+    #[serde(rename = "created_at")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "updated_at")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpsertTransactionLimitRequest {
+    #[serde(rename = "organization_id")]
+    pub organization_id: Uuid,
+    /// Set to limit a single account
+    #[serde(default)]
+    #[serde(rename = "account_id")]
+    pub account_id: Option<Uuid>,
+    /// Set to limit every account of a type
+    #[serde(default)]
+    #[serde(rename = "account_type")]
+    pub account_type: Option<AccountType>,
+    #[serde(default)]
+    #[serde(rename = "transaction_kind")]
+    pub transaction_kind: Option<TransactionKind>,
+    pub currency: Currency,
+    #[serde(default)]
+    #[serde(rename = "max_amount_per_transaction")]
+    pub max_amount_per_transaction: Option<i64>,
+    #[serde(default)]
+    #[serde(rename = "daily_amount")]
+    pub daily_amount: Option<i64>,
+    #[serde(default)]
+    #[serde(rename = "monthly_amount")]
+    pub monthly_amount: Option<i64>,
+    #[serde(default)]
+    #[serde(rename = "daily_count")]
+    pub daily_count: Option<i64>,
+    #[serde(default)]
+    #[serde(rename = "monthly_count")]
+    pub monthly_count: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitType {
+    MaxAmountPerTransaction,
+    DailyAmount,
+    MonthlyAmount,
+    DailyCount,
+    MonthlyCount,
+}
+
+impl LimitType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LimitType::MaxAmountPerTransaction => "max_amount_per_transaction",
+            LimitType::DailyAmount => "daily_amount",
+            LimitType::MonthlyAmount => "monthly_amount",
+            LimitType::DailyCount => "daily_count",
+            LimitType::MonthlyCount => "monthly_count",
+        }
+    }
+}
+
+/// Details returned to the client when a transaction is rejected by a limit.
+#[derive(Debug, Clone, Serialize)]
+pub struct LimitViolation {
+    #[serde(rename = "limit_id")]
+    pub limit_id: Uuid,
+    pub scope: LimitScope,
+    #[serde(rename = "limit_type")]
+    pub limit_type: LimitType,
+    /// Configured maximum (minor units for amounts)
+    pub limit: i64,
+    /// Usage in the current window before this transaction
+    pub used: i64,
+    /// Amount (or 1 for counts) this transaction would have added
+    pub requested: i64,
+    pub currency: String,
+    /// When the window resets; None for per-transaction limits
+    #[serde(rename = "resets_at")]
+    pub resets_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,65 @@
+use std::collections::BTreeMap;
+
+use crate::errors::AppError;
+
+/// Client-defined key-value pairs stored with accounts and transactions (JSONB object of
+/// strings). Ordered, so its JSON form is canonical.
+pub type Metadata = BTreeMap<String, String>;
+
+pub const MAX_METADATA_KEYS: usize = 50;
+pub const MAX_METADATA_KEY_CHARS: usize = 40;
+pub const MAX_METADATA_VALUE_CHARS: usize = 500;
+
+/// Keys are 1-40 characters of `[A-Za-z0-9_.-]`, so they can be named in a
+/// `metadata[<key>]` query parameter.
+fn validate_metadata_key(key: &str) -> Result<(), AppError> {
+    if key.is_empty() || key.chars().count() > MAX_METADATA_KEY_CHARS {
+        return Err(AppError::Validation(format!(
+            "metadata keys must be 1 to {} characters",
+            MAX_METADATA_KEY_CHARS
+        )));
+    }
+    if !key.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-')) {
+        return Err(AppError::Validation(format!(
+            "invalid metadata key '{}' (letters, digits, '_', '.' and '-' only)",
+            key
+        )));
+    }
+    Ok(())
+}
+
+pub fn validate_metadata(metadata: &Metadata) -> Result<(), AppError> {
+    if metadata.len() > MAX_METADATA_KEYS {
+        return Err(AppError::Validation(format!(
+            "metadata has at most {} keys",
+            MAX_METADATA_KEYS
+        )));
+    }
+    for (key, value) in metadata {
+        validate_metadata_key(key)?;
+        if value.chars().count() > MAX_METADATA_VALUE_CHARS {
+            return Err(AppError::Validation(format!(
+                "metadata value of '{}' exceeds {} characters",
+                key, MAX_METADATA_VALUE_CHARS
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Metadata filter from `metadata[<key>]=<value>` query parameters; listings return the
+/// records whose metadata contains all the pairs.
+pub fn metadata_filter(params: &[(String, String)]) -> Result<Metadata, AppError> {
+    let mut filter = Metadata::new();
+    for (name, value) in params {
+        let Some(key) = name.strip_prefix("metadata[").and_then(|rest| rest.strip_suffix(']')) else {
+            continue;
+        };
+        validate_metadata_key(key)?;
+        if filter.insert(key.to_string(), value.clone()).is_some() {
+            return Err(AppError::Validation(format!("metadata[{}] given more than once", key)));
+        }
+    }
+    validate_metadata(&filter)?;
+    Ok(filter)
+}
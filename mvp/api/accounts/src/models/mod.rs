@@ -1,8 +1,44 @@
 pub mod account;
+pub mod account_closure;
+pub mod audit;
+pub mod currency;
+pub mod event;
+pub mod fee;
+pub mod fixed_savings_plan;
+pub mod fx;
+pub mod hash_chain;
+pub mod idempotency;
+pub mod interest;
+pub mod interorg;
+pub mod limit;
+pub mod metadata;
+pub mod nacha;
+pub mod payout;
+pub mod rate_limit;
+pub mod statement;
 pub mod transaction;
+pub mod webhook;
 
 pub use account::*;
+pub use account_closure::*;
+pub use audit::*;
+pub use currency::*;
+pub use event::*;
+pub use fee::*;
+pub use fixed_savings_plan::*;
+pub use fx::*;
+pub use hash_chain::*;
+pub use idempotency::*;
+pub use interest::*;
+pub use interorg::*;
+pub use limit::*;
+pub use metadata::*;
+pub use nacha::*;
+pub use payout::*;
+pub use rate_limit::*;
+pub use statement::*;
 pub use transaction::*;
+pub use webhook::*;
 
 // Re-export PaginationMeta from account module for use in transaction module
 pub use account::PaginationMeta;
@@ -0,0 +1,42 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::models::Payout;
+
+/// A NACHA file written for one settlement window.
+#[derive(Debug, Clone, Serialize)]
+pub struct NachaFile {
+    pub id: Uuid,
+    pub environment: String,
+    #[serde(rename = "file_name")]
+    pub file_name: String,
+    #[serde(skip_serializing)]
+    pub file_path: String,
+    #[serde(rename = "file_creation_date")]
+    pub file_creation_date: NaiveDate,
+    #[serde(rename = "file_id_modifier")]
+    pub file_id_modifier: String,
+    #[serde(rename = "window_end")]
+    pub window_end: DateTime<Utc>,
+    #[serde(rename = "batch_count")]
+    pub batch_count: i32,
+    #[serde(rename = "entry_count")]
+    pub entry_count: i32,
+    #[serde(rename = "entry_hash")]
+    pub entry_hash: i64,
+    #[serde(rename = "total_credit_amount")]
+    pub total_credit_amount: i64,
+    #[serde(rename = "created_at")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Outcome of processing a NACHA return file.
+#[derive(Debug, Serialize)]
+pub struct NachaReturnsResult {
+    /// Payouts marked returned (and credited back)
+    pub returned: Vec<Payout>,
+    /// Original trace numbers that did not match a payout in this environment
+    #[serde(rename = "unmatched_trace_numbers")]
+    pub unmatched_trace_numbers: Vec<String>,
+}
@@ -0,0 +1,167 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::{AmountInput, TransactionResponse};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PayeeStatus {
+    Active,
+    Inactive,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BankAccountType {
+    Checking,
+    Savings,
+}
+
+/// External bank account an account owner can send payouts to.
+#[derive(Debug, Clone)]
+pub struct Payee {
+    pub id: Uuid,
+    pub environment: String,
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub bank_name: Option<String>,
+    pub routing_number: String,
+    pub account_number: String,
+    pub bank_account_type: BankAccountType,
+    pub status: PayeeStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Payee as returned by the API; the bank account number is masked.
+#[derive(Debug, Serialize)]
+pub struct PayeeResponse {
+    pub id: Uuid,
+    pub environment: String,
+    #[serde(rename = "organization_id")]
+    pub organization_id: Uuid,
+    #[serde(rename = "user_id")]
+    pub user_id: Uuid,
+    pub name: String,
+    #[serde(rename = "bank_name")]
+    pub bank_name: Option<String>,
+    #[serde(rename = "routing_number")]
+    pub routing_number: String,
+    #[serde(rename = "account_number_last4")]
+    pub account_number_last4: String,
+    #[serde(rename = "bank_account_type")]
+    pub bank_account_type: BankAccountType,
+    pub status: PayeeStatus,
+    #[serde(rename = "created_at")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "updated_at")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Payee> for PayeeResponse {
+    fn from(payee: Payee) -> Self {
+        let last4_start = payee.account_number.len().saturating_sub(4);
+        Self {
+            id: payee.id,
+            environment: payee.environment,
+            organization_id: payee.organization_id,
+            user_id: payee.user_id,
+            name: payee.name,
+            bank_name: payee.bank_name,
+            routing_number: payee.routing_number,
+            account_number_last4: payee.account_number[last4_start..].to_string(),
+            bank_account_type: payee.bank_account_type,
+            status: payee.status,
+            created_at: payee.created_at,
+            updated_at: payee.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePayeeRequest {
+    #[serde(rename = "organization_id")]
+    pub organization_id: Uuid,
+    #[serde(rename = "user_id")]
+    pub user_id: Uuid,
+    pub name: String,
+    #[serde(default)]
+    #[serde(rename = "bank_name")]
+    pub bank_name: Option<String>,
+    #[serde(rename = "routing_number")]
+    pub routing_number: String,
+    #[serde(rename = "account_number")]
+    pub account_number: String,
+    #[serde(default = "default_bank_account_type")]
+    #[serde(rename = "bank_account_type")]
+    pub bank_account_type: BankAccountType,
+}
+
+fn default_bank_account_type() -> BankAccountType {
+    BankAccountType::Checking
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PayoutStatus {
+    /// Account debited; waiting for the Ledger post or rail submission
+    Pending,
+    Submitted,
+    Settled,
+    /// Rejected by the receiving bank; the account has been credited back
+    Returned,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Payout {
+    pub id: Uuid,
+    pub environment: String,
+    #[serde(rename = "organization_id")]
+    pub organization_id: Uuid,
+    #[serde(rename = "account_id")]
+    pub account_id: Uuid,
+    #[serde(rename = "payee_id")]
+    pub payee_id: Uuid,
+    #[serde(rename = "transaction_id")]
+    pub transaction_id: Uuid,
+    #[serde(rename = "return_transaction_id")]
+    pub return_transaction_id: Option<Uuid>,
+    pub amount: i64,
+    pub currency: String,
+    pub status: PayoutStatus,
+    pub rail: String,
+    #[serde(rename = "rail_reference")]
+    pub rail_reference: Option<String>,
+    #[serde(rename = "return_code")]
+    pub return_code: Option<String>,
+    #[serde(rename = "return_reason")]
+    pub return_reason: Option<String>,
+    #[serde(rename = "failure_reason")]
+    pub failure_reason: Option<String>,
+    #[serde(rename = "submitted_at")]
+    pub submitted_at: Option<DateTime<Utc>>,
+    #[serde(rename = "settled_at")]
+    pub settled_at: Option<DateTime<Utc>>,
+    #[serde(rename = "returned_at")]
+    pub returned_at: Option<DateTime<Utc>>,
+    #[serde(rename = "created_at")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "updated_at")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePayoutRequest {
+    #[serde(rename = "payee_id")]
+    pub payee_id: Uuid,
+    /// Minor units (1234) or a decimal string in the account currency ("12.34")
+    pub amount: AmountInput,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PayoutResponse {
+    pub payout: Payout,
+    pub transaction: TransactionResponse,
+}
@@ -0,0 +1,10 @@
+use uuid::Uuid;
+
+/// An organization's budget for one route class, overriding the service default.
+#[derive(Debug, Clone)]
+pub struct RateLimitPolicy {
+    pub organization_id: Uuid,
+    pub route_class: String,
+    pub requests_per_minute: i32,
+    pub burst: i32,
+}
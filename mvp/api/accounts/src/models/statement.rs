@@ -0,0 +1,143 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::{AccountMovement, MovementKind};
+
+/// One posted movement on a statement and the account balance right after it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatementLine {
+    #[serde(flatten)]
+    pub movement: AccountMovement,
+    #[serde(rename = "running_balance")]
+    pub running_balance: i64,
+}
+
+/// Count and sums of one movement kind over the statement period (debits as positive amounts).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatementKindTotal {
+    pub kind: MovementKind,
+    pub count: i64,
+    pub credits: i64,
+    pub debits: i64,
+}
+
+/// Account statement for `[period_start, period_end]` (whole days, UTC).
+/// `id` is set once the statement is stored; only completed calendar months are stored,
+/// and stored statements never change.
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountStatement {
+    pub id: Option<Uuid>,
+    #[serde(rename = "account_id")]
+    pub account_id: Uuid,
+    #[serde(rename = "organization_id")]
+    pub organization_id: Option<Uuid>,
+    pub environment: String,
+    #[serde(rename = "period_start")]
+    pub period_start: NaiveDate,
+    #[serde(rename = "period_end")]
+    pub period_end: NaiveDate,
+    pub currency: String,
+    #[serde(rename = "opening_balance")]
+    pub opening_balance: i64,
+    #[serde(rename = "closing_balance")]
+    pub closing_balance: i64,
+    #[serde(rename = "total_credits")]
+    pub total_credits: i64,
+    #[serde(rename = "total_debits")]
+    pub total_debits: i64,
+    pub totals: Vec<StatementKindTotal>,
+    pub lines: Vec<StatementLine>,
+    #[serde(rename = "created_at")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Stored statement without its lines, for listings.
+#[derive(Debug, Clone, Serialize)]
+pub struct StatementSummary {
+    pub id: Uuid,
+    #[serde(rename = "period_start")]
+    pub period_start: NaiveDate,
+    #[serde(rename = "period_end")]
+    pub period_end: NaiveDate,
+    pub currency: String,
+    #[serde(rename = "opening_balance")]
+    pub opening_balance: i64,
+    #[serde(rename = "closing_balance")]
+    pub closing_balance: i64,
+    #[serde(rename = "line_count")]
+    pub line_count: i64,
+    #[serde(rename = "created_at")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl AccountStatement {
+    /// CSV rendering: an opening balance row, one row per line, a closing balance row.
+    /// Amounts are in minor units, like the JSON form.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "booked_at,kind,transaction_id,reference,counterparty_account_id,amount,running_balance,currency\n",
+        );
+
+        let start = self.period_start.to_string();
+        let end = self.period_end.to_string();
+        let opening_balance = self.opening_balance.to_string();
+        let closing_balance = self.closing_balance.to_string();
+        push_csv_row(
+            &mut csv,
+            &[&start, "opening_balance", "", "", "", "", &opening_balance, &self.currency],
+        );
+
+        for line in &self.lines {
+            let movement = &line.movement;
+            push_csv_row(
+                &mut csv,
+                &[
+                    &movement.booked_at.to_rfc3339(),
+                    kind_str(movement.kind),
+                    &movement.transaction_id.to_string(),
+                    &movement.reference,
+                    &movement
+                        .counterparty_account_id
+                        .map(|id| id.to_string())
+                        .unwrap_or_default(),
+                    &movement.amount.to_string(),
+                    &line.running_balance.to_string(),
+                    &movement.currency,
+                ],
+            );
+        }
+
+        push_csv_row(
+            &mut csv,
+            &[&end, "closing_balance", "", "", "", "", &closing_balance, &self.currency],
+        );
+        csv
+    }
+}
+
+fn kind_str(kind: MovementKind) -> &'static str {
+    match kind {
+        MovementKind::Deposit => "deposit",
+        MovementKind::Withdraw => "withdraw",
+        MovementKind::Transfer => "transfer",
+        MovementKind::Interest => "interest",
+        MovementKind::Fee => "fee",
+    }
+}
+
+fn push_csv_row(csv: &mut String, fields: &[&str]) {
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            csv.push(',');
+        }
+        if field.contains([',', '"', '\n', '\r']) {
+            csv.push('"');
+            csv.push_str(&field.replace('"', "\"\""));
+            csv.push('"');
+        } else {
+            csv.push_str(field);
+        }
+    }
+    csv.push('\n');
+}
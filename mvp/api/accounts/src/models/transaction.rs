@@ -1,8 +1,12 @@
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use crate::errors::AppError;
+use crate::models::{format_amount, validate_metadata, AmountInput, Currency, Metadata, TransactionFee};
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Transaction {
     pub id: Uuid,
@@ -22,10 +26,56 @@ pub struct Transaction {
     #[serde(rename = "idempotency_key")]
     pub idempotency_key: String,
     pub environment: Option<String>,
+    /// Amount credited to the destination account (cross-currency transfers only)
+    #[serde(rename = "destination_amount")]
+    pub destination_amount: Option<i64>,
+    #[serde(rename = "destination_currency")]
+    pub destination_currency: Option<String>,
+    /// Units of destination_currency per unit of currency
+    #[serde(default, with = "rust_decimal::serde::str_option")]
+    pub fx_rate: Option<Decimal>,
+    #[serde(rename = "fx_quote_id")]
+    pub fx_quote_id: Option<Uuid>,
+    /// Receiving organization of an inter-organization transfer
+    #[serde(rename = "counterparty_organization_id")]
+    pub counterparty_organization_id: Option<Uuid>,
+    #[serde(rename = "interorg_agreement_id")]
+    pub interorg_agreement_id: Option<Uuid>,
+    /// NACHA file and trace number the (payout) withdrawal was sent to the bank in
+    #[serde(rename = "nacha_file_id")]
+    pub nacha_file_id: Option<Uuid>,
+    #[serde(rename = "nacha_trace_number")]
+    pub nacha_trace_number: Option<String>,
+    pub description: Option<String>,
+    /// Client reference, e.g. an invoice or order number
+    pub reference: Option<String>,
+    #[serde(default)]
+    #[sqlx(json)]
+    pub metadata: Metadata,
     #[serde(rename = "created_at")]
     pub created_at: DateTime<Utc>,
     #[serde(rename = "updated_at")]
     pub updated_at: DateTime<Utc>,
+    /// Returned for an idempotency key that was already used (not part of the resource)
+    #[serde(skip)]
+    #[sqlx(default)]
+    pub replayed: bool,
+}
+
+impl Transaction {
+    /// Inter-organization transfers are posted to each organization's Ledger via its
+    /// inter-org clearing account.
+    pub fn is_cross_organization(&self) -> bool {
+        self.counterparty_organization_id
+            .is_some_and(|organization_id| organization_id != self.organization_id)
+    }
+
+    /// Cross-currency transfers are posted to the Ledger as two legs via FX clearing accounts.
+    pub fn is_cross_currency(&self) -> bool {
+        self.destination_currency
+            .as_deref()
+            .is_some_and(|currency| currency != self.currency)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
@@ -34,6 +84,7 @@ pub enum TransactionKind {
     Deposit,
     Withdraw,
     Transfer,
+    Interest,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
@@ -45,14 +96,61 @@ pub enum TransactionStatus {
     Failed,
 }
 
+/// Longest transaction description and reference accepted
+pub const MAX_DESCRIPTION_CHARS: usize = 500;
+pub const MAX_REFERENCE_CHARS: usize = 100;
+
+/// Client-supplied fields stored with an intent. They are part of the request an
+/// Idempotency-Key is bound to.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TransactionDetails {
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub reference: Option<String>,
+    #[serde(default)]
+    pub metadata: Metadata,
+}
+
+impl TransactionDetails {
+    /// Trims description and reference (blank ones are dropped) and checks the limits.
+    pub fn normalized(self) -> Result<Self, AppError> {
+        let trimmed = |value: Option<String>, field: &str, max_chars: usize| -> Result<Option<String>, AppError> {
+            let value = value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+            if value.as_ref().is_some_and(|v| v.chars().count() > max_chars) {
+                return Err(AppError::Validation(format!("{} exceeds {} characters", field, max_chars)));
+            }
+            Ok(value)
+        };
+
+        validate_metadata(&self.metadata)?;
+        Ok(Self {
+            description: trimmed(self.description, "description", MAX_DESCRIPTION_CHARS)?,
+            reference: trimmed(self.reference, "reference", MAX_REFERENCE_CHARS)?,
+            metadata: self.metadata,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.description.is_none() && self.reference.is_none() && self.metadata.is_empty()
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CreateTransactionRequest {
     #[serde(rename = "from_account_id")]
     pub from_account_id: Uuid,
     #[serde(rename = "to_account_id")]
     pub to_account_id: Uuid,
-    pub amount: i64,
-    pub currency: String,
+    /// Minor units (1234) or a decimal string in major units ("12.34")
+    pub amount: AmountInput,
+    pub currency: Currency,
+    /// Locked FX quote to use when the destination account holds a different currency
+    #[serde(default)]
+    #[serde(rename = "fx_quote_id")]
+    pub fx_quote_id: Option<Uuid>,
+    #[serde(flatten)]
+    pub details: TransactionDetails,
 }
 
 #[derive(Debug, Serialize)]
@@ -65,6 +163,9 @@ pub struct TransactionResponse {
     #[serde(rename = "to_account_id")]
     pub to_account_id: Uuid,
     pub amount: i64,
+    /// `amount` in major units, e.g. "12.34"
+    #[serde(rename = "amount_decimal")]
+    pub amount_decimal: Option<String>,
     pub currency: String,
     #[serde(rename = "transaction_kind")]
     pub transaction_kind: TransactionKind,
@@ -74,34 +175,139 @@ pub struct TransactionResponse {
     #[serde(rename = "idempotency_key")]
     pub idempotency_key: String,
     pub environment: Option<String>,
+    #[serde(rename = "destination_amount")]
+    pub destination_amount: Option<i64>,
+    #[serde(rename = "destination_amount_decimal")]
+    pub destination_amount_decimal: Option<String>,
+    #[serde(rename = "destination_currency")]
+    pub destination_currency: Option<String>,
+    #[serde(with = "rust_decimal::serde::str_option")]
+    pub fx_rate: Option<Decimal>,
+    #[serde(rename = "fx_quote_id")]
+    pub fx_quote_id: Option<Uuid>,
+    #[serde(rename = "counterparty_organization_id")]
+    pub counterparty_organization_id: Option<Uuid>,
+    #[serde(rename = "interorg_agreement_id")]
+    pub interorg_agreement_id: Option<Uuid>,
+    #[serde(rename = "nacha_file_id")]
+    pub nacha_file_id: Option<Uuid>,
+    #[serde(rename = "nacha_trace_number")]
+    pub nacha_trace_number: Option<String>,
+    pub description: Option<String>,
+    pub reference: Option<String>,
+    pub metadata: Metadata,
+    /// Fees charged on top of `amount` (see fee schedules)
+    pub fees: Vec<TransactionFee>,
     #[serde(rename = "created_at")]
     pub created_at: DateTime<Utc>,
     #[serde(rename = "updated_at")]
     pub updated_at: DateTime<Utc>,
 }
 
+impl TransactionResponse {
+    pub fn with_fees(mut self, fees: Vec<TransactionFee>) -> Self {
+        self.fees = fees;
+        self
+    }
+}
+
 impl From<Transaction> for TransactionResponse {
     fn from(transaction: Transaction) -> Self {
+        let amount_decimal = format_amount(&transaction.currency, transaction.amount);
+        let destination_amount_decimal = match (&transaction.destination_currency, transaction.destination_amount) {
+            (Some(currency), Some(amount)) => format_amount(currency, amount),
+            _ => None,
+        };
+
         Self {
             id: transaction.id,
             organization_id: transaction.organization_id,
             from_account_id: transaction.from_account_id,
             to_account_id: transaction.to_account_id,
             amount: transaction.amount,
+            amount_decimal,
             currency: transaction.currency,
             transaction_kind: transaction.transaction_kind,
             status: transaction.status,
             failure_reason: transaction.failure_reason,
             idempotency_key: transaction.idempotency_key,
             environment: transaction.environment,
+            destination_amount: transaction.destination_amount,
+            destination_amount_decimal,
+            destination_currency: transaction.destination_currency,
+            fx_rate: transaction.fx_rate,
+            fx_quote_id: transaction.fx_quote_id,
+            counterparty_organization_id: transaction.counterparty_organization_id,
+            interorg_agreement_id: transaction.interorg_agreement_id,
+            nacha_file_id: transaction.nacha_file_id,
+            nacha_trace_number: transaction.nacha_trace_number,
+            description: transaction.description,
+            reference: transaction.reference,
+            metadata: transaction.metadata,
+            fees: Vec::new(),
             created_at: transaction.created_at,
             updated_at: transaction.updated_at,
         }
     }
 }
 
+/// Optional filters for transaction listings. `from` is inclusive, `to` exclusive;
+/// amounts are in minor units of the transaction currency.
+#[derive(Debug, Clone, Default)]
+pub struct TransactionFilter {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub status: Option<TransactionStatus>,
+    pub kind: Option<TransactionKind>,
+    pub min_amount: Option<i64>,
+    pub max_amount: Option<i64>,
+    /// Either side of the transaction is this account
+    pub counterparty_account_id: Option<Uuid>,
+    pub idempotency_key_prefix: Option<String>,
+    pub reference: Option<String>,
+    /// Metadata containing all these pairs
+    pub metadata: Metadata,
+    pub order: SortOrder,
+}
+
+/// Listing order by creation time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
 #[derive(Debug, Serialize)]
 pub struct PaginatedTransactionsResponse {
     pub data: Vec<TransactionResponse>,
     pub pagination: crate::models::account::PaginationMeta,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MovementKind {
+    Deposit,
+    Withdraw,
+    Transfer,
+    Interest,
+    Fee,
+}
+
+/// A posted change to one account's balance: a transaction intent or a fee leg.
+/// `amount` is signed from the account's point of view (credits positive).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountMovement {
+    pub id: Uuid,
+    #[serde(rename = "transaction_id")]
+    pub transaction_id: Uuid,
+    pub kind: MovementKind,
+    pub amount: i64,
+    pub currency: String,
+    /// Idempotency key of the originating request
+    pub reference: String,
+    #[serde(rename = "counterparty_account_id")]
+    pub counterparty_account_id: Option<Uuid>,
+    #[serde(rename = "booked_at")]
+    pub booked_at: DateTime<Utc>,
+}
@@ -0,0 +1,103 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookEndpointStatus {
+    Active,
+    Disabled,
+}
+
+/// An organization's webhook receiver. `event_types` empty = every event type.
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookEndpoint {
+    pub id: Uuid,
+    #[serde(rename = "organization_id")]
+    pub organization_id: Uuid,
+    pub environment: String,
+    pub url: String,
+    pub description: Option<String>,
+    #[serde(rename = "event_types")]
+    pub event_types: Vec<String>,
+    pub status: WebhookEndpointStatus,
+    #[serde(rename = "created_at")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "updated_at")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Returned once, on creation: the endpoint and its signing secret.
+#[derive(Debug, Serialize)]
+pub struct CreatedWebhookEndpoint {
+    #[serde(flatten)]
+    pub endpoint: WebhookEndpoint,
+    pub secret: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateWebhookEndpointRequest {
+    #[serde(rename = "organization_id")]
+    pub organization_id: Uuid,
+    pub url: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    #[serde(rename = "event_types")]
+    pub event_types: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Succeeded,
+    Failed,
+}
+
+/// One event sent (or to be sent) to one endpoint.
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    #[serde(rename = "endpoint_id")]
+    pub endpoint_id: Uuid,
+    #[serde(rename = "event_id")]
+    pub event_id: Uuid,
+    #[serde(rename = "event_type")]
+    pub event_type: String,
+    pub status: WebhookDeliveryStatus,
+    #[serde(rename = "attempt_count")]
+    pub attempt_count: i32,
+    #[serde(rename = "next_attempt_at")]
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    #[serde(rename = "last_attempt_at")]
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    #[serde(rename = "last_response_status")]
+    pub last_response_status: Option<i32>,
+    #[serde(rename = "last_error")]
+    pub last_error: Option<String>,
+    #[serde(rename = "created_at")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookDeliveryAttempt {
+    #[serde(rename = "attempt_number")]
+    pub attempt_number: i32,
+    #[serde(rename = "response_status")]
+    pub response_status: Option<i32>,
+    pub error: Option<String>,
+    #[serde(rename = "duration_ms")]
+    pub duration_ms: i64,
+    #[serde(rename = "created_at")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Delivery log entry: the delivery, the event payload and every attempt.
+#[derive(Debug, Serialize)]
+pub struct WebhookDeliveryDetail {
+    #[serde(flatten)]
+    pub delivery: WebhookDelivery,
+    pub payload: serde_json::Value,
+    pub attempts: Vec<WebhookDeliveryAttempt>,
+}
@@ -0,0 +1,599 @@
+//! NACHA ACH file format: building PPD credit files for outbound payouts and parsing
+//! return files. All records are 94 characters; files are padded to blocks of 10 records.
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, Datelike, Days, NaiveDate, Utc, Weekday};
+
+use crate::errors::AppError;
+use crate::utils::is_valid_routing_number;
+
+const RECORD_SIZE: usize = 94;
+const BLOCKING_FACTOR: usize = 10;
+/// Credits only: payouts never debit the receiver
+const SERVICE_CLASS_CREDITS: &str = "220";
+/// Largest amount (cents) that fits the 10-digit entry amount field
+pub const MAX_ENTRY_AMOUNT: i64 = 9_999_999_999;
+
+/// Originator settings agreed with the bank partner (NACHA_* environment variables).
+#[derive(Debug, Clone)]
+pub struct NachaConfig {
+    /// Routing number of the bank receiving the file
+    pub immediate_destination: String,
+    pub immediate_destination_name: String,
+    /// Originator identifier assigned by the bank (up to 10 characters)
+    pub immediate_origin: String,
+    pub immediate_origin_name: String,
+    pub company_name: String,
+    pub company_id: String,
+    pub company_entry_description: String,
+    /// First 8 digits of the originating bank's routing number; prefix of every trace number
+    pub originating_dfi: String,
+    pub output_dir: PathBuf,
+}
+
+impl NachaConfig {
+    pub fn from_env() -> Result<Self, AppError> {
+        let required = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .ok_or_else(|| AppError::Internal(format!("{} must be set when PAYOUT_RAIL=nacha", name)))
+        };
+
+        let immediate_destination = required("NACHA_IMMEDIATE_DESTINATION")?;
+        if !is_valid_routing_number(&immediate_destination) {
+            return Err(AppError::Internal(
+                "NACHA_IMMEDIATE_DESTINATION must be a valid routing number".to_string(),
+            ));
+        }
+
+        let originating_dfi = std::env::var("NACHA_ORIGINATING_DFI")
+            .unwrap_or_else(|_| immediate_destination[..8].to_string());
+        if originating_dfi.len() != 8 || !originating_dfi.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(AppError::Internal("NACHA_ORIGINATING_DFI must be 8 digits".to_string()));
+        }
+
+        let immediate_origin = required("NACHA_IMMEDIATE_ORIGIN")?;
+        let company_id = required("NACHA_COMPANY_ID")?;
+        if immediate_origin.len() > 10 || company_id.len() > 10 {
+            return Err(AppError::Internal(
+                "NACHA_IMMEDIATE_ORIGIN and NACHA_COMPANY_ID must be at most 10 characters".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            immediate_destination,
+            immediate_destination_name: required("NACHA_IMMEDIATE_DESTINATION_NAME")?,
+            immediate_origin,
+            immediate_origin_name: required("NACHA_IMMEDIATE_ORIGIN_NAME")?,
+            company_name: required("NACHA_COMPANY_NAME")?,
+            company_id,
+            company_entry_description: std::env::var("NACHA_COMPANY_ENTRY_DESCRIPTION")
+                .unwrap_or_else(|_| "PAYOUT".to_string()),
+            originating_dfi,
+            output_dir: PathBuf::from(std::env::var("NACHA_OUTPUT_DIR").unwrap_or_else(|_| "./nacha".to_string())),
+        })
+    }
+
+    /// Trace number of an entry: originating DFI followed by a 7-digit sequence.
+    pub fn trace_number(&self, sequence: i64) -> String {
+        format!("{}{:07}", self.originating_dfi, sequence % 10_000_000)
+    }
+}
+
+/// One credit entry (with a single addenda record) to an external bank account.
+#[derive(Debug, Clone)]
+pub struct NachaEntry {
+    /// 22 = checking credit, 32 = savings credit
+    pub transaction_code: &'static str,
+    pub routing_number: String,
+    pub account_number: String,
+    /// Cents
+    pub amount: i64,
+    pub individual_id: String,
+    pub individual_name: String,
+    /// Free-form addenda text (80 characters max)
+    pub payment_info: String,
+    pub trace_number: String,
+}
+
+/// Entries of one company batch (one per organization).
+#[derive(Debug, Clone)]
+pub struct NachaBatch {
+    pub company_discretionary_data: String,
+    pub entries: Vec<NachaEntry>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NachaFileContents {
+    pub contents: String,
+    pub batch_count: i32,
+    pub entry_count: i32,
+    pub entry_hash: i64,
+    pub total_credit_amount: i64,
+}
+
+/// Build a complete file: file header, one batch (header, entries + addenda, control) per
+/// batch, file control, and `9` padding records up to a multiple of 10 records.
+pub fn build_file(
+    config: &NachaConfig,
+    batches: &[NachaBatch],
+    created_at: DateTime<Utc>,
+    file_id_modifier: char,
+    effective_entry_date: NaiveDate,
+) -> Result<NachaFileContents, AppError> {
+    let mut records: Vec<String> = Vec::new();
+
+    records.push(
+        [
+            "1".to_string(),
+            "01".to_string(),
+            alpha(&format!(" {}", config.immediate_destination), 10),
+            right(&config.immediate_origin, 10),
+            created_at.format("%y%m%d").to_string(),
+            created_at.format("%H%M").to_string(),
+            file_id_modifier.to_string(),
+            "094".to_string(),
+            "10".to_string(),
+            "1".to_string(),
+            alpha(&config.immediate_destination_name, 23),
+            alpha(&config.immediate_origin_name, 23),
+            alpha("", 8),
+        ]
+        .concat(),
+    );
+
+    let mut file_entry_count: i64 = 0;
+    let mut file_entry_hash: i64 = 0;
+    let mut file_total_credit: i64 = 0;
+
+    for (index, batch) in batches.iter().enumerate() {
+        let batch_number = numeric(index as i64 + 1, 7);
+
+        records.push(
+            [
+                "5".to_string(),
+                SERVICE_CLASS_CREDITS.to_string(),
+                alpha(&config.company_name, 16),
+                alpha(&batch.company_discretionary_data, 20),
+                alpha(&config.company_id, 10),
+                "PPD".to_string(),
+                alpha(&config.company_entry_description, 10),
+                created_at.format("%y%m%d").to_string(),
+                effective_entry_date.format("%y%m%d").to_string(),
+                alpha("", 3),
+                "1".to_string(),
+                config.originating_dfi.clone(),
+                batch_number.clone(),
+            ]
+            .concat(),
+        );
+
+        let mut batch_entry_count: i64 = 0;
+        let mut batch_entry_hash: i64 = 0;
+        let mut batch_total_credit: i64 = 0;
+
+        for entry in &batch.entries {
+            if entry.amount <= 0 || entry.amount > MAX_ENTRY_AMOUNT {
+                return Err(AppError::Validation(format!(
+                    "NACHA entry {} amount {} is out of range",
+                    entry.trace_number, entry.amount
+                )));
+            }
+            if !is_valid_routing_number(&entry.routing_number) {
+                return Err(AppError::Validation(format!(
+                    "NACHA entry {} has an invalid routing number",
+                    entry.trace_number
+                )));
+            }
+
+            let receiving_dfi = &entry.routing_number[..8];
+            records.push(
+                [
+                    "6".to_string(),
+                    entry.transaction_code.to_string(),
+                    receiving_dfi.to_string(),
+                    entry.routing_number[8..].to_string(),
+                    alpha(&entry.account_number, 17),
+                    numeric(entry.amount, 10),
+                    alpha(&entry.individual_id, 15),
+                    alpha(&entry.individual_name, 22),
+                    alpha("", 2),
+                    "1".to_string(),
+                    entry.trace_number.clone(),
+                ]
+                .concat(),
+            );
+
+            records.push(
+                [
+                    "7".to_string(),
+                    "05".to_string(),
+                    alpha(&entry.payment_info, 80),
+                    "0001".to_string(),
+                    entry.trace_number[entry.trace_number.len() - 7..].to_string(),
+                ]
+                .concat(),
+            );
+
+            batch_entry_count += 2;
+            batch_entry_hash += receiving_dfi.parse::<i64>().unwrap_or(0);
+            batch_total_credit += entry.amount;
+        }
+
+        records.push(
+            [
+                "8".to_string(),
+                SERVICE_CLASS_CREDITS.to_string(),
+                numeric(batch_entry_count, 6),
+                numeric(batch_entry_hash % 10_000_000_000, 10),
+                numeric(0, 12),
+                numeric(batch_total_credit, 12),
+                alpha(&config.company_id, 10),
+                alpha("", 19),
+                alpha("", 6),
+                config.originating_dfi.clone(),
+                batch_number,
+            ]
+            .concat(),
+        );
+
+        file_entry_count += batch_entry_count;
+        file_entry_hash += batch_entry_hash;
+        file_total_credit += batch_total_credit;
+    }
+
+    // File control is the last real record; padding is not counted in the block count
+    let block_count = (records.len() + 1).div_ceil(BLOCKING_FACTOR);
+    let entry_hash = file_entry_hash % 10_000_000_000;
+
+    records.push(
+        [
+            "9".to_string(),
+            numeric(batches.len() as i64, 6),
+            numeric(block_count as i64, 6),
+            numeric(file_entry_count, 8),
+            numeric(entry_hash, 10),
+            numeric(0, 12),
+            numeric(file_total_credit, 12),
+            alpha("", 39),
+        ]
+        .concat(),
+    );
+
+    while !records.len().is_multiple_of(BLOCKING_FACTOR) {
+        records.push("9".repeat(RECORD_SIZE));
+    }
+
+    debug_assert!(records.iter().all(|r| r.len() == RECORD_SIZE));
+
+    let mut contents = records.join("\n");
+    contents.push('\n');
+
+    Ok(NachaFileContents {
+        contents,
+        batch_count: batches.len() as i32,
+        entry_count: file_entry_count as i32,
+        entry_hash,
+        total_credit_amount: file_total_credit,
+    })
+}
+
+/// A returned entry from a NACHA return file (entry detail + addenda type 99).
+#[derive(Debug, Clone)]
+pub struct NachaReturn {
+    pub return_code: String,
+    /// Trace number of the entry as originally sent
+    pub original_trace_number: String,
+    /// Cents
+    pub amount: i64,
+}
+
+/// Extract the returned entries of a return file. Notifications of change (addenda 98)
+/// are ignored.
+pub fn parse_returns(contents: &str) -> Result<Vec<NachaReturn>, AppError> {
+    let mut returns = Vec::new();
+    let mut entry_amount: Option<i64> = None;
+
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if line.len() != RECORD_SIZE || !line.is_ascii() {
+            return Err(AppError::Validation(format!(
+                "NACHA record {} is not {} ASCII characters",
+                index + 1,
+                RECORD_SIZE
+            )));
+        }
+
+        match &line[..1] {
+            "6" => {
+                entry_amount = Some(line[29..39].parse::<i64>().map_err(|_| {
+                    AppError::Validation(format!("NACHA record {} has an invalid amount", index + 1))
+                })?);
+            }
+            "7" if &line[1..3] == "99" => {
+                let amount = entry_amount.take().ok_or_else(|| {
+                    AppError::Validation(format!(
+                        "NACHA return addenda at record {} has no entry detail record",
+                        index + 1
+                    ))
+                })?;
+
+                returns.push(NachaReturn {
+                    return_code: line[3..6].trim().to_string(),
+                    original_trace_number: line[6..21].trim().to_string(),
+                    amount,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    Ok(returns)
+}
+
+/// Description of an ACH return reason code.
+pub fn return_reason(code: &str) -> &'static str {
+    match code {
+        "R01" => "Insufficient funds",
+        "R02" => "Account closed",
+        "R03" => "No account/unable to locate account",
+        "R04" => "Invalid account number structure",
+        "R06" => "Returned per ODFI's request",
+        "R07" => "Authorization revoked by customer",
+        "R08" => "Payment stopped",
+        "R09" => "Uncollected funds",
+        "R10" => "Customer advises not authorized",
+        "R11" => "Customer advises entry not in accordance with the terms of the authorization",
+        "R12" => "Account sold to another DFI",
+        "R13" => "Invalid ACH routing number",
+        "R14" => "Representative payee deceased or unable to continue in that capacity",
+        "R15" => "Beneficiary or account holder deceased",
+        "R16" => "Account frozen/entry returned per OFAC instruction",
+        "R17" => "File record edit criteria",
+        "R20" => "Non-transaction account",
+        "R23" => "Credit entry refused by receiver",
+        "R24" => "Duplicate entry",
+        "R29" => "Corporate customer advises not authorized",
+        "R31" => "Permissible return entry",
+        _ => "Returned by receiving bank",
+    }
+}
+
+/// Next weekday after `date` (bank holidays are left to the ODFI to roll forward).
+pub fn next_business_day(date: NaiveDate) -> NaiveDate {
+    let mut next = date + Days::new(1);
+    while matches!(next.weekday(), Weekday::Sat | Weekday::Sun) {
+        next = next + Days::new(1);
+    }
+    next
+}
+
+/// File ID modifier for the n-th file of a day (A-Z, then 0-9).
+pub fn file_id_modifier(files_created_today: i64) -> Option<char> {
+    const MODIFIERS: &[u8; 36] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+    usize::try_from(files_created_today)
+        .ok()
+        .and_then(|n| MODIFIERS.get(n))
+        .map(|b| *b as char)
+}
+
+/// Uppercase, left-justified, space-padded alphanumeric field (non-printable characters blanked).
+fn alpha(value: &str, width: usize) -> String {
+    let cleaned: String = value
+        .chars()
+        .map(|c| if c.is_ascii_graphic() || c == ' ' { c.to_ascii_uppercase() } else { ' ' })
+        .take(width)
+        .collect();
+    format!("{:<width$}", cleaned, width = width)
+}
+
+/// Right-justified, space-padded field.
+fn right(value: &str, width: usize) -> String {
+    let cleaned: String = value.chars().filter(|c| c.is_ascii_graphic()).take(width).collect();
+    format!("{:>width$}", cleaned, width = width)
+}
+
+/// Right-justified, zero-padded numeric field.
+fn numeric(value: i64, width: usize) -> String {
+    format!("{:0width$}", value, width = width)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn config() -> NachaConfig {
+        NachaConfig {
+            immediate_destination: "021000021".to_string(),
+            immediate_destination_name: "JPMORGAN CHASE".to_string(),
+            immediate_origin: "1234567890".to_string(),
+            immediate_origin_name: "Example Payments".to_string(),
+            company_name: "Example Payments".to_string(),
+            company_id: "1234567890".to_string(),
+            company_entry_description: "PAYOUT".to_string(),
+            originating_dfi: "02100002".to_string(),
+            output_dir: PathBuf::from("./nacha"),
+        }
+    }
+
+    fn entry(routing_number: &str, amount: i64, sequence: i64) -> NachaEntry {
+        NachaEntry {
+            transaction_code: "22",
+            routing_number: routing_number.to_string(),
+            account_number: "123456789".to_string(),
+            amount,
+            individual_id: "user-1".to_string(),
+            individual_name: "Jane Doe".to_string(),
+            payment_info: "payout".to_string(),
+            trace_number: config().trace_number(sequence),
+        }
+    }
+
+    fn build(batches: &[NachaBatch]) -> NachaFileContents {
+        let created_at = Utc.with_ymd_and_hms(2026, 10, 16, 14, 5, 0).unwrap();
+        let effective = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        build_file(&config(), batches, created_at, 'A', effective).unwrap()
+    }
+
+    fn batch(entries: Vec<NachaEntry>) -> NachaBatch {
+        NachaBatch { company_discretionary_data: "org".to_string(), entries }
+    }
+
+    #[test]
+    fn records_are_94_characters_in_blocks_of_ten() {
+        let file = build(&[batch(vec![entry("021000021", 1500, 1), entry("011000015", 250, 2)])]);
+        let records: Vec<&str> = file.contents.lines().collect();
+
+        assert!(records.iter().all(|r| r.len() == RECORD_SIZE));
+        // 1 file header + 1 batch header + 2 x (entry + addenda) + batch control + file control = 8
+        assert_eq!(records.len(), 10);
+        assert_eq!(records[8], "9".repeat(RECORD_SIZE));
+        assert_eq!(records[9], "9".repeat(RECORD_SIZE));
+        assert_eq!(records.iter().map(|r| &r[..1]).collect::<String>(), "1567678999");
+    }
+
+    #[test]
+    fn record_fields_are_at_their_positions() {
+        let file = build(&[batch(vec![entry("021000021", 1500, 1)])]);
+        let records: Vec<&str> = file.contents.lines().collect();
+
+        let header = records[0];
+        assert_eq!(&header[3..13], " 021000021");
+        assert_eq!(&header[13..23], "1234567890");
+        assert_eq!(&header[23..29], "261016");
+        assert_eq!(&header[29..33], "1405");
+        assert_eq!(&header[33..34], "A");
+        assert_eq!(&header[34..40], "094101");
+
+        let batch_header = records[1];
+        assert_eq!(&batch_header[1..4], "220");
+        assert_eq!(&batch_header[50..53], "PPD");
+        assert_eq!(&batch_header[69..75], "261019");
+        assert_eq!(&batch_header[79..87], "02100002");
+        assert_eq!(&batch_header[87..94], "0000001");
+
+        let detail = records[2];
+        assert_eq!(&detail[1..3], "22");
+        assert_eq!(&detail[3..11], "02100002");
+        assert_eq!(&detail[11..12], "1");
+        assert_eq!(&detail[12..29], "123456789        ");
+        assert_eq!(&detail[29..39], "0000001500");
+        assert_eq!(&detail[54..76], "JANE DOE              ");
+        assert_eq!(&detail[78..79], "1");
+        assert_eq!(&detail[79..94], "021000020000001");
+
+        let addenda = records[3];
+        assert_eq!(&addenda[1..3], "05");
+        assert_eq!(&addenda[83..87], "0001");
+        assert_eq!(&addenda[87..94], "0000001");
+    }
+
+    #[test]
+    fn controls_carry_counts_hash_and_totals() {
+        let file = build(&[
+            batch(vec![entry("021000021", 1500, 1), entry("011000015", 250, 2)]),
+            batch(vec![entry("021000021", 99, 3)]),
+        ]);
+        let records: Vec<&str> = file.contents.lines().collect();
+
+        // Entry hash: sum of the 8-digit receiving DFI numbers
+        assert_eq!(file.entry_hash, 2100002 + 1100001 + 2100002);
+        assert_eq!(file.batch_count, 2);
+        assert_eq!(file.entry_count, 6);
+        assert_eq!(file.total_credit_amount, 1849);
+
+        let first_control = records[6];
+        assert_eq!(&first_control[4..10], "000004");
+        assert_eq!(&first_control[10..20], "0003200003");
+        assert_eq!(&first_control[32..44], "000000001750");
+
+        // 1 + (1 + 4 + 1) + (1 + 2 + 1) + 1 = 12 records, so 2 blocks
+        let file_control = records[11];
+        assert_eq!(&file_control[..1], "9");
+        assert_eq!(&file_control[1..7], "000002");
+        assert_eq!(&file_control[7..13], "000002");
+        assert_eq!(&file_control[13..21], "00000006");
+        assert_eq!(&file_control[21..31], "0005300005");
+        assert_eq!(&file_control[43..55], "000000001849");
+        assert_eq!(records.len(), 20);
+    }
+
+    #[test]
+    fn block_count_includes_the_file_control_record() {
+        // 1 + (1 + 6 + 1) = 9 records before the file control, which fills the block exactly
+        let file = build(&[batch((1..=3).map(|n| entry("021000021", 100, n)).collect())]);
+        let records: Vec<&str> = file.contents.lines().collect();
+
+        assert_eq!(records.len(), 10);
+        assert_eq!(&records[9][7..13], "000001");
+    }
+
+    #[test]
+    fn entry_hash_keeps_the_low_ten_digits() {
+        let entries = (1..=5000).map(|n| entry("091000019", 1, n)).collect();
+        let file = build(&[batch(entries)]);
+
+        assert_eq!(file.entry_hash, (9100001_i64 * 5000) % 10_000_000_000);
+    }
+
+    #[test]
+    fn rejects_out_of_range_amounts_and_invalid_routing_numbers() {
+        let created_at = Utc.with_ymd_and_hms(2026, 10, 16, 14, 5, 0).unwrap();
+        let effective = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        let invalid = [
+            entry("021000021", 0, 1),
+            entry("021000021", MAX_ENTRY_AMOUNT + 1, 1),
+            entry("021000022", 1, 1),
+        ];
+        for bad in invalid {
+            let result = build_file(&config(), &[batch(vec![bad])], created_at, 'A', effective);
+            assert!(matches!(result, Err(AppError::Validation(_))));
+        }
+    }
+
+    #[test]
+    fn parses_returns_from_entry_and_addenda_records() {
+        let detail = format!("{:<29}{}{:<55}", "626021000021123456789", "0000001500", "");
+        let addenda = format!("799R03{:<15}{:<73}", "021000020000001", "");
+        let change = format!("798C01{:<15}{:<73}", "021000020000002", "");
+        let contents = [detail.as_str(), addenda.as_str(), detail.as_str(), change.as_str()].join("\r\n");
+
+        let returns = parse_returns(&contents).unwrap();
+
+        assert_eq!(returns.len(), 1);
+        assert_eq!(returns[0].return_code, "R03");
+        assert_eq!(returns[0].original_trace_number, "021000020000001");
+        assert_eq!(returns[0].amount, 1500);
+        assert_eq!(return_reason(&returns[0].return_code), "No account/unable to locate account");
+    }
+
+    #[test]
+    fn rejects_malformed_return_files() {
+        let short = "6".repeat(93);
+        assert!(matches!(parse_returns(&short), Err(AppError::Validation(_))));
+
+        let orphan_addenda = format!("799R01{:<15}{:<73}", "021000020000001", "");
+        assert!(matches!(parse_returns(&orphan_addenda), Err(AppError::Validation(_))));
+
+        let bad_amount = format!("{:<29}{}{:<55}", "626021000021123456789", "00000015X0", "");
+        assert!(matches!(parse_returns(&bad_amount), Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn file_id_modifiers_and_business_days() {
+        assert_eq!(file_id_modifier(0), Some('A'));
+        assert_eq!(file_id_modifier(26), Some('0'));
+        assert_eq!(file_id_modifier(36), None);
+
+        let friday = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        assert_eq!(next_business_day(friday), NaiveDate::from_ymd_opt(2026, 10, 19).unwrap());
+    }
+}
@@ -0,0 +1,203 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+
+use crate::errors::AppError;
+use crate::models::{Payee, Payout};
+use crate::nacha::NachaConfig;
+
+/// Outcome of a submitted payout as reported by the rail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RailStatus {
+    /// Accepted by the rail, not yet settled
+    Submitted,
+    Settled,
+    /// Rejected by the receiving bank (e.g. ACH return code R03)
+    Returned { code: String, reason: String },
+}
+
+/// Payment network that moves payout funds to an external payee.
+pub trait PayoutRail {
+    /// Stored on each payout so its status is always checked against the rail it went out on.
+    fn name(&self) -> &'static str;
+
+    /// Rails that batch payouts into files (NACHA) leave pending payouts to their file job.
+    fn submits_individually(&self) -> bool {
+        true
+    }
+
+    fn supports_currency(&self, _currency: &str) -> bool {
+        true
+    }
+
+    /// Hand a payout to the rail. Returns the rail's reference for it.
+    /// Must be idempotent per payout id: the worker resubmits after errors.
+    async fn submit(&self, payout: &Payout, payee: &Payee) -> Result<String, AppError>;
+
+    /// Current status of a submitted payout.
+    async fn status(&self, payout: &Payout, payee: &Payee) -> Result<RailStatus, AppError>;
+}
+
+/// Local rail for development and sandbox: every payout settles `settle_after` its submission,
+/// except to payee account numbers ending in 0000, which are returned with R03.
+#[derive(Clone)]
+pub struct SimulatedPayoutRail {
+    settle_after: Duration,
+}
+
+impl SimulatedPayoutRail {
+    pub fn new(settle_after: Duration) -> Self {
+        Self { settle_after }
+    }
+}
+
+impl PayoutRail for SimulatedPayoutRail {
+    fn name(&self) -> &'static str {
+        "simulated"
+    }
+
+    async fn submit(&self, payout: &Payout, _payee: &Payee) -> Result<String, AppError> {
+        Ok(format!("SIM-{}", payout.id.simple()))
+    }
+
+    async fn status(&self, payout: &Payout, payee: &Payee) -> Result<RailStatus, AppError> {
+        if payee.account_number.ends_with("0000") {
+            return Ok(RailStatus::Returned {
+                code: "R03".to_string(),
+                reason: "No account/unable to locate account".to_string(),
+            });
+        }
+
+        match payout.submitted_at {
+            Some(submitted_at) if Utc::now() >= submitted_at + self.settle_after => Ok(RailStatus::Settled),
+            _ => Ok(RailStatus::Submitted),
+        }
+    }
+}
+
+/// ACH through the bank partner: payouts are written to NACHA files once per settlement
+/// window (see `services::nacha_service`). An entry is considered settled `settle_after` its
+/// file went out; returns arrive later in return files.
+#[derive(Clone)]
+pub struct NachaPayoutRail {
+    pub config: Arc<NachaConfig>,
+    settle_after: Duration,
+}
+
+impl NachaPayoutRail {
+    pub fn new(config: NachaConfig, settle_after: Duration) -> Self {
+        Self { config: Arc::new(config), settle_after }
+    }
+}
+
+impl PayoutRail for NachaPayoutRail {
+    fn name(&self) -> &'static str {
+        "nacha"
+    }
+
+    fn submits_individually(&self) -> bool {
+        false
+    }
+
+    fn supports_currency(&self, currency: &str) -> bool {
+        currency == "USD"
+    }
+
+    async fn submit(&self, _payout: &Payout, _payee: &Payee) -> Result<String, AppError> {
+        Err(AppError::BusinessLogic(
+            "NACHA payouts are submitted in settlement window files".to_string(),
+        ))
+    }
+
+    async fn status(&self, payout: &Payout, _payee: &Payee) -> Result<RailStatus, AppError> {
+        match payout.submitted_at {
+            Some(submitted_at) if Utc::now() >= submitted_at + self.settle_after => Ok(RailStatus::Settled),
+            _ => Ok(RailStatus::Submitted),
+        }
+    }
+}
+
+/// Rail selected at startup (PAYOUT_RAIL=simulated|nacha).
+#[derive(Clone)]
+pub enum PayoutRails {
+    Simulated(SimulatedPayoutRail),
+    Nacha(NachaPayoutRail),
+}
+
+impl PayoutRails {
+    pub fn from_env() -> Result<Self, AppError> {
+        let rail = std::env::var("PAYOUT_RAIL").unwrap_or_else(|_| "simulated".to_string());
+
+        match rail.as_str() {
+            "simulated" => {
+                let settle_secs = std::env::var("PAYOUT_SIMULATED_SETTLE_SECS")
+                    .ok()
+                    .and_then(|v| v.parse::<i64>().ok())
+                    .filter(|v| *v >= 0)
+                    .unwrap_or(60);
+                Ok(Self::Simulated(SimulatedPayoutRail::new(Duration::seconds(settle_secs))))
+            }
+            "nacha" => {
+                let settlement_days = std::env::var("NACHA_SETTLEMENT_DAYS")
+                    .ok()
+                    .and_then(|v| v.parse::<i64>().ok())
+                    .filter(|v| *v >= 0)
+                    .unwrap_or(2);
+                Ok(Self::Nacha(NachaPayoutRail::new(
+                    NachaConfig::from_env()?,
+                    Duration::days(settlement_days),
+                )))
+            }
+            other => Err(AppError::Internal(format!("unknown PAYOUT_RAIL: {}", other))),
+        }
+    }
+}
+
+impl PayoutRails {
+    /// The NACHA rail, for file generation; errors when another rail is configured.
+    pub fn nacha(&self) -> Result<&NachaPayoutRail, AppError> {
+        match self {
+            Self::Nacha(rail) => Ok(rail),
+            _ => Err(AppError::BusinessLogic(
+                "NACHA files are only produced when PAYOUT_RAIL=nacha".to_string(),
+            )),
+        }
+    }
+}
+
+impl PayoutRail for PayoutRails {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Simulated(rail) => rail.name(),
+            Self::Nacha(rail) => rail.name(),
+        }
+    }
+
+    fn submits_individually(&self) -> bool {
+        match self {
+            Self::Simulated(rail) => rail.submits_individually(),
+            Self::Nacha(rail) => rail.submits_individually(),
+        }
+    }
+
+    fn supports_currency(&self, currency: &str) -> bool {
+        match self {
+            Self::Simulated(rail) => rail.supports_currency(currency),
+            Self::Nacha(rail) => rail.supports_currency(currency),
+        }
+    }
+
+    async fn submit(&self, payout: &Payout, payee: &Payee) -> Result<String, AppError> {
+        match self {
+            Self::Simulated(rail) => rail.submit(payout, payee).await,
+            Self::Nacha(rail) => rail.submit(payout, payee).await,
+        }
+    }
+
+    async fn status(&self, payout: &Payout, payee: &Payee) -> Result<RailStatus, AppError> {
+        match self {
+            Self::Simulated(rail) => rail.status(payout, payee).await,
+            Self::Nacha(rail) => rail.status(payout, payee).await,
+        }
+    }
+}
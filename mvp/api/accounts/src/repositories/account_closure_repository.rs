@@ -0,0 +1,301 @@
+use crate::errors::AppError;
+use crate::models::{
+    Account, AccountClosure, AccountClosureRequest, ClosureStatus, ClosureSweep, PayoutStatus, StatusReason,
+};
+use crate::repositories::TransactionRepository;
+use sqlx::postgres::PgRow;
+use sqlx::{PgConnection, PgPool, Row};
+use uuid::Uuid;
+
+const CLOSURE_COLUMNS: &str = r#"
+    id, account_id, organization_id, environment, status, reason, note, actor_type, actor_id,
+    sweep_to_account_id, payee_id, currency, sweep_attempt, sweep_transaction_id, settled_intents,
+    cancelled_intents, last_error, created_at, completed_at
+"#;
+
+pub struct AccountClosureRepository;
+
+impl AccountClosureRepository {
+    /// Starts the account's closure, or returns the existing one, locked until the caller's
+    /// transaction ends. A closure in progress takes the new reason and destination until its
+    /// current sweep intent exists.
+    pub async fn start(
+        conn: &mut PgConnection,
+        account: &Account,
+        environment: &str,
+        currency: &str,
+        request: &AccountClosureRequest,
+        actor: (&'static str, Option<Uuid>),
+    ) -> Result<AccountClosure, AppError> {
+        let organization_id = account
+            .organization_id
+            .ok_or_else(|| AppError::Validation("organization_id is required".to_string()))?;
+        let (actor_type, actor_id) = actor;
+
+        let sql = format!(
+            r#"
+            INSERT INTO account_closures (
+                account_id, organization_id, environment, reason, note, actor_type, actor_id,
+                sweep_to_account_id, payee_id, currency
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (account_id) DO UPDATE
+            SET reason = EXCLUDED.reason,
+                note = EXCLUDED.note,
+                sweep_to_account_id = EXCLUDED.sweep_to_account_id,
+                payee_id = EXCLUDED.payee_id,
+                updated_at = NOW()
+            WHERE account_closures.status = 'in_progress' AND account_closures.sweep_transaction_id IS NULL
+            RETURNING {}
+            "#,
+            CLOSURE_COLUMNS
+        );
+
+        let row = sqlx::query(&sql)
+            .bind(account.id)
+            .bind(organization_id)
+            .bind(environment)
+            .bind(request.reason.as_str())
+            .bind(request.note.as_deref())
+            .bind(actor_type)
+            .bind(actor_id)
+            .bind(request.sweep_to_account_id)
+            .bind(request.payee_id)
+            .bind(currency)
+            .fetch_optional(&mut *conn)
+            .await?;
+
+        match row {
+            Some(row) => Self::row_to_closure(&row),
+            None => Self::find_by_account(&mut *conn, account.id)
+                .await?
+                .ok_or_else(|| AppError::Internal(format!("closure of account {} not found", account.id))),
+        }
+    }
+
+    pub async fn find_by_account(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        account_id: Uuid,
+    ) -> Result<Option<AccountClosure>, AppError> {
+        let sql = format!("SELECT {} FROM account_closures WHERE account_id = $1", CLOSURE_COLUMNS);
+        let row = sqlx::query(&sql).bind(account_id).fetch_optional(executor).await?;
+        row.map(|row| Self::row_to_closure(&row)).transpose()
+    }
+
+    pub async fn find_by_id(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        id: Uuid,
+    ) -> Result<AccountClosure, AppError> {
+        let sql = format!("SELECT {} FROM account_closures WHERE id = $1", CLOSURE_COLUMNS);
+        let row = sqlx::query(&sql)
+            .bind(id)
+            .fetch_optional(executor)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Account closure {} not found", id)))?;
+        Self::row_to_closure(&row)
+    }
+
+    /// The closure if it is in progress and no other transaction holds it, locked until the
+    /// caller's transaction ends.
+    pub async fn lock_in_progress(conn: &mut PgConnection, id: Uuid) -> Result<Option<AccountClosure>, AppError> {
+        let sql = format!(
+            "SELECT {} FROM account_closures WHERE id = $1 AND status = 'in_progress' FOR UPDATE SKIP LOCKED",
+            CLOSURE_COLUMNS
+        );
+        let row = sqlx::query(&sql).bind(id).fetch_optional(&mut *conn).await?;
+        row.map(|row| Self::row_to_closure(&row)).transpose()
+    }
+
+    /// Closures in progress across all organizations, least recently advanced first.
+    pub async fn find_in_progress(pool: &PgPool, limit: i64) -> Result<Vec<AccountClosure>, AppError> {
+        let sql = format!(
+            "SELECT {} FROM account_closures WHERE status = 'in_progress' ORDER BY updated_at LIMIT $1",
+            CLOSURE_COLUMNS
+        );
+        let rows = sqlx::query(&sql).bind(limit).fetch_all(pool).await?;
+        rows.iter().map(Self::row_to_closure).collect()
+    }
+
+    /// Links the sweep intent of `attempt`; no-op if another attempt or intent is current.
+    pub async fn record_sweep(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        id: Uuid,
+        attempt: i32,
+        transaction_id: Uuid,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE account_closures
+            SET sweep_transaction_id = $3, updated_at = NOW()
+            WHERE id = $1 AND sweep_attempt = $2 AND sweep_transaction_id IS NULL
+            "#,
+        )
+        .bind(id)
+        .bind(attempt)
+        .bind(transaction_id)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Moves on from the sweep of `attempt` (failed, or followed by new funds).
+    pub async fn next_sweep_attempt(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        id: Uuid,
+        attempt: i32,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE account_closures
+            SET sweep_attempt = sweep_attempt + 1, sweep_transaction_id = NULL, updated_at = NOW()
+            WHERE id = $1 AND sweep_attempt = $2
+            "#,
+        )
+        .bind(id)
+        .bind(attempt)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn record_progress(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        id: Uuid,
+        settled: i32,
+        cancelled: i32,
+        last_error: Option<&str>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE account_closures
+            SET settled_intents = settled_intents + $2,
+                cancelled_intents = cancelled_intents + $3,
+                last_error = $4,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(settled)
+        .bind(cancelled)
+        .bind(last_error)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn complete(conn: &mut PgConnection, id: Uuid) -> Result<AccountClosure, AppError> {
+        sqlx::query(
+            r#"
+            UPDATE account_closures
+            SET status = 'completed', last_error = NULL, completed_at = COALESCE(completed_at, NOW()), updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .execute(&mut *conn)
+        .await?;
+
+        Self::find_by_id(&mut *conn, id).await
+    }
+
+    /// Payouts of the account not yet settled or returned, other than the closure's own sweeps.
+    pub async fn count_other_payouts_in_flight(pool: &PgPool, closure: &AccountClosure) -> Result<i64, AppError> {
+        let count: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM payouts p
+            JOIN transactions t ON t.id = p.transaction_id
+            WHERE p.account_id = $1
+              AND p.status IN ('pending', 'submitted')
+              AND NOT starts_with(t.idempotency_key, $2)
+            "#,
+        )
+        .bind(closure.account_id)
+        .bind(closure.sweep_key_prefix())
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Sweep intents of the closure, oldest first.
+    pub async fn find_sweeps(pool: &PgPool, closure: &AccountClosure) -> Result<Vec<ClosureSweep>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT t.id, t.organization_id, t.from_account_id, t.to_account_id, t.amount, t.currency,
+                   t.transaction_kind, t.status, t.failure_reason, t.idempotency_key, t.environment,
+                   t.destination_amount, t.destination_currency, t.fx_rate, t.fx_quote_id,
+                   t.counterparty_organization_id, t.interorg_agreement_id, t.nacha_file_id, t.nacha_trace_number,
+                   t.description, t.reference, t.metadata, t.created_at, t.updated_at, p.id AS payout_id, p.status AS payout_status
+            FROM transactions t
+            LEFT JOIN payouts p ON p.transaction_id = t.id
+            WHERE t.organization_id = $1
+              AND t.environment = $2
+              AND starts_with(t.idempotency_key, $3)
+            ORDER BY t.created_at, t.id
+            "#,
+        )
+        .bind(closure.organization_id)
+        .bind(&closure.environment)
+        .bind(closure.sweep_key_prefix())
+        .fetch_all(pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                let transaction = TransactionRepository::row_to_transaction(row)?;
+                let payout_status = match row.get::<Option<String>, _>("payout_status").as_deref() {
+                    None => None,
+                    Some("pending") => Some(PayoutStatus::Pending),
+                    Some("submitted") => Some(PayoutStatus::Submitted),
+                    Some("settled") => Some(PayoutStatus::Settled),
+                    Some("returned") => Some(PayoutStatus::Returned),
+                    Some(_) => return Err(AppError::Internal("Invalid payout status".to_string())),
+                };
+                Ok(ClosureSweep {
+                    transaction_id: transaction.id,
+                    amount: transaction.amount,
+                    status: transaction.status,
+                    payout_id: row.get("payout_id"),
+                    payout_status,
+                    created_at: transaction.created_at,
+                })
+            })
+            .collect()
+    }
+
+    fn row_to_closure(row: &PgRow) -> Result<AccountClosure, AppError> {
+        let status_str: String = row.get("status");
+        let status = ClosureStatus::parse(&status_str)
+            .ok_or_else(|| AppError::Internal("Invalid account closure status".to_string()))?;
+        let reason_str: String = row.get("reason");
+        let reason = StatusReason::parse(&reason_str)
+            .ok_or_else(|| AppError::Internal("Invalid account closure reason".to_string()))?;
+
+        Ok(AccountClosure {
+            id: row.get("id"),
+            account_id: row.get("account_id"),
+            organization_id: row.get("organization_id"),
+            environment: row.get("environment"),
+            status,
+            reason,
+            note: row.get("note"),
+            actor_type: row.get("actor_type"),
+            actor_id: row.get("actor_id"),
+            sweep_to_account_id: row.get("sweep_to_account_id"),
+            payee_id: row.get("payee_id"),
+            currency: row.get("currency"),
+            sweep_attempt: row.get("sweep_attempt"),
+            sweep_transaction_id: row.get("sweep_transaction_id"),
+            settled_intents: row.get("settled_intents"),
+            cancelled_intents: row.get("cancelled_intents"),
+            last_error: row.get("last_error"),
+            created_at: row.get("created_at"),
+            completed_at: row.get("completed_at"),
+        })
+    }
+}
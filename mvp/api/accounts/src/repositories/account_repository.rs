@@ -1,11 +1,17 @@
 use crate::errors::AppError;
-use crate::models::{Account, AccountStatus, AccountType, PaginatedAccountsResponse, PaginationMeta, AccountResponse};
-use sqlx::{PgPool, Row};
+use crate::models::{
+    Account, AccountResponse, AccountStatus, AccountStatusChange, AccountType, Metadata, NewAccountStatusChange,
+    PaginatedAccountsResponse, PaginationMeta, StatusReason,
+};
+use crate::utils::pagination::{keyset_page, PageRequest};
+use sqlx::types::Json;
+use sqlx::{PgConnection, PgPool, Postgres, QueryBuilder, Row};
 use uuid::Uuid;
 
 pub struct AccountRepository;
 
 impl AccountRepository {
+    #[allow(clippy::too_many_arguments)]
     pub async fn create(
         pool: &PgPool,
         account_number: &str,
@@ -14,6 +20,8 @@ impl AccountRepository {
         environment: &str,
         user_id: Uuid,
         currency: &str,
+        nickname: Option<&str>,
+        metadata: &Metadata,
     ) -> Result<Account, AppError> {
         let account_type_str: &str = match account_type {
             AccountType::Checking => "checking",
@@ -22,9 +30,9 @@ impl AccountRepository {
 
         let row = sqlx::query(
             r#"
-            INSERT INTO accounts (account_number, account_type, organization_id, environment, user_id, currency)
-            VALUES ($1, $2, $3, $4, $5, $6)
-            RETURNING id, account_number, account_type, organization_id, environment, user_id, currency, status, created_at, updated_at
+            INSERT INTO accounts (account_number, account_type, organization_id, environment, user_id, currency, nickname, metadata)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id, account_number, account_type, organization_id, environment, user_id, currency, status, nickname, metadata, created_at, updated_at
             "#,
         )
         .bind(account_number)
@@ -33,6 +41,8 @@ impl AccountRepository {
         .bind(environment)
         .bind(user_id)
         .bind(currency)
+        .bind(nickname)
+        .bind(Json(metadata))
         .fetch_one(pool)
         .await?;
 
@@ -49,6 +59,8 @@ impl AccountRepository {
         admin_user_id: Option<Uuid>,
         user_role: Option<String>,
         currency: &str,
+        nickname: Option<&str>,
+        metadata: &Metadata,
     ) -> Result<Account, sqlx::Error> {
         let account_type_str: &str = match account_type {
             AccountType::Checking => "checking",
@@ -57,9 +69,12 @@ impl AccountRepository {
 
         let row = sqlx::query(
             r#"
-            INSERT INTO accounts (account_number, account_type, organization_id, environment, user_id, admin_user_id, user_role, currency)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-            RETURNING id, account_number, account_type, organization_id, environment, user_id, admin_user_id, user_role, currency, status, created_at, updated_at
+            INSERT INTO accounts (
+                account_number, account_type, organization_id, environment, user_id, admin_user_id, user_role, currency,
+                nickname, metadata
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING id, account_number, account_type, organization_id, environment, user_id, admin_user_id, user_role, currency, status, nickname, metadata, created_at, updated_at
             "#,
         )
         .bind(account_number)
@@ -70,6 +85,8 @@ impl AccountRepository {
         .bind(admin_user_id)
         .bind(user_role)
         .bind(currency)
+        .bind(nickname)
+        .bind(Json(metadata))
         .fetch_one(executor)
         .await?;
 
@@ -79,7 +96,7 @@ impl AccountRepository {
     pub async fn find_by_id(pool: &PgPool, id: Uuid, environment: &str) -> Result<Account, AppError> {
         let row = sqlx::query(
             r#"
-            SELECT id, account_number, account_type, organization_id, environment, user_id, admin_user_id, user_role, currency, status, created_at, updated_at
+            SELECT id, account_number, account_type, organization_id, environment, user_id, admin_user_id, user_role, currency, status, nickname, metadata, created_at, updated_at
             FROM accounts
             WHERE id = $1 AND environment = $2
             "#,
@@ -93,10 +110,121 @@ impl AccountRepository {
         Ok(Self::row_to_account(&row)?)
     }
 
+    /// Environment of an account, whichever one it is in; `None` when the account has none.
+    /// For background jobs starting from records that do not carry it (legacy transactions).
+    pub async fn find_environment(pool: &PgPool, id: Uuid) -> Result<Option<String>, AppError> {
+        let row = sqlx::query("SELECT environment FROM accounts WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Account with id {} not found", id)))?;
+
+        Ok(row.get("environment"))
+    }
+
+    /// `find_by_id` limited to one organization: another organization's account is reported
+    /// as not found.
+    pub async fn find_by_id_for_organization(
+        pool: &PgPool,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<Account, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, account_number, account_type, organization_id, environment, user_id, admin_user_id, user_role, currency, status, nickname, metadata, created_at, updated_at
+            FROM accounts
+            WHERE id = $1 AND organization_id = $2 AND environment = $3
+            "#,
+        )
+        .bind(id)
+        .bind(organization_id)
+        .bind(environment)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Account with id {} not found in environment {}", id, environment)))?;
+
+        Self::row_to_account(&row)
+    }
+
+    /// An account the organization may transfer to: one of its own, or another organization's
+    /// account covered by an active inter-organization agreement. Any other account is reported
+    /// as not found, like `find_by_id_for_organization`.
+    pub async fn find_transfer_destination(
+        pool: &PgPool,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<Account, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, account_number, account_type, organization_id, environment, user_id, admin_user_id, user_role, currency, status, nickname, metadata, created_at, updated_at
+            FROM accounts
+            WHERE id = $1
+              AND environment = $3
+              AND (
+                  organization_id = $2
+                  OR EXISTS (
+                      SELECT 1
+                      FROM interorg_agreements g
+                      WHERE g.counterparty_account_id = accounts.id
+                        AND g.organization_id = $2
+                        AND g.environment = $3
+                        AND g.status = 'active'
+                  )
+              )
+            "#,
+        )
+        .bind(id)
+        .bind(organization_id)
+        .bind(environment)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Account with id {} not found in environment {}", id, environment)))?;
+
+        Self::row_to_account(&row)
+    }
+
+    /// Find the owner's oldest active checking account for a given saving account.
+    /// Scoped to the same organization, environment and currency as the saving account.
+    /// Both accounts are locked `FOR SHARE` like `lock_active`, so neither can be closed
+    /// before the caller's transaction ends.
+    pub async fn find_owner_checking_account(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        saving_account_id: Uuid,
+        currency: &str,
+    ) -> Result<Option<Account>, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT c.id, c.account_number, c.account_type, c.organization_id, c.environment, c.user_id,
+                   c.admin_user_id, c.user_role, c.currency, c.status, c.nickname, c.metadata, c.created_at, c.updated_at
+            FROM accounts s
+            JOIN accounts c
+              ON c.user_id = s.user_id
+             AND c.organization_id = s.organization_id
+             AND c.environment = s.environment
+            WHERE s.id = $1
+              AND s.status = 'active'
+              AND c.account_type = 'checking'
+              AND c.status = 'active'
+              AND c.currency = $2
+            ORDER BY c.created_at ASC, c.id ASC
+            LIMIT 1
+            FOR SHARE OF s, c
+            "#,
+        )
+        .bind(saving_account_id)
+        .bind(currency)
+        .fetch_optional(executor)
+        .await?;
+
+        row.as_ref().map(Self::row_to_account).transpose()
+    }
+
     pub async fn find_by_user_id(pool: &PgPool, user_id: Uuid, environment: &str) -> Result<Vec<Account>, AppError> {
         let rows = sqlx::query(
             r#"
-            SELECT id, account_number, account_type, organization_id, environment, user_id, admin_user_id, user_role, currency, status, created_at, updated_at
+            SELECT id, account_number, account_type, organization_id, environment, user_id, admin_user_id, user_role, currency, status, nickname, metadata, created_at, updated_at
             FROM accounts
             WHERE user_id = $1 AND environment = $2
             ORDER BY created_at DESC, id DESC
@@ -118,7 +246,7 @@ impl AccountRepository {
     pub async fn find_by_organization_id(pool: &PgPool, organization_id: Uuid, environment: &str) -> Result<Vec<Account>, AppError> {
         let rows = sqlx::query(
             r#"
-            SELECT id, account_number, account_type, organization_id, environment, user_id, admin_user_id, user_role, currency, status, created_at, updated_at
+            SELECT id, account_number, account_type, organization_id, environment, user_id, admin_user_id, user_role, currency, status, nickname, metadata, created_at, updated_at
             FROM accounts
             WHERE organization_id = $1 AND environment = $2
             ORDER BY created_at DESC, id DESC
@@ -140,7 +268,7 @@ impl AccountRepository {
     pub async fn find_by_admin_user_id(pool: &PgPool, admin_user_id: Uuid, environment: &str) -> Result<Vec<Account>, AppError> {
         let rows = sqlx::query(
             r#"
-            SELECT id, account_number, account_type, organization_id, environment, user_id, admin_user_id, user_role, currency, status, created_at, updated_at
+            SELECT id, account_number, account_type, organization_id, environment, user_id, admin_user_id, user_role, currency, status, nickname, metadata, created_at, updated_at
             FROM accounts
             WHERE admin_user_id = $1 AND environment = $2
             ORDER BY created_at DESC, id DESC
@@ -161,193 +289,344 @@ impl AccountRepository {
 
     pub async fn find_by_user_id_paginated(
         pool: &PgPool,
+        organization_id: Uuid,
         user_id: Uuid,
         environment: &str,
-        page: u32,
-        per_page: u32,
+        metadata: &Metadata,
+        page: &PageRequest,
     ) -> Result<PaginatedAccountsResponse, AppError> {
-        let offset = (page - 1) * per_page;
+        Self::find_paginated(pool, organization_id, Some(("user_id", user_id)), environment, metadata, page).await
+    }
 
-        // Get total count (filtered by environment)
-        let count_row = sqlx::query(
-            "SELECT COUNT(*) as count FROM accounts WHERE user_id = $1 AND environment = $2"
-        )
-        .bind(user_id)
-        .bind(environment)
-        .fetch_one(pool)
-        .await?;
+    pub async fn find_by_organization_id_paginated(
+        pool: &PgPool,
+        organization_id: Uuid,
+        environment: &str,
+        metadata: &Metadata,
+        page: &PageRequest,
+    ) -> Result<PaginatedAccountsResponse, AppError> {
+        Self::find_paginated(pool, organization_id, None, environment, metadata, page).await
+    }
 
-        let total_count: i64 = count_row.get("count");
-        let total_pages = ((total_count as f64) / (per_page as f64)).ceil() as u32;
+    pub async fn find_by_admin_user_id_paginated(
+        pool: &PgPool,
+        organization_id: Uuid,
+        admin_user_id: Uuid,
+        environment: &str,
+        metadata: &Metadata,
+        page: &PageRequest,
+    ) -> Result<PaginatedAccountsResponse, AppError> {
+        Self::find_paginated(pool, organization_id, Some(("admin_user_id", admin_user_id)), environment, metadata, page)
+            .await
+    }
 
-        // Fetch paginated results (filtered by environment)
-        let rows = sqlx::query(
+    /// The organization's accounts, optionally only those where `owner_column` = `owner_id`,
+    /// whose metadata contains `metadata`, newest first. Page requests count and use OFFSET; cursor requests seek on
+    /// `(created_at, id)` and skip the count.
+    async fn find_paginated(
+        pool: &PgPool,
+        organization_id: Uuid,
+        owner: Option<(&'static str, Uuid)>,
+        environment: &str,
+        metadata: &Metadata,
+        page: &PageRequest,
+    ) -> Result<PaginatedAccountsResponse, AppError> {
+        let per_page = page.per_page();
+
+        let mut query = QueryBuilder::<Postgres>::new(
             r#"
-            SELECT id, account_number, account_type, organization_id, environment, user_id, admin_user_id, user_role, currency, status, created_at, updated_at
+            SELECT id, account_number, account_type, organization_id, environment, user_id, admin_user_id, user_role, currency, status, nickname, metadata, created_at, updated_at
             FROM accounts
-            WHERE user_id = $1 AND environment = $2
-            ORDER BY created_at DESC, id DESC
-            LIMIT $3 OFFSET $4
-            "#,
-        )
-        .bind(user_id)
-        .bind(environment)
-        .bind(per_page as i64)
-        .bind(offset as i64)
-        .fetch_all(pool)
-        .await?;
+            WHERE organization_id = "#,
+        );
+        query.push_bind(organization_id);
+        query.push(" AND environment = ");
+        query.push_bind(environment);
+        if let Some((owner_column, owner_id)) = owner {
+            query.push(" AND ");
+            query.push(owner_column);
+            query.push(" = ");
+            query.push_bind(owner_id);
+        }
+        if !metadata.is_empty() {
+            query.push(" AND metadata @> ");
+            query.push_bind(Json(metadata));
+        }
+
+        let total_count = match page {
+            PageRequest::Page { page, .. } => {
+                // Get total count (filtered by organization, environment and owner)
+                let mut count = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM accounts WHERE organization_id = ");
+                count.push_bind(organization_id);
+                count.push(" AND environment = ");
+                count.push_bind(environment);
+                if let Some((owner_column, owner_id)) = owner {
+                    count.push(" AND ");
+                    count.push(owner_column);
+                    count.push(" = ");
+                    count.push_bind(owner_id);
+                }
+                if !metadata.is_empty() {
+                    count.push(" AND metadata @> ");
+                    count.push_bind(Json(metadata));
+                }
+                let total_count: i64 = count.build_query_scalar().fetch_one(pool).await?;
+
+                query.push(" ORDER BY created_at DESC, id DESC LIMIT ");
+                query.push_bind(per_page as i64);
+                query.push(" OFFSET ");
+                query.push_bind(((page - 1) * per_page) as i64);
+                total_count
+            }
+            PageRequest::Cursor { cursor, .. } => {
+                query.push(" AND (created_at, id) ");
+                query.push(cursor.comparison(true));
+                query.push(" (");
+                query.push_bind(cursor.created_at);
+                query.push(", ");
+                query.push_bind(cursor.id);
+                query.push(")");
+                query.push(if cursor.scan_descending(true) {
+                    " ORDER BY created_at DESC, id DESC LIMIT "
+                } else {
+                    " ORDER BY created_at ASC, id ASC LIMIT "
+                });
+                query.push_bind(per_page as i64 + 1);
+                0
+            }
+        };
+
+        let rows = query.build().fetch_all(pool).await?;
 
         let accounts: Vec<Account> = rows
             .iter()
             .map(|row| Self::row_to_account(row))
             .collect::<Result<Vec<_>, _>>()?;
 
+        let key = |account: &Account| (account.created_at.unwrap_or_default(), account.id);
+        let (accounts, pagination) = match page {
+            PageRequest::Page { page, .. } => {
+                let pagination = PaginationMeta::page(&accounts, *page, per_page, total_count, key);
+                (accounts, pagination)
+            }
+            PageRequest::Cursor { cursor, .. } => {
+                let (accounts, next_cursor, prev_cursor) = keyset_page(accounts, cursor, per_page, key);
+                (accounts, PaginationMeta::cursor(per_page, next_cursor, prev_cursor))
+            }
+        };
+
         Ok(PaginatedAccountsResponse {
             data: accounts.into_iter().map(AccountResponse::from).collect(),
-            pagination: PaginationMeta {
-                page,
-                per_page,
-                total_count,
-                total_pages,
-            },
+            pagination,
         })
     }
 
-    pub async fn find_by_organization_id_paginated(
+    /// Sets the nickname (`Some("")` removes it) and replaces the metadata; `None` keeps the
+    /// current value.
+    pub async fn update_details(
         pool: &PgPool,
-        organization_id: Uuid,
+        id: Uuid,
         environment: &str,
-        page: u32,
-        per_page: u32,
-    ) -> Result<PaginatedAccountsResponse, AppError> {
-        let offset = (page - 1) * per_page;
+        nickname: Option<&str>,
+        metadata: Option<&Metadata>,
+    ) -> Result<Account, AppError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE accounts
+            SET nickname = CASE WHEN $3::TEXT IS NULL THEN nickname ELSE NULLIF($3, '') END,
+                metadata = COALESCE($4, metadata),
+                updated_at = NOW()
+            WHERE id = $1 AND environment = $2
+            RETURNING id, account_number, account_type, organization_id, environment, user_id, admin_user_id, user_role, currency, status, nickname, metadata, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(environment)
+        .bind(nickname)
+        .bind(metadata.map(Json))
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Account with id {} not found in environment {}", id, environment)))?;
+
+        Self::row_to_account(&row)
+    }
 
-        // Get total count (filtered by environment)
-        let count_row = sqlx::query(
-            "SELECT COUNT(*) as count FROM accounts WHERE organization_id = $1 AND environment = $2"
+    /// The account, locked `FOR UPDATE` until the caller's transaction ends. Status changes
+    /// take this lock, so they wait for intents being created against the account.
+    pub async fn find_by_id_for_update(
+        conn: &mut PgConnection,
+        id: Uuid,
+        environment: &str,
+    ) -> Result<Account, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, account_number, account_type, organization_id, environment, user_id, admin_user_id, user_role, currency, status, nickname, metadata, created_at, updated_at
+            FROM accounts
+            WHERE id = $1 AND environment = $2
+            FOR UPDATE
+            "#,
         )
-        .bind(organization_id)
+        .bind(id)
         .bind(environment)
-        .fetch_one(pool)
-        .await?;
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Account with id {} not found in environment {}", id, environment)))?;
 
-        let total_count: i64 = count_row.get("count");
-        let total_pages = ((total_count as f64) / (per_page as f64)).ceil() as u32;
+        Self::row_to_account(&row)
+    }
 
-        // Fetch paginated results (filtered by environment)
+    /// Locks the accounts `FOR SHARE` (in id order) until the caller's transaction ends and
+    /// requires each to be active. Intents take this lock, so an account cannot be suspended
+    /// or closed between the status check and the intent's commit.
+    pub async fn lock_active(conn: &mut PgConnection, ids: &[Uuid]) -> Result<(), AppError> {
         let rows = sqlx::query(
             r#"
-            SELECT id, account_number, account_type, organization_id, environment, user_id, admin_user_id, user_role, currency, status, created_at, updated_at
+            SELECT id, status
             FROM accounts
-            WHERE organization_id = $1 AND environment = $2
-            ORDER BY created_at DESC, id DESC
-            LIMIT $3 OFFSET $4
+            WHERE id = ANY($1)
+            ORDER BY id
+            FOR SHARE
             "#,
         )
-        .bind(organization_id)
-        .bind(environment)
-        .bind(per_page as i64)
-        .bind(offset as i64)
-        .fetch_all(pool)
+        .bind(ids)
+        .fetch_all(&mut *conn)
         .await?;
 
-        let accounts: Vec<Account> = rows
-            .iter()
-            .map(|row| Self::row_to_account(row))
-            .collect::<Result<Vec<_>, _>>()?;
+        for id in ids {
+            let row = rows
+                .iter()
+                .find(|row| row.get::<Uuid, _>("id") == *id)
+                .ok_or_else(|| AppError::NotFound(format!("Account with id {} not found", id)))?;
+            if row.get::<String, _>("status") != AccountStatus::Active.as_str() {
+                return Err(AppError::AccountNotActive);
+            }
+        }
+
+        Ok(())
+    }
 
-        Ok(PaginatedAccountsResponse {
-            data: accounts.into_iter().map(AccountResponse::from).collect(),
-            pagination: PaginationMeta {
-                page,
-                per_page,
-                total_count,
-                total_pages,
-            },
-        })
+    /// Status of the account, locked `FOR SHARE` like `lock_active` until the caller's
+    /// transaction ends.
+    pub async fn lock_status(conn: &mut PgConnection, id: Uuid) -> Result<AccountStatus, AppError> {
+        let status: String = sqlx::query_scalar("SELECT status FROM accounts WHERE id = $1 FOR SHARE")
+            .bind(id)
+            .fetch_optional(&mut *conn)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Account with id {} not found", id)))?;
+
+        AccountStatus::parse(&status).ok_or_else(|| AppError::Internal(format!("Invalid account status {}", status)))
     }
 
-    pub async fn find_by_admin_user_id_paginated(
-        pool: &PgPool,
-        admin_user_id: Uuid,
+    /// Applies a status change if the account is still in `from_status`, and records it in
+    /// the status history, in the caller's transaction.
+    pub async fn change_status(
+        conn: &mut PgConnection,
+        id: Uuid,
         environment: &str,
-        page: u32,
-        per_page: u32,
-    ) -> Result<PaginatedAccountsResponse, AppError> {
-        let offset = (page - 1) * per_page;
+        change: &NewAccountStatusChange,
+    ) -> Result<Account, AppError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE accounts
+            SET status = $4, updated_at = NOW()
+            WHERE id = $1 AND environment = $2 AND status = $3
+            RETURNING id, account_number, account_type, organization_id, environment, user_id, admin_user_id, user_role, currency, status, nickname, metadata, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(environment)
+        .bind(change.from_status.as_str())
+        .bind(change.to_status.as_str())
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or_else(|| AppError::Conflict("account status changed concurrently; retry".to_string()))?;
 
-        // Get total count (filtered by environment)
-        let count_row = sqlx::query(
-            "SELECT COUNT(*) as count FROM accounts WHERE admin_user_id = $1 AND environment = $2"
+        let account = Self::row_to_account(&row)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO account_status_history (
+                account_id, organization_id, environment, from_status, to_status, reason, note, actor_type, actor_id
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
         )
-        .bind(admin_user_id)
+        .bind(id)
+        .bind(account.organization_id)
         .bind(environment)
-        .fetch_one(pool)
+        .bind(change.from_status.as_str())
+        .bind(change.to_status.as_str())
+        .bind(change.reason.map(|reason| reason.as_str()))
+        .bind(change.note.as_deref())
+        .bind(&change.actor_type)
+        .bind(change.actor_id)
+        .execute(&mut *conn)
         .await?;
 
-        let total_count: i64 = count_row.get("count");
-        let total_pages = ((total_count as f64) / (per_page as f64)).ceil() as u32;
+        Ok(account)
+    }
 
-        // Fetch paginated results (filtered by environment)
+    /// Status changes of an account, newest first.
+    pub async fn find_status_history(
+        pool: &PgPool,
+        account_id: Uuid,
+    ) -> Result<Vec<AccountStatusChange>, AppError> {
         let rows = sqlx::query(
             r#"
-            SELECT id, account_number, account_type, organization_id, environment, user_id, admin_user_id, user_role, currency, status, created_at, updated_at
-            FROM accounts
-            WHERE admin_user_id = $1 AND environment = $2
+            SELECT id, account_id, from_status, to_status, reason, note, actor_type, actor_id, created_at
+            FROM account_status_history
+            WHERE account_id = $1
             ORDER BY created_at DESC, id DESC
-            LIMIT $3 OFFSET $4
             "#,
         )
-        .bind(admin_user_id)
-        .bind(environment)
-        .bind(per_page as i64)
-        .bind(offset as i64)
+        .bind(account_id)
         .fetch_all(pool)
         .await?;
 
-        let accounts: Vec<Account> = rows
-            .iter()
-            .map(|row| Self::row_to_account(row))
-            .collect::<Result<Vec<_>, _>>()?;
-
-        Ok(PaginatedAccountsResponse {
-            data: accounts.into_iter().map(AccountResponse::from).collect(),
-            pagination: PaginationMeta {
-                page,
-                per_page,
-                total_count,
-                total_pages,
-            },
-        })
+        rows.iter()
+            .map(|row| {
+                let status = |column: &str| {
+                    let value: String = row.get(column);
+                    AccountStatus::parse(&value)
+                        .ok_or_else(|| AppError::Internal(format!("Invalid account status {}", value)))
+                };
+                Ok(AccountStatusChange {
+                    id: row.get("id"),
+                    account_id: row.get("account_id"),
+                    from_status: status("from_status")?,
+                    to_status: status("to_status")?,
+                    reason: row.get::<Option<String>, _>("reason").as_deref().and_then(StatusReason::parse),
+                    note: row.get("note"),
+                    actor_type: row.get("actor_type"),
+                    actor_id: row.get("actor_id"),
+                    created_at: row.get("created_at"),
+                })
+            })
+            .collect()
     }
 
-    pub async fn update_status(
-        pool: &PgPool,
+    /// Intents of the account still waiting for the Ledger, plus payouts not yet settled or
+    /// returned (a return credits the account).
+    pub async fn count_pending_movements(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
         id: Uuid,
-        environment: &str,
-        status: AccountStatus,
-    ) -> Result<Account, AppError> {
-        let status_str: &str = match status {
-            AccountStatus::Active => "active",
-            AccountStatus::Suspended => "suspended",
-            AccountStatus::Closed => "closed",
-        };
-
-        let row = sqlx::query(
+    ) -> Result<i64, AppError> {
+        let count: Option<i64> = sqlx::query_scalar(
             r#"
-            UPDATE accounts
-            SET status = $3, updated_at = NOW()
-            WHERE id = $1 AND environment = $2
-            RETURNING id, account_number, account_type, organization_id, environment, user_id, admin_user_id, user_role, currency, status, created_at, updated_at
+            SELECT (
+                SELECT COUNT(*) FROM transactions
+                WHERE (from_account_id = $1 OR to_account_id = $1) AND status = 'pending'
+            ) + (
+                SELECT COUNT(*) FROM payouts
+                WHERE account_id = $1 AND status IN ('pending', 'submitted')
+            )
             "#,
         )
         .bind(id)
-        .bind(environment)
-        .bind(status_str)
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
 
-        Ok(Self::row_to_account(&row)?)
+        Ok(count.unwrap_or(0))
     }
 
     fn row_to_account(row: &sqlx::postgres::PgRow) -> Result<Account, AppError> {
@@ -359,12 +638,8 @@ impl AccountRepository {
         };
 
         let status_str: String = row.get("status");
-        let status = match status_str.as_str() {
-            "active" => AccountStatus::Active,
-            "suspended" => AccountStatus::Suspended,
-            "closed" => AccountStatus::Closed,
-            _ => return Err(AppError::Internal("Invalid account status".to_string())),
-        };
+        let status = AccountStatus::parse(&status_str)
+            .ok_or_else(|| AppError::Internal("Invalid account status".to_string()))?;
 
         Ok(Account {
             id: row.get("id"),
@@ -377,6 +652,8 @@ impl AccountRepository {
             user_role: row.try_get("user_role").ok(),
             currency: Some(row.get("currency")),
             status: Some(status),
+            nickname: row.get("nickname"),
+            metadata: row.get::<Json<_>, _>("metadata").0,
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
         })
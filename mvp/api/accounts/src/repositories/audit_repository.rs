@@ -0,0 +1,183 @@
+use crate::errors::AppError;
+use crate::models::{AuditEvent, AuditEventFilter, NewAuditEvent, PaginationMeta};
+use crate::utils::pagination::{keyset_page, PageRequest};
+use sqlx::{PgPool, Postgres, QueryBuilder, Row};
+use uuid::Uuid;
+
+const COLUMNS: &str = r#"
+    SELECT id, organization_id, environment, actor_type, actor_id, action, target_type, target_id,
+           before, after, status_code, correlation_id, ip_address, created_at
+    FROM audit_events"#;
+
+pub struct AuditRepository;
+
+impl AuditRepository {
+    pub async fn insert(pool: &PgPool, event: &NewAuditEvent) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO audit_events (
+                organization_id, environment, actor_type, actor_id, action, target_type, target_id,
+                before, after, status_code, correlation_id, ip_address
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            "#,
+        )
+        .bind(event.organization_id)
+        .bind(&event.environment)
+        .bind(event.actor_type)
+        .bind(event.actor_id)
+        .bind(&event.action)
+        .bind(&event.target_type)
+        .bind(event.target_id)
+        .bind(&event.before)
+        .bind(&event.after)
+        .bind(event.status_code)
+        .bind(&event.correlation_id)
+        .bind(&event.ip_address)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Events of the organization and environment, newest first.
+    pub async fn find_paginated(
+        pool: &PgPool,
+        organization_id: Uuid,
+        environment: &str,
+        filter: &AuditEventFilter,
+        page: &PageRequest,
+    ) -> Result<(Vec<AuditEvent>, PaginationMeta), AppError> {
+        let per_page = page.per_page();
+
+        let mut query = QueryBuilder::<Postgres>::new(COLUMNS);
+        Self::push_scope(&mut query, organization_id, environment, filter);
+
+        let total_count = match page {
+            PageRequest::Page { page, .. } => {
+                let mut count_query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) AS count FROM audit_events");
+                Self::push_scope(&mut count_query, organization_id, environment, filter);
+                let count_row = count_query.build().fetch_one(pool).await?;
+
+                query.push(" ORDER BY created_at DESC, id DESC LIMIT ");
+                query.push_bind(per_page as i64);
+                query.push(" OFFSET ");
+                query.push_bind(((page - 1) * per_page) as i64);
+                count_row.get("count")
+            }
+            PageRequest::Cursor { cursor, .. } => {
+                query.push(" AND (created_at, id) ");
+                query.push(cursor.comparison(true));
+                query.push(" (");
+                query.push_bind(cursor.created_at);
+                query.push(", ");
+                query.push_bind(cursor.id);
+                query.push(")");
+                query.push(if cursor.scan_descending(true) {
+                    " ORDER BY created_at DESC, id DESC"
+                } else {
+                    " ORDER BY created_at ASC, id ASC"
+                });
+                query.push(" LIMIT ");
+                query.push_bind(per_page as i64 + 1);
+                0
+            }
+        };
+
+        let rows = query.build().fetch_all(pool).await?;
+        let events: Vec<AuditEvent> = rows.iter().map(Self::row_to_event).collect();
+
+        let key = |event: &AuditEvent| (event.created_at, event.id);
+        Ok(match page {
+            PageRequest::Page { page, .. } => {
+                let pagination = PaginationMeta::page(&events, *page, per_page, total_count, key);
+                (events, pagination)
+            }
+            PageRequest::Cursor { cursor, .. } => {
+                let (events, next_cursor, prev_cursor) = keyset_page(events, cursor, per_page, key);
+                (events, PaginationMeta::cursor(per_page, next_cursor, prev_cursor))
+            }
+        })
+    }
+
+    /// Events of the organization and environment in recording order, at most `limit`.
+    pub async fn find_for_export(
+        pool: &PgPool,
+        organization_id: Uuid,
+        environment: &str,
+        filter: &AuditEventFilter,
+        limit: i64,
+    ) -> Result<Vec<AuditEvent>, AppError> {
+        let mut query = QueryBuilder::<Postgres>::new(COLUMNS);
+        Self::push_scope(&mut query, organization_id, environment, filter);
+        query.push(" ORDER BY created_at ASC, id ASC LIMIT ");
+        query.push_bind(limit);
+
+        let rows = query.build().fetch_all(pool).await?;
+        Ok(rows.iter().map(Self::row_to_event).collect())
+    }
+
+    fn push_scope(
+        query: &mut QueryBuilder<'_, Postgres>,
+        organization_id: Uuid,
+        environment: &str,
+        filter: &AuditEventFilter,
+    ) {
+        query.push(" WHERE organization_id = ");
+        query.push_bind(organization_id);
+        query.push(" AND environment = ");
+        query.push_bind(environment.to_string());
+
+        if let Some(actor_type) = &filter.actor_type {
+            query.push(" AND actor_type = ");
+            query.push_bind(actor_type.clone());
+        }
+        if let Some(actor_id) = filter.actor_id {
+            query.push(" AND actor_id = ");
+            query.push_bind(actor_id);
+        }
+        if let Some(action) = &filter.action {
+            query.push(" AND action = ");
+            query.push_bind(action.clone());
+        }
+        if let Some(target_type) = &filter.target_type {
+            query.push(" AND target_type = ");
+            query.push_bind(target_type.clone());
+        }
+        if let Some(target_id) = filter.target_id {
+            query.push(" AND target_id = ");
+            query.push_bind(target_id);
+        }
+        if let Some(correlation_id) = &filter.correlation_id {
+            query.push(" AND correlation_id = ");
+            query.push_bind(correlation_id.clone());
+        }
+        if let Some(from) = filter.from {
+            query.push(" AND created_at >= ");
+            query.push_bind(from);
+        }
+        if let Some(to) = filter.to {
+            query.push(" AND created_at < ");
+            query.push_bind(to);
+        }
+    }
+
+    fn row_to_event(row: &sqlx::postgres::PgRow) -> AuditEvent {
+        AuditEvent {
+            id: row.get("id"),
+            organization_id: row.get("organization_id"),
+            environment: row.get("environment"),
+            actor_type: row.get("actor_type"),
+            actor_id: row.get("actor_id"),
+            action: row.get("action"),
+            target_type: row.get("target_type"),
+            target_id: row.get("target_id"),
+            before: row.get("before"),
+            after: row.get("after"),
+            status_code: row.get("status_code"),
+            correlation_id: row.get("correlation_id"),
+            ip_address: row.get("ip_address"),
+            created_at: row.get("created_at"),
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use crate::errors::AppError;
+use crate::models::{EventFilter, StreamEvent};
+use sqlx::{PgPool, Postgres, QueryBuilder, Row};
+use uuid::Uuid;
+
+pub struct EventRepository;
+
+impl EventRepository {
+    /// Events of the organization and environment after `after_sequence`, oldest first.
+    pub async fn find_after(
+        pool: &PgPool,
+        organization_id: Uuid,
+        environment: &str,
+        after_sequence: i64,
+        filter: &EventFilter,
+        limit: i64,
+    ) -> Result<Vec<StreamEvent>, AppError> {
+        let mut query = QueryBuilder::<Postgres>::new(
+            r#"
+            SELECT sequence, id, event_type, payload, created_at
+            FROM webhook_events
+            WHERE organization_id = "#,
+        );
+        query
+            .push_bind(organization_id)
+            .push(" AND environment = ")
+            .push_bind(environment)
+            .push(" AND sequence > ")
+            .push_bind(after_sequence);
+
+        if let Some(account_id) = filter.account_id {
+            let account_id = account_id.to_string();
+            query
+                .push(" AND (payload->>'from_account_id' = ")
+                .push_bind(account_id.clone())
+                .push(" OR payload->>'to_account_id' = ")
+                .push_bind(account_id.clone())
+                .push(" OR (event_type LIKE 'account.%' AND payload->>'id' = ")
+                .push_bind(account_id)
+                .push("))");
+        }
+
+        if !filter.event_types.is_empty() {
+            query.push(" AND event_type = ANY(").push_bind(filter.event_types.clone()).push(")");
+        }
+
+        query.push(" ORDER BY sequence LIMIT ").push_bind(limit);
+
+        let rows = query.build().fetch_all(pool).await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| StreamEvent {
+                sequence: row.get("sequence"),
+                id: row.get("id"),
+                event_type: row.get("event_type"),
+                created_at: row.get("created_at"),
+                data: row.get("payload"),
+            })
+            .collect())
+    }
+
+    /// Current end of the organization's log (0 when empty); streams without
+    /// Last-Event-ID start from here.
+    pub async fn latest_sequence(pool: &PgPool, organization_id: Uuid, environment: &str) -> Result<i64, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT COALESCE(MAX(sequence), 0) AS sequence
+            FROM webhook_events
+            WHERE organization_id = $1 AND environment = $2
+            "#,
+        )
+        .bind(organization_id)
+        .bind(environment)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(row.get("sequence"))
+    }
+}
@@ -0,0 +1,350 @@
+use crate::errors::AppError;
+use crate::models::{
+    AccountType, CreateFeeScheduleRequest, FeeSchedule, Transaction, TransactionFee, TransactionKind,
+    TransactionStatus,
+};
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+pub struct FeeRepository;
+
+impl FeeRepository {
+    pub async fn create_schedule(
+        pool: &PgPool,
+        environment: &str,
+        request: &CreateFeeScheduleRequest,
+        percentage: Decimal,
+    ) -> Result<FeeSchedule, AppError> {
+        let kind_str: &str = match request.transaction_kind {
+            TransactionKind::Deposit => "deposit",
+            TransactionKind::Withdraw => "withdraw",
+            TransactionKind::Transfer => "transfer",
+            TransactionKind::Interest => "interest",
+        };
+        let account_type_str: Option<&str> = request.account_type.map(|t| match t {
+            AccountType::Checking => "checking",
+            AccountType::Saving => "saving",
+        });
+
+        let row = sqlx::query(
+            r#"
+            INSERT INTO fee_schedules (
+                organization_id, environment, name, transaction_kind, account_type, currency,
+                flat_amount, percentage, min_amount, max_amount, revenue_account_id
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING id, organization_id, environment, name, transaction_kind, account_type, currency,
+                      flat_amount, percentage, min_amount, max_amount, revenue_account_id, active, created_at, updated_at
+            "#,
+        )
+        .bind(request.organization_id)
+        .bind(environment)
+        .bind(request.name.trim())
+        .bind(kind_str)
+        .bind(account_type_str)
+        .bind(request.currency.code())
+        .bind(request.flat_amount)
+        .bind(percentage)
+        .bind(request.min_amount)
+        .bind(request.max_amount)
+        .bind(request.revenue_account_id)
+        .fetch_one(pool)
+        .await?;
+
+        Self::row_to_schedule(&row)
+    }
+
+    pub async fn find_schedules_by_organization(
+        pool: &PgPool,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<Vec<FeeSchedule>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, organization_id, environment, name, transaction_kind, account_type, currency,
+                   flat_amount, percentage, min_amount, max_amount, revenue_account_id, active, created_at, updated_at
+            FROM fee_schedules
+            WHERE organization_id = $1 AND environment = $2 AND active
+            ORDER BY transaction_kind ASC, name ASC
+            "#,
+        )
+        .bind(organization_id)
+        .bind(environment)
+        .fetch_all(pool)
+        .await?;
+
+        rows.iter().map(Self::row_to_schedule).collect()
+    }
+
+    pub async fn deactivate_schedule(
+        pool: &PgPool,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<FeeSchedule, AppError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE fee_schedules
+            SET active = FALSE, updated_at = NOW()
+            WHERE id = $1 AND environment = $2 AND organization_id = $3
+            RETURNING id, organization_id, environment, name, transaction_kind, account_type, currency,
+                      flat_amount, percentage, min_amount, max_amount, revenue_account_id, active, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(environment)
+        .bind(organization_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Fee schedule with id {} not found in environment {}", id, environment)))?;
+
+        Self::row_to_schedule(&row)
+    }
+
+    /// Active schedules matching a transaction kind, the payer's account type and the intent's currency.
+    pub async fn find_applicable(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        organization_id: Uuid,
+        environment: &str,
+        transaction_kind: TransactionKind,
+        account_type: AccountType,
+        currency: &str,
+    ) -> Result<Vec<FeeSchedule>, AppError> {
+        let kind_str: &str = match transaction_kind {
+            TransactionKind::Deposit => "deposit",
+            TransactionKind::Withdraw => "withdraw",
+            TransactionKind::Transfer => "transfer",
+            TransactionKind::Interest => "interest",
+        };
+        let account_type_str: &str = match account_type {
+            AccountType::Checking => "checking",
+            AccountType::Saving => "saving",
+        };
+
+        let rows = sqlx::query(
+            r#"
+            SELECT id, organization_id, environment, name, transaction_kind, account_type, currency,
+                   flat_amount, percentage, min_amount, max_amount, revenue_account_id, active, created_at, updated_at
+            FROM fee_schedules
+            WHERE organization_id = $1
+              AND environment = $2
+              AND transaction_kind = $3
+              AND (account_type IS NULL OR account_type = $4)
+              AND currency = $5
+              AND active
+            ORDER BY name ASC
+            "#,
+        )
+        .bind(organization_id)
+        .bind(environment)
+        .bind(kind_str)
+        .bind(account_type_str)
+        .bind(currency)
+        .fetch_all(executor)
+        .await?;
+
+        rows.iter().map(Self::row_to_schedule).collect()
+    }
+
+    /// Record a fee for a transaction. Replays of the same transaction/schedule are ignored.
+    pub async fn insert_fee(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        transaction: &Transaction,
+        schedule: &FeeSchedule,
+        payer_account_id: Uuid,
+        amount: i64,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO transaction_fees (
+                transaction_id, fee_schedule_id, organization_id, environment, name,
+                payer_account_id, revenue_account_id, amount, currency, idempotency_key
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (transaction_id, fee_schedule_id) DO NOTHING
+            "#,
+        )
+        .bind(transaction.id)
+        .bind(schedule.id)
+        .bind(transaction.organization_id)
+        .bind(&schedule.environment)
+        .bind(&schedule.name)
+        .bind(payer_account_id)
+        .bind(schedule.revenue_account_id)
+        .bind(amount)
+        .bind(&transaction.currency)
+        .bind(format!("{}:fee:{}", transaction.idempotency_key, schedule.id))
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_by_transaction_id(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        transaction_id: Uuid,
+    ) -> Result<Vec<TransactionFee>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, transaction_id, fee_schedule_id, organization_id, environment, name, payer_account_id,
+                   revenue_account_id, amount, currency, status, failure_reason, idempotency_key, created_at
+            FROM transaction_fees
+            WHERE transaction_id = $1
+            ORDER BY name ASC
+            "#,
+        )
+        .bind(transaction_id)
+        .fetch_all(executor)
+        .await?;
+
+        rows.iter().map(Self::row_to_fee).collect()
+    }
+
+    pub async fn find_by_transaction_ids(
+        pool: &PgPool,
+        transaction_ids: &[Uuid],
+    ) -> Result<Vec<TransactionFee>, AppError> {
+        if transaction_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let rows = sqlx::query(
+            r#"
+            SELECT id, transaction_id, fee_schedule_id, organization_id, environment, name, payer_account_id,
+                   revenue_account_id, amount, currency, status, failure_reason, idempotency_key, created_at
+            FROM transaction_fees
+            WHERE transaction_id = ANY($1)
+            ORDER BY name ASC
+            "#,
+        )
+        .bind(transaction_ids)
+        .fetch_all(pool)
+        .await?;
+
+        rows.iter().map(Self::row_to_fee).collect()
+    }
+
+    /// Pending fees whose parent transaction is already posted. Used by the ledger retry worker.
+    pub async fn find_pending_with_posted_parent(
+        pool: &PgPool,
+        older_than: Duration,
+        limit: i64,
+    ) -> Result<Vec<TransactionFee>, AppError> {
+        let cutoff: DateTime<Utc> = Utc::now() - older_than;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT f.id, f.transaction_id, f.fee_schedule_id, f.organization_id, f.environment, f.name,
+                   f.payer_account_id, f.revenue_account_id, f.amount, f.currency, f.status,
+                   f.failure_reason, f.idempotency_key, f.created_at
+            FROM transaction_fees f
+            JOIN transactions t ON t.id = f.transaction_id
+            WHERE f.status = 'pending'
+              AND t.status = 'posted'
+              AND f.created_at < $1
+            ORDER BY f.created_at ASC
+            LIMIT $2
+            "#,
+        )
+        .bind(cutoff)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        rows.iter().map(Self::row_to_fee).collect()
+    }
+
+    pub async fn update_status(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        id: Uuid,
+        status: TransactionStatus,
+        failure_reason: Option<&str>,
+    ) -> Result<(), AppError> {
+        let status_str: &str = match status {
+            TransactionStatus::Pending => "pending",
+            TransactionStatus::Posted => "posted",
+            TransactionStatus::Failed => "failed",
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE transaction_fees
+            SET status = $2, failure_reason = $3, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(status_str)
+        .bind(failure_reason)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    fn row_to_schedule(row: &sqlx::postgres::PgRow) -> Result<FeeSchedule, AppError> {
+        let kind_str: String = row.get("transaction_kind");
+        let transaction_kind = match kind_str.as_str() {
+            "deposit" => TransactionKind::Deposit,
+            "withdraw" => TransactionKind::Withdraw,
+            "transfer" => TransactionKind::Transfer,
+            "interest" => TransactionKind::Interest,
+            _ => return Err(AppError::Internal("Invalid transaction kind".to_string())),
+        };
+
+        let account_type_str: Option<String> = row.get("account_type");
+        let account_type = match account_type_str.as_deref() {
+            None => None,
+            Some("checking") => Some(AccountType::Checking),
+            Some("saving") => Some(AccountType::Saving),
+            Some(_) => return Err(AppError::InvalidAccountType),
+        };
+
+        Ok(FeeSchedule {
+            id: row.get("id"),
+            organization_id: row.get("organization_id"),
+            environment: row.get("environment"),
+            name: row.get("name"),
+            transaction_kind,
+            account_type,
+            currency: row.get("currency"),
+            flat_amount: row.get("flat_amount"),
+            percentage: row.get("percentage"),
+            min_amount: row.get("min_amount"),
+            max_amount: row.get("max_amount"),
+            revenue_account_id: row.get("revenue_account_id"),
+            active: row.get("active"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+
+    fn row_to_fee(row: &sqlx::postgres::PgRow) -> Result<TransactionFee, AppError> {
+        let status_str: String = row.get("status");
+        let status = match status_str.as_str() {
+            "pending" => TransactionStatus::Pending,
+            "posted" => TransactionStatus::Posted,
+            "failed" => TransactionStatus::Failed,
+            _ => return Err(AppError::Internal("Invalid transaction fee status".to_string())),
+        };
+
+        Ok(TransactionFee {
+            id: row.get("id"),
+            transaction_id: row.get("transaction_id"),
+            fee_schedule_id: row.get("fee_schedule_id"),
+            organization_id: row.get("organization_id"),
+            environment: row.get("environment"),
+            name: row.get("name"),
+            payer_account_id: row.get("payer_account_id"),
+            revenue_account_id: row.get("revenue_account_id"),
+            amount: row.get("amount"),
+            currency: row.get("currency"),
+            status,
+            failure_reason: row.get("failure_reason"),
+            idempotency_key: row.get("idempotency_key"),
+            created_at: row.get("created_at"),
+        })
+    }
+}
@@ -0,0 +1,155 @@
+use crate::errors::AppError;
+use crate::models::{FixedSavingsPlan, FixedSavingsPlanStatus, FixedSavingsPlanType};
+use chrono::NaiveDate;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+pub struct FixedSavingsRepository;
+
+impl FixedSavingsRepository {
+    /// Ids of active auto-withdraw plans with a withdrawal due on or before `as_of`.
+    /// Oldest due date first so plans that fell behind during downtime are caught up first.
+    pub async fn find_due_auto_withdraw_ids(
+        pool: &PgPool,
+        as_of: NaiveDate,
+        limit: i64,
+    ) -> Result<Vec<Uuid>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id
+            FROM fixed_savings_plans
+            WHERE plan_type = 'auto_withdraw'
+              AND status = 'active'
+              AND next_withdraw_date <= $1
+            ORDER BY next_withdraw_date ASC, id ASC
+            LIMIT $2
+            "#,
+        )
+        .bind(as_of)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.iter().map(|row| row.get("id")).collect())
+    }
+
+    /// Lock a single due auto-withdraw plan for processing.
+    /// Returns None if the plan is no longer due or another worker holds the lock.
+    pub async fn lock_due_auto_withdraw(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        id: Uuid,
+        as_of: NaiveDate,
+    ) -> Result<Option<FixedSavingsPlan>, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, account_id, plan_type, initial_amount, current_balance, currency,
+                   monthly_withdraw_amount, next_withdraw_date, unlock_date, status, suspended_reason,
+                   created_at, updated_at
+            FROM fixed_savings_plans
+            WHERE id = $1
+              AND plan_type = 'auto_withdraw'
+              AND status = 'active'
+              AND next_withdraw_date <= $2
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .bind(id)
+        .bind(as_of)
+        .fetch_optional(executor)
+        .await?;
+
+        row.as_ref().map(Self::row_to_plan).transpose()
+    }
+
+    pub async fn record_withdrawal(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        id: Uuid,
+        current_balance: i64,
+        next_withdraw_date: Option<NaiveDate>,
+        status: FixedSavingsPlanStatus,
+    ) -> Result<FixedSavingsPlan, AppError> {
+        let status_str: &str = match status {
+            FixedSavingsPlanStatus::Active => "active",
+            FixedSavingsPlanStatus::Suspended => "suspended",
+            FixedSavingsPlanStatus::Completed => "completed",
+            FixedSavingsPlanStatus::Cancelled => "cancelled",
+        };
+
+        let row = sqlx::query(
+            r#"
+            UPDATE fixed_savings_plans
+            SET current_balance = $2, next_withdraw_date = $3, status = $4, updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, account_id, plan_type, initial_amount, current_balance, currency,
+                      monthly_withdraw_amount, next_withdraw_date, unlock_date, status, suspended_reason,
+                      created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(current_balance)
+        .bind(next_withdraw_date)
+        .bind(status_str)
+        .fetch_one(executor)
+        .await?;
+
+        Self::row_to_plan(&row)
+    }
+
+    /// Stop paying out a plan until it is set back to active.
+    pub async fn suspend(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        id: Uuid,
+        reason: &str,
+    ) -> Result<FixedSavingsPlan, AppError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE fixed_savings_plans
+            SET status = 'suspended', suspended_reason = $2, updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, account_id, plan_type, initial_amount, current_balance, currency,
+                      monthly_withdraw_amount, next_withdraw_date, unlock_date, status, suspended_reason,
+                      created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(reason)
+        .fetch_one(executor)
+        .await?;
+
+        Self::row_to_plan(&row)
+    }
+
+    fn row_to_plan(row: &sqlx::postgres::PgRow) -> Result<FixedSavingsPlan, AppError> {
+        let plan_type_str: String = row.get("plan_type");
+        let plan_type = match plan_type_str.as_str() {
+            "auto_withdraw" => FixedSavingsPlanType::AutoWithdraw,
+            "date_locked" => FixedSavingsPlanType::DateLocked,
+            _ => return Err(AppError::Internal("Invalid fixed savings plan type".to_string())),
+        };
+
+        let status_str: String = row.get("status");
+        let status = match status_str.as_str() {
+            "active" => FixedSavingsPlanStatus::Active,
+            "suspended" => FixedSavingsPlanStatus::Suspended,
+            "completed" => FixedSavingsPlanStatus::Completed,
+            "cancelled" => FixedSavingsPlanStatus::Cancelled,
+            _ => return Err(AppError::Internal("Invalid fixed savings plan status".to_string())),
+        };
+
+        Ok(FixedSavingsPlan {
+            id: row.get("id"),
+            account_id: row.get("account_id"),
+            plan_type,
+            initial_amount: row.get("initial_amount"),
+            current_balance: row.get("current_balance"),
+            currency: row.get("currency"),
+            monthly_withdraw_amount: row.get("monthly_withdraw_amount"),
+            next_withdraw_date: row.get("next_withdraw_date"),
+            unlock_date: row.get("unlock_date"),
+            status,
+            suspended_reason: row.get("suspended_reason"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+}
@@ -0,0 +1,193 @@
+use crate::errors::AppError;
+use crate::models::{FxConversion, FxQuote, FxQuoteStatus};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+pub struct FxRepository;
+
+impl FxRepository {
+    /// Reference rate for base -> quote, falling back to the inverse of quote -> base.
+    pub async fn find_rate(
+        pool: &PgPool,
+        base_currency: &str,
+        quote_currency: &str,
+    ) -> Result<Option<Decimal>, AppError> {
+        let rate: Option<Decimal> = sqlx::query_scalar(
+            r#"
+            SELECT rate FROM (
+                SELECT rate, 0 AS priority
+                FROM fx_rates
+                WHERE base_currency = $1 AND quote_currency = $2
+                UNION ALL
+                SELECT ROUND(1 / rate, 10) AS rate, 1 AS priority
+                FROM fx_rates
+                WHERE base_currency = $2 AND quote_currency = $1
+            ) rates
+            ORDER BY priority ASC
+            LIMIT 1
+            "#,
+        )
+        .bind(base_currency)
+        .bind(quote_currency)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(rate)
+    }
+
+    pub async fn create_quote(
+        pool: &PgPool,
+        organization_id: Uuid,
+        environment: &str,
+        conversion: &FxConversion,
+        expires_at: DateTime<Utc>,
+    ) -> Result<FxQuote, AppError> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO fx_quotes (
+                organization_id, environment, source_currency, destination_currency,
+                rate, source_amount, destination_amount, expires_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id, organization_id, environment, source_currency, destination_currency, rate,
+                      source_amount, destination_amount, status, expires_at, locked_at, transaction_id, created_at
+            "#,
+        )
+        .bind(organization_id)
+        .bind(environment)
+        .bind(conversion.source_currency.code())
+        .bind(conversion.destination_currency.code())
+        .bind(conversion.rate)
+        .bind(conversion.source_amount)
+        .bind(conversion.destination_amount)
+        .bind(expires_at)
+        .fetch_one(pool)
+        .await?;
+
+        Self::row_to_quote(&row)
+    }
+
+    pub async fn find_quote(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<FxQuote, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, organization_id, environment, source_currency, destination_currency, rate,
+                   source_amount, destination_amount, status, expires_at, locked_at, transaction_id, created_at
+            FROM fx_quotes
+            WHERE id = $1 AND environment = $2 AND organization_id = $3
+            "#,
+        )
+        .bind(id)
+        .bind(environment)
+        .bind(organization_id)
+        .fetch_optional(executor)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("FX quote with id {} not found in environment {}", id, environment)))?;
+
+        Self::row_to_quote(&row)
+    }
+
+    /// Row-locks a quote so concurrent transfers cannot both consume it.
+    pub async fn find_quote_for_update(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        id: Uuid,
+        environment: &str,
+    ) -> Result<FxQuote, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, organization_id, environment, source_currency, destination_currency, rate,
+                   source_amount, destination_amount, status, expires_at, locked_at, transaction_id, created_at
+            FROM fx_quotes
+            WHERE id = $1 AND environment = $2
+            FOR UPDATE
+            "#,
+        )
+        .bind(id)
+        .bind(environment)
+        .fetch_optional(executor)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("FX quote with id {} not found in environment {}", id, environment)))?;
+
+        Self::row_to_quote(&row)
+    }
+
+    /// Lock an open, unexpired quote until `expires_at`. Returns None if the quote cannot be locked.
+    pub async fn lock_quote(
+        pool: &PgPool,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Option<FxQuote>, AppError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE fx_quotes
+            SET status = 'locked', locked_at = NOW(), expires_at = $3, updated_at = NOW()
+            WHERE id = $1 AND environment = $2 AND organization_id = $4 AND status = 'open' AND expires_at > NOW()
+            RETURNING id, organization_id, environment, source_currency, destination_currency, rate,
+                      source_amount, destination_amount, status, expires_at, locked_at, transaction_id, created_at
+            "#,
+        )
+        .bind(id)
+        .bind(environment)
+        .bind(expires_at)
+        .bind(organization_id)
+        .fetch_optional(pool)
+        .await?;
+
+        row.as_ref().map(Self::row_to_quote).transpose()
+    }
+
+    /// Mark a quote as consumed by a transaction. Returns false if another transaction already used it.
+    pub async fn mark_quote_used(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        id: Uuid,
+        transaction_id: Uuid,
+    ) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE fx_quotes
+            SET status = 'used', transaction_id = $2, updated_at = NOW()
+            WHERE id = $1 AND (status <> 'used' OR transaction_id = $2)
+            "#,
+        )
+        .bind(id)
+        .bind(transaction_id)
+        .execute(executor)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    fn row_to_quote(row: &sqlx::postgres::PgRow) -> Result<FxQuote, AppError> {
+        let status_str: String = row.get("status");
+        let status = match status_str.as_str() {
+            "open" => FxQuoteStatus::Open,
+            "locked" => FxQuoteStatus::Locked,
+            "used" => FxQuoteStatus::Used,
+            _ => return Err(AppError::Internal("Invalid FX quote status".to_string())),
+        };
+
+        Ok(FxQuote {
+            id: row.get("id"),
+            organization_id: row.get("organization_id"),
+            environment: row.get("environment"),
+            source_currency: row.get("source_currency"),
+            destination_currency: row.get("destination_currency"),
+            rate: row.get("rate"),
+            source_amount: row.get("source_amount"),
+            destination_amount: row.get("destination_amount"),
+            status,
+            expires_at: row.get("expires_at"),
+            locked_at: row.get("locked_at"),
+            transaction_id: row.get("transaction_id"),
+            created_at: row.get("created_at"),
+        })
+    }
+}
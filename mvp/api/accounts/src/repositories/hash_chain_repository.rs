@@ -0,0 +1,298 @@
+use crate::errors::AppError;
+use crate::hash_chain::{checkpoint_message, Canonical, Chain};
+use crate::models::ChainCheckpoint;
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+/// A chained record as stored, with its canonical content rebuilt from the columns.
+pub struct ChainRecord {
+    pub id: Uuid,
+    pub chain_seq: i64,
+    pub prev_hash: String,
+    pub record_hash: String,
+    pub content: Canonical,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Last link of a chain.
+pub struct ChainHead {
+    pub organization_id: Uuid,
+    pub chain: Chain,
+    pub last_seq: i64,
+    pub last_hash: String,
+}
+
+pub struct HashChainRepository;
+
+impl HashChainRepository {
+    /// Last link of each of the organization's chains (every organization's with `None`).
+    pub async fn find_heads(
+        pool: &PgPool,
+        organization_id: Option<Uuid>,
+    ) -> Result<Vec<ChainHead>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT organization_id, chain, last_seq, last_hash
+            FROM hash_chain_heads
+            WHERE $1::uuid IS NULL OR organization_id = $1
+            ORDER BY organization_id, chain
+            "#,
+        )
+        .bind(organization_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| {
+                let chain = Chain::parse(row.get("chain"))?;
+                Some(ChainHead {
+                    organization_id: row.get("organization_id"),
+                    chain,
+                    last_seq: row.get("last_seq"),
+                    last_hash: row.get("last_hash"),
+                })
+            })
+            .collect())
+    }
+
+    /// Records of a chain after `after_seq`, in chain order.
+    pub async fn find_records(
+        pool: &PgPool,
+        organization_id: Uuid,
+        chain: Chain,
+        after_seq: i64,
+        limit: i64,
+    ) -> Result<Vec<ChainRecord>, AppError> {
+        let sql = match chain {
+            Chain::Transactions => {
+                r#"
+                SELECT id, chain_seq, prev_hash, record_hash, organization_id, environment, transaction_kind,
+                       from_account_id, to_account_id, amount, currency, destination_amount,
+                       destination_currency, fx_rate::text AS fx_rate, fx_quote_id,
+                       counterparty_organization_id, interorg_agreement_id, idempotency_key, created_at
+                FROM transactions
+                WHERE organization_id = $1 AND chain_seq > $2
+                ORDER BY chain_seq
+                LIMIT $3
+                "#
+            }
+            Chain::AuditEvents => {
+                r#"
+                SELECT id, chain_seq, prev_hash, record_hash, organization_id, environment, actor_type, actor_id,
+                       action, target_type, target_id, before::text AS before, after::text AS after,
+                       status_code, correlation_id, ip_address, created_at
+                FROM audit_events
+                WHERE COALESCE(organization_id, '00000000-0000-0000-0000-000000000000'::uuid) = $1
+                  AND chain_seq > $2
+                ORDER BY chain_seq
+                LIMIT $3
+                "#
+            }
+        };
+
+        let rows = sqlx::query(sql)
+            .bind(organization_id)
+            .bind(after_seq)
+            .bind(limit)
+            .fetch_all(pool)
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let content = match chain {
+                    Chain::Transactions => Self::transaction_content(row),
+                    Chain::AuditEvents => Self::audit_event_content(row),
+                };
+                ChainRecord {
+                    id: row.get("id"),
+                    chain_seq: row.get("chain_seq"),
+                    prev_hash: row.get("prev_hash"),
+                    record_hash: row.get("record_hash"),
+                    content,
+                    created_at: row.get("created_at"),
+                }
+            })
+            .collect())
+    }
+
+    /// Field order of `transactions_hash_chain` in the hash chain migration.
+    fn transaction_content(row: &PgRow) -> Canonical {
+        Canonical::default()
+            .uuid(Some(row.get("id")))
+            .uuid(Some(row.get("organization_id")))
+            .field(row.get::<Option<String>, _>("environment").as_deref())
+            .field(Some(row.get::<String, _>("transaction_kind").as_str()))
+            .uuid(Some(row.get("from_account_id")))
+            .uuid(Some(row.get("to_account_id")))
+            .int(Some(row.get("amount")))
+            .field(Some(row.get::<String, _>("currency").as_str()))
+            .int(row.get("destination_amount"))
+            .field(row.get::<Option<String>, _>("destination_currency").as_deref())
+            .field(row.get::<Option<String>, _>("fx_rate").as_deref())
+            .uuid(row.get("fx_quote_id"))
+            .uuid(row.get("counterparty_organization_id"))
+            .uuid(row.get("interorg_agreement_id"))
+            .field(Some(row.get::<String, _>("idempotency_key").as_str()))
+            .timestamp(row.get("created_at"))
+    }
+
+    /// Field order of `audit_events_hash_chain` in the hash chain migration.
+    fn audit_event_content(row: &PgRow) -> Canonical {
+        Canonical::default()
+            .uuid(Some(row.get("id")))
+            .uuid(row.get("organization_id"))
+            .field(row.get::<Option<String>, _>("environment").as_deref())
+            .field(Some(row.get::<String, _>("actor_type").as_str()))
+            .uuid(row.get("actor_id"))
+            .field(Some(row.get::<String, _>("action").as_str()))
+            .field(Some(row.get::<String, _>("target_type").as_str()))
+            .uuid(row.get("target_id"))
+            .field(row.get::<Option<String>, _>("before").as_deref())
+            .field(row.get::<Option<String>, _>("after").as_deref())
+            .int(Some(i64::from(row.get::<i32, _>("status_code"))))
+            .field(row.get::<Option<String>, _>("correlation_id").as_deref())
+            .field(row.get::<Option<String>, _>("ip_address").as_deref())
+            .timestamp(row.get("created_at"))
+    }
+
+    /// Latest link of a chain created before `before`.
+    pub async fn find_link_before(
+        pool: &PgPool,
+        organization_id: Uuid,
+        chain: Chain,
+        before: DateTime<Utc>,
+    ) -> Result<Option<(i64, String)>, AppError> {
+        let sql = match chain {
+            Chain::Transactions => {
+                r#"
+                SELECT chain_seq, record_hash FROM transactions
+                WHERE organization_id = $1 AND chain_seq IS NOT NULL AND created_at < $2
+                ORDER BY chain_seq DESC
+                LIMIT 1
+                "#
+            }
+            Chain::AuditEvents => {
+                r#"
+                SELECT chain_seq, record_hash FROM audit_events
+                WHERE COALESCE(organization_id, '00000000-0000-0000-0000-000000000000'::uuid) = $1
+                  AND chain_seq IS NOT NULL AND created_at < $2
+                ORDER BY chain_seq DESC
+                LIMIT 1
+                "#
+            }
+        };
+
+        let row = sqlx::query(sql)
+            .bind(organization_id)
+            .bind(before)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(row.map(|row| (row.get("chain_seq"), row.get("record_hash"))))
+    }
+
+    /// Day after the chain's last checkpoint, or the day of its first record.
+    pub async fn find_next_checkpoint_date(
+        pool: &PgPool,
+        organization_id: Uuid,
+        chain: Chain,
+    ) -> Result<Option<NaiveDate>, AppError> {
+        let last: Option<NaiveDate> = sqlx::query_scalar(
+            r#"
+            SELECT MAX(checkpoint_date) FROM hash_chain_checkpoints
+            WHERE organization_id = $1 AND chain = $2
+            "#,
+        )
+        .bind(organization_id)
+        .bind(chain.as_str())
+        .fetch_one(pool)
+        .await?;
+
+        if let Some(last) = last {
+            return Ok(last.succ_opt());
+        }
+
+        let first = Self::find_records(pool, organization_id, chain, 0, 1).await?;
+        Ok(first.first().map(|record| record.created_at.date_naive()))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn insert_checkpoint(
+        pool: &PgPool,
+        organization_id: Uuid,
+        chain: Chain,
+        date: NaiveDate,
+        chain_seq: i64,
+        record_hash: &str,
+        signature: &str,
+        public_key: &str,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO hash_chain_checkpoints (
+                organization_id, chain, checkpoint_date, chain_seq, record_hash, signature, public_key
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (organization_id, chain, checkpoint_date) DO NOTHING
+            "#,
+        )
+        .bind(organization_id)
+        .bind(chain.as_str())
+        .bind(date)
+        .bind(chain_seq)
+        .bind(record_hash)
+        .bind(signature)
+        .bind(public_key)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Checkpoints of the organization dated within `[from, to]`, oldest first.
+    pub async fn find_checkpoints(
+        pool: &PgPool,
+        organization_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<ChainCheckpoint>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT organization_id, chain, checkpoint_date, chain_seq, record_hash, signature, public_key, created_at
+            FROM hash_chain_checkpoints
+            WHERE organization_id = $1 AND checkpoint_date BETWEEN $2 AND $3
+            ORDER BY checkpoint_date, chain
+            "#,
+        )
+        .bind(organization_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| {
+                let chain = Chain::parse(row.get("chain"))?;
+                let date: NaiveDate = row.get("checkpoint_date");
+                let chain_seq: i64 = row.get("chain_seq");
+                let record_hash: String = row.get("record_hash");
+                Some(ChainCheckpoint {
+                    organization_id,
+                    chain: chain.as_str().to_string(),
+                    date,
+                    chain_seq,
+                    message: checkpoint_message(organization_id, chain, date, chain_seq, &record_hash),
+                    record_hash,
+                    signature: row.get("signature"),
+                    public_key: row.get("public_key"),
+                    created_at: row.get("created_at"),
+                })
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,126 @@
+use crate::errors::AppError;
+use crate::models::{IdempotencyRecord, StoredResponse};
+use sqlx::{PgPool, Row};
+
+pub struct IdempotencyRepository;
+
+impl IdempotencyRepository {
+    /// Take the key for a request: inserts it, or takes over an expired key or a stale lock
+    /// of the same request. Returns false when the key is held by another record.
+    pub async fn acquire(
+        pool: &PgPool,
+        tenant: &str,
+        idempotency_key: &str,
+        request_fingerprint: &str,
+        lock_secs: i64,
+        ttl_secs: i64,
+    ) -> Result<bool, AppError> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO idempotency_keys (tenant, idempotency_key, request_fingerprint, locked_until, expires_at)
+            VALUES ($1, $2, $3, NOW() + make_interval(secs => $4), NOW() + make_interval(secs => $5))
+            ON CONFLICT (tenant, idempotency_key) DO UPDATE
+            SET request_fingerprint = EXCLUDED.request_fingerprint,
+                response_status = NULL,
+                response_content_type = NULL,
+                response_body = NULL,
+                locked_until = EXCLUDED.locked_until,
+                expires_at = EXCLUDED.expires_at,
+                created_at = NOW()
+            WHERE idempotency_keys.expires_at <= NOW()
+               OR (idempotency_keys.response_status IS NULL
+                   AND idempotency_keys.locked_until <= NOW()
+                   AND idempotency_keys.request_fingerprint = EXCLUDED.request_fingerprint)
+            RETURNING tenant
+            "#,
+        )
+        .bind(tenant)
+        .bind(idempotency_key)
+        .bind(request_fingerprint)
+        .bind(lock_secs as f64)
+        .bind(ttl_secs as f64)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.is_some())
+    }
+
+    pub async fn find(
+        pool: &PgPool,
+        tenant: &str,
+        idempotency_key: &str,
+    ) -> Result<Option<IdempotencyRecord>, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT request_fingerprint, response_status, response_content_type, response_body
+            FROM idempotency_keys
+            WHERE tenant = $1 AND idempotency_key = $2 AND expires_at > NOW()
+            "#,
+        )
+        .bind(tenant)
+        .bind(idempotency_key)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.map(|row| {
+            let status: Option<i32> = row.get("response_status");
+            IdempotencyRecord {
+                request_fingerprint: row.get("request_fingerprint"),
+                response: status.map(|status| StoredResponse {
+                    status: status as u16,
+                    content_type: row.get("response_content_type"),
+                    body: row.get::<Option<Vec<u8>>, _>("response_body").unwrap_or_default(),
+                }),
+            }
+        }))
+    }
+
+    /// Store the response of the request holding the key.
+    pub async fn complete(
+        pool: &PgPool,
+        tenant: &str,
+        idempotency_key: &str,
+        response: &StoredResponse,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE idempotency_keys
+            SET response_status = $3, response_content_type = $4, response_body = $5, locked_until = NOW()
+            WHERE tenant = $1 AND idempotency_key = $2
+            "#,
+        )
+        .bind(tenant)
+        .bind(idempotency_key)
+        .bind(response.status as i32)
+        .bind(response.content_type.as_deref())
+        .bind(&response.body)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Drop an in-flight key (the request failed and may be retried with it).
+    pub async fn release(pool: &PgPool, tenant: &str, idempotency_key: &str) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            DELETE FROM idempotency_keys
+            WHERE tenant = $1 AND idempotency_key = $2 AND response_status IS NULL
+            "#,
+        )
+        .bind(tenant)
+        .bind(idempotency_key)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn purge_expired(pool: &PgPool) -> Result<u64, AppError> {
+        let result = sqlx::query("DELETE FROM idempotency_keys WHERE expires_at <= NOW()")
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
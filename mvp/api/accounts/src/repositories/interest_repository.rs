@@ -0,0 +1,400 @@
+use crate::errors::AppError;
+use crate::models::{
+    InterestAccrual, InterestAccrualCandidate, InterestMethod, InterestPostingPeriod,
+    InterestRateConfig, InterestRateTier, PaginationMeta,
+};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+pub struct InterestRepository;
+
+impl InterestRepository {
+    /// Create or replace the rate configuration (and all its tiers) for an organization/environment/currency.
+    pub async fn upsert_config(
+        pool: &PgPool,
+        organization_id: Uuid,
+        environment: &str,
+        currency: &str,
+        interest_method: InterestMethod,
+        tiers: &[InterestRateTier],
+    ) -> Result<InterestRateConfig, AppError> {
+        let mut tx = pool.begin().await?;
+
+        let row = sqlx::query(
+            r#"
+            INSERT INTO interest_rate_configs (organization_id, environment, currency, interest_method)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (organization_id, environment, currency)
+            DO UPDATE SET interest_method = EXCLUDED.interest_method, updated_at = NOW()
+            RETURNING id, organization_id, environment, currency, interest_method, created_at, updated_at
+            "#,
+        )
+        .bind(organization_id)
+        .bind(environment)
+        .bind(currency)
+        .bind(interest_method.as_str())
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let config_id: Uuid = row.get("id");
+
+        sqlx::query("DELETE FROM interest_rate_tiers WHERE config_id = $1")
+            .bind(config_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for tier in tiers {
+            sqlx::query(
+                r#"
+                INSERT INTO interest_rate_tiers (config_id, min_balance, annual_rate)
+                VALUES ($1, $2, $3)
+                "#,
+            )
+            .bind(config_id)
+            .bind(tier.min_balance)
+            .bind(tier.annual_rate)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        let mut tiers = tiers.to_vec();
+        tiers.sort_by_key(|tier| tier.min_balance);
+        Self::row_to_config(&row, tiers)
+    }
+
+    pub async fn find_config(
+        pool: &PgPool,
+        organization_id: Uuid,
+        environment: &str,
+        currency: &str,
+    ) -> Result<Option<InterestRateConfig>, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, organization_id, environment, currency, interest_method, created_at, updated_at
+            FROM interest_rate_configs
+            WHERE organization_id = $1 AND environment = $2 AND currency = $3
+            "#,
+        )
+        .bind(organization_id)
+        .bind(environment)
+        .bind(currency)
+        .fetch_optional(pool)
+        .await?;
+
+        match row {
+            Some(row) => {
+                let tiers = Self::find_tiers(pool, row.get("id")).await?;
+                Ok(Some(Self::row_to_config(&row, tiers)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub async fn find_configs_by_organization(
+        pool: &PgPool,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<Vec<InterestRateConfig>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, organization_id, environment, currency, interest_method, created_at, updated_at
+            FROM interest_rate_configs
+            WHERE organization_id = $1 AND environment = $2
+            ORDER BY currency ASC
+            "#,
+        )
+        .bind(organization_id)
+        .bind(environment)
+        .fetch_all(pool)
+        .await?;
+
+        let mut configs = Vec::with_capacity(rows.len());
+        for row in rows {
+            let tiers = Self::find_tiers(pool, row.get("id")).await?;
+            configs.push(Self::row_to_config(&row, tiers)?);
+        }
+
+        Ok(configs)
+    }
+
+    async fn find_tiers(pool: &PgPool, config_id: Uuid) -> Result<Vec<InterestRateTier>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT min_balance, annual_rate
+            FROM interest_rate_tiers
+            WHERE config_id = $1
+            ORDER BY min_balance ASC
+            "#,
+        )
+        .bind(config_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| InterestRateTier {
+                min_balance: row.get("min_balance"),
+                annual_rate: row.get("annual_rate"),
+            })
+            .collect())
+    }
+
+    /// Non-closed saving accounts that have a rate configuration for their currency.
+    pub async fn find_accrual_candidates(pool: &PgPool) -> Result<Vec<InterestAccrualCandidate>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT a.id AS account_id, a.organization_id, a.environment, a.currency,
+                   GREATEST(a.created_at, c.created_at)::date AS start_date,
+                   (SELECT MAX(ia.accrual_date) FROM interest_accruals ia WHERE ia.account_id = a.id) AS last_accrual_date
+            FROM accounts a
+            JOIN interest_rate_configs c
+              ON c.organization_id = a.organization_id
+             AND c.environment = a.environment
+             AND c.currency = a.currency
+            WHERE a.account_type = 'saving'
+              AND a.status <> 'closed'
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| InterestAccrualCandidate {
+                account_id: row.get("account_id"),
+                organization_id: row.get("organization_id"),
+                environment: row.get("environment"),
+                currency: row.get("currency"),
+                start_date: row.get("start_date"),
+                last_accrual_date: row.get("last_accrual_date"),
+            })
+            .collect())
+    }
+
+    /// Sum of accrued interest not yet posted (compound interest base).
+    pub async fn unposted_total(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        account_id: Uuid,
+    ) -> Result<Decimal, AppError> {
+        let total: Option<Decimal> = sqlx::query_scalar(
+            "SELECT SUM(accrued_amount) FROM interest_accruals WHERE account_id = $1 AND posted_at IS NULL",
+        )
+        .bind(account_id)
+        .fetch_one(executor)
+        .await?;
+
+        Ok(total.unwrap_or(Decimal::ZERO))
+    }
+
+    /// Store a daily accrual. A second accrual for the same account/day is ignored.
+    pub async fn insert_accrual(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        candidate: &InterestAccrualCandidate,
+        accrual_date: NaiveDate,
+        balance: i64,
+        annual_rate: Decimal,
+        interest_method: InterestMethod,
+        accrued_amount: Decimal,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            INSERT INTO interest_accruals (
+                organization_id, environment, account_id, accrual_date, balance,
+                annual_rate, interest_method, accrued_amount, currency
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (account_id, accrual_date) DO NOTHING
+            "#,
+        )
+        .bind(candidate.organization_id)
+        .bind(&candidate.environment)
+        .bind(candidate.account_id)
+        .bind(accrual_date)
+        .bind(balance)
+        .bind(annual_rate)
+        .bind(interest_method.as_str())
+        .bind(accrued_amount)
+        .bind(&candidate.currency)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Unposted accruals grouped per account and calendar month, for months starting before `before`.
+    pub async fn find_unposted_periods(
+        pool: &PgPool,
+        before: NaiveDate,
+    ) -> Result<Vec<InterestPostingPeriod>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT account_id, organization_id, environment, currency,
+                   date_trunc('month', accrual_date)::date AS period_start,
+                   SUM(accrued_amount) AS accrued_total
+            FROM interest_accruals
+            WHERE posted_at IS NULL AND accrual_date < $1
+            GROUP BY account_id, organization_id, environment, currency, date_trunc('month', accrual_date)
+            ORDER BY period_start ASC
+            "#,
+        )
+        .bind(before)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| InterestPostingPeriod {
+                account_id: row.get("account_id"),
+                organization_id: row.get("organization_id"),
+                environment: row.get("environment"),
+                currency: row.get("currency"),
+                period_start: row.get("period_start"),
+                accrued_total: row.get("accrued_total"),
+            })
+            .collect())
+    }
+
+    /// Mark the accruals of a period as posted by the given transaction (None when the total rounded to zero).
+    pub async fn mark_posted(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        account_id: Uuid,
+        period_start: NaiveDate,
+        period_end: NaiveDate,
+        transaction_id: Option<Uuid>,
+    ) -> Result<u64, AppError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE interest_accruals
+            SET transaction_id = $4, posted_at = NOW()
+            WHERE account_id = $1
+              AND accrual_date >= $2
+              AND accrual_date < $3
+              AND posted_at IS NULL
+            "#,
+        )
+        .bind(account_id)
+        .bind(period_start)
+        .bind(period_end)
+        .bind(transaction_id)
+        .execute(executor)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn find_by_account_id_paginated(
+        pool: &PgPool,
+        account_id: Uuid,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        page: u32,
+        per_page: u32,
+    ) -> Result<(Vec<InterestAccrual>, PaginationMeta), AppError> {
+        let offset = (page - 1) * per_page;
+
+        let count_row = sqlx::query(
+            r#"
+            SELECT COUNT(*) as count
+            FROM interest_accruals
+            WHERE account_id = $1
+              AND ($2::date IS NULL OR accrual_date >= $2)
+              AND ($3::date IS NULL OR accrual_date <= $3)
+            "#,
+        )
+        .bind(account_id)
+        .bind(from)
+        .bind(to)
+        .fetch_one(pool)
+        .await?;
+
+        let total_count: i64 = count_row.get("count");
+        let total_pages = ((total_count as f64) / (per_page as f64)).ceil() as u32;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT id, organization_id, environment, account_id, accrual_date, balance, annual_rate,
+                   interest_method, accrued_amount, currency, transaction_id, posted_at, created_at
+            FROM interest_accruals
+            WHERE account_id = $1
+              AND ($2::date IS NULL OR accrual_date >= $2)
+              AND ($3::date IS NULL OR accrual_date <= $3)
+            ORDER BY accrual_date DESC
+            LIMIT $4 OFFSET $5
+            "#,
+        )
+        .bind(account_id)
+        .bind(from)
+        .bind(to)
+        .bind(per_page as i64)
+        .bind(offset as i64)
+        .fetch_all(pool)
+        .await?;
+
+        let accruals = rows
+            .iter()
+            .map(Self::row_to_accrual)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok((
+            accruals,
+            PaginationMeta {
+                page: Some(page),
+                per_page,
+                total_count: Some(total_count),
+                total_pages: Some(total_pages),
+                next_cursor: None,
+                prev_cursor: None,
+            },
+        ))
+    }
+
+    fn parse_method(method: &str) -> Result<InterestMethod, AppError> {
+        match method {
+            "simple" => Ok(InterestMethod::Simple),
+            "compound" => Ok(InterestMethod::Compound),
+            _ => Err(AppError::Internal("Invalid interest method".to_string())),
+        }
+    }
+
+    fn row_to_config(
+        row: &sqlx::postgres::PgRow,
+        tiers: Vec<InterestRateTier>,
+    ) -> Result<InterestRateConfig, AppError> {
+        let method_str: String = row.get("interest_method");
+
+        Ok(InterestRateConfig {
+            id: row.get("id"),
+            organization_id: row.get("organization_id"),
+            environment: row.get("environment"),
+            currency: row.get("currency"),
+            interest_method: Self::parse_method(&method_str)?,
+            tiers,
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+
+    fn row_to_accrual(row: &sqlx::postgres::PgRow) -> Result<InterestAccrual, AppError> {
+        let method_str: String = row.get("interest_method");
+
+        Ok(InterestAccrual {
+            id: row.get("id"),
+            organization_id: row.get("organization_id"),
+            environment: row.get("environment"),
+            account_id: row.get("account_id"),
+            accrual_date: row.get("accrual_date"),
+            balance: row.get("balance"),
+            annual_rate: row.get("annual_rate"),
+            interest_method: Self::parse_method(&method_str)?,
+            accrued_amount: row.get("accrued_amount"),
+            currency: row.get("currency"),
+            transaction_id: row.get("transaction_id"),
+            posted_at: row.get("posted_at"),
+            created_at: row.get("created_at"),
+        })
+    }
+}
@@ -0,0 +1,275 @@
+use crate::errors::AppError;
+use crate::models::{CreateInterorgAgreementRequest, InterorgAgreement, InterorgAgreementStatus};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+pub struct InterorgRepository;
+
+impl InterorgRepository {
+    /// Create a pending agreement; the counterparty organization is set when it accepts.
+    pub async fn create(
+        pool: &PgPool,
+        environment: &str,
+        request: &CreateInterorgAgreementRequest,
+    ) -> Result<InterorgAgreement, AppError> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO interorg_agreements (
+                environment, organization_id, counterparty_account_id, max_amount_per_transfer, daily_amount
+            )
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, environment, organization_id, counterparty_organization_id, counterparty_account_id,
+                      max_amount_per_transfer, daily_amount, status, accepted_at, revoked_at, created_at, updated_at
+            "#,
+        )
+        .bind(environment)
+        .bind(request.organization_id)
+        .bind(request.counterparty_account_id)
+        .bind(request.max_amount_per_transfer)
+        .bind(request.daily_amount)
+        .fetch_one(pool)
+        .await?;
+
+        Self::row_to_agreement(&row)
+    }
+
+    /// Agreements where the organization is either the payer or the counterparty, including
+    /// pending agreements naming one of its accounts.
+    pub async fn find_by_organization(
+        pool: &PgPool,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<Vec<InterorgAgreement>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, environment, organization_id, counterparty_organization_id, counterparty_account_id,
+                   max_amount_per_transfer, daily_amount, status, accepted_at, revoked_at, created_at, updated_at
+            FROM interorg_agreements
+            WHERE (
+                  organization_id = $1
+                  OR counterparty_organization_id = $1
+                  OR (
+                      status = 'pending'
+                      AND counterparty_account_id IN (
+                          SELECT id FROM accounts WHERE organization_id = $1 AND environment = $2
+                      )
+                  )
+              )
+              AND environment = $2
+            ORDER BY created_at DESC, id DESC
+            "#,
+        )
+        .bind(organization_id)
+        .bind(environment)
+        .fetch_all(pool)
+        .await?;
+
+        rows.iter().map(Self::row_to_agreement).collect()
+    }
+
+    /// The payer's pending or active agreement for a counterparty account, if any.
+    pub async fn find_open(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        environment: &str,
+        organization_id: Uuid,
+        counterparty_account_id: Uuid,
+    ) -> Result<Option<InterorgAgreement>, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, environment, organization_id, counterparty_organization_id, counterparty_account_id,
+                   max_amount_per_transfer, daily_amount, status, accepted_at, revoked_at, created_at, updated_at
+            FROM interorg_agreements
+            WHERE environment = $1
+              AND organization_id = $2
+              AND counterparty_account_id = $3
+              AND status IN ('pending', 'active')
+            "#,
+        )
+        .bind(environment)
+        .bind(organization_id)
+        .bind(counterparty_account_id)
+        .fetch_optional(executor)
+        .await?;
+
+        row.as_ref().map(Self::row_to_agreement).transpose()
+    }
+
+    /// The payer's active agreement for a counterparty account, row-locked so its limits are
+    /// checked one transfer at a time.
+    pub async fn find_active_for_update(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        environment: &str,
+        organization_id: Uuid,
+        counterparty_account_id: Uuid,
+    ) -> Result<Option<InterorgAgreement>, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, environment, organization_id, counterparty_organization_id, counterparty_account_id,
+                   max_amount_per_transfer, daily_amount, status, accepted_at, revoked_at, created_at, updated_at
+            FROM interorg_agreements
+            WHERE environment = $1
+              AND organization_id = $2
+              AND counterparty_account_id = $3
+              AND status = 'active'
+            FOR UPDATE
+            "#,
+        )
+        .bind(environment)
+        .bind(organization_id)
+        .bind(counterparty_account_id)
+        .fetch_optional(executor)
+        .await?;
+
+        row.as_ref().map(Self::row_to_agreement).transpose()
+    }
+
+    /// A pending agreement naming one of the organization's accounts, row-locked until the
+    /// caller's transaction ends. Agreements naming other organizations' accounts are not found.
+    pub async fn find_pending_for_counterparty_for_update(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<InterorgAgreement, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT g.id, g.environment, g.organization_id, g.counterparty_organization_id, g.counterparty_account_id,
+                   g.max_amount_per_transfer, g.daily_amount, g.status, g.accepted_at, g.revoked_at,
+                   g.created_at, g.updated_at
+            FROM interorg_agreements g
+            JOIN accounts a ON a.id = g.counterparty_account_id
+            WHERE g.id = $1
+              AND g.environment = $2
+              AND g.status = 'pending'
+              AND a.organization_id = $3
+              AND a.environment = $2
+            FOR UPDATE OF g
+            "#,
+        )
+        .bind(id)
+        .bind(environment)
+        .bind(organization_id)
+        .fetch_optional(executor)
+        .await?
+        .ok_or_else(|| {
+            AppError::NotFound(format!(
+                "Pending inter-organization agreement with id {} not found in environment {}",
+                id, environment
+            ))
+        })?;
+
+        Self::row_to_agreement(&row)
+    }
+
+    pub async fn accept(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        id: Uuid,
+        counterparty_organization_id: Uuid,
+    ) -> Result<InterorgAgreement, AppError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE interorg_agreements
+            SET status = 'active', counterparty_organization_id = $2, accepted_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND status = 'pending'
+            RETURNING id, environment, organization_id, counterparty_organization_id, counterparty_account_id,
+                      max_amount_per_transfer, daily_amount, status, accepted_at, revoked_at, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(counterparty_organization_id)
+        .fetch_one(executor)
+        .await?;
+
+        Self::row_to_agreement(&row)
+    }
+
+    /// Revoke a pending or active agreement. Either organization can revoke: the payer, or the
+    /// organization owning the counterparty account.
+    pub async fn revoke(
+        pool: &PgPool,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<InterorgAgreement, AppError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE interorg_agreements
+            SET status = 'revoked', revoked_at = NOW(), updated_at = NOW()
+            WHERE id = $1
+              AND environment = $2
+              AND status IN ('pending', 'active')
+              AND (
+                  organization_id = $3
+                  OR counterparty_account_id IN (
+                      SELECT id FROM accounts WHERE organization_id = $3 AND environment = $2
+                  )
+              )
+            RETURNING id, environment, organization_id, counterparty_organization_id, counterparty_account_id,
+                      max_amount_per_transfer, daily_amount, status, accepted_at, revoked_at, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(environment)
+        .bind(organization_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| {
+            AppError::NotFound(format!(
+                "Inter-organization agreement with id {} not found in environment {}",
+                id, environment
+            ))
+        })?;
+
+        Self::row_to_agreement(&row)
+    }
+
+    /// Amount transferred under an agreement since 00:00 UTC today (pending or posted),
+    /// excluding the intent for `idempotency_key` so replays are not counted twice.
+    pub async fn transferred_today(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        agreement_id: Uuid,
+        idempotency_key: &str,
+    ) -> Result<i64, AppError> {
+        let total: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(SUM(amount), 0)::BIGINT
+            FROM transactions
+            WHERE interorg_agreement_id = $1
+              AND status <> 'failed'
+              AND idempotency_key <> $2
+              AND created_at >= date_trunc('day', NOW() AT TIME ZONE 'UTC') AT TIME ZONE 'UTC'
+            "#,
+        )
+        .bind(agreement_id)
+        .bind(idempotency_key)
+        .fetch_one(executor)
+        .await?;
+
+        Ok(total)
+    }
+
+    fn row_to_agreement(row: &sqlx::postgres::PgRow) -> Result<InterorgAgreement, AppError> {
+        let status_str: String = row.get("status");
+        let status = match status_str.as_str() {
+            "pending" => InterorgAgreementStatus::Pending,
+            "active" => InterorgAgreementStatus::Active,
+            "revoked" => InterorgAgreementStatus::Revoked,
+            _ => return Err(AppError::Internal("Invalid inter-organization agreement status".to_string())),
+        };
+
+        Ok(InterorgAgreement {
+            id: row.get("id"),
+            environment: row.get("environment"),
+            organization_id: row.get("organization_id"),
+            counterparty_organization_id: row.get("counterparty_organization_id"),
+            counterparty_account_id: row.get("counterparty_account_id"),
+            max_amount_per_transfer: row.get("max_amount_per_transfer"),
+            daily_amount: row.get("daily_amount"),
+            status,
+            accepted_at: row.get("accepted_at"),
+            revoked_at: row.get("revoked_at"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+}
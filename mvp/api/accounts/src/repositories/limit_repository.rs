@@ -0,0 +1,281 @@
+use crate::errors::AppError;
+use crate::models::{AccountType, LimitScope, TransactionKind, TransactionLimit, UpsertTransactionLimitRequest};
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+pub struct LimitRepository;
+
+impl LimitRepository {
+    /// Create the limit for a scope/kind/currency, or replace its values if it already exists.
+    pub async fn upsert(
+        pool: &PgPool,
+        environment: &str,
+        request: &UpsertTransactionLimitRequest,
+    ) -> Result<TransactionLimit, AppError> {
+        let account_type_str: Option<&str> = request.account_type.map(|t| match t {
+            AccountType::Checking => "checking",
+            AccountType::Saving => "saving",
+        });
+        let kind_str: Option<&str> = request.transaction_kind.map(|k| match k {
+            TransactionKind::Deposit => "deposit",
+            TransactionKind::Withdraw => "withdraw",
+            TransactionKind::Transfer => "transfer",
+            TransactionKind::Interest => "interest",
+        });
+
+        let row = sqlx::query(
+            r#"
+            WITH updated AS (
+                UPDATE transaction_limits
+                SET max_amount_per_transaction = $7,
+                    daily_amount = $8,
+                    monthly_amount = $9,
+                    daily_count = $10,
+                    monthly_count = $11,
+                    updated_at = NOW()
+                WHERE organization_id = $1
+                  AND environment = $2
+                  AND currency = $3
+                  AND account_type IS NOT DISTINCT FROM $4
+                  AND account_id IS NOT DISTINCT FROM $5
+                  AND transaction_kind IS NOT DISTINCT FROM $6
+                RETURNING id, organization_id, environment, account_type, account_id, transaction_kind, currency,
+                          max_amount_per_transaction, daily_amount, monthly_amount, daily_count, monthly_count,
+                          created_at, updated_at
+            ),
+            inserted AS (
+                INSERT INTO transaction_limits (
+                    organization_id, environment, currency, account_type, account_id, transaction_kind,
+                    max_amount_per_transaction, daily_amount, monthly_amount, daily_count, monthly_count
+                )
+                SELECT $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11
+                WHERE NOT EXISTS (SELECT 1 FROM updated)
+                RETURNING id, organization_id, environment, account_type, account_id, transaction_kind, currency,
+                          max_amount_per_transaction, daily_amount, monthly_amount, daily_count, monthly_count,
+                          created_at, updated_at
+            )
+            SELECT * FROM updated
+            UNION ALL
+            SELECT * FROM inserted
+            "#,
+        )
+        .bind(request.organization_id)
+        .bind(environment)
+        .bind(request.currency.code())
+        .bind(account_type_str)
+        .bind(request.account_id)
+        .bind(kind_str)
+        .bind(request.max_amount_per_transaction)
+        .bind(request.daily_amount)
+        .bind(request.monthly_amount)
+        .bind(request.daily_count)
+        .bind(request.monthly_count)
+        .fetch_one(pool)
+        .await?;
+
+        Self::row_to_limit(&row)
+    }
+
+    pub async fn find_by_organization(
+        pool: &PgPool,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<Vec<TransactionLimit>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, organization_id, environment, account_type, account_id, transaction_kind, currency,
+                   max_amount_per_transaction, daily_amount, monthly_amount, daily_count, monthly_count,
+                   created_at, updated_at
+            FROM transaction_limits
+            WHERE organization_id = $1 AND environment = $2
+            ORDER BY currency ASC, account_id NULLS FIRST, account_type NULLS FIRST, transaction_kind NULLS FIRST
+            "#,
+        )
+        .bind(organization_id)
+        .bind(environment)
+        .fetch_all(pool)
+        .await?;
+
+        rows.iter().map(Self::row_to_limit).collect()
+    }
+
+    pub async fn delete(
+        pool: &PgPool,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<(), AppError> {
+        let result =
+            sqlx::query("DELETE FROM transaction_limits WHERE id = $1 AND environment = $2 AND organization_id = $3")
+                .bind(id)
+                .bind(environment)
+                .bind(organization_id)
+                .execute(pool)
+                .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "Transaction limit with id {} not found in environment {}",
+                id, environment
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Limits that apply to an outgoing transaction from `account_id`: the account's own limits,
+    /// its account type's and the organization's, ordered by id (the advisory lock order).
+    pub async fn find_applicable(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        organization_id: Uuid,
+        environment: &str,
+        currency: &str,
+        account_id: Uuid,
+        account_type: AccountType,
+        transaction_kind: TransactionKind,
+    ) -> Result<Vec<TransactionLimit>, AppError> {
+        let account_type_str: &str = match account_type {
+            AccountType::Checking => "checking",
+            AccountType::Saving => "saving",
+        };
+        let kind_str: &str = match transaction_kind {
+            TransactionKind::Deposit => "deposit",
+            TransactionKind::Withdraw => "withdraw",
+            TransactionKind::Transfer => "transfer",
+            TransactionKind::Interest => "interest",
+        };
+
+        let rows = sqlx::query(
+            r#"
+            SELECT id, organization_id, environment, account_type, account_id, transaction_kind, currency,
+                   max_amount_per_transaction, daily_amount, monthly_amount, daily_count, monthly_count,
+                   created_at, updated_at
+            FROM transaction_limits
+            WHERE organization_id = $1
+              AND environment = $2
+              AND currency = $3
+              AND (
+                  account_id = $4
+                  OR (account_id IS NULL AND account_type = $5)
+                  OR (account_id IS NULL AND account_type IS NULL)
+              )
+              AND (transaction_kind IS NULL OR transaction_kind = $6)
+            ORDER BY id ASC
+            "#,
+        )
+        .bind(organization_id)
+        .bind(environment)
+        .bind(currency)
+        .bind(account_id)
+        .bind(account_type_str)
+        .bind(kind_str)
+        .fetch_all(executor)
+        .await?;
+
+        rows.iter().map(Self::row_to_limit).collect()
+    }
+
+    /// Serialize enforcement of a limit until the surrounding transaction ends, so concurrent
+    /// requests cannot both pass the usage check.
+    pub async fn lock(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        limit_id: Uuid,
+    ) -> Result<(), AppError> {
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1::text, 0))")
+            .bind(limit_id)
+            .execute(executor)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Total amount and count of outgoing intents (pending or posted) covered by a limit since `since`.
+    pub async fn usage_since(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        limit: &TransactionLimit,
+        since: DateTime<Utc>,
+    ) -> Result<(i64, i64), AppError> {
+        let kinds: Vec<&str> = match limit.transaction_kind {
+            Some(TransactionKind::Withdraw) => vec!["withdraw"],
+            Some(TransactionKind::Transfer) => vec!["transfer"],
+            _ => vec!["withdraw", "transfer"],
+        };
+        let account_type_str: Option<&str> = limit.account_type.map(|t| match t {
+            AccountType::Checking => "checking",
+            AccountType::Saving => "saving",
+        });
+
+        let row = sqlx::query(
+            r#"
+            SELECT COALESCE(SUM(t.amount), 0)::BIGINT AS total, COUNT(*) AS count
+            FROM transactions t
+            JOIN accounts a ON a.id = t.from_account_id
+            WHERE t.organization_id = $1
+              AND t.environment = $2
+              AND t.currency = $3
+              AND t.transaction_kind = ANY($4)
+              AND t.status <> 'failed'
+              AND t.created_at >= $5
+              AND ($6::uuid IS NULL OR t.from_account_id = $6)
+              AND ($7::varchar IS NULL OR a.account_type = $7)
+            "#,
+        )
+        .bind(limit.organization_id)
+        .bind(&limit.environment)
+        .bind(&limit.currency)
+        .bind(&kinds)
+        .bind(since)
+        .bind(limit.account_id)
+        .bind(account_type_str)
+        .fetch_one(executor)
+        .await?;
+
+        Ok((row.get("total"), row.get("count")))
+    }
+
+    fn row_to_limit(row: &sqlx::postgres::PgRow) -> Result<TransactionLimit, AppError> {
+        let account_type_str: Option<String> = row.get("account_type");
+        let account_type = match account_type_str.as_deref() {
+            None => None,
+            Some("checking") => Some(AccountType::Checking),
+            Some("saving") => Some(AccountType::Saving),
+            Some(_) => return Err(AppError::InvalidAccountType),
+        };
+
+        let kind_str: Option<String> = row.get("transaction_kind");
+        let transaction_kind = match kind_str.as_deref() {
+            None => None,
+            Some("withdraw") => Some(TransactionKind::Withdraw),
+            Some("transfer") => Some(TransactionKind::Transfer),
+            Some(_) => return Err(AppError::Internal("Invalid transaction kind".to_string())),
+        };
+
+        let account_id: Option<Uuid> = row.get("account_id");
+        let scope = if account_id.is_some() {
+            LimitScope::Account
+        } else if account_type.is_some() {
+            LimitScope::AccountType
+        } else {
+            LimitScope::Organization
+        };
+
+        Ok(TransactionLimit {
+            id: row.get("id"),
+            organization_id: row.get("organization_id"),
+            environment: row.get("environment"),
+            scope,
+            account_type,
+            account_id,
+            transaction_kind,
+            currency: row.get("currency"),
+            max_amount_per_transaction: row.get("max_amount_per_transaction"),
+            daily_amount: row.get("daily_amount"),
+            monthly_amount: row.get("monthly_amount"),
+            daily_count: row.get("daily_count"),
+            monthly_count: row.get("monthly_count"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+}
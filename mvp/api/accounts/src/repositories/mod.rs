@@ -1,5 +1,39 @@
+pub mod account_closure_repository;
 pub mod account_repository;
+pub mod audit_repository;
+pub mod event_repository;
+pub mod fee_repository;
+pub mod fixed_savings_repository;
+pub mod fx_repository;
+pub mod hash_chain_repository;
+pub mod idempotency_repository;
+pub mod interest_repository;
+pub mod interorg_repository;
+pub mod limit_repository;
+pub mod nacha_repository;
+pub mod payee_repository;
+pub mod payout_repository;
+pub mod rate_limit_repository;
+pub mod statement_repository;
+pub mod webhook_repository;
 pub mod transaction_repository;
 
+pub use account_closure_repository::AccountClosureRepository;
 pub use account_repository::AccountRepository;
+pub use audit_repository::AuditRepository;
+pub use event_repository::EventRepository;
+pub use fee_repository::FeeRepository;
+pub use fixed_savings_repository::FixedSavingsRepository;
+pub use fx_repository::FxRepository;
+pub use hash_chain_repository::{ChainHead, HashChainRepository};
+pub use idempotency_repository::IdempotencyRepository;
+pub use interest_repository::InterestRepository;
+pub use interorg_repository::InterorgRepository;
+pub use limit_repository::LimitRepository;
+pub use nacha_repository::{NachaRepository, NewNachaFile};
+pub use payee_repository::PayeeRepository;
+pub use payout_repository::PayoutRepository;
+pub use rate_limit_repository::RateLimitRepository;
+pub use statement_repository::StatementRepository;
+pub use webhook_repository::{DueDelivery, WebhookRepository};
 pub use transaction_repository::TransactionRepository;
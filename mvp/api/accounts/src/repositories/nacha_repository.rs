@@ -0,0 +1,147 @@
+use crate::errors::AppError;
+use crate::models::NachaFile;
+use crate::nacha::NachaFileContents;
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+pub struct NachaRepository;
+
+/// Identity of a file being recorded (everything except its computed totals).
+pub struct NewNachaFile<'a> {
+    pub environment: &'a str,
+    pub file_name: &'a str,
+    pub file_path: &'a str,
+    pub file_creation_date: NaiveDate,
+    pub file_id_modifier: char,
+    pub window_end: DateTime<Utc>,
+}
+
+impl NachaRepository {
+    /// Serialize file generation per environment for the rest of the database transaction.
+    pub async fn lock_environment(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        environment: &str,
+    ) -> Result<(), AppError> {
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended('nacha_file:' || $1, 0))")
+            .bind(environment)
+            .execute(executor)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn next_trace_sequence(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+    ) -> Result<i64, AppError> {
+        let sequence: i64 = sqlx::query_scalar("SELECT nextval('nacha_trace_seq')")
+            .fetch_one(executor)
+            .await?;
+
+        Ok(sequence)
+    }
+
+    pub async fn count_files_for_date(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        environment: &str,
+        file_creation_date: NaiveDate,
+    ) -> Result<i64, AppError> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM nacha_files WHERE environment = $1 AND file_creation_date = $2",
+        )
+        .bind(environment)
+        .bind(file_creation_date)
+        .fetch_one(executor)
+        .await?;
+
+        Ok(count)
+    }
+
+    pub async fn create(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        file: &NewNachaFile<'_>,
+        contents: &NachaFileContents,
+    ) -> Result<NachaFile, AppError> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO nacha_files (
+                environment, file_name, file_path, file_creation_date, file_id_modifier, window_end,
+                batch_count, entry_count, entry_hash, total_credit_amount
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING id, environment, file_name, file_path, file_creation_date, file_id_modifier,
+                      window_end, batch_count, entry_count, entry_hash, total_credit_amount, created_at
+            "#,
+        )
+        .bind(file.environment)
+        .bind(file.file_name)
+        .bind(file.file_path)
+        .bind(file.file_creation_date)
+        .bind(file.file_id_modifier.to_string())
+        .bind(file.window_end)
+        .bind(contents.batch_count)
+        .bind(contents.entry_count)
+        .bind(contents.entry_hash)
+        .bind(contents.total_credit_amount)
+        .fetch_one(executor)
+        .await?;
+
+        Ok(Self::row_to_file(&row))
+    }
+
+    pub async fn find_by_id(pool: &PgPool, id: Uuid, environment: &str) -> Result<NachaFile, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, environment, file_name, file_path, file_creation_date, file_id_modifier,
+                   window_end, batch_count, entry_count, entry_hash, total_credit_amount, created_at
+            FROM nacha_files
+            WHERE id = $1 AND environment = $2
+            "#,
+        )
+        .bind(id)
+        .bind(environment)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| {
+            AppError::NotFound(format!("NACHA file with id {} not found in environment {}", id, environment))
+        })?;
+
+        Ok(Self::row_to_file(&row))
+    }
+
+    pub async fn find_recent(pool: &PgPool, environment: &str, limit: i64) -> Result<Vec<NachaFile>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, environment, file_name, file_path, file_creation_date, file_id_modifier,
+                   window_end, batch_count, entry_count, entry_hash, total_credit_amount, created_at
+            FROM nacha_files
+            WHERE environment = $1
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(environment)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.iter().map(Self::row_to_file).collect())
+    }
+
+    fn row_to_file(row: &sqlx::postgres::PgRow) -> NachaFile {
+        NachaFile {
+            id: row.get("id"),
+            environment: row.get("environment"),
+            file_name: row.get("file_name"),
+            file_path: row.get("file_path"),
+            file_creation_date: row.get("file_creation_date"),
+            file_id_modifier: row.get("file_id_modifier"),
+            window_end: row.get("window_end"),
+            batch_count: row.get("batch_count"),
+            entry_count: row.get("entry_count"),
+            entry_hash: row.get("entry_hash"),
+            total_credit_amount: row.get("total_credit_amount"),
+            created_at: row.get("created_at"),
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::errors::AppError;
+use crate::models::{BankAccountType, CreatePayeeRequest, Payee, PayeeStatus};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+pub struct PayeeRepository;
+
+impl PayeeRepository {
+    pub async fn create(
+        pool: &PgPool,
+        environment: &str,
+        request: &CreatePayeeRequest,
+    ) -> Result<Payee, AppError> {
+        let bank_account_type = match request.bank_account_type {
+            BankAccountType::Checking => "checking",
+            BankAccountType::Savings => "savings",
+        };
+
+        let row = sqlx::query(
+            r#"
+            INSERT INTO payees (
+                environment, organization_id, user_id, name, bank_name,
+                routing_number, account_number, bank_account_type
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id, environment, organization_id, user_id, name, bank_name, routing_number,
+                      account_number, bank_account_type, status, created_at, updated_at
+            "#,
+        )
+        .bind(environment)
+        .bind(request.organization_id)
+        .bind(request.user_id)
+        .bind(request.name.trim())
+        .bind(request.bank_name.as_deref())
+        .bind(&request.routing_number)
+        .bind(&request.account_number)
+        .bind(bank_account_type)
+        .fetch_one(pool)
+        .await?;
+
+        Self::row_to_payee(&row)
+    }
+
+    pub async fn find_by_id(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<Payee, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, environment, organization_id, user_id, name, bank_name, routing_number,
+                   account_number, bank_account_type, status, created_at, updated_at
+            FROM payees
+            WHERE id = $1 AND environment = $2 AND organization_id = $3
+            "#,
+        )
+        .bind(id)
+        .bind(environment)
+        .bind(organization_id)
+        .fetch_optional(executor)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Payee with id {} not found in environment {}", id, environment)))?;
+
+        Self::row_to_payee(&row)
+    }
+
+    /// Payees of an organization, optionally narrowed to one account owner.
+    pub async fn find_by_organization(
+        pool: &PgPool,
+        organization_id: Uuid,
+        user_id: Option<Uuid>,
+        environment: &str,
+    ) -> Result<Vec<Payee>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, environment, organization_id, user_id, name, bank_name, routing_number,
+                   account_number, bank_account_type, status, created_at, updated_at
+            FROM payees
+            WHERE organization_id = $1
+              AND environment = $2
+              AND ($3::UUID IS NULL OR user_id = $3)
+            ORDER BY created_at DESC, id DESC
+            "#,
+        )
+        .bind(organization_id)
+        .bind(environment)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        rows.iter().map(Self::row_to_payee).collect()
+    }
+
+    pub async fn deactivate(
+        pool: &PgPool,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<Payee, AppError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE payees
+            SET status = 'inactive', updated_at = NOW()
+            WHERE id = $1 AND environment = $2 AND organization_id = $3
+            RETURNING id, environment, organization_id, user_id, name, bank_name, routing_number,
+                      account_number, bank_account_type, status, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(environment)
+        .bind(organization_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Payee with id {} not found in environment {}", id, environment)))?;
+
+        Self::row_to_payee(&row)
+    }
+
+    fn row_to_payee(row: &sqlx::postgres::PgRow) -> Result<Payee, AppError> {
+        let bank_account_type_str: String = row.get("bank_account_type");
+        let bank_account_type = match bank_account_type_str.as_str() {
+            "checking" => BankAccountType::Checking,
+            "savings" => BankAccountType::Savings,
+            _ => return Err(AppError::Internal("Invalid payee bank account type".to_string())),
+        };
+
+        let status_str: String = row.get("status");
+        let status = match status_str.as_str() {
+            "active" => PayeeStatus::Active,
+            "inactive" => PayeeStatus::Inactive,
+            _ => return Err(AppError::Internal("Invalid payee status".to_string())),
+        };
+
+        Ok(Payee {
+            id: row.get("id"),
+            environment: row.get("environment"),
+            organization_id: row.get("organization_id"),
+            user_id: row.get("user_id"),
+            name: row.get("name"),
+            bank_name: row.get("bank_name"),
+            routing_number: row.get("routing_number"),
+            account_number: row.get("account_number"),
+            bank_account_type,
+            status,
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+}
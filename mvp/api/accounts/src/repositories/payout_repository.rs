@@ -0,0 +1,336 @@
+use crate::errors::AppError;
+use crate::models::{Payee, Payout, PayoutStatus, Transaction};
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+pub struct PayoutRepository;
+
+impl PayoutRepository {
+    /// Create the payout for a withdraw intent, or return the existing one on replay.
+    pub async fn create_or_get(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        transaction: &Transaction,
+        payee: &Payee,
+        rail: &str,
+    ) -> Result<Payout, AppError> {
+        let row = sqlx::query(
+            r#"
+            WITH existing AS (
+                SELECT id, environment, organization_id, account_id, payee_id, transaction_id,
+                       return_transaction_id, amount, currency, status, rail, rail_reference,
+                       return_code, return_reason, failure_reason, submitted_at, settled_at,
+                       returned_at, created_at, updated_at
+                FROM payouts
+                WHERE transaction_id = $4
+            ),
+            inserted AS (
+                INSERT INTO payouts (
+                    environment, organization_id, account_id, payee_id, transaction_id,
+                    amount, currency, rail
+                )
+                SELECT $1, $2, $3, $5, $4, $6, $7, $8
+                WHERE NOT EXISTS (SELECT 1 FROM existing)
+                RETURNING id, environment, organization_id, account_id, payee_id, transaction_id,
+                          return_transaction_id, amount, currency, status, rail, rail_reference,
+                          return_code, return_reason, failure_reason, submitted_at, settled_at,
+                          returned_at, created_at, updated_at
+            )
+            SELECT * FROM inserted
+            UNION ALL
+            SELECT * FROM existing
+            LIMIT 1
+            "#,
+        )
+        .bind(&payee.environment)
+        .bind(transaction.organization_id)
+        .bind(transaction.from_account_id)
+        .bind(transaction.id)
+        .bind(payee.id)
+        .bind(transaction.amount)
+        .bind(&transaction.currency)
+        .bind(rail)
+        .fetch_one(executor)
+        .await?;
+
+        Self::row_to_payout(&row)
+    }
+
+    pub async fn find_by_id(
+        pool: &PgPool,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<Payout, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, environment, organization_id, account_id, payee_id, transaction_id,
+                   return_transaction_id, amount, currency, status, rail, rail_reference,
+                   return_code, return_reason, failure_reason, submitted_at, settled_at,
+                   returned_at, created_at, updated_at
+            FROM payouts
+            WHERE id = $1 AND environment = $2 AND organization_id = $3
+            "#,
+        )
+        .bind(id)
+        .bind(environment)
+        .bind(organization_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Payout with id {} not found in environment {}", id, environment)))?;
+
+        Self::row_to_payout(&row)
+    }
+
+    /// Lock a payout for a status change (returns are recorded at most once).
+    pub async fn find_by_id_for_update(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        id: Uuid,
+    ) -> Result<Payout, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, environment, organization_id, account_id, payee_id, transaction_id,
+                   return_transaction_id, amount, currency, status, rail, rail_reference,
+                   return_code, return_reason, failure_reason, submitted_at, settled_at,
+                   returned_at, created_at, updated_at
+            FROM payouts
+            WHERE id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(executor)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Payout with id {} not found", id)))?;
+
+        Self::row_to_payout(&row)
+    }
+
+    pub async fn find_by_account(
+        pool: &PgPool,
+        account_id: Uuid,
+        environment: &str,
+    ) -> Result<Vec<Payout>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, environment, organization_id, account_id, payee_id, transaction_id,
+                   return_transaction_id, amount, currency, status, rail, rail_reference,
+                   return_code, return_reason, failure_reason, submitted_at, settled_at,
+                   returned_at, created_at, updated_at
+            FROM payouts
+            WHERE account_id = $1 AND environment = $2
+            ORDER BY created_at DESC, id DESC
+            "#,
+        )
+        .bind(account_id)
+        .bind(environment)
+        .fetch_all(pool)
+        .await?;
+
+        rows.iter().map(Self::row_to_payout).collect()
+    }
+
+    /// Pending and submitted payouts, oldest first. Used by the payout worker.
+    pub async fn find_in_flight(pool: &PgPool, limit: i64) -> Result<Vec<Payout>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, environment, organization_id, account_id, payee_id, transaction_id,
+                   return_transaction_id, amount, currency, status, rail, rail_reference,
+                   return_code, return_reason, failure_reason, submitted_at, settled_at,
+                   returned_at, created_at, updated_at
+            FROM payouts
+            WHERE status IN ('pending', 'submitted')
+            ORDER BY created_at ASC
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        rows.iter().map(Self::row_to_payout).collect()
+    }
+
+    pub async fn find_by_transaction_id(pool: &PgPool, transaction_id: Uuid) -> Result<Option<Payout>, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, environment, organization_id, account_id, payee_id, transaction_id,
+                   return_transaction_id, amount, currency, status, rail, rail_reference,
+                   return_code, return_reason, failure_reason, submitted_at, settled_at,
+                   returned_at, created_at, updated_at
+            FROM payouts
+            WHERE transaction_id = $1
+            "#,
+        )
+        .bind(transaction_id)
+        .fetch_optional(pool)
+        .await?;
+
+        row.as_ref().map(Self::row_to_payout).transpose()
+    }
+
+    /// Pending payouts on a batch rail whose debit is posted, created up to `window_end`.
+    /// Rows are locked until the file that includes them is recorded.
+    pub async fn find_ready_for_file(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        environment: &str,
+        rail: &str,
+        window_end: DateTime<Utc>,
+    ) -> Result<Vec<Payout>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT p.id, p.environment, p.organization_id, p.account_id, p.payee_id, p.transaction_id,
+                   p.return_transaction_id, p.amount, p.currency, p.status, p.rail, p.rail_reference,
+                   p.return_code, p.return_reason, p.failure_reason, p.submitted_at, p.settled_at,
+                   p.returned_at, p.created_at, p.updated_at
+            FROM payouts p
+            JOIN transactions t ON t.id = p.transaction_id
+            WHERE p.environment = $1
+              AND p.rail = $2
+              AND p.status = 'pending'
+              AND p.created_at <= $3
+              AND t.status = 'posted'
+            ORDER BY p.organization_id, p.created_at, p.id
+            FOR UPDATE OF p
+            "#,
+        )
+        .bind(environment)
+        .bind(rail)
+        .bind(window_end)
+        .fetch_all(executor)
+        .await?;
+
+        rows.iter().map(Self::row_to_payout).collect()
+    }
+
+    /// Mark a pending payout submitted. Returns None if it was no longer pending.
+    pub async fn mark_submitted(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        id: Uuid,
+        rail_reference: &str,
+    ) -> Result<Option<Payout>, AppError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE payouts
+            SET status = 'submitted', rail_reference = $2, failure_reason = NULL,
+                submitted_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND status = 'pending'
+            RETURNING id, environment, organization_id, account_id, payee_id, transaction_id,
+                      return_transaction_id, amount, currency, status, rail, rail_reference,
+                      return_code, return_reason, failure_reason, submitted_at, settled_at,
+                      returned_at, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(rail_reference)
+        .fetch_optional(executor)
+        .await?;
+
+        row.as_ref().map(Self::row_to_payout).transpose()
+    }
+
+    /// Record why a pending payout could not be submitted; it stays pending for the worker.
+    pub async fn record_failure(pool: &PgPool, id: Uuid, reason: &str) -> Result<Payout, AppError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE payouts
+            SET failure_reason = $2, updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, environment, organization_id, account_id, payee_id, transaction_id,
+                      return_transaction_id, amount, currency, status, rail, rail_reference,
+                      return_code, return_reason, failure_reason, submitted_at, settled_at,
+                      returned_at, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(reason)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Payout with id {} not found", id)))?;
+
+        Self::row_to_payout(&row)
+    }
+
+    /// Mark a submitted payout settled. Returns None if it was no longer submitted.
+    pub async fn mark_settled(pool: &PgPool, id: Uuid) -> Result<Option<Payout>, AppError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE payouts
+            SET status = 'settled', settled_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND status = 'submitted'
+            RETURNING id, environment, organization_id, account_id, payee_id, transaction_id,
+                      return_transaction_id, amount, currency, status, rail, rail_reference,
+                      return_code, return_reason, failure_reason, submitted_at, settled_at,
+                      returned_at, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+        row.as_ref().map(Self::row_to_payout).transpose()
+    }
+
+    pub async fn mark_returned(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        id: Uuid,
+        return_transaction_id: Uuid,
+        return_code: &str,
+        return_reason: &str,
+    ) -> Result<Payout, AppError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE payouts
+            SET status = 'returned', return_transaction_id = $2, return_code = $3, return_reason = $4,
+                returned_at = NOW(), updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, environment, organization_id, account_id, payee_id, transaction_id,
+                      return_transaction_id, amount, currency, status, rail, rail_reference,
+                      return_code, return_reason, failure_reason, submitted_at, settled_at,
+                      returned_at, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(return_transaction_id)
+        .bind(return_code)
+        .bind(return_reason)
+        .fetch_one(executor)
+        .await?;
+
+        Self::row_to_payout(&row)
+    }
+
+    fn row_to_payout(row: &sqlx::postgres::PgRow) -> Result<Payout, AppError> {
+        let status_str: String = row.get("status");
+        let status = match status_str.as_str() {
+            "pending" => PayoutStatus::Pending,
+            "submitted" => PayoutStatus::Submitted,
+            "settled" => PayoutStatus::Settled,
+            "returned" => PayoutStatus::Returned,
+            _ => return Err(AppError::Internal("Invalid payout status".to_string())),
+        };
+
+        Ok(Payout {
+            id: row.get("id"),
+            environment: row.get("environment"),
+            organization_id: row.get("organization_id"),
+            account_id: row.get("account_id"),
+            payee_id: row.get("payee_id"),
+            transaction_id: row.get("transaction_id"),
+            return_transaction_id: row.get("return_transaction_id"),
+            amount: row.get("amount"),
+            currency: row.get("currency"),
+            status,
+            rail: row.get("rail"),
+            rail_reference: row.get("rail_reference"),
+            return_code: row.get("return_code"),
+            return_reason: row.get("return_reason"),
+            failure_reason: row.get("failure_reason"),
+            submitted_at: row.get("submitted_at"),
+            settled_at: row.get("settled_at"),
+            returned_at: row.get("returned_at"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+}
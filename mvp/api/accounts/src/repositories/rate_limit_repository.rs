@@ -0,0 +1,29 @@
+use crate::errors::AppError;
+use crate::models::RateLimitPolicy;
+use sqlx::{PgPool, Row};
+
+pub struct RateLimitRepository;
+
+impl RateLimitRepository {
+    /// Every organization's overrides; the table holds a few rows per organization at most.
+    pub async fn find_all(pool: &PgPool) -> Result<Vec<RateLimitPolicy>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT organization_id, route_class, requests_per_minute, burst
+            FROM rate_limits
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| RateLimitPolicy {
+                organization_id: row.get("organization_id"),
+                route_class: row.get("route_class"),
+                requests_per_minute: row.get("requests_per_minute"),
+                burst: row.get("burst"),
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,197 @@
+use crate::errors::AppError;
+use crate::models::{AccountStatement, StatementKindTotal, StatementLine, StatementSummary};
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::types::Json;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+pub struct StatementRepository;
+
+impl StatementRepository {
+    /// Store a statement, or return the one already stored for the same account and period
+    /// (stored statements are immutable, so the first one generated wins).
+    pub async fn create_or_get(pool: &PgPool, statement: &AccountStatement) -> Result<AccountStatement, AppError> {
+        let row = sqlx::query(
+            r#"
+            WITH inserted AS (
+                INSERT INTO account_statements (
+                    account_id, organization_id, environment, period_start, period_end, currency,
+                    opening_balance, closing_balance, total_credits, total_debits, totals, lines
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                ON CONFLICT (account_id, period_start, period_end) DO NOTHING
+                RETURNING id, account_id, organization_id, environment, period_start, period_end, currency,
+                          opening_balance, closing_balance, total_credits, total_debits, totals, lines, created_at
+            )
+            SELECT * FROM inserted
+            UNION ALL
+            SELECT id, account_id, organization_id, environment, period_start, period_end, currency,
+                   opening_balance, closing_balance, total_credits, total_debits, totals, lines, created_at
+            FROM account_statements
+            WHERE account_id = $1 AND period_start = $4 AND period_end = $5
+            LIMIT 1
+            "#,
+        )
+        .bind(statement.account_id)
+        .bind(statement.organization_id)
+        .bind(&statement.environment)
+        .bind(statement.period_start)
+        .bind(statement.period_end)
+        .bind(&statement.currency)
+        .bind(statement.opening_balance)
+        .bind(statement.closing_balance)
+        .bind(statement.total_credits)
+        .bind(statement.total_debits)
+        .bind(Json(&statement.totals))
+        .bind(Json(&statement.lines))
+        .fetch_one(pool)
+        .await?;
+
+        Ok(Self::row_to_statement(&row))
+    }
+
+    pub async fn find_by_period(
+        pool: &PgPool,
+        account_id: Uuid,
+        environment: &str,
+        period_start: NaiveDate,
+        period_end: NaiveDate,
+    ) -> Result<Option<AccountStatement>, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, account_id, organization_id, environment, period_start, period_end, currency,
+                   opening_balance, closing_balance, total_credits, total_debits, totals, lines, created_at
+            FROM account_statements
+            WHERE account_id = $1 AND environment = $2 AND period_start = $3 AND period_end = $4
+            "#,
+        )
+        .bind(account_id)
+        .bind(environment)
+        .bind(period_start)
+        .bind(period_end)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.as_ref().map(Self::row_to_statement))
+    }
+
+    /// Stored statements of an account, newest period first.
+    pub async fn find_by_account(
+        pool: &PgPool,
+        account_id: Uuid,
+        environment: &str,
+    ) -> Result<Vec<StatementSummary>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, period_start, period_end, currency, opening_balance, closing_balance,
+                   jsonb_array_length(lines)::BIGINT AS line_count, created_at
+            FROM account_statements
+            WHERE account_id = $1 AND environment = $2
+            ORDER BY period_start DESC, period_end DESC
+            "#,
+        )
+        .bind(account_id)
+        .bind(environment)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| StatementSummary {
+                id: row.get("id"),
+                period_start: row.get("period_start"),
+                period_end: row.get("period_end"),
+                currency: row.get("currency"),
+                opening_balance: row.get("opening_balance"),
+                closing_balance: row.get("closing_balance"),
+                line_count: row.get("line_count"),
+                created_at: row.get("created_at"),
+            })
+            .collect())
+    }
+
+    /// Intents and fee legs on the account created in `[from, to)` that are still pending
+    /// (a period with pending movements can still change and is not stored yet).
+    pub async fn count_pending_movements(
+        pool: &PgPool,
+        account_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<i64, AppError> {
+        let count: i64 = sqlx::query_scalar(
+            r#"
+            SELECT (
+                SELECT COUNT(*) FROM transactions
+                WHERE (from_account_id = $1 OR to_account_id = $1)
+                  AND status = 'pending' AND created_at >= $2 AND created_at < $3
+            ) + (
+                SELECT COUNT(*) FROM transaction_fees
+                WHERE (payer_account_id = $1 OR revenue_account_id = $1)
+                  AND status = 'pending' AND created_at >= $2 AND created_at < $3
+            )
+            "#,
+        )
+        .bind(account_id)
+        .bind(from)
+        .bind(to)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Accounts (with their environment) that existed before `opened_before` and have no
+    /// stored statement for the period yet.
+    pub async fn find_accounts_missing_statement(
+        pool: &PgPool,
+        period_start: NaiveDate,
+        period_end: NaiveDate,
+        opened_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<(Uuid, String)>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT a.id, a.environment
+            FROM accounts a
+            WHERE a.created_at < $3
+              AND a.environment IS NOT NULL
+              AND NOT EXISTS (
+                  SELECT 1 FROM account_statements s
+                  WHERE s.account_id = a.id AND s.period_start = $1 AND s.period_end = $2
+              )
+            ORDER BY a.created_at
+            LIMIT $4
+            "#,
+        )
+        .bind(period_start)
+        .bind(period_end)
+        .bind(opened_before)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.iter().map(|row| (row.get("id"), row.get("environment"))).collect())
+    }
+
+    fn row_to_statement(row: &sqlx::postgres::PgRow) -> AccountStatement {
+        let totals: Json<Vec<StatementKindTotal>> = row.get("totals");
+        let lines: Json<Vec<StatementLine>> = row.get("lines");
+
+        AccountStatement {
+            id: Some(row.get("id")),
+            account_id: row.get("account_id"),
+            organization_id: row.get("organization_id"),
+            environment: row.get("environment"),
+            period_start: row.get("period_start"),
+            period_end: row.get("period_end"),
+            currency: row.get("currency"),
+            opening_balance: row.get("opening_balance"),
+            closing_balance: row.get("closing_balance"),
+            total_credits: row.get("total_credits"),
+            total_debits: row.get("total_debits"),
+            totals: totals.0,
+            lines: lines.0,
+            created_at: row.get("created_at"),
+        }
+    }
+}
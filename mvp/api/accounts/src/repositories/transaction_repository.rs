@@ -1,7 +1,13 @@
 use crate::errors::AppError;
-use crate::models::{Transaction, TransactionKind, TransactionStatus, PaginationMeta};
+use crate::models::{
+    AccountMovement, FxConversion, InterorgAgreement, MovementKind, SortOrder, Transaction, TransactionFilter,
+    TransactionDetails, TransactionKind, TransactionStatus, PaginationMeta,
+};
+use crate::utils::pagination::{keyset_page, PageRequest};
 use chrono::{DateTime, Duration, Utc};
-use sqlx::{PgPool, Row};
+use sha2::{Digest, Sha256};
+use sqlx::types::Json;
+use sqlx::{PgPool, Postgres, QueryBuilder, Row};
 use uuid::Uuid;
 
 pub struct TransactionRepository;
@@ -17,12 +23,16 @@ impl TransactionRepository {
         transaction_kind: TransactionKind,
         idempotency_key: &str,
         environment: Option<&str>,
+        details: &TransactionDetails,
     ) -> Result<Transaction, AppError> {
         let kind_str: &str = match transaction_kind {
             TransactionKind::Deposit => "deposit",
             TransactionKind::Withdraw => "withdraw",
             TransactionKind::Transfer => "transfer",
+            TransactionKind::Interest => "interest",
         };
+        let fingerprint =
+            request_fingerprint(kind_str, from_account_id, to_account_id, amount, currency, None, details);
 
         // Use a CTE-based approach to handle idempotency with the COALESCE-based unique index.
         // This avoids ON CONFLICT issues with expression-based indexes.
@@ -33,7 +43,10 @@ impl TransactionRepository {
             r#"
             WITH existing AS (
                 SELECT id, organization_id, from_account_id, to_account_id, amount, currency,
-                       transaction_kind, status, failure_reason, idempotency_key, environment, created_at, updated_at
+                       transaction_kind, status, failure_reason, idempotency_key, environment,
+                       destination_amount, destination_currency, fx_rate, fx_quote_id,
+                       counterparty_organization_id, interorg_agreement_id, nacha_file_id, nacha_trace_number,
+                       description, reference, metadata, created_at, updated_at, request_fingerprint, true AS replayed
                 FROM transactions
                 WHERE organization_id = $1
                   AND COALESCE(environment, '') = COALESCE($8, '')
@@ -43,12 +56,16 @@ impl TransactionRepository {
             inserted AS (
                 INSERT INTO transactions (
                     organization_id, from_account_id, to_account_id, amount, currency,
-                    transaction_kind, status, failure_reason, idempotency_key, environment
+                    transaction_kind, status, failure_reason, idempotency_key, environment, request_fingerprint,
+                    description, reference, metadata
                 )
-                SELECT $1, $2, $3, $4, $5, $6, 'pending', NULL, $7, $8
+                SELECT $1, $2, $3, $4, $5, $6, 'pending', NULL, $7, $8, $9, $10, $11, $12
                 WHERE NOT EXISTS (SELECT 1 FROM existing)
                 RETURNING id, organization_id, from_account_id, to_account_id, amount, currency,
-                          transaction_kind, status, failure_reason, idempotency_key, environment, created_at, updated_at
+                          transaction_kind, status, failure_reason, idempotency_key, environment,
+                          destination_amount, destination_currency, fx_rate, fx_quote_id,
+                          counterparty_organization_id, interorg_agreement_id, nacha_file_id, nacha_trace_number,
+                          description, reference, metadata, created_at, updated_at, request_fingerprint, false AS replayed
             )
             SELECT * FROM inserted
             UNION ALL
@@ -64,17 +81,229 @@ impl TransactionRepository {
         .bind(kind_str)
         .bind(idempotency_key)
         .bind(environment)
+        .bind(&fingerprint)
+        .bind(details.description.as_deref())
+        .bind(details.reference.as_deref())
+        .bind(Json(&details.metadata))
         .fetch_one(executor)
         .await?;
 
-        Ok(Self::row_to_transaction(&row)?)
+        check_replay(&row, &fingerprint, idempotency_key)?;
+        Self::row_to_transaction(&row)
+    }
+
+    /// Same as `create_or_get_by_idempotency` for a transfer between accounts in different
+    /// currencies. Replays return the original intent with the rate it was created at.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_or_get_cross_currency_by_idempotency(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        organization_id: Uuid,
+        from_account_id: Uuid,
+        to_account_id: Uuid,
+        conversion: &FxConversion,
+        idempotency_key: &str,
+        environment: Option<&str>,
+        details: &TransactionDetails,
+    ) -> Result<Transaction, AppError> {
+        let fingerprint = request_fingerprint(
+            "transfer",
+            from_account_id,
+            to_account_id,
+            conversion.source_amount,
+            conversion.source_currency.code(),
+            conversion.quote_id,
+            details,
+        );
+
+        let row = sqlx::query(
+            r#"
+            WITH existing AS (
+                SELECT id, organization_id, from_account_id, to_account_id, amount, currency,
+                       transaction_kind, status, failure_reason, idempotency_key, environment,
+                       destination_amount, destination_currency, fx_rate, fx_quote_id,
+                       counterparty_organization_id, interorg_agreement_id, nacha_file_id, nacha_trace_number,
+                       description, reference, metadata, created_at, updated_at, request_fingerprint, true AS replayed
+                FROM transactions
+                WHERE organization_id = $1
+                  AND COALESCE(environment, '') = COALESCE($7, '')
+                  AND idempotency_key = $6
+                LIMIT 1
+            ),
+            inserted AS (
+                INSERT INTO transactions (
+                    organization_id, from_account_id, to_account_id, amount, currency,
+                    transaction_kind, status, failure_reason, idempotency_key, environment,
+                    destination_amount, destination_currency, fx_rate, fx_quote_id, request_fingerprint,
+                    description, reference, metadata
+                )
+                SELECT $1, $2, $3, $4, $5, 'transfer', 'pending', NULL, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15
+                WHERE NOT EXISTS (SELECT 1 FROM existing)
+                RETURNING id, organization_id, from_account_id, to_account_id, amount, currency,
+                          transaction_kind, status, failure_reason, idempotency_key, environment,
+                          destination_amount, destination_currency, fx_rate, fx_quote_id,
+                          counterparty_organization_id, interorg_agreement_id, nacha_file_id, nacha_trace_number,
+                          description, reference, metadata, created_at, updated_at, request_fingerprint, false AS replayed
+            )
+            SELECT * FROM inserted
+            UNION ALL
+            SELECT * FROM existing
+            LIMIT 1
+            "#,
+        )
+        .bind(organization_id)
+        .bind(from_account_id)
+        .bind(to_account_id)
+        .bind(conversion.source_amount)
+        .bind(conversion.source_currency.code())
+        .bind(idempotency_key)
+        .bind(environment)
+        .bind(conversion.destination_amount)
+        .bind(conversion.destination_currency.code())
+        .bind(conversion.rate)
+        .bind(conversion.quote_id)
+        .bind(&fingerprint)
+        .bind(details.description.as_deref())
+        .bind(details.reference.as_deref())
+        .bind(Json(&details.metadata))
+        .fetch_one(executor)
+        .await?;
+
+        check_replay(&row, &fingerprint, idempotency_key)?;
+        Self::row_to_transaction(&row)
+    }
+
+    /// Same as `create_or_get_by_idempotency` for a transfer to another organization's account
+    /// under an inter-organization agreement. The intent belongs to the payer organization.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_or_get_interorg_by_idempotency(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        from_account_id: Uuid,
+        to_account_id: Uuid,
+        amount: i64,
+        currency: &str,
+        agreement: &InterorgAgreement,
+        idempotency_key: &str,
+        details: &TransactionDetails,
+    ) -> Result<Transaction, AppError> {
+        let fingerprint =
+            request_fingerprint("transfer", from_account_id, to_account_id, amount, currency, None, details);
+
+        let row = sqlx::query(
+            r#"
+            WITH existing AS (
+                SELECT id, organization_id, from_account_id, to_account_id, amount, currency,
+                       transaction_kind, status, failure_reason, idempotency_key, environment,
+                       destination_amount, destination_currency, fx_rate, fx_quote_id,
+                       counterparty_organization_id, interorg_agreement_id, nacha_file_id, nacha_trace_number,
+                       description, reference, metadata, created_at, updated_at, request_fingerprint, true AS replayed
+                FROM transactions
+                WHERE organization_id = $1
+                  AND COALESCE(environment, '') = COALESCE($7, '')
+                  AND idempotency_key = $6
+                LIMIT 1
+            ),
+            inserted AS (
+                INSERT INTO transactions (
+                    organization_id, from_account_id, to_account_id, amount, currency,
+                    transaction_kind, status, failure_reason, idempotency_key, environment,
+                    counterparty_organization_id, interorg_agreement_id, request_fingerprint,
+                    description, reference, metadata
+                )
+                SELECT $1, $2, $3, $4, $5, 'transfer', 'pending', NULL, $6, $7, $8, $9, $10, $11, $12, $13
+                WHERE NOT EXISTS (SELECT 1 FROM existing)
+                RETURNING id, organization_id, from_account_id, to_account_id, amount, currency,
+                          transaction_kind, status, failure_reason, idempotency_key, environment,
+                          destination_amount, destination_currency, fx_rate, fx_quote_id,
+                          counterparty_organization_id, interorg_agreement_id, nacha_file_id, nacha_trace_number,
+                          description, reference, metadata, created_at, updated_at, request_fingerprint, false AS replayed
+            )
+            SELECT * FROM inserted
+            UNION ALL
+            SELECT * FROM existing
+            LIMIT 1
+            "#,
+        )
+        .bind(agreement.organization_id)
+        .bind(from_account_id)
+        .bind(to_account_id)
+        .bind(amount)
+        .bind(currency)
+        .bind(idempotency_key)
+        .bind(&agreement.environment)
+        .bind(agreement.counterparty_organization_id)
+        .bind(agreement.id)
+        .bind(&fingerprint)
+        .bind(details.description.as_deref())
+        .bind(details.reference.as_deref())
+        .bind(Json(&details.metadata))
+        .fetch_one(executor)
+        .await?;
+
+        check_replay(&row, &fingerprint, idempotency_key)?;
+        Self::row_to_transaction(&row)
+    }
+
+    /// Whether an intent already exists for an idempotency key (i.e. a request is a replay).
+    pub async fn exists_by_idempotency(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        organization_id: Uuid,
+        environment: Option<&str>,
+        idempotency_key: &str,
+    ) -> Result<bool, AppError> {
+        let exists: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM transactions
+                WHERE organization_id = $1
+                  AND COALESCE(environment, '') = COALESCE($2, '')
+                  AND idempotency_key = $3
+            )
+            "#,
+        )
+        .bind(organization_id)
+        .bind(environment)
+        .bind(idempotency_key)
+        .fetch_one(executor)
+        .await?;
+
+        Ok(exists)
+    }
+
+    /// The intent created under an idempotency key, if any.
+    pub async fn find_by_idempotency_key(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        organization_id: Uuid,
+        environment: &str,
+        idempotency_key: &str,
+    ) -> Result<Option<Transaction>, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, organization_id, from_account_id, to_account_id, amount, currency,
+                   transaction_kind, status, failure_reason, idempotency_key, environment,
+                   destination_amount, destination_currency, fx_rate, fx_quote_id,
+                   counterparty_organization_id, interorg_agreement_id, nacha_file_id, nacha_trace_number,
+                   description, reference, metadata, created_at, updated_at
+            FROM transactions
+            WHERE organization_id = $1 AND environment = $2 AND idempotency_key = $3
+            "#,
+        )
+        .bind(organization_id)
+        .bind(environment)
+        .bind(idempotency_key)
+        .fetch_optional(executor)
+        .await?;
+
+        row.as_ref().map(Self::row_to_transaction).transpose()
     }
 
     pub async fn find_by_id(pool: &PgPool, id: Uuid) -> Result<Transaction, AppError> {
         let row = sqlx::query(
             r#"
             SELECT id, organization_id, from_account_id, to_account_id, amount, currency,
-                   transaction_kind, status, failure_reason, idempotency_key, environment, created_at, updated_at
+                   transaction_kind, status, failure_reason, idempotency_key, environment,
+                   destination_amount, destination_currency, fx_rate, fx_quote_id,
+                   counterparty_organization_id, interorg_agreement_id, nacha_file_id, nacha_trace_number,
+                   description, reference, metadata, created_at, updated_at
             FROM transactions
             WHERE id = $1
             "#,
@@ -87,48 +316,70 @@ impl TransactionRepository {
         Ok(Self::row_to_transaction(&row)?)
     }
 
+    /// Transaction visible to an organization: its own, or an inter-organization transfer it
+    /// received. Others are reported as not found.
+    pub async fn find_by_id_for_organization(
+        pool: &PgPool,
+        id: Uuid,
+        organization_id: Uuid,
+    ) -> Result<Transaction, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, organization_id, from_account_id, to_account_id, amount, currency,
+                   transaction_kind, status, failure_reason, idempotency_key, environment,
+                   destination_amount, destination_currency, fx_rate, fx_quote_id,
+                   counterparty_organization_id, interorg_agreement_id, nacha_file_id, nacha_trace_number,
+                   description, reference, metadata, created_at, updated_at
+            FROM transactions
+            WHERE id = $1 AND (organization_id = $2 OR counterparty_organization_id = $2)
+            "#,
+        )
+        .bind(id)
+        .bind(organization_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Transaction with id {} not found", id)))?;
+
+        Self::row_to_transaction(&row)
+    }
+
     pub async fn find_by_account_id(
         pool: &PgPool,
         account_id: Uuid,
         limit: Option<i64>,
         environment: Option<&str>,
+        filter: &TransactionFilter,
     ) -> Result<Vec<Transaction>, AppError> {
         let limit = limit.unwrap_or(100);
 
+        let mut query = QueryBuilder::<Postgres>::new(
+            r#"
+            SELECT id, organization_id, from_account_id, to_account_id, amount, currency,
+                   transaction_kind, status, failure_reason, idempotency_key, environment,
+                   destination_amount, destination_currency, fx_rate, fx_quote_id,
+                   counterparty_organization_id, interorg_agreement_id, nacha_file_id, nacha_trace_number,
+                   description, reference, metadata, created_at, updated_at
+            FROM transactions
+            WHERE (from_account_id = "#,
+        );
+        query.push_bind(account_id);
+        query.push(" OR to_account_id = ");
+        query.push_bind(account_id);
+        query.push(")");
+
         // Optionally filter by environment, but include legacy transactions (NULL environment)
-        let rows = if let Some(env) = environment {
-            sqlx::query(
-                r#"
-                SELECT id, organization_id, from_account_id, to_account_id, amount, currency,
-                       transaction_kind, status, failure_reason, idempotency_key, environment, created_at, updated_at
-                FROM transactions
-                WHERE (from_account_id = $1 OR to_account_id = $1)
-                  AND (environment = $3 OR environment IS NULL)
-                ORDER BY created_at DESC
-                LIMIT $2
-                "#,
-            )
-            .bind(account_id)
-            .bind(limit)
-            .bind(env)
-            .fetch_all(pool)
-            .await?
-        } else {
-            sqlx::query(
-                r#"
-                SELECT id, organization_id, from_account_id, to_account_id, amount, currency,
-                       transaction_kind, status, failure_reason, idempotency_key, environment, created_at, updated_at
-                FROM transactions
-                WHERE from_account_id = $1 OR to_account_id = $1
-                ORDER BY created_at DESC
-                LIMIT $2
-                "#,
-            )
-            .bind(account_id)
-            .bind(limit)
-            .fetch_all(pool)
-            .await?
-        };
+        if let Some(env) = environment {
+            query.push(" AND (environment = ");
+            query.push_bind(env);
+            query.push(" OR environment IS NULL)");
+        }
+
+        Self::push_filter(&mut query, filter);
+        Self::push_order(&mut query, filter.order);
+        query.push(" LIMIT ");
+        query.push_bind(limit);
+
+        let rows = query.build().fetch_all(pool).await?;
 
         let transactions = rows
             .iter()
@@ -138,65 +389,309 @@ impl TransactionRepository {
         Ok(transactions)
     }
 
+    /// Page requests count and use OFFSET; cursor requests seek on `(created_at, id)` in the
+    /// filter's order and skip the count.
     pub async fn find_by_organization_id_paginated(
         pool: &PgPool,
         organization_id: Uuid,
         environment: &str,
-        page: u32,
-        per_page: u32,
+        filter: &TransactionFilter,
+        page: &PageRequest,
     ) -> Result<(Vec<Transaction>, PaginationMeta), AppError> {
-        let offset = (page - 1) * per_page;
+        let per_page = page.per_page();
+        let descending = filter.order == SortOrder::Desc;
 
-        // Get total count (filtered by environment, but include legacy transactions with NULL environment)
-        let count_row = sqlx::query(
+        // Fetch paginated results with deterministic ordering
+        let mut query = QueryBuilder::<Postgres>::new(
             r#"
-            SELECT COUNT(*) as count 
+            SELECT id, organization_id, from_account_id, to_account_id, amount, currency,
+                   transaction_kind, status, failure_reason, idempotency_key, environment,
+                   destination_amount, destination_currency, fx_rate, fx_quote_id,
+                   counterparty_organization_id, interorg_agreement_id, nacha_file_id, nacha_trace_number,
+                   description, reference, metadata, created_at, updated_at
+            FROM transactions"#,
+        );
+        Self::push_organization_scope(&mut query, organization_id, environment);
+        Self::push_filter(&mut query, filter);
+
+        let total_count = match page {
+            PageRequest::Page { page, .. } => {
+                // Get total count (filtered by environment, but include legacy transactions with NULL environment)
+                let mut count_query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) as count FROM transactions");
+                Self::push_organization_scope(&mut count_query, organization_id, environment);
+                Self::push_filter(&mut count_query, filter);
+
+                let count_row = count_query.build().fetch_one(pool).await?;
+
+                Self::push_order(&mut query, filter.order);
+                query.push(" LIMIT ");
+                query.push_bind(per_page as i64);
+                query.push(" OFFSET ");
+                query.push_bind(((page - 1) * per_page) as i64);
+                count_row.get("count")
+            }
+            PageRequest::Cursor { cursor, .. } => {
+                query.push(" AND (created_at, id) ");
+                query.push(cursor.comparison(descending));
+                query.push(" (");
+                query.push_bind(cursor.created_at);
+                query.push(", ");
+                query.push_bind(cursor.id);
+                query.push(")");
+                Self::push_order(
+                    &mut query,
+                    if cursor.scan_descending(descending) { SortOrder::Desc } else { SortOrder::Asc },
+                );
+                query.push(" LIMIT ");
+                query.push_bind(per_page as i64 + 1);
+                0
+            }
+        };
+
+        let rows = query.build().fetch_all(pool).await?;
+
+        let transactions = rows
+            .iter()
+            .map(|row| Self::row_to_transaction(row))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let key = |transaction: &Transaction| (transaction.created_at, transaction.id);
+        Ok(match page {
+            PageRequest::Page { page, .. } => {
+                let pagination = PaginationMeta::page(&transactions, *page, per_page, total_count, key);
+                (transactions, pagination)
+            }
+            PageRequest::Cursor { cursor, .. } => {
+                let (transactions, next_cursor, prev_cursor) = keyset_page(transactions, cursor, per_page, key);
+                (transactions, PaginationMeta::cursor(per_page, next_cursor, prev_cursor))
+            }
+        })
+    }
+
+    /// Transactions of the organization, either as payer or as inter-org counterparty.
+    fn push_organization_scope(query: &mut QueryBuilder<'_, Postgres>, organization_id: Uuid, environment: &str) {
+        query.push(" WHERE (organization_id = ");
+        query.push_bind(organization_id);
+        query.push(" OR counterparty_organization_id = ");
+        query.push_bind(organization_id);
+        query.push(") AND (environment = ");
+        query.push_bind(environment.to_string());
+        query.push(" OR environment IS NULL)");
+    }
+
+    fn push_filter(query: &mut QueryBuilder<'_, Postgres>, filter: &TransactionFilter) {
+        if let Some(from) = filter.from {
+            query.push(" AND created_at >= ");
+            query.push_bind(from);
+        }
+        if let Some(to) = filter.to {
+            query.push(" AND created_at < ");
+            query.push_bind(to);
+        }
+        if let Some(status) = filter.status {
+            query.push(" AND status = ");
+            query.push_bind(match status {
+                TransactionStatus::Pending => "pending",
+                TransactionStatus::Posted => "posted",
+                TransactionStatus::Failed => "failed",
+            });
+        }
+        if let Some(kind) = filter.kind {
+            query.push(" AND transaction_kind = ");
+            query.push_bind(match kind {
+                TransactionKind::Deposit => "deposit",
+                TransactionKind::Withdraw => "withdraw",
+                TransactionKind::Transfer => "transfer",
+                TransactionKind::Interest => "interest",
+            });
+        }
+        if let Some(min_amount) = filter.min_amount {
+            query.push(" AND amount >= ");
+            query.push_bind(min_amount);
+        }
+        if let Some(max_amount) = filter.max_amount {
+            query.push(" AND amount <= ");
+            query.push_bind(max_amount);
+        }
+        if let Some(counterparty_account_id) = filter.counterparty_account_id {
+            query.push(" AND (from_account_id = ");
+            query.push_bind(counterparty_account_id);
+            query.push(" OR to_account_id = ");
+            query.push_bind(counterparty_account_id);
+            query.push(")");
+        }
+        if let Some(prefix) = &filter.idempotency_key_prefix {
+            // Prefix match; LIKE wildcards in the prefix are matched literally
+            let pattern = format!(
+                "{}%",
+                prefix.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+            );
+            query.push(" AND idempotency_key LIKE ");
+            query.push_bind(pattern);
+            query.push(" ESCAPE '\\'");
+        }
+        if let Some(reference) = &filter.reference {
+            query.push(" AND reference = ");
+            query.push_bind(reference.clone());
+        }
+        if !filter.metadata.is_empty() {
+            query.push(" AND metadata @> ");
+            query.push_bind(Json(filter.metadata.clone()));
+        }
+    }
+
+    fn push_order(query: &mut QueryBuilder<'_, Postgres>, order: SortOrder) {
+        query.push(match order {
+            SortOrder::Asc => " ORDER BY created_at ASC, id ASC",
+            SortOrder::Desc => " ORDER BY created_at DESC, id DESC",
+        });
+    }
+
+    /// Balance of an account (minor units) from posted intents created before `cutoff`.
+    /// Used for end-of-day balances, which the Ledger cannot provide retroactively.
+    pub async fn balance_as_of(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        account_id: Uuid,
+        cutoff: DateTime<Utc>,
+    ) -> Result<i64, AppError> {
+        let balance: Option<i64> = sqlx::query_scalar(
+            r#"
+            SELECT ((
+                SELECT COALESCE(SUM(
+                    CASE
+                        WHEN transaction_kind IN ('deposit', 'interest') THEN amount
+                        WHEN transaction_kind = 'withdraw' THEN -amount
+                        WHEN transaction_kind = 'transfer' AND to_account_id = $1 THEN COALESCE(destination_amount, amount)
+                        WHEN transaction_kind = 'transfer' AND from_account_id = $1 THEN -amount
+                        ELSE 0
+                    END
+                ), 0)
+                FROM transactions
+                WHERE (from_account_id = $1 OR to_account_id = $1)
+                  AND status = 'posted'
+                  AND created_at < $2
+            ) + (
+                -- Fee legs: debit the payer, credit the revenue account
+                SELECT COALESCE(SUM(CASE WHEN revenue_account_id = $1 THEN amount ELSE -amount END), 0)
+                FROM transaction_fees
+                WHERE (payer_account_id = $1 OR revenue_account_id = $1)
+                  AND status = 'posted'
+                  AND created_at < $2
+            ))::BIGINT
+            "#,
+        )
+        .bind(account_id)
+        .bind(cutoff)
+        .fetch_one(executor)
+        .await?;
+
+        Ok(balance.unwrap_or(0))
+    }
+
+    /// Posted movements of an account booked in `[from, to)`, oldest first. Uses the same
+    /// rules as `balance_as_of`, so opening balance + movements = closing balance.
+    pub async fn posted_movements(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        account_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<AccountMovement>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, id AS transaction_id, transaction_kind AS kind,
+                   CASE
+                       WHEN transaction_kind IN ('deposit', 'interest') THEN amount
+                       WHEN transaction_kind = 'withdraw' THEN -amount
+                       WHEN to_account_id = $1 THEN COALESCE(destination_amount, amount)
+                       ELSE -amount
+                   END AS amount,
+                   CASE
+                       WHEN transaction_kind = 'transfer' AND to_account_id = $1
+                           THEN COALESCE(destination_currency, currency)
+                       ELSE currency
+                   END AS currency,
+                   idempotency_key AS reference,
+                   CASE
+                       WHEN transaction_kind <> 'transfer' THEN NULL
+                       WHEN to_account_id = $1 THEN from_account_id
+                       ELSE to_account_id
+                   END AS counterparty_account_id,
+                   created_at AS booked_at
             FROM transactions
-            WHERE organization_id = $1 
-              AND (environment = $2 OR environment IS NULL)
-            "#
+            WHERE (from_account_id = $1 OR to_account_id = $1)
+              AND status = 'posted'
+              AND created_at >= $2
+              AND created_at < $3
+            UNION ALL
+            SELECT id, transaction_id, 'fee' AS kind,
+                   CASE WHEN revenue_account_id = $1 THEN amount ELSE -amount END AS amount,
+                   currency,
+                   idempotency_key AS reference,
+                   CASE WHEN revenue_account_id = $1 THEN payer_account_id ELSE revenue_account_id END
+                       AS counterparty_account_id,
+                   created_at AS booked_at
+            FROM transaction_fees
+            WHERE (payer_account_id = $1 OR revenue_account_id = $1)
+              AND status = 'posted'
+              AND created_at >= $2
+              AND created_at < $3
+            ORDER BY booked_at, id
+            "#,
         )
-        .bind(organization_id)
-        .bind(environment)
-        .fetch_one(pool)
+        .bind(account_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(executor)
         .await?;
 
-        let total_count: i64 = count_row.get("count");
-        let total_pages = ((total_count as f64) / (per_page as f64)).ceil() as u32;
+        rows.iter()
+            .map(|row| {
+                let kind_str: String = row.get("kind");
+                let kind = match kind_str.as_str() {
+                    "deposit" => MovementKind::Deposit,
+                    "withdraw" => MovementKind::Withdraw,
+                    "transfer" => MovementKind::Transfer,
+                    "interest" => MovementKind::Interest,
+                    "fee" => MovementKind::Fee,
+                    _ => return Err(AppError::Internal("Invalid movement kind".to_string())),
+                };
 
-        // Fetch paginated results with deterministic ordering
+                Ok(AccountMovement {
+                    id: row.get("id"),
+                    transaction_id: row.get("transaction_id"),
+                    kind,
+                    amount: row.get("amount"),
+                    currency: row.get("currency"),
+                    reference: row.get("reference"),
+                    counterparty_account_id: row.get("counterparty_account_id"),
+                    booked_at: row.get("booked_at"),
+                })
+            })
+            .collect()
+    }
+
+    /// Pending intents debiting or crediting the account, oldest first.
+    pub async fn find_pending_by_account(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        account_id: Uuid,
+    ) -> Result<Vec<Transaction>, AppError> {
         let rows = sqlx::query(
             r#"
             SELECT id, organization_id, from_account_id, to_account_id, amount, currency,
-                   transaction_kind, status, failure_reason, idempotency_key, environment, created_at, updated_at
+                   transaction_kind, status, failure_reason, idempotency_key, environment,
+                   destination_amount, destination_currency, fx_rate, fx_quote_id,
+                   counterparty_organization_id, interorg_agreement_id, nacha_file_id, nacha_trace_number,
+                   description, reference, metadata, created_at, updated_at
             FROM transactions
-            WHERE organization_id = $1 
-              AND (environment = $2 OR environment IS NULL)
-            ORDER BY created_at DESC, id DESC
-            LIMIT $3 OFFSET $4
-            "#
+            WHERE (from_account_id = $1 OR to_account_id = $1) AND status = 'pending'
+            ORDER BY created_at ASC
+            "#,
         )
-        .bind(organization_id)
-        .bind(environment)
-        .bind(per_page as i64)
-        .bind(offset as i64)
-        .fetch_all(pool)
+        .bind(account_id)
+        .fetch_all(executor)
         .await?;
 
-        let transactions = rows
-            .iter()
-            .map(|row| Self::row_to_transaction(row))
-            .collect::<Result<Vec<_>, _>>()?;
-
-        Ok((
-            transactions,
-            PaginationMeta {
-                page,
-                per_page,
-                total_count,
-                total_pages,
-            },
-        ))
+        rows.iter().map(Self::row_to_transaction).collect()
     }
 
     /// Find pending transactions across all organizations older than a cutoff.
@@ -216,7 +711,10 @@ impl TransactionRepository {
             sqlx::query(
                 r#"
                 SELECT id, organization_id, from_account_id, to_account_id, amount, currency,
-                       transaction_kind, status, failure_reason, idempotency_key, environment, created_at, updated_at
+                       transaction_kind, status, failure_reason, idempotency_key, environment,
+                       destination_amount, destination_currency, fx_rate, fx_quote_id,
+                       counterparty_organization_id, interorg_agreement_id, nacha_file_id, nacha_trace_number,
+                       description, reference, metadata, created_at, updated_at
                 FROM transactions
                 WHERE status = 'pending' 
                   AND created_at < $1
@@ -234,7 +732,10 @@ impl TransactionRepository {
             sqlx::query(
                 r#"
                 SELECT id, organization_id, from_account_id, to_account_id, amount, currency,
-                       transaction_kind, status, failure_reason, idempotency_key, environment, created_at, updated_at
+                       transaction_kind, status, failure_reason, idempotency_key, environment,
+                       destination_amount, destination_currency, fx_rate, fx_quote_id,
+                       counterparty_organization_id, interorg_agreement_id, nacha_file_id, nacha_trace_number,
+                       description, reference, metadata, created_at, updated_at
                 FROM transactions
                 WHERE status = 'pending' AND created_at < $1
                 ORDER BY created_at ASC
@@ -253,6 +754,57 @@ impl TransactionRepository {
             .collect::<Result<Vec<_>, _>>()?)
     }
 
+    /// Record the NACHA file and trace number a withdrawal was sent to the bank in.
+    pub async fn mark_nacha_entry(
+        executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+        id: Uuid,
+        nacha_file_id: Uuid,
+        nacha_trace_number: &str,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE transactions
+            SET nacha_file_id = $2, nacha_trace_number = $3, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(nacha_file_id)
+        .bind(nacha_trace_number)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Transaction sent with a NACHA trace number. Trace sequences eventually wrap, so the
+    /// most recent match wins.
+    pub async fn find_by_nacha_trace_number(
+        pool: &PgPool,
+        environment: &str,
+        nacha_trace_number: &str,
+    ) -> Result<Option<Transaction>, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, organization_id, from_account_id, to_account_id, amount, currency,
+                   transaction_kind, status, failure_reason, idempotency_key, environment,
+                   destination_amount, destination_currency, fx_rate, fx_quote_id,
+                   counterparty_organization_id, interorg_agreement_id, nacha_file_id, nacha_trace_number,
+                   description, reference, metadata, created_at, updated_at
+            FROM transactions
+            WHERE nacha_trace_number = $1 AND environment = $2
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(nacha_trace_number)
+        .bind(environment)
+        .fetch_optional(pool)
+        .await?;
+
+        row.as_ref().map(Self::row_to_transaction).transpose()
+    }
+
     pub async fn update_status(
         executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
         id: Uuid,
@@ -271,7 +823,10 @@ impl TransactionRepository {
             SET status = $2, failure_reason = $3, updated_at = NOW()
             WHERE id = $1
             RETURNING id, organization_id, from_account_id, to_account_id, amount, currency,
-                      transaction_kind, status, failure_reason, idempotency_key, environment, created_at, updated_at
+                      transaction_kind, status, failure_reason, idempotency_key, environment,
+                      destination_amount, destination_currency, fx_rate, fx_quote_id,
+                      counterparty_organization_id, interorg_agreement_id, nacha_file_id, nacha_trace_number,
+                      description, reference, metadata, created_at, updated_at
             "#,
         )
         .bind(id)
@@ -289,6 +844,7 @@ impl TransactionRepository {
             "deposit" => TransactionKind::Deposit,
             "withdraw" => TransactionKind::Withdraw,
             "transfer" => TransactionKind::Transfer,
+            "interest" => TransactionKind::Interest,
             _ => return Err(AppError::Internal("Invalid transaction kind".to_string())),
         };
 
@@ -312,8 +868,152 @@ impl TransactionRepository {
             failure_reason: row.get("failure_reason"),
             idempotency_key: row.get("idempotency_key"),
             environment: row.get("environment"),
+            destination_amount: row.get("destination_amount"),
+            destination_currency: row.get("destination_currency"),
+            fx_rate: row.get("fx_rate"),
+            fx_quote_id: row.get("fx_quote_id"),
+            counterparty_organization_id: row.get("counterparty_organization_id"),
+            interorg_agreement_id: row.get("interorg_agreement_id"),
+            nacha_file_id: row.get("nacha_file_id"),
+            nacha_trace_number: row.get("nacha_trace_number"),
+            description: row.get("description"),
+            reference: row.get("reference"),
+            metadata: row.get::<Json<_>, _>("metadata").0,
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
+            replayed: row.try_get("replayed").unwrap_or(false),
         })
     }
 }
+
+/// SHA-256 (hex) of the canonical form of an intent request. It is stored with the
+/// idempotency key; the migration backfilling existing intents computes the same string.
+/// Client details are appended (as JSON) only when given, so earlier fingerprints still match.
+fn request_fingerprint(
+    kind: &str,
+    from_account_id: Uuid,
+    to_account_id: Uuid,
+    amount: i64,
+    currency: &str,
+    fx_quote_id: Option<Uuid>,
+    details: &TransactionDetails,
+) -> String {
+    let mut canonical = format!(
+        "{}|{}|{}|{}|{}|{}",
+        kind,
+        from_account_id,
+        to_account_id,
+        amount,
+        currency,
+        fx_quote_id.map(|id| id.to_string()).unwrap_or_default()
+    );
+    if !details.is_empty() {
+        canonical.push('|');
+        canonical.push_str(
+            &serde_json::json!([details.description, details.reference, details.metadata]).to_string(),
+        );
+    }
+    hex::encode(Sha256::digest(canonical.as_bytes()))
+}
+
+/// A replayed key must come with the request it was first used for.
+fn check_replay(row: &sqlx::postgres::PgRow, fingerprint: &str, idempotency_key: &str) -> Result<(), AppError> {
+    let stored: Option<String> = row.get("request_fingerprint");
+    if stored.is_some_and(|stored| stored != fingerprint) {
+        return Err(AppError::IdempotencyConflict(format!(
+            "Idempotency-Key '{}' was already used for a different request",
+            idempotency_key
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Account, AccountType};
+    use crate::testing;
+
+    async fn intent(
+        pool: &PgPool,
+        from: &Account,
+        to: &Account,
+        amount: i64,
+        details: &TransactionDetails,
+    ) -> Result<Transaction, AppError> {
+        TransactionRepository::create_or_get_by_idempotency(
+            pool,
+            from.organization_id.unwrap(),
+            from.id,
+            to.id,
+            amount,
+            "USD",
+            TransactionKind::Transfer,
+            "fingerprint-key",
+            Some(testing::ENVIRONMENT),
+            details,
+        )
+        .await
+    }
+
+    #[test]
+    fn details_only_change_the_fingerprint_when_given() {
+        let (from, to) = (Uuid::new_v4(), Uuid::new_v4());
+        let plain = request_fingerprint("transfer", from, to, 500, "USD", None, &TransactionDetails::default());
+        let canonical = format!("transfer|{}|{}|500|USD|", from, to);
+        assert_eq!(plain, hex::encode(Sha256::digest(canonical.as_bytes())));
+
+        let described = TransactionDetails { description: Some("rent".to_string()), ..Default::default() };
+        assert_ne!(request_fingerprint("transfer", from, to, 500, "USD", None, &described), plain);
+    }
+
+    #[sqlx::test(migrations = "./migrations_accounts")]
+    #[ignore = "needs Postgres: DATABASE_URL=... cargo test -- --ignored"]
+    async fn fingerprints_match_the_migration_backfill(pool: PgPool) {
+        let organization_id = Uuid::new_v4();
+        let from = testing::account(&pool, organization_id, AccountType::Checking, "USD").await;
+        let to = testing::account(&pool, organization_id, AccountType::Checking, "USD").await;
+        let transaction = intent(&pool, &from, &to, 500, &TransactionDetails::default()).await.unwrap();
+
+        // The expression 20261018000014_idempotency_request_fingerprint.sql backfills with
+        let (stored, backfilled): (String, String) = sqlx::query_as(
+            r#"
+            SELECT request_fingerprint,
+                   encode(
+                       sha256(convert_to(
+                           transaction_kind || '|' || from_account_id::text || '|' || to_account_id::text || '|'
+                               || amount::text || '|' || currency || '|' || COALESCE(fx_quote_id::text, ''),
+                           'UTF8'
+                       )),
+                       'hex'
+                   )
+            FROM transactions
+            WHERE id = $1
+            "#,
+        )
+        .bind(transaction.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(stored, backfilled);
+    }
+
+    #[sqlx::test(migrations = "./migrations_accounts")]
+    #[ignore = "needs Postgres: DATABASE_URL=... cargo test -- --ignored"]
+    async fn replays_must_match_the_original_request(pool: PgPool) {
+        let organization_id = Uuid::new_v4();
+        let from = testing::account(&pool, organization_id, AccountType::Checking, "USD").await;
+        let to = testing::account(&pool, organization_id, AccountType::Checking, "USD").await;
+        let details = TransactionDetails::default();
+
+        let original = intent(&pool, &from, &to, 500, &details).await.unwrap();
+        let replay = intent(&pool, &from, &to, 500, &details).await.unwrap();
+        assert_eq!((replay.id, replay.replayed), (original.id, true));
+
+        let different_amount = intent(&pool, &from, &to, 600, &details).await;
+        assert!(matches!(different_amount, Err(AppError::IdempotencyConflict(_))));
+
+        let described = TransactionDetails { description: Some("rent".to_string()), ..Default::default() };
+        assert!(matches!(intent(&pool, &from, &to, 500, &described).await, Err(AppError::IdempotencyConflict(_))));
+    }
+}
@@ -0,0 +1,423 @@
+use crate::errors::AppError;
+use crate::models::{
+    WebhookDelivery, WebhookDeliveryAttempt, WebhookDeliveryStatus, WebhookEndpoint, WebhookEndpointStatus,
+};
+use crate::webhook::AttemptOutcome;
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+pub struct WebhookRepository;
+
+/// A delivery claimed for sending, with everything needed to build the request.
+pub struct DueDelivery {
+    pub id: Uuid,
+    pub attempt_count: i32,
+    pub url: String,
+    pub secret: String,
+    pub endpoint_active: bool,
+    pub event_id: Uuid,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub event_created_at: DateTime<Utc>,
+}
+
+impl WebhookRepository {
+    pub async fn create_endpoint(
+        pool: &PgPool,
+        organization_id: Uuid,
+        environment: &str,
+        url: &str,
+        description: Option<&str>,
+        secret: &str,
+        event_types: &[String],
+    ) -> Result<WebhookEndpoint, AppError> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO webhook_endpoints (organization_id, environment, url, description, secret, event_types)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, organization_id, environment, url, description, event_types, status,
+                      created_at, updated_at
+            "#,
+        )
+        .bind(organization_id)
+        .bind(environment)
+        .bind(url)
+        .bind(description)
+        .bind(secret)
+        .bind(event_types)
+        .fetch_one(pool)
+        .await?;
+
+        Self::row_to_endpoint(&row)
+    }
+
+    pub async fn find_endpoints(
+        pool: &PgPool,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<Vec<WebhookEndpoint>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, organization_id, environment, url, description, event_types, status,
+                   created_at, updated_at
+            FROM webhook_endpoints
+            WHERE organization_id = $1 AND environment = $2
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(organization_id)
+        .bind(environment)
+        .fetch_all(pool)
+        .await?;
+
+        rows.iter().map(Self::row_to_endpoint).collect()
+    }
+
+    pub async fn find_endpoint(
+        pool: &PgPool,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<WebhookEndpoint, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, organization_id, environment, url, description, event_types, status,
+                   created_at, updated_at
+            FROM webhook_endpoints
+            WHERE id = $1 AND environment = $2 AND organization_id = $3
+            "#,
+        )
+        .bind(id)
+        .bind(environment)
+        .bind(organization_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Webhook endpoint {} not found", id)))?;
+
+        Self::row_to_endpoint(&row)
+    }
+
+    /// Disable an active endpoint; its pending deliveries fail on their next attempt.
+    pub async fn disable_endpoint(
+        pool: &PgPool,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<WebhookEndpoint, AppError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE webhook_endpoints
+            SET status = 'disabled', updated_at = NOW()
+            WHERE id = $1 AND environment = $2 AND organization_id = $3 AND status = 'active'
+            RETURNING id, organization_id, environment, url, description, event_types, status,
+                      created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(environment)
+        .bind(organization_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Active webhook endpoint {} not found", id)))?;
+
+        Self::row_to_endpoint(&row)
+    }
+
+    /// Create deliveries for up to `limit` recorded events, one per active endpoint of the
+    /// event's organization and environment that subscribes to it and existed when it happened.
+    /// Returns the number of deliveries created.
+    pub async fn fan_out_events(pool: &PgPool, limit: i64) -> Result<u64, AppError> {
+        let result = sqlx::query(
+            r#"
+            WITH events AS (
+                UPDATE webhook_events
+                SET fanned_out_at = NOW()
+                WHERE id IN (
+                    SELECT id FROM webhook_events
+                    WHERE fanned_out_at IS NULL
+                    ORDER BY created_at
+                    LIMIT $1
+                    FOR UPDATE SKIP LOCKED
+                )
+                RETURNING id, organization_id, environment, event_type, created_at
+            )
+            INSERT INTO webhook_deliveries (endpoint_id, event_id, next_attempt_at)
+            SELECT e.id, ev.id, NOW()
+            FROM events ev
+            JOIN webhook_endpoints e
+              ON e.organization_id = ev.organization_id
+             AND e.environment = ev.environment
+             AND e.status = 'active'
+             AND e.created_at <= ev.created_at
+             AND (cardinality(e.event_types) = 0 OR ev.event_type = ANY(e.event_types))
+            ON CONFLICT (endpoint_id, event_id) DO NOTHING
+            "#,
+        )
+        .bind(limit)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Claim up to `limit` due deliveries by pushing their next attempt `lease_secs` into the
+    /// future, so concurrent workers do not send the same delivery twice.
+    pub async fn claim_due_deliveries(pool: &PgPool, limit: i64, lease_secs: i64) -> Result<Vec<DueDelivery>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            UPDATE webhook_deliveries d
+            SET next_attempt_at = NOW() + make_interval(secs => $2), updated_at = NOW()
+            FROM webhook_endpoints e, webhook_events ev
+            WHERE d.id IN (
+                SELECT id FROM webhook_deliveries
+                WHERE status = 'pending' AND next_attempt_at <= NOW()
+                ORDER BY next_attempt_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+              AND e.id = d.endpoint_id
+              AND ev.id = d.event_id
+            RETURNING d.id, d.attempt_count, e.url, e.secret, e.status AS endpoint_status,
+                      ev.id AS event_id, ev.event_type, ev.payload, ev.created_at AS event_created_at
+            "#,
+        )
+        .bind(limit)
+        .bind(lease_secs as f64)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.iter().map(Self::row_to_due_delivery).collect())
+    }
+
+    /// A delivery of the environment, for manual redelivery (any status).
+    pub async fn find_for_delivery(
+        pool: &PgPool,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<DueDelivery, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT d.id, d.attempt_count, e.url, e.secret, e.status AS endpoint_status,
+                   ev.id AS event_id, ev.event_type, ev.payload, ev.created_at AS event_created_at
+            FROM webhook_deliveries d
+            JOIN webhook_endpoints e ON e.id = d.endpoint_id
+            JOIN webhook_events ev ON ev.id = d.event_id
+            WHERE d.id = $1 AND e.environment = $2 AND e.organization_id = $3
+            "#,
+        )
+        .bind(id)
+        .bind(environment)
+        .bind(organization_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Webhook delivery {} not found", id)))?;
+
+        Ok(Self::row_to_due_delivery(&row))
+    }
+
+    /// Log an attempt and move the delivery to its next state (`next_attempt_at` only for pending).
+    pub async fn record_attempt(
+        pool: &PgPool,
+        delivery_id: Uuid,
+        attempt_number: i32,
+        outcome: &AttemptOutcome,
+        status: WebhookDeliveryStatus,
+        next_attempt_at: DateTime<Utc>,
+    ) -> Result<WebhookDelivery, AppError> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO webhook_delivery_attempts (delivery_id, attempt_number, response_status, error, duration_ms)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(delivery_id)
+        .bind(attempt_number)
+        .bind(outcome.response_status.map(i32::from))
+        .bind(outcome.error.as_deref())
+        .bind(outcome.duration_ms)
+        .execute(&mut *tx)
+        .await?;
+
+        let status_str = match status {
+            WebhookDeliveryStatus::Pending => "pending",
+            WebhookDeliveryStatus::Succeeded => "succeeded",
+            WebhookDeliveryStatus::Failed => "failed",
+        };
+
+        let row = sqlx::query(
+            r#"
+            WITH updated AS (
+                UPDATE webhook_deliveries
+                SET status = $2,
+                    attempt_count = $3,
+                    next_attempt_at = $4,
+                    last_attempt_at = NOW(),
+                    last_response_status = $5,
+                    last_error = $6,
+                    updated_at = NOW()
+                WHERE id = $1
+                RETURNING id, endpoint_id, event_id, status, attempt_count, next_attempt_at, last_attempt_at,
+                          last_response_status, last_error, created_at
+            )
+            SELECT u.*, ev.event_type
+            FROM updated u
+            JOIN webhook_events ev ON ev.id = u.event_id
+            "#,
+        )
+        .bind(delivery_id)
+        .bind(status_str)
+        .bind(attempt_number)
+        .bind(next_attempt_at)
+        .bind(outcome.response_status.map(i32::from))
+        .bind(outcome.error.as_deref())
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Self::row_to_delivery(&row)
+    }
+
+    /// Most recent deliveries of an endpoint.
+    pub async fn find_deliveries_by_endpoint(
+        pool: &PgPool,
+        endpoint_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT d.id, d.endpoint_id, d.event_id, d.status, d.attempt_count, d.next_attempt_at,
+                   d.last_attempt_at, d.last_response_status, d.last_error, d.created_at, ev.event_type
+            FROM webhook_deliveries d
+            JOIN webhook_events ev ON ev.id = d.event_id
+            WHERE d.endpoint_id = $1
+            ORDER BY d.created_at DESC, d.id DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(endpoint_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        rows.iter().map(Self::row_to_delivery).collect()
+    }
+
+    /// A delivery of the environment with its event payload.
+    pub async fn find_delivery(
+        pool: &PgPool,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<(WebhookDelivery, serde_json::Value), AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT d.id, d.endpoint_id, d.event_id, d.status, d.attempt_count, d.next_attempt_at,
+                   d.last_attempt_at, d.last_response_status, d.last_error, d.created_at, ev.event_type,
+                   ev.payload
+            FROM webhook_deliveries d
+            JOIN webhook_endpoints e ON e.id = d.endpoint_id
+            JOIN webhook_events ev ON ev.id = d.event_id
+            WHERE d.id = $1 AND e.environment = $2 AND e.organization_id = $3
+            "#,
+        )
+        .bind(id)
+        .bind(environment)
+        .bind(organization_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Webhook delivery {} not found", id)))?;
+
+        Ok((Self::row_to_delivery(&row)?, row.get("payload")))
+    }
+
+    pub async fn find_attempts(pool: &PgPool, delivery_id: Uuid) -> Result<Vec<WebhookDeliveryAttempt>, AppError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT attempt_number, response_status, error, duration_ms, created_at
+            FROM webhook_delivery_attempts
+            WHERE delivery_id = $1
+            ORDER BY attempt_number, created_at
+            "#,
+        )
+        .bind(delivery_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| WebhookDeliveryAttempt {
+                attempt_number: row.get("attempt_number"),
+                response_status: row.get("response_status"),
+                error: row.get("error"),
+                duration_ms: row.get("duration_ms"),
+                created_at: row.get("created_at"),
+            })
+            .collect())
+    }
+
+    fn row_to_endpoint(row: &sqlx::postgres::PgRow) -> Result<WebhookEndpoint, AppError> {
+        let status_str: String = row.get("status");
+        let status = match status_str.as_str() {
+            "active" => WebhookEndpointStatus::Active,
+            "disabled" => WebhookEndpointStatus::Disabled,
+            other => return Err(AppError::Internal(format!("Invalid webhook endpoint status: {}", other))),
+        };
+
+        Ok(WebhookEndpoint {
+            id: row.get("id"),
+            organization_id: row.get("organization_id"),
+            environment: row.get("environment"),
+            url: row.get("url"),
+            description: row.get("description"),
+            event_types: row.get("event_types"),
+            status,
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+
+    fn row_to_delivery(row: &sqlx::postgres::PgRow) -> Result<WebhookDelivery, AppError> {
+        let status_str: String = row.get("status");
+        let status = match status_str.as_str() {
+            "pending" => WebhookDeliveryStatus::Pending,
+            "succeeded" => WebhookDeliveryStatus::Succeeded,
+            "failed" => WebhookDeliveryStatus::Failed,
+            other => return Err(AppError::Internal(format!("Invalid webhook delivery status: {}", other))),
+        };
+
+        Ok(WebhookDelivery {
+            id: row.get("id"),
+            endpoint_id: row.get("endpoint_id"),
+            event_id: row.get("event_id"),
+            event_type: row.get("event_type"),
+            status,
+            attempt_count: row.get("attempt_count"),
+            next_attempt_at: Some(row.get("next_attempt_at")).filter(|_| status == WebhookDeliveryStatus::Pending),
+            last_attempt_at: row.get("last_attempt_at"),
+            last_response_status: row.get("last_response_status"),
+            last_error: row.get("last_error"),
+            created_at: row.get("created_at"),
+        })
+    }
+
+    fn row_to_due_delivery(row: &sqlx::postgres::PgRow) -> DueDelivery {
+        let endpoint_status: String = row.get("endpoint_status");
+
+        DueDelivery {
+            id: row.get("id"),
+            attempt_count: row.get("attempt_count"),
+            url: row.get("url"),
+            secret: row.get("secret"),
+            endpoint_active: endpoint_status == "active",
+            event_id: row.get("event_id"),
+            event_type: row.get("event_type"),
+            payload: row.get("payload"),
+            event_created_at: row.get("event_created_at"),
+        }
+    }
+}
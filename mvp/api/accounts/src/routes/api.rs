@@ -1,9 +1,9 @@
 use axum::{
     body::Body,
     http::Request,
-    middleware::{from_fn, Next},
+    middleware::{from_fn, from_fn_with_state, Next},
     response::Response,
-    routing::{get, post},
+    routing::{delete, get, post, put},
     Router,
 };
 use sqlx::PgPool;
@@ -13,36 +13,143 @@ use crate::handlers::{
     accounts::*,
     transactions::{create_transaction, get_transaction, list_account_transactions, list_transactions},
     health::health_check,
+    fees::{create_fee_schedule, deactivate_fee_schedule, list_fee_schedules},
+    fx::{create_fx_quote, get_fx_quote, lock_fx_quote},
+    interest::{list_account_interest_accruals, list_interest_rate_configs, upsert_interest_rate_config},
+    limits::{delete_transaction_limit, list_transaction_limits, upsert_transaction_limit},
+    interorg::{
+        accept_interorg_agreement, create_interorg_agreement, list_interorg_agreements, revoke_interorg_agreement,
+    },
+    iso20022::{download_camt053, download_pain001},
+    nacha::{download_nacha_file, generate_nacha_file, list_nacha_files, process_nacha_returns},
+    payouts::{create_payee, create_payout, deactivate_payee, get_payout, list_account_payouts, list_payees},
+    events::stream_events,
+    audit::{export_audit_events, list_audit_events},
+    closures::{close_account_with_sweep, get_account_closure},
+    hash_chain::{list_hash_chain_checkpoints, verify_hash_chain},
+    statements::{get_monthly_statement, get_statement, list_account_statements},
+    webhooks::{
+        create_webhook_endpoint, disable_webhook_endpoint, get_webhook_delivery, list_webhook_deliveries,
+        list_webhook_endpoints, redeliver_webhook,
+    },
 };
 
+use crate::auth::Authenticator;
 use crate::errors::AppError;
+use crate::middleware::audit::audit_middleware;
+use crate::middleware::idempotency::idempotency_middleware;
+use crate::middleware::rate_limit::{rate_limit_middleware, RateLimiter, RouteClass};
+use crate::event_stream::EventNotifier;
+use crate::fx::FxRates;
 use crate::ledger_grpc::LedgerGrpc;
+use crate::payout_rail::PayoutRails;
+use crate::webhook::WebhookClient;
 
 #[derive(Clone)]
 pub struct AppState {
     pub pool: PgPool,
+    pub authenticator: Authenticator,
     pub ledger_grpc: LedgerGrpc,
+    pub fx_rates: FxRates,
+    pub payout_rail: PayoutRails,
+    pub webhook_client: WebhookClient,
+    pub event_notifier: EventNotifier,
+    pub rate_limiter: RateLimiter,
 }
 
-pub fn create_router(pool: PgPool, ledger_grpc: LedgerGrpc) -> Router {
-    let state = AppState { pool, ledger_grpc };
+#[allow(clippy::too_many_arguments)]
+pub fn create_router(
+    pool: PgPool,
+    authenticator: Authenticator,
+    ledger_grpc: LedgerGrpc,
+    fx_rates: FxRates,
+    payout_rail: PayoutRails,
+    webhook_client: WebhookClient,
+    event_notifier: EventNotifier,
+    rate_limiter: RateLimiter,
+) -> Router {
+    let state = AppState {
+        pool,
+        authenticator,
+        ledger_grpc,
+        fx_rates,
+        payout_rail,
+        webhook_client,
+        event_notifier,
+        rate_limiter,
+    };
     Router::<AppState>::new()
         .route("/health", get(health_check))
-        .nest("/api/v1", create_api_routes())
+        .nest("/api/v1", create_api_routes(&state))
         .layer(from_fn(correlation_id_middleware))
         .with_state(state)
 }
 
-fn create_api_routes() -> Router<AppState> {
-    Router::<AppState>::new()
-        .route("/accounts", post(create_account).get(list_accounts))
-        .route("/accounts/:id", get(get_account).patch(update_account_status).delete(close_account))
+fn create_api_routes(state: &AppState) -> Router<AppState> {
+    // Money movement: Idempotency-Key is enforced on the transaction intent itself, so a
+    // replay returns the intent's current state rather than a stored response
+    let intent_routes = Router::<AppState>::new()
         .route("/accounts/:id/deposit", post(deposit))
         .route("/accounts/:id/withdraw", post(withdraw))
         .route("/accounts/:id/transfer", post(transfer))
+        .route("/accounts/:id/payouts", post(create_payout))
+        .route("/accounts/:id/close", post(close_account_with_sweep))
+        .route("/transactions", post(create_transaction))
+        .route_layer(from_fn_with_state(state.clone(), audit_middleware))
+        .route_layer(from_fn_with_state((state.clone(), RouteClass::MoneyMovement), rate_limit_middleware));
+
+    // Operator endpoints across all organizations, authorized by internal service token and
+    // not rate limited
+    let operator_routes = Router::<AppState>::new()
+        .route("/nacha/files", post(generate_nacha_file).get(list_nacha_files))
+        .route("/nacha/files/:id/download", get(download_nacha_file))
+        .route("/nacha/returns", post(process_nacha_returns))
+        .route_layer(from_fn_with_state(state.clone(), audit_middleware));
+
+    Router::<AppState>::new()
+        .route("/accounts", post(create_account).get(list_accounts))
+        .route("/accounts/:id", get(get_account).patch(update_account).delete(close_account))
         .route("/accounts/:account_id/transactions", get(list_account_transactions))
-        .route("/transactions", post(create_transaction).get(list_transactions))
+        .route("/accounts/:account_id/interest-accruals", get(list_account_interest_accruals))
+        .route("/accounts/:id/payouts", get(list_account_payouts))
+        .route("/accounts/:id/status-history", get(list_account_status_history))
+        .route("/accounts/:id/closure", get(get_account_closure))
+        .route("/accounts/:id/statement", get(get_statement))
+        .route("/accounts/:id/statements", get(list_account_statements))
+        .route("/accounts/:id/statements/:period", get(get_monthly_statement))
+        .route("/accounts/:id/statements/camt053", get(download_camt053))
+        .route("/accounts/:id/payments/pain001", get(download_pain001))
+        .route("/payouts/:id", get(get_payout))
+        .route("/payees", post(create_payee).get(list_payees))
+        .route("/payees/:id", delete(deactivate_payee))
+        .route("/interest-rate-configs", put(upsert_interest_rate_config).get(list_interest_rate_configs))
+        .route("/fee-schedules", post(create_fee_schedule).get(list_fee_schedules))
+        .route("/fee-schedules/:id", delete(deactivate_fee_schedule))
+        .route("/transaction-limits", put(upsert_transaction_limit).get(list_transaction_limits))
+        .route("/transaction-limits/:id", delete(delete_transaction_limit))
+        .route("/interorg-agreements", post(create_interorg_agreement).get(list_interorg_agreements))
+        .route("/interorg-agreements/:id", delete(revoke_interorg_agreement))
+        .route("/interorg-agreements/:id/accept", post(accept_interorg_agreement))
+        .route("/webhook-endpoints", post(create_webhook_endpoint).get(list_webhook_endpoints))
+        .route("/webhook-endpoints/:id", delete(disable_webhook_endpoint))
+        .route("/webhook-endpoints/:id/deliveries", get(list_webhook_deliveries))
+        .route("/webhook-deliveries/:id", get(get_webhook_delivery))
+        .route("/webhook-deliveries/:id/redeliver", post(redeliver_webhook))
+        .route("/events/stream", get(stream_events))
+        .route("/fx/quotes", post(create_fx_quote))
+        .route("/fx/quotes/:id", get(get_fx_quote))
+        .route("/fx/quotes/:id/lock", post(lock_fx_quote))
+        .route("/transactions", get(list_transactions))
         .route("/transactions/:id", get(get_transaction))
+        .route("/audit-events", get(list_audit_events))
+        .route("/audit-events/export", get(export_audit_events))
+        .route("/hash-chain/verify", get(verify_hash_chain))
+        .route("/hash-chain/checkpoints", get(list_hash_chain_checkpoints))
+        .route_layer(from_fn_with_state(state.clone(), audit_middleware))
+        .route_layer(from_fn_with_state(state.clone(), idempotency_middleware))
+        .route_layer(from_fn_with_state((state.clone(), RouteClass::Write), rate_limit_middleware))
+        .merge(intent_routes)
+        .merge(operator_routes)
 }
 
 async fn correlation_id_middleware(
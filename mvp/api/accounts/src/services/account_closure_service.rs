@@ -0,0 +1,749 @@
+use sqlx::{PgConnection, PgPool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::ledger_grpc::LedgerGrpc;
+use crate::models::{
+    Account, AccountClosure, AccountClosureReceipt, AccountClosureRequest, AccountStatus, ClosureStatus,
+    NewAccountStatusChange, PayeeStatus, PayoutStatus, Transaction, TransactionDetails, TransactionKind,
+    TransactionStatus,
+};
+use crate::payout_rail::{PayoutRail, PayoutRails};
+use crate::repositories::{
+    AccountClosureRepository, AccountRepository, PayeeRepository, PayoutRepository, TransactionRepository,
+};
+use crate::services::transaction_retry::post_to_ledger;
+use crate::services::AccountService;
+
+/// Sweeps tried per advance before the closure waits for the next one (funds arriving while
+/// the balance is swept start another sweep)
+const MAX_SWEEPS_PER_ADVANCE: usize = 3;
+
+/// Closures advanced per background run
+const CLOSURE_BATCH_SIZE: i64 = 50;
+
+/// Longest note accepted with a closure
+const MAX_NOTE_CHARS: usize = 1000;
+
+/// Outcome of a closure step.
+enum Progress {
+    /// Nothing holds the closure back
+    Ready,
+    /// Waiting on the Ledger, a payout, or a sweep to post or its payout to settle
+    Waiting(String),
+}
+
+pub struct AccountClosureService;
+
+impl AccountClosureService {
+    /// Closes the account after settling or cancelling its pending intents and sweeping its
+    /// residual balance to another account or a payee. The account is `Closing` from the start
+    /// (no new intents) and `Closed` only once the sweep has posted and its payout settled.
+    /// Calling again resumes the closure and returns its receipt.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn close(
+        pool: &PgPool,
+        ledger_grpc: &LedgerGrpc,
+        payout_rail: &PayoutRails,
+        account_id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+        mut request: AccountClosureRequest,
+        actor: (&'static str, Option<Uuid>),
+    ) -> Result<AccountClosureReceipt, AppError> {
+        let account = AccountRepository::find_by_id_for_organization(pool, account_id, organization_id, environment).await?;
+
+        if account.status == Some(AccountStatus::Closed) {
+            let closure = AccountClosureRepository::find_by_account(pool, account_id)
+                .await?
+                .ok_or_else(|| AppError::BusinessLogic("account is already closed".to_string()))?;
+            let closure = Self::advance(pool, ledger_grpc, payout_rail, closure).await?;
+            return Self::receipt(pool, closure).await;
+        }
+
+        request.note = request.note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+        if request.note.as_ref().is_some_and(|n| n.chars().count() > MAX_NOTE_CHARS) {
+            return Err(AppError::Validation(format!("note must be at most {} characters", MAX_NOTE_CHARS)));
+        }
+
+        let currency = account.currency()?;
+        Self::validate_destination(pool, payout_rail, &account, organization_id, environment, &request).await?;
+
+        // The closure row is locked before the account, in the same order as `advance`
+        let mut tx = pool.begin().await?;
+        let closure =
+            AccountClosureRepository::start(&mut tx, &account, environment, currency.code(), &request, actor).await?;
+        if closure.sweep_to_account_id != request.sweep_to_account_id || closure.payee_id != request.payee_id {
+            return Err(AppError::Conflict(
+                "the account is already being swept to another destination".to_string(),
+            ));
+        }
+
+        // Intents need an active account, so none are created once the account is closing
+        let account = AccountRepository::find_by_id_for_update(&mut tx, account_id, environment).await?;
+        let current = account.status.unwrap_or(AccountStatus::Active);
+        if current == AccountStatus::Closed {
+            return Err(AppError::BusinessLogic("account is already closed".to_string()));
+        }
+        if current != AccountStatus::Closing {
+            let change = NewAccountStatusChange {
+                from_status: current,
+                to_status: AccountStatus::Closing,
+                reason: Some(closure.reason),
+                note: closure.note.clone(),
+                actor_type: closure.actor_type.clone(),
+                actor_id: closure.actor_id,
+            };
+            AccountRepository::change_status(&mut tx, account_id, environment, &change).await?;
+        }
+        tx.commit().await?;
+
+        info!(
+            closure_id = %closure.id,
+            account_id = %account_id,
+            reason = closure.reason.as_str(),
+            "account_closure_started"
+        );
+
+        let closure = Self::advance(pool, ledger_grpc, payout_rail, closure).await?;
+        Self::receipt(pool, closure).await
+    }
+
+    pub async fn get_closure(
+        pool: &PgPool,
+        account_id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<AccountClosureReceipt, AppError> {
+        AccountRepository::find_by_id_for_organization(pool, account_id, organization_id, environment).await?;
+        let closure = AccountClosureRepository::find_by_account(pool, account_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Account {} has no closure", account_id)))?;
+        Self::receipt(pool, closure).await
+    }
+
+    /// The sweep destination must be able to receive the balance: an active account of the
+    /// same organization, environment and currency, or an active payee of the account owner
+    /// on a rail supporting the currency.
+    async fn validate_destination(
+        pool: &PgPool,
+        payout_rail: &PayoutRails,
+        account: &Account,
+        organization_id: Uuid,
+        environment: &str,
+        request: &AccountClosureRequest,
+    ) -> Result<(), AppError> {
+        let currency = account.currency()?;
+
+        match (request.sweep_to_account_id, request.payee_id) {
+            (Some(_), Some(_)) => Err(AppError::Validation(
+                "sweep_to_account_id and payee_id are mutually exclusive".to_string(),
+            )),
+            (Some(sweep_to_account_id), None) => {
+                if sweep_to_account_id == account.id {
+                    return Err(AppError::Validation("cannot sweep an account into itself".to_string()));
+                }
+                let destination =
+                    AccountRepository::find_by_id_for_organization(pool, sweep_to_account_id, organization_id, environment)
+                        .await?;
+                if destination.status != Some(AccountStatus::Active) {
+                    return Err(AppError::BusinessLogic("sweep account is not active".to_string()));
+                }
+                if destination.currency()? != currency {
+                    return Err(AppError::Validation(format!(
+                        "sweep account must hold {}",
+                        currency.code()
+                    )));
+                }
+                Ok(())
+            }
+            (None, Some(payee_id)) => {
+                let payee = PayeeRepository::find_by_id(pool, payee_id, organization_id, environment).await?;
+                if payee.user_id != account.user_id {
+                    return Err(AppError::Validation("payee must belong to the account owner".to_string()));
+                }
+                if payee.status != PayeeStatus::Active {
+                    return Err(AppError::BusinessLogic("payee is not active".to_string()));
+                }
+                if !payout_rail.supports_currency(currency.code()) {
+                    return Err(AppError::BusinessLogic(format!(
+                        "the {} payout rail does not support {}",
+                        payout_rail.name(),
+                        currency.code()
+                    )));
+                }
+                Ok(())
+            }
+            (None, None) => Ok(()),
+        }
+    }
+
+    /// Takes the closure as far as it can go now. Every step can be repeated: intents are
+    /// posted under their idempotency keys, and each sweep intent is created under the key of
+    /// its attempt. One advance runs at a time: it holds the closure row for its whole run and
+    /// writes it through that transaction; a closure held by another advance (the request
+    /// handler or the background job) is returned as it is.
+    pub async fn advance(
+        pool: &PgPool,
+        ledger_grpc: &LedgerGrpc,
+        payout_rail: &PayoutRails,
+        closure: AccountClosure,
+    ) -> Result<AccountClosure, AppError> {
+        if closure.status == ClosureStatus::Completed {
+            return Ok(closure);
+        }
+
+        let mut lock = pool.begin().await?;
+        let Some(closure) = AccountClosureRepository::lock_in_progress(&mut lock, closure.id).await? else {
+            return AccountClosureRepository::find_by_id(pool, closure.id).await;
+        };
+
+        match Self::advance_locked(pool, &mut lock, ledger_grpc, payout_rail, &closure).await {
+            Ok(closure) => {
+                lock.commit().await?;
+                Ok(closure)
+            }
+            Err(e) => {
+                // Keep why the closure stopped, unless the failure aborted the transaction
+                if AccountClosureRepository::record_progress(&mut *lock, closure.id, 0, 0, Some(&e.to_string()))
+                    .await
+                    .is_ok()
+                {
+                    lock.commit().await?;
+                }
+                Err(e)
+            }
+        }
+    }
+
+    async fn advance_locked(
+        pool: &PgPool,
+        conn: &mut PgConnection,
+        ledger_grpc: &LedgerGrpc,
+        payout_rail: &PayoutRails,
+        closure: &AccountClosure,
+    ) -> Result<AccountClosure, AppError> {
+        let account = AccountRepository::find_by_id(pool, closure.account_id, &closure.environment).await?;
+
+        // An advance interrupted after closing the account only has the closure left to complete
+        if account.status != Some(AccountStatus::Closed) {
+            if let Progress::Waiting(reason) =
+                Self::settle_and_sweep(pool, conn, ledger_grpc, payout_rail, closure, &account).await?
+            {
+                info!(closure_id = %closure.id, reason = %reason, "account_closure_waiting");
+                AccountClosureRepository::record_progress(&mut *conn, closure.id, 0, 0, Some(&reason)).await?;
+                return AccountClosureRepository::find_by_id(&mut *conn, closure.id).await;
+            }
+
+            // Only returned payouts credit a closing account: re-check under the account lock
+            // (which they take too) that none arrived after the sweep
+            let account = AccountRepository::find_by_id_for_update(conn, account.id, &closure.environment).await?;
+            let pending = TransactionRepository::find_pending_by_account(&mut *conn, account.id).await?;
+            if !pending.is_empty() {
+                let reason = format!("{} intents arrived during the sweep", pending.len());
+                info!(closure_id = %closure.id, reason = %reason, "account_closure_waiting");
+                AccountClosureRepository::record_progress(&mut *conn, closure.id, 0, 0, Some(&reason)).await?;
+                return AccountClosureRepository::find_by_id(&mut *conn, closure.id).await;
+            }
+
+            let change = NewAccountStatusChange {
+                from_status: account.status.unwrap_or(AccountStatus::Closing),
+                to_status: AccountStatus::Closed,
+                reason: Some(closure.reason),
+                note: closure.note.clone(),
+                actor_type: closure.actor_type.clone(),
+                actor_id: closure.actor_id,
+            };
+            AccountRepository::change_status(conn, account.id, &closure.environment, &change).await?;
+        }
+
+        let closure = AccountClosureRepository::complete(conn, closure.id).await?;
+        info!(closure_id = %closure.id, account_id = %closure.account_id, "account_closure_completed");
+        Ok(closure)
+    }
+
+    async fn settle_and_sweep(
+        pool: &PgPool,
+        conn: &mut PgConnection,
+        ledger_grpc: &LedgerGrpc,
+        payout_rail: &PayoutRails,
+        closure: &AccountClosure,
+        account: &Account,
+    ) -> Result<Progress, AppError> {
+        if let Progress::Waiting(reason) = Self::settle_pending_intents(pool, conn, ledger_grpc, closure).await? {
+            return Ok(Progress::Waiting(reason));
+        }
+
+        let in_flight = AccountClosureRepository::count_other_payouts_in_flight(pool, closure).await?;
+        if in_flight > 0 {
+            return Ok(Progress::Waiting(format!("waiting for {} payouts to settle", in_flight)));
+        }
+
+        let mut closure = closure.clone();
+        for _ in 0..MAX_SWEEPS_PER_ADVANCE {
+            // A sweep committed by an advance that stopped before linking it is the current one
+            if closure.sweep_transaction_id.is_none() {
+                let key = closure.sweep_key(closure.sweep_attempt);
+                let orphan = TransactionRepository::find_by_idempotency_key(
+                    pool,
+                    closure.organization_id,
+                    &closure.environment,
+                    &key,
+                )
+                .await?;
+                if let Some(sweep) = orphan {
+                    AccountClosureRepository::record_sweep(&mut *conn, closure.id, closure.sweep_attempt, sweep.id)
+                        .await?;
+                    closure = AccountClosureRepository::find_by_id(&mut *conn, closure.id).await?;
+                }
+            }
+
+            // Post the current sweep, or move on from it if the Ledger rejected it
+            if let Some(transaction_id) = closure.sweep_transaction_id {
+                let sweep = TransactionRepository::find_by_id(pool, transaction_id).await?;
+                let status = match sweep.status {
+                    TransactionStatus::Pending => {
+                        match Self::post_intent(pool, ledger_grpc, &sweep, &closure.environment).await? {
+                            Ok(status) => status,
+                            Err(reason) => {
+                                return Ok(Progress::Waiting(format!("sweep {} not posted: {}", sweep.id, reason)))
+                            }
+                        }
+                    }
+                    status => status,
+                };
+                if status == TransactionStatus::Failed {
+                    warn!(closure_id = %closure.id, transaction_id = %sweep.id, "account_closure_sweep_failed");
+                    AccountClosureRepository::next_sweep_attempt(&mut *conn, closure.id, closure.sweep_attempt).await?;
+                    closure = AccountClosureRepository::find_by_id(&mut *conn, closure.id).await?;
+                    continue;
+                }
+            }
+
+            let balance = match AccountService::ledger_balance(ledger_grpc, account, &closure.environment).await {
+                Ok(balance) => balance,
+                Err(e) => return Ok(Progress::Waiting(format!("Ledger balance unavailable: {}", e))),
+            };
+
+            if balance == 0 {
+                // A payee sweep is done once its payout settled: a returned one credits the
+                // account again (and is swept again)
+                let unsettled = AccountClosureRepository::find_sweeps(pool, &closure)
+                    .await?
+                    .iter()
+                    .filter(|sweep| sweep.status == TransactionStatus::Posted)
+                    .filter(|sweep| {
+                        matches!(sweep.payout_status, Some(PayoutStatus::Pending | PayoutStatus::Submitted))
+                    })
+                    .count();
+                if unsettled > 0 {
+                    return Ok(Progress::Waiting(format!("waiting for {} sweep payouts to settle", unsettled)));
+                }
+                return Ok(Progress::Ready);
+            }
+            if balance < 0 {
+                return Err(AppError::BusinessLogic(format!(
+                    "account balance is negative ({} minor units) and must be settled before closing",
+                    balance
+                )));
+            }
+
+            // Funds arrived after the current sweep posted
+            if closure.sweep_transaction_id.is_some() {
+                AccountClosureRepository::next_sweep_attempt(&mut *conn, closure.id, closure.sweep_attempt).await?;
+                closure = AccountClosureRepository::find_by_id(&mut *conn, closure.id).await?;
+            }
+
+            let transaction_id = Self::create_sweep(pool, payout_rail, &closure, account, balance).await?;
+            AccountClosureRepository::record_sweep(&mut *conn, closure.id, closure.sweep_attempt, transaction_id)
+                .await?;
+            closure = AccountClosureRepository::find_by_id(&mut *conn, closure.id).await?;
+        }
+
+        Ok(Progress::Waiting("sweep not settled yet".to_string()))
+    }
+
+    /// Posts each pending intent of the account (other than the closure's sweeps). Intents the
+    /// Ledger rejects are cancelled; if the Ledger cannot be reached the closure waits.
+    async fn settle_pending_intents(
+        pool: &PgPool,
+        conn: &mut PgConnection,
+        ledger_grpc: &LedgerGrpc,
+        closure: &AccountClosure,
+    ) -> Result<Progress, AppError> {
+        let prefix = closure.sweep_key_prefix();
+        let pending = TransactionRepository::find_pending_by_account(pool, closure.account_id).await?;
+
+        let (mut settled, mut cancelled) = (0, 0);
+        let mut waiting = None;
+        for transaction in pending.iter().filter(|t| !t.idempotency_key.starts_with(&prefix)) {
+            let environment = transaction.environment.as_deref().unwrap_or(&closure.environment);
+            match Self::post_intent(pool, ledger_grpc, transaction, environment).await? {
+                Ok(TransactionStatus::Posted) => settled += 1,
+                Ok(_) => cancelled += 1,
+                Err(reason) => {
+                    waiting = Some(format!("pending intent {} not posted: {}", transaction.id, reason));
+                    break;
+                }
+            }
+        }
+
+        if settled > 0 || cancelled > 0 {
+            info!(closure_id = %closure.id, settled, cancelled, "account_closure_intents_settled");
+            AccountClosureRepository::record_progress(&mut *conn, closure.id, settled, cancelled, None).await?;
+        }
+
+        Ok(match waiting {
+            Some(reason) => Progress::Waiting(reason),
+            None => Progress::Ready,
+        })
+    }
+
+    /// Posts a pending intent: `Ok(Posted)`, `Ok(Failed)` when the Ledger rejected it, or
+    /// `Err(reason)` when it stays pending.
+    async fn post_intent(
+        pool: &PgPool,
+        ledger_grpc: &LedgerGrpc,
+        transaction: &Transaction,
+        environment: &str,
+    ) -> Result<Result<TransactionStatus, String>, AppError> {
+        match post_to_ledger(ledger_grpc, transaction, environment).await {
+            Ok(()) => {
+                TransactionRepository::update_status(pool, transaction.id, TransactionStatus::Posted, None).await?;
+                Ok(Ok(TransactionStatus::Posted))
+            }
+            Err(AppError::BusinessLogic(reason)) => {
+                let reason = format!("cancelled by account closure: {}", reason);
+                TransactionRepository::update_status(pool, transaction.id, TransactionStatus::Failed, Some(&reason))
+                    .await?;
+                Ok(Ok(TransactionStatus::Failed))
+            }
+            Err(e) => {
+                let reason = e.to_string();
+                TransactionRepository::update_status(pool, transaction.id, TransactionStatus::Pending, Some(&reason))
+                    .await?;
+                Ok(Err(reason))
+            }
+        }
+    }
+
+    /// Creates and commits the sweep intent of the current attempt for `amount` (with its
+    /// payout for a payee), so it exists before it is posted; the caller links it to the
+    /// closure. Sweeps are not subject to limits or fees.
+    async fn create_sweep(
+        pool: &PgPool,
+        payout_rail: &PayoutRails,
+        closure: &AccountClosure,
+        account: &Account,
+        amount: i64,
+    ) -> Result<Uuid, AppError> {
+        let key = closure.sweep_key(closure.sweep_attempt);
+        let mut tx = pool.begin().await?;
+
+        let transaction = match (closure.sweep_to_account_id, closure.payee_id) {
+            (Some(sweep_to_account_id), _) => {
+                // The destination stays active until the sweep commits
+                AccountRepository::lock_active(&mut tx, &[sweep_to_account_id]).await?;
+                TransactionRepository::create_or_get_by_idempotency(
+                    &mut *tx,
+                    closure.organization_id,
+                    account.id,
+                    sweep_to_account_id,
+                    amount,
+                    &closure.currency,
+                    TransactionKind::Transfer,
+                    &key,
+                    Some(&closure.environment),
+                    &TransactionDetails::default(),
+                )
+                .await?
+            }
+            (None, Some(payee_id)) => {
+                let payee =
+                    PayeeRepository::find_by_id(&mut *tx, payee_id, closure.organization_id, &closure.environment)
+                        .await?;
+                let transaction = TransactionRepository::create_or_get_by_idempotency(
+                    &mut *tx,
+                    closure.organization_id,
+                    account.id,
+                    account.id,
+                    amount,
+                    &closure.currency,
+                    TransactionKind::Withdraw,
+                    &key,
+                    Some(&closure.environment),
+                    &TransactionDetails::default(),
+                )
+                .await?;
+                PayoutRepository::create_or_get(&mut *tx, &transaction, &payee, payout_rail.name()).await?;
+                transaction
+            }
+            (None, None) => {
+                return Err(AppError::Validation(format!(
+                    "the account holds {} minor units; sweep_to_account_id or payee_id is required to close it",
+                    amount
+                )));
+            }
+        };
+
+        tx.commit().await?;
+
+        info!(
+            closure_id = %closure.id,
+            transaction_id = %transaction.id,
+            amount,
+            "account_closure_sweep_created"
+        );
+        Ok(transaction.id)
+    }
+
+    async fn receipt(pool: &PgPool, closure: AccountClosure) -> Result<AccountClosureReceipt, AppError> {
+        let sweeps = AccountClosureRepository::find_sweeps(pool, &closure).await?;
+        let swept_amount = sweeps
+            .iter()
+            .filter(|sweep| sweep.status == TransactionStatus::Posted)
+            .map(|sweep| sweep.amount)
+            .sum();
+
+        Ok(AccountClosureReceipt {
+            id: closure.id,
+            account_id: closure.account_id,
+            status: closure.status,
+            reason: closure.reason,
+            note: closure.note,
+            sweep_to_account_id: closure.sweep_to_account_id,
+            payee_id: closure.payee_id,
+            swept_amount,
+            currency: closure.currency,
+            sweeps,
+            settled_intents: closure.settled_intents,
+            cancelled_intents: closure.cancelled_intents,
+            last_error: closure.last_error,
+            created_at: closure.created_at,
+            completed_at: closure.completed_at,
+        })
+    }
+}
+
+/// Background job: resume closures left in progress (Ledger unavailable, payouts in flight,
+/// or a crash mid-closure).
+pub async fn run(pool: PgPool, ledger_grpc: LedgerGrpc, payout_rail: PayoutRails) {
+    let interval_secs = std::env::var("CLOSURE_JOB_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(60);
+
+    info!(interval_secs, "Account closure job started");
+
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(interval_secs)).await;
+
+        let closures = match AccountClosureRepository::find_in_progress(&pool, CLOSURE_BATCH_SIZE).await {
+            Ok(closures) => closures,
+            Err(e) => {
+                warn!(error = %e, "account_closure_job_failed");
+                continue;
+            }
+        };
+
+        for closure in closures {
+            let closure_id = closure.id;
+            if let Err(e) = AccountClosureService::advance(&pool, &ledger_grpc, &payout_rail, closure).await {
+                warn!(closure_id = %closure_id, error = %e, "account_closure_advance_failed");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AccountType, BankAccountType, CreatePayeeRequest, Payee, StatusReason};
+    use crate::payout_rail::SimulatedPayoutRail;
+    use crate::services::PayoutService;
+    use crate::testing::{self, FakeLedger, ENVIRONMENT};
+
+    fn rail() -> PayoutRails {
+        PayoutRails::Simulated(SimulatedPayoutRail::new(chrono::Duration::seconds(60)))
+    }
+
+    fn request(sweep_to_account_id: Option<Uuid>, payee_id: Option<Uuid>) -> AccountClosureRequest {
+        AccountClosureRequest { reason: StatusReason::CustomerRequest, note: None, sweep_to_account_id, payee_id }
+    }
+
+    async fn close(
+        pool: &PgPool,
+        ledger_grpc: &LedgerGrpc,
+        account: &Account,
+        request: AccountClosureRequest,
+    ) -> Result<AccountClosureReceipt, AppError> {
+        AccountClosureService::close(
+            pool,
+            ledger_grpc,
+            &rail(),
+            account.id,
+            account.organization_id.unwrap(),
+            ENVIRONMENT,
+            request,
+            ("api_key", None),
+        )
+        .await
+    }
+
+    async fn payee(pool: &PgPool, account: &Account) -> Payee {
+        let request = CreatePayeeRequest {
+            organization_id: account.organization_id.unwrap(),
+            user_id: account.user_id,
+            name: "Account Owner".to_string(),
+            bank_name: None,
+            routing_number: "021000021".to_string(),
+            account_number: "000123456789".to_string(),
+            bank_account_type: BankAccountType::Checking,
+        };
+        PayeeRepository::create(pool, ENVIRONMENT, &request).await.unwrap()
+    }
+
+    async fn status(pool: &PgPool, account: &Account) -> Option<AccountStatus> {
+        AccountRepository::find_by_id(pool, account.id, ENVIRONMENT).await.unwrap().status
+    }
+
+    #[sqlx::test(migrations = "./migrations_accounts")]
+    #[ignore = "needs Postgres: DATABASE_URL=... cargo test -- --ignored"]
+    async fn the_destination_is_fixed_once_a_sweep_exists(pool: PgPool) {
+        let (ledger, ledger_grpc) = FakeLedger::start().await;
+        let organization_id = Uuid::new_v4();
+        let account = testing::account(&pool, organization_id, AccountType::Checking, "USD").await;
+        let first = testing::account(&pool, organization_id, AccountType::Checking, "USD").await;
+        let second = testing::account(&pool, organization_id, AccountType::Checking, "USD").await;
+        let payee = payee(&pool, &account).await;
+
+        // The balance cannot be read, so the closure waits with the account closing
+        ledger.set_available(false);
+        let receipt = close(&pool, &ledger_grpc, &account, request(Some(first.id), None)).await.unwrap();
+        assert_eq!(receipt.status, ClosureStatus::InProgress);
+        assert!(receipt.last_error.unwrap().contains("Ledger balance unavailable"));
+        assert_eq!(status(&pool, &account).await, Some(AccountStatus::Closing));
+
+        // Nothing has been swept yet, so another destination replaces it
+        let receipt = close(&pool, &ledger_grpc, &account, request(Some(second.id), None)).await.unwrap();
+        assert_eq!(receipt.sweep_to_account_id, Some(second.id));
+
+        // The payee sweep waits for its payout to settle
+        ledger.set_available(true);
+        ledger.credit(account.id, 300);
+        let receipt = close(&pool, &ledger_grpc, &account, request(None, Some(payee.id))).await.unwrap();
+        assert_eq!((receipt.sweep_to_account_id, receipt.payee_id), (None, Some(payee.id)));
+        assert_eq!(receipt.sweeps.len(), 1);
+
+        let err = close(&pool, &ledger_grpc, &account, request(Some(first.id), None)).await.unwrap_err();
+        assert!(matches!(err, AppError::Conflict(_)), "{:?}", err);
+
+        let receipt = close(&pool, &ledger_grpc, &account, request(None, Some(payee.id))).await.unwrap();
+        assert_eq!(receipt.status, ClosureStatus::InProgress);
+        assert_eq!(receipt.sweeps.len(), 1);
+    }
+
+    #[sqlx::test(migrations = "./migrations_accounts")]
+    #[ignore = "needs Postgres: DATABASE_URL=... cargo test -- --ignored"]
+    async fn resumes_a_partially_swept_closure(pool: PgPool) {
+        let (ledger, ledger_grpc) = FakeLedger::start().await;
+        let organization_id = Uuid::new_v4();
+        let account = testing::account(&pool, organization_id, AccountType::Checking, "USD").await;
+        let destination = testing::account(&pool, organization_id, AccountType::Checking, "USD").await;
+
+        ledger.set_available(false);
+        close(&pool, &ledger_grpc, &account, request(Some(destination.id), None)).await.unwrap();
+
+        // An advance committed the sweep of 500 and stopped before linking or posting it;
+        // 200 more arrived since
+        let closure = AccountClosureRepository::find_by_account(&pool, account.id).await.unwrap().unwrap();
+        let orphan = testing::intent(
+            &pool,
+            &account,
+            &destination,
+            TransactionKind::Transfer,
+            500,
+            &closure.sweep_key(closure.sweep_attempt),
+        )
+        .await;
+        ledger.credit(account.id, 700);
+
+        ledger.set_available(true);
+        let closure = AccountClosureService::advance(&pool, &ledger_grpc, &rail(), closure).await.unwrap();
+        assert_eq!(closure.status, ClosureStatus::Completed);
+
+        let receipt = AccountClosureService::get_closure(&pool, account.id, organization_id, ENVIRONMENT).await.unwrap();
+        let swept: Vec<(Uuid, i64, TransactionStatus)> =
+            receipt.sweeps.iter().map(|sweep| (sweep.transaction_id, sweep.amount, sweep.status)).collect();
+        assert_eq!(swept.len(), 2);
+        assert_eq!(swept[0], (orphan.id, 500, TransactionStatus::Posted));
+        assert_eq!((swept[1].1, swept[1].2), (200, TransactionStatus::Posted));
+        assert_eq!(receipt.swept_amount, 700);
+        assert_eq!(ledger.balance(account.id), 0);
+        assert_eq!(ledger.balance(destination.id), 700);
+        assert_eq!(status(&pool, &account).await, Some(AccountStatus::Closed));
+    }
+
+    #[sqlx::test(migrations = "./migrations_accounts")]
+    #[ignore = "needs Postgres: DATABASE_URL=... cargo test -- --ignored"]
+    async fn stays_closing_until_the_sweep_payout_settles(pool: PgPool) {
+        let (ledger, ledger_grpc) = FakeLedger::start().await;
+        let organization_id = Uuid::new_v4();
+        let account = testing::account(&pool, organization_id, AccountType::Checking, "USD").await;
+        let payee = payee(&pool, &account).await;
+        ledger.credit(account.id, 300);
+
+        let receipt = close(&pool, &ledger_grpc, &account, request(None, Some(payee.id))).await.unwrap();
+        assert_eq!(receipt.status, ClosureStatus::InProgress);
+        assert_eq!(receipt.swept_amount, 300);
+        assert_eq!(receipt.last_error.as_deref(), Some("waiting for 1 sweep payouts to settle"));
+        assert_eq!(ledger.balance(account.id), 0);
+        assert_eq!(status(&pool, &account).await, Some(AccountStatus::Closing));
+
+        let payout_id = receipt.sweeps[0].payout_id.unwrap();
+        PayoutRepository::mark_submitted(&pool, payout_id, "rail-ref").await.unwrap().unwrap();
+        let closure = AccountClosureRepository::find_by_account(&pool, account.id).await.unwrap().unwrap();
+        let closure = AccountClosureService::advance(&pool, &ledger_grpc, &rail(), closure).await.unwrap();
+        assert_eq!(closure.status, ClosureStatus::InProgress);
+        assert_eq!(status(&pool, &account).await, Some(AccountStatus::Closing));
+
+        PayoutRepository::mark_settled(&pool, payout_id).await.unwrap().unwrap();
+        let closure = AccountClosureService::advance(&pool, &ledger_grpc, &rail(), closure).await.unwrap();
+        assert_eq!(closure.status, ClosureStatus::Completed);
+        assert_eq!(status(&pool, &account).await, Some(AccountStatus::Closed));
+    }
+
+    #[sqlx::test(migrations = "./migrations_accounts")]
+    #[ignore = "needs Postgres: DATABASE_URL=... cargo test -- --ignored"]
+    async fn a_late_return_goes_to_the_sweep_account(pool: PgPool) {
+        let (ledger, ledger_grpc) = FakeLedger::start().await;
+        let organization_id = Uuid::new_v4();
+        let account = testing::account(&pool, organization_id, AccountType::Checking, "USD").await;
+        let destination = testing::account(&pool, organization_id, AccountType::Checking, "USD").await;
+        let payee = payee(&pool, &account).await;
+
+        // A payout of 100 settled before the closure; 300 is left
+        let withdrawal = testing::intent(&pool, &account, &account, TransactionKind::Withdraw, 100, "payout-1").await;
+        TransactionRepository::update_status(&pool, withdrawal.id, TransactionStatus::Posted, None).await.unwrap();
+        let payout = PayoutRepository::create_or_get(&pool, &withdrawal, &payee, "simulated").await.unwrap();
+        PayoutRepository::mark_submitted(&pool, payout.id, "rail-ref").await.unwrap().unwrap();
+        PayoutRepository::mark_settled(&pool, payout.id).await.unwrap().unwrap();
+        ledger.credit(account.id, 300);
+
+        let receipt = close(&pool, &ledger_grpc, &account, request(Some(destination.id), None)).await.unwrap();
+        assert_eq!(receipt.status, ClosureStatus::Completed);
+
+        let payout = PayoutService::record_return(&pool, &ledger_grpc, payout.id, "R01", "Insufficient funds")
+            .await
+            .unwrap();
+        assert_eq!(payout.status, PayoutStatus::Returned);
+        let credit = TransactionRepository::find_by_id(&pool, payout.return_transaction_id.unwrap()).await.unwrap();
+        assert_eq!(credit.to_account_id, destination.id);
+        assert_eq!(credit.status, TransactionStatus::Posted);
+        assert_eq!(ledger.balance(destination.id), 400);
+        assert_eq!(ledger.balance(account.id), 0);
+        assert_eq!(status(&pool, &account).await, Some(AccountStatus::Closed));
+    }
+}
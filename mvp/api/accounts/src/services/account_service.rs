@@ -1,12 +1,37 @@
 use tracing::info;
 use crate::errors::AppError;
 use crate::ledger_grpc::LedgerGrpc;
-use crate::models::{Account, AccountStatus, CreateAccountRequest, TransactionKind, TransactionStatus, PaginatedAccountsResponse};
+use crate::models::{
+    validate_metadata, Account, AccountStatus, AccountStatusChange, AmountInput, CreateAccountRequest, Metadata,
+    NewAccountStatusChange, PaginatedAccountsResponse, StatusReason, TransactionDetails, TransactionKind,
+    TransactionStatus, UpdateAccountRequest,
+};
 use crate::repositories::{AccountRepository, TransactionRepository};
+use crate::fx::FxRates;
+use crate::services::{FeeService, FxService, InterorgService, LimitService};
 use crate::utils::generate_account_number;
-use sqlx::PgPool;
+use crate::utils::pagination::PageRequest;
+use sqlx::{PgConnection, PgPool};
 use uuid::Uuid;
 
+/// Longest note accepted with a status change
+const MAX_STATUS_NOTE_CHARS: usize = 1000;
+
+/// Longest account nickname accepted
+const MAX_NICKNAME_CHARS: usize = 100;
+
+/// Trimmed nickname; an empty one stays empty (it removes the nickname on update).
+fn normalize_nickname(nickname: Option<String>) -> Result<Option<String>, AppError> {
+    let nickname = nickname.map(|n| n.trim().to_string());
+    if nickname.as_ref().is_some_and(|n| n.chars().count() > MAX_NICKNAME_CHARS) {
+        return Err(AppError::Validation(format!(
+            "nickname exceeds {} characters",
+            MAX_NICKNAME_CHARS
+        )));
+    }
+    Ok(nickname)
+}
+
 pub struct AccountService;
 
 impl AccountService {
@@ -14,6 +39,9 @@ impl AccountService {
         pool: &PgPool,
         request: CreateAccountRequest,
     ) -> Result<Account, AppError> {
+        let nickname = normalize_nickname(request.nickname)?.filter(|n| !n.is_empty());
+        validate_metadata(&request.metadata)?;
+
         let account_number = generate_account_number(pool, 12)
             .await?;
 
@@ -28,7 +56,9 @@ impl AccountService {
                 request.user_id,
                 Some(admin_user_id),
                 Some("CUSTOMER".to_string()),  // Customer accounts require admin
-                &request.currency,
+                request.currency.code(),
+                nickname.as_deref(),
+                &request.metadata,
             )
             .await?
         } else {
@@ -39,7 +69,9 @@ impl AccountService {
                 request.organization_id,
                 &request.environment.unwrap_or_else(|| "sandbox".to_string()),
                 request.user_id,
-                &request.currency,
+                request.currency.code(),
+                nickname.as_deref(),
+                &request.metadata,
             )
             .await?
         };
@@ -52,8 +84,13 @@ impl AccountService {
         Ok(account)
     }
 
-    pub async fn get_account(pool: &PgPool, id: Uuid, environment: &str) -> Result<Account, AppError> {
-        AccountRepository::find_by_id(pool, id, environment).await
+    pub async fn get_account(
+        pool: &PgPool,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<Account, AppError> {
+        AccountRepository::find_by_id_for_organization(pool, id, organization_id, environment).await
     }
 
     pub async fn get_accounts_by_user(
@@ -82,106 +119,305 @@ impl AccountService {
 
     pub async fn get_accounts_by_user_paginated(
         pool: &PgPool,
+        organization_id: Uuid,
         user_id: Uuid,
         environment: &str,
-        page: u32,
-        per_page: u32,
+        metadata: &Metadata,
+        page: &PageRequest,
     ) -> Result<PaginatedAccountsResponse, AppError> {
-        AccountRepository::find_by_user_id_paginated(pool, user_id, environment, page, per_page).await
+        AccountRepository::find_by_user_id_paginated(pool, organization_id, user_id, environment, metadata, page)
+            .await
     }
 
     pub async fn get_accounts_by_organization_paginated(
         pool: &PgPool,
         organization_id: Uuid,
         environment: &str,
-        page: u32,
-        per_page: u32,
+        metadata: &Metadata,
+        page: &PageRequest,
     ) -> Result<PaginatedAccountsResponse, AppError> {
-        AccountRepository::find_by_organization_id_paginated(pool, organization_id, environment, page, per_page).await
+        AccountRepository::find_by_organization_id_paginated(pool, organization_id, environment, metadata, page).await
     }
 
     pub async fn get_accounts_by_admin_paginated(
         pool: &PgPool,
+        organization_id: Uuid,
         admin_user_id: Uuid,
         environment: &str,
-        page: u32,
-        per_page: u32,
+        metadata: &Metadata,
+        page: &PageRequest,
     ) -> Result<PaginatedAccountsResponse, AppError> {
-        AccountRepository::find_by_admin_user_id_paginated(pool, admin_user_id, environment, page, per_page).await
+        AccountRepository::find_by_admin_user_id_paginated(
+            pool,
+            organization_id,
+            admin_user_id,
+            environment,
+            metadata,
+            page,
+        )
+        .await
     }
 
+    /// Changes the account's status and/or sets its nickname (an empty one removes it) and
+    /// replaces its metadata. Everything is validated before the status changes; the
+    /// details are applied after it.
+    pub async fn update_account(
+        pool: &PgPool,
+        ledger_grpc: &LedgerGrpc,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+        request: UpdateAccountRequest,
+        actor: (&'static str, Option<Uuid>),
+    ) -> Result<Account, AppError> {
+        let nickname = normalize_nickname(request.nickname)?;
+        if let Some(metadata) = &request.metadata {
+            validate_metadata(metadata)?;
+        }
+        let updates_details = nickname.is_some() || request.metadata.is_some();
+
+        let account = match request.status {
+            Some(status) => {
+                Self::update_account_status(
+                    pool,
+                    ledger_grpc,
+                    id,
+                    organization_id,
+                    environment,
+                    status,
+                    request.reason,
+                    request.note,
+                    actor,
+                )
+                .await?
+            }
+            None if updates_details => {
+                // Scopes the update to the caller's organization
+                AccountRepository::find_by_id_for_organization(pool, id, organization_id, environment).await?
+            }
+            None => {
+                return Err(AppError::Validation(
+                    "status, nickname or metadata is required".to_string(),
+                ))
+            }
+        };
+
+        if !updates_details {
+            return Ok(account);
+        }
+        AccountRepository::update_details(pool, id, environment, nickname.as_deref(), request.metadata.as_ref()).await
+    }
+
+    /// Moves the account along the lifecycle (see `AccountStatus::can_transition_to`) and
+    /// records the change. Closing requires a zero balance and nothing in flight.
+    /// The account row is locked for the whole change, so no intent can be created between
+    /// the checks and the new status.
+    #[allow(clippy::too_many_arguments)]
     pub async fn update_account_status(
         pool: &PgPool,
+        ledger_grpc: &LedgerGrpc,
         id: Uuid,
+        organization_id: Uuid,
         environment: &str,
         status: AccountStatus,
+        reason: Option<StatusReason>,
+        note: Option<String>,
+        actor: (&'static str, Option<Uuid>),
     ) -> Result<Account, AppError> {
-        let account = AccountRepository::find_by_id(pool, id, environment).await?;
+        AccountRepository::find_by_id_for_organization(pool, id, organization_id, environment).await?;
 
-        if account.status == Some(AccountStatus::Closed) && status != AccountStatus::Closed {
-            return Err(AppError::BusinessLogic(
-                "Cannot reactivate a closed account".to_string(),
-            ));
+        let mut tx = pool.begin().await?;
+        let account = AccountRepository::find_by_id_for_update(&mut tx, id, environment).await?;
+        let current = account.status.unwrap_or(AccountStatus::Active);
+
+        // Only the closure workflow moves accounts into and out of closing
+        if current == AccountStatus::Closing || status == AccountStatus::Closing {
+            return Err(AppError::BusinessLogic(format!(
+                "Account closure is managed by POST /api/v1/accounts/{}/close",
+                id
+            )));
+        }
+
+        if !current.can_transition_to(status) {
+            return Err(AppError::BusinessLogic(format!(
+                "Cannot change account status from {} to {}",
+                current.as_str(),
+                status.as_str()
+            )));
+        }
+
+        if status.requires_reason() && reason.is_none() {
+            return Err(AppError::Validation(format!(
+                "reason is required to change account status to {}",
+                status.as_str()
+            )));
+        }
+
+        let note = note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+        if note.as_ref().is_some_and(|n| n.chars().count() > MAX_STATUS_NOTE_CHARS) {
+            return Err(AppError::Validation(format!(
+                "note must be at most {} characters",
+                MAX_STATUS_NOTE_CHARS
+            )));
+        }
+
+        if status == AccountStatus::Closed {
+            Self::ensure_closable(&mut tx, ledger_grpc, &account, environment).await?;
         }
 
         info!(
-            "Updating account {} status to {:?}",
-            id, status
+            "Updating account {} status from {:?} to {:?} ({:?})",
+            id, current, status, reason
         );
 
-        AccountRepository::update_status(pool, id, environment, status).await
+        let (actor_type, actor_id) = actor;
+        let change = NewAccountStatusChange {
+            from_status: current,
+            to_status: status,
+            reason,
+            note,
+            actor_type: actor_type.to_string(),
+            actor_id,
+        };
+        let account = AccountRepository::change_status(&mut tx, id, environment, &change).await?;
+        tx.commit().await?;
+        Ok(account)
     }
 
-    pub async fn close_account(pool: &PgPool, id: Uuid, environment: &str) -> Result<Account, AppError> {
-        Self::update_account_status(pool, id, environment, AccountStatus::Closed).await
+    #[allow(clippy::too_many_arguments)]
+    pub async fn close_account(
+        pool: &PgPool,
+        ledger_grpc: &LedgerGrpc,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+        reason: StatusReason,
+        note: Option<String>,
+        actor: (&'static str, Option<Uuid>),
+    ) -> Result<Account, AppError> {
+        Self::update_account_status(
+            pool,
+            ledger_grpc,
+            id,
+            organization_id,
+            environment,
+            AccountStatus::Closed,
+            Some(reason),
+            note,
+            actor,
+        )
+        .await
     }
 
+    pub async fn get_status_history(
+        pool: &PgPool,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<Vec<AccountStatusChange>, AppError> {
+        AccountRepository::find_by_id_for_organization(pool, id, organization_id, environment).await?;
+        AccountRepository::find_status_history(pool, id).await
+    }
+
+    /// Closure preconditions: no pending intents or unsettled payouts, and a zero Ledger
+    /// balance. The intents are not the book of record, so the closure is refused while the
+    /// Ledger is unreachable.
+    async fn ensure_closable(
+        conn: &mut PgConnection,
+        ledger_grpc: &LedgerGrpc,
+        account: &Account,
+        environment: &str,
+    ) -> Result<(), AppError> {
+        let pending = AccountRepository::count_pending_movements(&mut *conn, account.id).await?;
+        if pending > 0 {
+            return Err(AppError::BusinessLogic(format!(
+                "Cannot close an account with {} pending transactions or payouts",
+                pending
+            )));
+        }
+
+        let balance = Self::ledger_balance(ledger_grpc, account, environment).await.map_err(|e| {
+            tracing::warn!(account_id = %account.id, error = %e, "Ledger balance unavailable; refusing closure");
+            AppError::BusinessLogic("Cannot close the account while its Ledger balance is unavailable; retry later".to_string())
+        })?;
+        if balance != 0 {
+            return Err(AppError::BusinessLogic(format!(
+                "Cannot close an account with a non-zero balance ({} minor units)",
+                balance
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Current Ledger balance of the account in its currency.
+    pub async fn ledger_balance(ledger_grpc: &LedgerGrpc, account: &Account, environment: &str) -> Result<i64, AppError> {
+        let organization_id = account
+            .organization_id
+            .ok_or_else(|| AppError::Validation("organization_id is required".to_string()))?;
+
+        ledger_grpc
+            .get_account_balance(
+                organization_id,
+                environment,
+                account.id.to_string(),
+                account.currency()?.code().to_string(),
+            )
+            .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub async fn deposit_with_idempotency(
         pool: &PgPool,
         account_id: Uuid,
+        organization_id: Uuid,
         environment: &str,
-        amount: i64,
+        amount: &AmountInput,
         idempotency_key: &str,
+        details: TransactionDetails,
         ledger_grpc: &LedgerGrpc,
         correlation_id: Option<String>,
     ) -> Result<(Account, crate::models::Transaction), AppError> {
         if idempotency_key.trim().is_empty() {
             return Err(AppError::Validation("Idempotency-Key header is required".to_string()));
         }
+        let details = details.normalized()?;
 
-        let account = AccountRepository::find_by_id(pool, account_id, environment).await?;
+        let account =
+            AccountRepository::find_by_id_for_organization(pool, account_id, organization_id, environment).await?;
 
-        if account.status != Some(AccountStatus::Active) {
-            return Err(AppError::AccountNotActive);
-        }
+        let currency = account.currency()?;
 
-        let organization_id = account
-            .organization_id
-            .ok_or_else(|| AppError::Validation("organization_id is required".to_string()))?;
-
-        let currency = account
-            .currency
-            .clone()
-            .unwrap_or_else(|| "USD".to_string());
+        let amount = amount.to_minor_units(currency)?;
+        if amount <= 0 {
+            return Err(AppError::Validation("Amount must be greater than zero".to_string()));
+        }
 
         // Use environment from header (already validated), not from account record
         // This ensures we're operating in the correct environment context
 
         let mut tx = pool.begin().await?;
 
+        // The account stays active until the intent commits
+        AccountRepository::lock_active(&mut tx, &[account_id]).await?;
+
         let transaction = TransactionRepository::create_or_get_by_idempotency(
             &mut *tx,
             organization_id,
             account_id,
             account_id,
             amount,
-            &currency,
+            currency.code(),
             TransactionKind::Deposit,
             idempotency_key,
             Some(environment), // Always pass environment for new transactions
+            &details,
         )
         .await?;
 
+        // Fees are recorded with the intent; replays return the fees charged originally
+        FeeService::apply_fees(&mut tx, &transaction, account_id, account.account_type).await?;
+
         tx.commit().await?;
 
         info!(
@@ -202,14 +438,16 @@ impl AccountService {
                 "SYSTEM_CASH_CONTROL".to_string(),
                 account_id.to_string(),
                 amount,
-                currency.clone(),
+                currency.code().to_string(),
                 transaction.id,
                 transaction.idempotency_key.clone(),
-                correlation_id,
+                correlation_id.clone(),
             )
             .await;
 
-        let transaction = match post_result {
+        // The status update re-reads the intent; keep whether this request was a replay
+        let replayed = transaction.replayed;
+        let mut transaction = match post_result {
             Ok(()) => {
                 TransactionRepository::update_status(pool, transaction.id, TransactionStatus::Posted, None).await?
             }
@@ -229,59 +467,93 @@ impl AccountService {
                 .await?
             }
         };
+        transaction.replayed = replayed;
+
+        // Fee legs are posted once the principal is in the Ledger
+        FeeService::post_fees(pool, ledger_grpc, &transaction, &correlation_id).await?;
 
         Ok((account, transaction))
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn withdraw_with_idempotency(
         pool: &PgPool,
         account_id: Uuid,
+        organization_id: Uuid,
         environment: &str,
-        amount: i64,
+        amount: &AmountInput,
         idempotency_key: &str,
+        details: TransactionDetails,
         ledger_grpc: &LedgerGrpc,
         correlation_id: Option<String>,
     ) -> Result<(Account, crate::models::Transaction), AppError> {
+        let details = details.normalized()?;
+
+        let account =
+            AccountRepository::find_by_id_for_organization(pool, account_id, organization_id, environment).await?;
+        let amount = amount.to_minor_units(account.currency()?)?;
+
+        let mut tx = pool.begin().await?;
+        let transaction =
+            Self::create_withdraw_intent(&mut tx, &account, environment, amount, idempotency_key, &details).await?;
+        tx.commit().await?;
+
+        let transaction = Self::post_withdraw(pool, ledger_grpc, environment, transaction, correlation_id).await?;
+
+        Ok((account, transaction))
+    }
+
+    /// Record a withdraw intent and its fees in the caller's database transaction, so records
+    /// that depend on the intent (a payout) commit together with it.
+    pub async fn create_withdraw_intent(
+        conn: &mut PgConnection,
+        account: &Account,
+        environment: &str,
+        amount: i64,
+        idempotency_key: &str,
+        details: &TransactionDetails,
+    ) -> Result<crate::models::Transaction, AppError> {
         // Note: Withdrawals are negative amounts, but we store as positive
         // The ledger will handle the debit/credit logic
         if idempotency_key.trim().is_empty() {
             return Err(AppError::Validation("Idempotency-Key header is required".to_string()));
         }
 
-        let account = AccountRepository::find_by_id(pool, account_id, environment).await?;
-
-        if account.status != Some(AccountStatus::Active) {
-            return Err(AppError::AccountNotActive);
+        if amount <= 0 {
+            return Err(AppError::Validation("Amount must be greater than zero".to_string()));
         }
 
+        // The account stays active until the caller commits the intent
+        AccountRepository::lock_active(&mut *conn, &[account.id]).await?;
+
         let organization_id = account
             .organization_id
             .ok_or_else(|| AppError::Validation("organization_id is required".to_string()))?;
-
-        let currency = account
-            .currency
-            .clone()
-            .unwrap_or_else(|| "USD".to_string());
+        let currency = account.currency()?;
 
         // Use environment from header (already validated), not from account record
         // This ensures we're operating in the correct environment context
 
-        let mut tx = pool.begin().await?;
+        // Velocity and amount limits are checked under lock in the same transaction as the intent
+        LimitService::enforce(&mut *conn, account, environment, TransactionKind::Withdraw, amount, idempotency_key)
+            .await?;
 
         let transaction = TransactionRepository::create_or_get_by_idempotency(
-            &mut *tx,
+            &mut *conn,
             organization_id,
-            account_id,
-            account_id,
+            account.id,
+            account.id,
             amount,
-            &currency,
+            currency.code(),
             TransactionKind::Withdraw,
             idempotency_key,
             Some(environment), // Always pass environment for new transactions
+            details,
         )
         .await?;
 
-        tx.commit().await?;
+        // Fees are recorded with the intent; replays return the fees charged originally
+        FeeService::apply_fees(&mut *conn, &transaction, account.id, account.account_type).await?;
 
         info!(
             organization_id = %organization_id,
@@ -292,23 +564,36 @@ impl AccountService {
             "transaction_intent_created"
         );
 
-        // Attempt to post to Ledger via gRPC (withdraw = account -> SYSTEM_CASH_CONTROL)
+        Ok(transaction)
+    }
+
+    /// Post a committed withdraw intent to the Ledger (account -> SYSTEM_CASH_CONTROL), then its fees.
+    /// A failed post leaves the intent pending for the retry worker.
+    pub async fn post_withdraw(
+        pool: &PgPool,
+        ledger_grpc: &LedgerGrpc,
+        environment: &str,
+        transaction: crate::models::Transaction,
+        correlation_id: Option<String>,
+    ) -> Result<crate::models::Transaction, AppError> {
         let correlation_id = correlation_id.unwrap_or_else(|| transaction.id.to_string());
         let post_result = ledger_grpc
             .post_transaction(
-                organization_id,
-                &environment,
-                account_id.to_string(),
+                transaction.organization_id,
+                environment,
+                transaction.from_account_id.to_string(),
                 "SYSTEM_CASH_CONTROL".to_string(),
-                amount,
-                currency.clone(),
+                transaction.amount,
+                transaction.currency.clone(),
                 transaction.id,
                 transaction.idempotency_key.clone(),
-                correlation_id,
+                correlation_id.clone(),
             )
             .await;
 
-        let transaction = match post_result {
+        // The status update re-reads the intent; keep whether this request was a replay
+        let replayed = transaction.replayed;
+        let mut transaction = match post_result {
             Ok(()) => {
                 TransactionRepository::update_status(pool, transaction.id, TransactionStatus::Posted, None).await?
             }
@@ -328,62 +613,56 @@ impl AccountService {
                 .await?
             }
         };
+        transaction.replayed = replayed;
 
-        Ok((account, transaction))
+        // Fee legs are posted once the principal is in the Ledger
+        FeeService::post_fees(pool, ledger_grpc, &transaction, &correlation_id).await?;
+
+        Ok(transaction)
     }
 
     pub async fn transfer_with_idempotency(
         pool: &PgPool,
         from_account_id: Uuid,
+        organization_id: Uuid,
         environment: &str,
         to_account_id: Uuid,
-        amount: i64,
+        amount: &AmountInput,
         idempotency_key: &str,
+        details: TransactionDetails,
         ledger_grpc: &LedgerGrpc,
+        fx_rates: &FxRates,
+        fx_quote_id: Option<Uuid>,
         correlation_id: Option<String>,
     ) -> Result<(Account, Account, crate::models::Transaction), AppError> {
         if idempotency_key.trim().is_empty() {
             return Err(AppError::Validation("Idempotency-Key header is required".to_string()));
         }
+        let details = details.normalized()?;
 
-        let from_account = AccountRepository::find_by_id(pool, from_account_id, environment).await?;
-
-        if from_account.status != Some(AccountStatus::Active) {
-            return Err(AppError::AccountNotActive);
-        }
-
+        let from_account =
+            AccountRepository::find_by_id_for_organization(pool, from_account_id, organization_id, environment).await?;
 
-        let to_account = AccountRepository::find_by_id(pool, to_account_id, environment).await?;
+        // The destination may belong to another organization that accepted an agreement
+        let to_account =
+            AccountRepository::find_transfer_destination(pool, to_account_id, organization_id, environment).await?;
 
-        if to_account.status != Some(AccountStatus::Active) {
-            return Err(AppError::AccountNotActive);
-        }
-
-        let from_org = from_account
-            .organization_id
-            .ok_or_else(|| AppError::Validation("organization_id is required".to_string()))?;
+        let from_org = organization_id;
         let to_org = to_account
             .organization_id
             .ok_or_else(|| AppError::Validation("organization_id is required".to_string()))?;
 
-        if from_org != to_org {
-            return Err(AppError::Validation(
-                "accounts must belong to the same organization".to_string(),
-            ));
-        }
+        let from_currency = from_account.currency()?;
+        let to_currency = to_account.currency()?;
 
-        let from_currency = from_account
-            .currency
-            .clone()
-            .unwrap_or_else(|| "USD".to_string());
-        let to_currency = to_account
-            .currency
-            .clone()
-            .unwrap_or_else(|| "USD".to_string());
+        let amount = amount.to_minor_units(from_currency)?;
+        if amount <= 0 {
+            return Err(AppError::Validation("Amount must be greater than zero".to_string()));
+        }
 
-        if from_currency != to_currency {
+        if from_currency == to_currency && fx_quote_id.is_some() {
             return Err(AppError::Validation(
-                "currency must match both accounts".to_string(),
+                "fx_quote_id only applies to transfers between different currencies".to_string(),
             ));
         }
 
@@ -392,18 +671,85 @@ impl AccountService {
 
         let mut tx = pool.begin().await?;
 
-        let transaction = TransactionRepository::create_or_get_by_idempotency(
-            &mut *tx,
-            from_org,
-            from_account_id,
-            to_account_id,
-            amount,
-            &from_currency,
-            TransactionKind::Transfer,
-            idempotency_key,
-            Some(environment), // Always pass environment for new transactions
-        )
-        .await?;
+        // Both accounts stay active until the intent commits
+        AccountRepository::lock_active(&mut tx, &[from_account_id, to_account_id]).await?;
+
+        // Velocity and amount limits are checked under lock in the same transaction as the intent
+        LimitService::enforce(&mut tx, &from_account, environment, TransactionKind::Transfer, amount, idempotency_key)
+            .await?;
+
+        let transaction = if from_org != to_org {
+            // Cross-organization transfers need an active agreement covering the destination account
+            let agreement = InterorgService::authorize_transfer(
+                &mut tx,
+                environment,
+                &from_account,
+                &to_account,
+                amount,
+                idempotency_key,
+            )
+            .await?;
+
+            TransactionRepository::create_or_get_interorg_by_idempotency(
+                &mut *tx,
+                from_account_id,
+                to_account_id,
+                amount,
+                from_currency.code(),
+                &agreement,
+                idempotency_key,
+                &details,
+            )
+            .await?
+        } else if from_currency == to_currency {
+            TransactionRepository::create_or_get_by_idempotency(
+                &mut *tx,
+                from_org,
+                from_account_id,
+                to_account_id,
+                amount,
+                from_currency.code(),
+                TransactionKind::Transfer,
+                idempotency_key,
+                Some(environment), // Always pass environment for new transactions
+                &details,
+            )
+            .await?
+        } else {
+            let conversion = match fx_quote_id {
+                Some(quote_id) => {
+                    FxService::quoted_conversion(
+                        &mut tx,
+                        quote_id,
+                        from_org,
+                        environment,
+                        from_currency,
+                        to_currency,
+                        amount,
+                    )
+                    .await?
+                }
+                None => FxService::live_conversion(fx_rates, from_currency, to_currency, amount).await?,
+            };
+
+            let transaction = TransactionRepository::create_or_get_cross_currency_by_idempotency(
+                &mut *tx,
+                from_org,
+                from_account_id,
+                to_account_id,
+                &conversion,
+                idempotency_key,
+                Some(environment),
+                &details,
+            )
+            .await?;
+
+            FxService::consume_quote(&mut tx, &conversion, &transaction).await?;
+            transaction
+        };
+
+        // Fees are recorded with the intent; replays return the fees charged originally
+        FeeService::apply_fees(&mut tx, &transaction, from_account_id, from_account.account_type).await?;
 
         tx.commit().await?;
 
@@ -418,21 +764,29 @@ impl AccountService {
 
         // Attempt to post to Ledger via gRPC (eventual consistency: keep pending on failure)
         let correlation_id = correlation_id.unwrap_or_else(|| transaction.id.to_string());
-        let post_result = ledger_grpc
-            .post_transaction(
-                from_org,
-                &environment,
-                from_account_id.to_string(),
-                to_account_id.to_string(),
-                amount,
-                from_currency.clone(),
-                transaction.id,
-                transaction.idempotency_key.clone(),
-                correlation_id,
-            )
-            .await;
+        let post_result = if transaction.is_cross_organization() {
+            InterorgService::post_to_ledger(ledger_grpc, &transaction, environment, &correlation_id).await
+        } else if transaction.is_cross_currency() {
+            FxService::post_to_ledger(ledger_grpc, &transaction, environment, &correlation_id).await
+        } else {
+            ledger_grpc
+                .post_transaction(
+                    from_org,
+                    &environment,
+                    from_account_id.to_string(),
+                    to_account_id.to_string(),
+                    transaction.amount,
+                    transaction.currency.clone(),
+                    transaction.id,
+                    transaction.idempotency_key.clone(),
+                    correlation_id.clone(),
+                )
+                .await
+        };
 
-        let transaction = match post_result {
+        // The status update re-reads the intent; keep whether this request was a replay
+        let replayed = transaction.replayed;
+        let mut transaction = match post_result {
             Ok(()) => {
                 TransactionRepository::update_status(pool, transaction.id, TransactionStatus::Posted, None).await?
             }
@@ -452,6 +806,10 @@ impl AccountService {
                 .await?
             }
         };
+        transaction.replayed = replayed;
+
+        // Fee legs are posted once the principal is in the Ledger
+        FeeService::post_fees(pool, ledger_grpc, &transaction, &correlation_id).await?;
 
         Ok((from_account, to_account, transaction))
     }
@@ -0,0 +1,125 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::models::{AuditEvent, AuditEventFilter, AuditExportFormat, NewAuditEvent, PaginationMeta};
+use crate::repositories::AuditRepository;
+use crate::utils::pagination::PageRequest;
+
+/// Events in one export at most; larger exports must be split by time range
+const MAX_EXPORT_EVENTS: i64 = 100_000;
+
+const CSV_COLUMNS: [&str; 14] = [
+    "id",
+    "created_at",
+    "organization_id",
+    "environment",
+    "actor_type",
+    "actor_id",
+    "action",
+    "target_type",
+    "target_id",
+    "status_code",
+    "correlation_id",
+    "ip_address",
+    "before",
+    "after",
+];
+
+pub struct AuditService;
+
+impl AuditService {
+    pub async fn record(pool: &PgPool, event: &NewAuditEvent) -> Result<(), AppError> {
+        AuditRepository::insert(pool, event).await
+    }
+
+    pub async fn get_events_paginated(
+        pool: &PgPool,
+        organization_id: Uuid,
+        environment: &str,
+        filter: &AuditEventFilter,
+        page: &PageRequest,
+    ) -> Result<(Vec<AuditEvent>, PaginationMeta), AppError> {
+        AuditRepository::find_paginated(pool, organization_id, environment, filter, page).await
+    }
+
+    /// Every matching event, oldest first, as a CSV or JSON Lines document.
+    pub async fn export(
+        pool: &PgPool,
+        organization_id: Uuid,
+        environment: &str,
+        filter: &AuditEventFilter,
+        format: AuditExportFormat,
+    ) -> Result<String, AppError> {
+        let events =
+            AuditRepository::find_for_export(pool, organization_id, environment, filter, MAX_EXPORT_EVENTS + 1).await?;
+        if events.len() as i64 > MAX_EXPORT_EVENTS {
+            return Err(AppError::Validation(format!(
+                "more than {} audit events match; narrow the from/to range",
+                MAX_EXPORT_EVENTS
+            )));
+        }
+
+        match format {
+            AuditExportFormat::Csv => Ok(to_csv(&events)),
+            AuditExportFormat::Jsonl => {
+                let mut out = String::new();
+                for event in &events {
+                    let line = serde_json::to_string(event)
+                        .map_err(|e| AppError::Internal(format!("failed to serialize audit event: {}", e)))?;
+                    out.push_str(&line);
+                    out.push('\n');
+                }
+                Ok(out)
+            }
+        }
+    }
+}
+
+fn to_csv(events: &[AuditEvent]) -> String {
+    let mut out = CSV_COLUMNS.join(",");
+    out.push_str("\r\n");
+
+    for event in events {
+        let fields = [
+            event.id.to_string(),
+            event.created_at.to_rfc3339(),
+            optional(event.organization_id),
+            event.environment.clone().unwrap_or_default(),
+            event.actor_type.clone(),
+            optional(event.actor_id),
+            event.action.clone(),
+            event.target_type.clone(),
+            optional(event.target_id),
+            event.status_code.to_string(),
+            event.correlation_id.clone().unwrap_or_default(),
+            event.ip_address.clone().unwrap_or_default(),
+            optional(event.before.as_ref()),
+            optional(event.after.as_ref()),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        out.push_str(&row.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+/// RFC 4180 quoting; fields starting with a formula character are prefixed with `'` so
+/// spreadsheets do not evaluate them.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
@@ -0,0 +1,248 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use futures_util::Stream;
+use sqlx::PgPool;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::event_stream::EventNotifier;
+use crate::models::{EventFilter, StreamEvent};
+use crate::repositories::EventRepository;
+use crate::webhook::EVENT_TYPES;
+
+/// Events read from the log per query
+const BATCH_SIZE: i64 = 100;
+
+/// The log is re-read at least this often even without notifications
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+pub struct EventStreamService;
+
+struct StreamState {
+    pool: PgPool,
+    organization_id: Uuid,
+    environment: String,
+    key: String,
+    filter: EventFilter,
+    last_sequence: i64,
+    notices: broadcast::Receiver<String>,
+    pending: VecDeque<StreamEvent>,
+}
+
+impl EventStreamService {
+    /// Live events of an organization and environment. With `last_event_id` the stream first
+    /// replays the logged events after it; otherwise it starts with the next new event.
+    /// The stream ends if the log cannot be read; clients reconnect with their last event id.
+    pub async fn subscribe(
+        pool: &PgPool,
+        notifier: &EventNotifier,
+        organization_id: Uuid,
+        environment: &str,
+        filter: EventFilter,
+        last_event_id: Option<i64>,
+    ) -> Result<impl Stream<Item = StreamEvent>, AppError> {
+        if let Some(event_type) = filter.event_types.iter().find(|t| !EVENT_TYPES.contains(&t.as_str())) {
+            return Err(AppError::Validation(format!(
+                "unknown event type '{}' (expected one of {})",
+                event_type,
+                EVENT_TYPES.join(", ")
+            )));
+        }
+
+        // Subscribe before reading the start position so no notice falls in between
+        let notices = notifier.subscribe();
+        let last_sequence = match last_event_id {
+            Some(id) => id,
+            None => EventRepository::latest_sequence(pool, organization_id, environment).await?,
+        };
+
+        let state = StreamState {
+            pool: pool.clone(),
+            organization_id,
+            environment: environment.to_string(),
+            key: EventNotifier::key(organization_id, environment),
+            filter,
+            last_sequence,
+            notices,
+            pending: VecDeque::new(),
+        };
+
+        Ok(futures_util::stream::unfold(state, Self::next_event))
+    }
+
+    async fn next_event(mut state: StreamState) -> Option<(StreamEvent, StreamState)> {
+        loop {
+            if let Some(event) = state.pending.pop_front() {
+                state.last_sequence = event.sequence;
+                return Some((event, state));
+            }
+
+            match EventRepository::find_after(
+                &state.pool,
+                state.organization_id,
+                &state.environment,
+                state.last_sequence,
+                &state.filter,
+                BATCH_SIZE,
+            )
+            .await
+            {
+                Ok(events) if !events.is_empty() => {
+                    state.pending.extend(events);
+                    continue;
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    warn!(organization_id = %state.organization_id, error = %e, "event_stream_read_failed");
+                    return None;
+                }
+            }
+
+            // Wait for a notice about this organization, a missed-notice gap or the poll interval
+            loop {
+                match tokio::time::timeout(POLL_INTERVAL, state.notices.recv()).await {
+                    Ok(Ok(key)) if key != state.key => continue,
+                    Ok(Ok(_)) | Ok(Err(RecvError::Lagged(_))) | Err(_) => break,
+                    Ok(Err(RecvError::Closed)) => return None,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AccountType, CreateInterorgAgreementRequest, TransactionDetails};
+    use crate::repositories::TransactionRepository;
+    use crate::services::InterorgService;
+    use crate::testing;
+
+    async fn record_event(executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>, organization_id: Uuid) {
+        sqlx::query(
+            "INSERT INTO webhook_events (organization_id, environment, event_type, payload) \
+             VALUES ($1, $2, 'transaction.created', '{}')",
+        )
+        .bind(organization_id)
+        .bind(testing::ENVIRONMENT)
+        .execute(executor)
+        .await
+        .unwrap();
+    }
+
+    async fn positions(pool: &PgPool, organization_id: Uuid) -> Vec<i64> {
+        EventRepository::find_after(pool, organization_id, testing::ENVIRONMENT, 0, &EventFilter::default(), 10)
+            .await
+            .unwrap()
+            .iter()
+            .map(|event| event.sequence)
+            .collect()
+    }
+
+    #[sqlx::test(migrations = "./migrations_accounts")]
+    #[ignore = "needs Postgres: DATABASE_URL=... cargo test -- --ignored"]
+    async fn overlapping_inserts_become_visible_in_position_order(pool: PgPool) {
+        let organization_id = Uuid::new_v4();
+
+        let mut first = pool.begin().await.unwrap();
+        record_event(&mut *first, organization_id).await;
+
+        // Inserted after the first event but would commit before it
+        let second = tokio::spawn({
+            let pool = pool.clone();
+            async move {
+                let mut tx = pool.begin().await.unwrap();
+                record_event(&mut *tx, organization_id).await;
+                tx.commit().await.unwrap();
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        // A reader must not see a later position while an earlier one is still uncommitted
+        assert_eq!(positions(&pool, organization_id).await, Vec::<i64>::new());
+        assert!(!second.is_finished());
+
+        first.commit().await.unwrap();
+        second.await.unwrap();
+        assert_eq!(positions(&pool, organization_id).await, vec![1, 2]);
+
+        // Other organizations' streams are numbered independently
+        let other = Uuid::new_v4();
+        record_event(&pool, other).await;
+        assert_eq!(positions(&pool, other).await, vec![1]);
+    }
+
+    #[sqlx::test(migrations = "./migrations_accounts")]
+    #[ignore = "needs Postgres: DATABASE_URL=... cargo test -- --ignored"]
+    async fn opposing_interorg_transfers_do_not_deadlock(pool: PgPool) {
+        let env = testing::ENVIRONMENT;
+        let (low, high) = {
+            let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+            (a.min(b), a.max(b))
+        };
+        let low_account = testing::account(&pool, low, AccountType::Checking, "USD").await;
+        let high_account = testing::account(&pool, high, AccountType::Checking, "USD").await;
+
+        let mut agreements = Vec::new();
+        for (payer, counterparty) in [(low, &high_account), (high, &low_account)] {
+            let request = CreateInterorgAgreementRequest {
+                organization_id: payer,
+                counterparty_account_id: counterparty.id,
+                max_amount_per_transfer: None,
+                daily_amount: None,
+            };
+            let agreement = InterorgService::create_agreement(&pool, env, request).await.unwrap();
+            let counterparty_organization_id = counterparty.organization_id.unwrap();
+            agreements.push(
+                InterorgService::accept_agreement(&pool, agreement.id, counterparty_organization_id, env)
+                    .await
+                    .unwrap(),
+            );
+        }
+        record_event(&pool, low).await;
+        record_event(&pool, high).await;
+
+        // Holding the lower stream makes both transfers wait with whatever they locked so far
+        let mut blocker = pool.begin().await.unwrap();
+        sqlx::query("SELECT 1 FROM event_stream_heads WHERE organization_id = $1 FOR UPDATE")
+            .bind(low)
+            .execute(&mut *blocker)
+            .await
+            .unwrap();
+
+        let directions = [(&low_account, &high_account, &agreements[0]), (&high_account, &low_account, &agreements[1])];
+        let transfers: Vec<_> = directions
+            .into_iter()
+            .map(|(from, to, agreement)| {
+                let (pool, from, to, agreement) = (pool.clone(), from.id, to.id, agreement.clone());
+                tokio::spawn(async move {
+                    let mut tx = pool.begin().await?;
+                    TransactionRepository::create_or_get_interorg_by_idempotency(
+                        &mut *tx,
+                        from,
+                        to,
+                        100,
+                        "USD",
+                        &agreement,
+                        &Uuid::new_v4().to_string(),
+                        &TransactionDetails::default(),
+                    )
+                    .await?;
+                    tx.commit().await?;
+                    Ok::<_, AppError>(())
+                })
+            })
+            .collect();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        blocker.commit().await.unwrap();
+
+        for transfer in transfers {
+            transfer.await.unwrap().unwrap();
+        }
+        assert_eq!(positions(&pool, low).await, vec![1, 2, 3]);
+        assert_eq!(positions(&pool, high).await, vec![1, 2, 3]);
+    }
+}
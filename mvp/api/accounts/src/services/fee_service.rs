@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+
+use crate::errors::AppError;
+use crate::ledger_grpc::LedgerGrpc;
+use crate::models::{
+    AccountStatus, AccountType, CreateFeeScheduleRequest, FeeSchedule, Transaction, TransactionFee,
+    TransactionKind, TransactionResponse, TransactionStatus,
+};
+use crate::repositories::{AccountRepository, FeeRepository};
+use rust_decimal::Decimal;
+use sqlx::{PgConnection, PgPool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+pub struct FeeService;
+
+impl FeeService {
+    pub async fn create_schedule(
+        pool: &PgPool,
+        environment: &str,
+        request: CreateFeeScheduleRequest,
+    ) -> Result<FeeSchedule, AppError> {
+        if request.name.trim().is_empty() {
+            return Err(AppError::Validation("name is required".to_string()));
+        }
+
+        if request.transaction_kind == TransactionKind::Interest {
+            return Err(AppError::Validation(
+                "fees can only be charged on deposit, withdraw or transfer".to_string(),
+            ));
+        }
+
+        let percentage = request.percentage.unwrap_or(Decimal::ZERO);
+        if percentage < Decimal::ZERO || percentage > Decimal::ONE {
+            return Err(AppError::Validation(
+                "percentage must be a fraction between 0 and 1".to_string(),
+            ));
+        }
+
+        if request.flat_amount < 0
+            || request.min_amount.is_some_and(|v| v < 0)
+            || request.max_amount.is_some_and(|v| v < 0)
+        {
+            return Err(AppError::Validation("fee amounts must not be negative".to_string()));
+        }
+
+        if let (Some(min), Some(max)) = (request.min_amount, request.max_amount) {
+            if min > max {
+                return Err(AppError::Validation("min_amount must not exceed max_amount".to_string()));
+            }
+        }
+
+        if let Some(revenue_account_id) = request.revenue_account_id {
+            let revenue_account = AccountRepository::find_by_id_for_organization(
+                pool,
+                revenue_account_id,
+                request.organization_id,
+                environment,
+            )
+            .await?;
+            if revenue_account.status != Some(AccountStatus::Active) {
+                return Err(AppError::AccountNotActive);
+            }
+            if revenue_account.currency()? != request.currency {
+                return Err(AppError::Validation(
+                    "revenue account currency must match the schedule currency".to_string(),
+                ));
+            }
+        }
+
+        let schedule = FeeRepository::create_schedule(pool, environment, &request, percentage).await?;
+
+        info!(
+            organization_id = %schedule.organization_id,
+            fee_schedule_id = %schedule.id,
+            name = %schedule.name,
+            transaction_kind = ?schedule.transaction_kind,
+            currency = %schedule.currency,
+            "fee_schedule_created"
+        );
+
+        Ok(schedule)
+    }
+
+    pub async fn get_schedules(
+        pool: &PgPool,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<Vec<FeeSchedule>, AppError> {
+        FeeRepository::find_schedules_by_organization(pool, organization_id, environment).await
+    }
+
+    pub async fn deactivate_schedule(
+        pool: &PgPool,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<FeeSchedule, AppError> {
+        let schedule = FeeRepository::deactivate_schedule(pool, id, organization_id, environment).await?;
+        info!(fee_schedule_id = %schedule.id, "fee_schedule_deactivated");
+        Ok(schedule)
+    }
+
+    /// Compute and record the fees for a freshly created transaction intent; a replayed intent
+    /// returns the fees charged originally. Must run in the same database transaction as the
+    /// intent so fees and intent commit together.
+    pub async fn apply_fees(
+        conn: &mut PgConnection,
+        transaction: &Transaction,
+        payer_account_id: Uuid,
+        payer_account_type: AccountType,
+    ) -> Result<Vec<TransactionFee>, AppError> {
+        if transaction.replayed {
+            return FeeRepository::find_by_transaction_id(&mut *conn, transaction.id).await;
+        }
+
+        let environment = transaction.environment.as_deref().unwrap_or("sandbox");
+
+        let schedules = FeeRepository::find_applicable(
+            &mut *conn,
+            transaction.organization_id,
+            environment,
+            transaction.transaction_kind,
+            payer_account_type,
+            &transaction.currency,
+        )
+        .await?;
+
+        for (schedule, amount) in Self::fees_due(transaction.amount, &schedules) {
+            FeeRepository::insert_fee(&mut *conn, transaction, schedule, payer_account_id, amount).await?;
+        }
+
+        FeeRepository::find_by_transaction_id(&mut *conn, transaction.id).await
+    }
+
+    /// Non-zero fee of each schedule for an intent amount.
+    fn fees_due(amount: i64, schedules: &[FeeSchedule]) -> Vec<(&FeeSchedule, i64)> {
+        schedules
+            .iter()
+            .map(|schedule| (schedule, schedule.calculate(amount)))
+            .filter(|(_, amount)| *amount > 0)
+            .collect()
+    }
+
+    /// Post the pending fee legs (payer -> revenue account) of a posted transaction.
+    /// Failures leave the fee pending for the retry worker.
+    pub async fn post_fees(
+        pool: &PgPool,
+        ledger_grpc: &LedgerGrpc,
+        transaction: &Transaction,
+        correlation_id: &str,
+    ) -> Result<(), AppError> {
+        if transaction.status != TransactionStatus::Posted {
+            return Ok(());
+        }
+
+        let fees = FeeRepository::find_by_transaction_id(pool, transaction.id).await?;
+        for fee in fees.iter().filter(|f| f.status == TransactionStatus::Pending) {
+            Self::post_fee(pool, ledger_grpc, fee, correlation_id).await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn post_fee(
+        pool: &PgPool,
+        ledger_grpc: &LedgerGrpc,
+        fee: &TransactionFee,
+        correlation_id: &str,
+    ) -> Result<(), AppError> {
+        let post_result = ledger_grpc
+            .post_transaction(
+                fee.organization_id,
+                &fee.environment,
+                fee.payer_account_id.to_string(),
+                fee.revenue_external_account_id(),
+                fee.amount,
+                fee.currency.clone(),
+                fee.id,
+                fee.idempotency_key.clone(),
+                correlation_id.to_string(),
+            )
+            .await;
+
+        match post_result {
+            Ok(()) => FeeRepository::update_status(pool, fee.id, TransactionStatus::Posted, None).await,
+            Err(e) => {
+                let reason = format!("{}", e);
+                warn!(
+                    transaction_id = %fee.transaction_id,
+                    fee_id = %fee.id,
+                    error = %reason,
+                    "Ledger gRPC fee post failed; leaving fee pending"
+                );
+                FeeRepository::update_status(pool, fee.id, TransactionStatus::Pending, Some(&reason)).await
+            }
+        }
+    }
+
+    /// Build a transaction response including its fee breakdown.
+    pub async fn with_fees(pool: &PgPool, transaction: Transaction) -> Result<TransactionResponse, AppError> {
+        let fees = FeeRepository::find_by_transaction_id(pool, transaction.id).await?;
+        Ok(TransactionResponse::from(transaction).with_fees(fees))
+    }
+
+    /// Build transaction responses including fee breakdowns (single query for all fees).
+    pub async fn with_fees_many(
+        pool: &PgPool,
+        transactions: Vec<Transaction>,
+    ) -> Result<Vec<TransactionResponse>, AppError> {
+        let ids: Vec<Uuid> = transactions.iter().map(|t| t.id).collect();
+        let mut fees_by_transaction: HashMap<Uuid, Vec<TransactionFee>> = HashMap::new();
+        for fee in FeeRepository::find_by_transaction_ids(pool, &ids).await? {
+            fees_by_transaction.entry(fee.transaction_id).or_default().push(fee);
+        }
+
+        Ok(transactions
+            .into_iter()
+            .map(|t| {
+                let fees = fees_by_transaction.remove(&t.id).unwrap_or_default();
+                TransactionResponse::from(t).with_fees(fees)
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Account, Currency};
+    use crate::testing;
+    use chrono::Utc;
+
+    fn schedule(flat_amount: i64, percentage: &str) -> FeeSchedule {
+        FeeSchedule {
+            id: Uuid::new_v4(),
+            organization_id: Uuid::new_v4(),
+            environment: "sandbox".to_string(),
+            name: "transfer fee".to_string(),
+            transaction_kind: TransactionKind::Transfer,
+            account_type: None,
+            currency: "USD".to_string(),
+            flat_amount,
+            percentage: percentage.parse().unwrap(),
+            min_amount: None,
+            max_amount: None,
+            revenue_account_id: None,
+            active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn create_request(organization_id: Uuid, name: &str, currency: &str, flat_amount: i64) -> CreateFeeScheduleRequest {
+        CreateFeeScheduleRequest {
+            organization_id,
+            name: name.to_string(),
+            transaction_kind: TransactionKind::Transfer,
+            account_type: None,
+            currency: Currency::from_code(currency).unwrap(),
+            flat_amount,
+            percentage: None,
+            min_amount: None,
+            max_amount: None,
+            revenue_account_id: None,
+        }
+    }
+
+    async fn charge(pool: &PgPool, from: &Account, to: &Account, idempotency_key: &str) -> (Transaction, Vec<i64>) {
+        let mut tx = pool.begin().await.unwrap();
+        let transaction =
+            testing::intent(&mut *tx, from, to, TransactionKind::Transfer, 10_000, idempotency_key).await;
+        let fees = FeeService::apply_fees(&mut tx, &transaction, from.id, from.account_type).await.unwrap();
+        tx.commit().await.unwrap();
+        (transaction, fees.iter().map(|fee| fee.amount).collect())
+    }
+
+    #[test]
+    fn charges_each_schedule_with_a_fee() {
+        let schedules = [schedule(25, "0"), schedule(0, "0.01"), schedule(0, "0")];
+        let due = FeeService::fees_due(10_000, &schedules);
+        let amounts: Vec<i64> = due.iter().map(|(_, amount)| *amount).collect();
+        assert_eq!(amounts, vec![25, 100]);
+    }
+
+    #[sqlx::test(migrations = "./migrations_accounts")]
+    #[ignore = "needs Postgres: DATABASE_URL=... cargo test -- --ignored"]
+    async fn replays_return_the_original_fees(pool: PgPool) {
+        let organization_id = Uuid::new_v4();
+        let from = testing::account(&pool, organization_id, AccountType::Checking, "USD").await;
+        let to = testing::account(&pool, organization_id, AccountType::Checking, "USD").await;
+        FeeService::create_schedule(&pool, testing::ENVIRONMENT, create_request(organization_id, "flat", "USD", 25))
+            .await
+            .unwrap();
+
+        let (original, fees) = charge(&pool, &from, &to, "fee-replay").await;
+        assert!(!original.replayed);
+        assert_eq!(fees, vec![25]);
+
+        // A schedule created after the original request does not apply to its replay
+        FeeService::create_schedule(&pool, testing::ENVIRONMENT, create_request(organization_id, "later", "USD", 50))
+            .await
+            .unwrap();
+        let (replay, fees) = charge(&pool, &from, &to, "fee-replay").await;
+        assert!(replay.replayed);
+        assert_eq!(replay.id, original.id);
+        assert_eq!(fees, vec![25]);
+
+        let (_, fees) = charge(&pool, &from, &to, "fee-new").await;
+        assert_eq!(fees, vec![25, 50]);
+    }
+
+    #[sqlx::test(migrations = "./migrations_accounts")]
+    #[ignore = "needs Postgres: DATABASE_URL=... cargo test -- --ignored"]
+    async fn schedules_apply_to_intents_in_their_currency(pool: PgPool) {
+        let organization_id = Uuid::new_v4();
+        let usd = testing::account(&pool, organization_id, AccountType::Checking, "USD").await;
+        let jpy = testing::account(&pool, organization_id, AccountType::Checking, "JPY").await;
+        let jpy_to = testing::account(&pool, organization_id, AccountType::Checking, "JPY").await;
+        FeeService::create_schedule(&pool, testing::ENVIRONMENT, create_request(organization_id, "usd", "USD", 100))
+            .await
+            .unwrap();
+
+        let (_, fees) = charge(&pool, &jpy, &jpy_to, "jpy-1").await;
+        assert!(fees.is_empty());
+
+        FeeService::create_schedule(&pool, testing::ENVIRONMENT, create_request(organization_id, "jpy", "JPY", 1))
+            .await
+            .unwrap();
+        let (_, fees) = charge(&pool, &jpy, &jpy_to, "jpy-2").await;
+        assert_eq!(fees, vec![1]);
+
+        let (_, fees) = charge(&pool, &usd, &jpy, "usd-1").await;
+        assert_eq!(fees, vec![100]);
+    }
+}
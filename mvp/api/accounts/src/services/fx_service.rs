@@ -0,0 +1,320 @@
+use chrono::{Duration, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
+use sqlx::{PgConnection, PgPool};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::fx::{FxRateProvider, FxRates};
+use crate::ledger_grpc::LedgerGrpc;
+use crate::models::{CreateFxQuoteRequest, Currency, FxConversion, FxQuote, FxQuoteStatus, Transaction};
+use crate::repositories::FxRepository;
+
+/// Ledger account (one per currency) that balances the two legs of a cross-currency transfer.
+pub const FX_CLEARING_ACCOUNT: &str = "SYSTEM_FX_CLEARING";
+
+pub struct FxService;
+
+impl FxService {
+    /// Seconds a new quote is honoured for (FX_QUOTE_TTL_SECS, default 30).
+    fn quote_ttl() -> Duration {
+        let secs = std::env::var("FX_QUOTE_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(30);
+        Duration::seconds(secs)
+    }
+
+    /// Seconds a locked quote is honoured for (FX_QUOTE_LOCK_SECS, default 300).
+    fn lock_ttl() -> Duration {
+        let secs = std::env::var("FX_QUOTE_LOCK_SECS")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(300);
+        Duration::seconds(secs)
+    }
+
+    /// Convert a minor-unit amount at `rate`, accounting for each currency's exponent
+    /// (e.g. USD 2 -> JPY 0) and rounding half-even to the destination's minor unit.
+    pub fn convert(
+        source_amount: i64,
+        source_currency: Currency,
+        destination_currency: Currency,
+        rate: Decimal,
+    ) -> Result<i64, AppError> {
+        let source_exponent = source_currency.exponent();
+        let destination_exponent = destination_currency.exponent();
+
+        let mut converted = Decimal::from(source_amount) * rate;
+        if destination_exponent >= source_exponent {
+            converted *= Decimal::from(10i64.pow(destination_exponent - source_exponent));
+        } else {
+            converted /= Decimal::from(10i64.pow(source_exponent - destination_exponent));
+        }
+
+        let destination_amount = converted
+            .round_dp_with_strategy(0, RoundingStrategy::MidpointNearestEven)
+            .to_i64()
+            .ok_or_else(|| AppError::Validation("converted amount is out of range".to_string()))?;
+
+        if destination_amount <= 0 {
+            return Err(AppError::Validation(
+                "amount is too small to convert".to_string(),
+            ));
+        }
+
+        Ok(destination_amount)
+    }
+
+    /// Price a conversion at the provider's current rate.
+    pub async fn live_conversion(
+        fx_rates: &FxRates,
+        source_currency: Currency,
+        destination_currency: Currency,
+        source_amount: i64,
+    ) -> Result<FxConversion, AppError> {
+        if source_currency == destination_currency {
+            return Err(AppError::Validation(
+                "source and destination currency must differ".to_string(),
+            ));
+        }
+
+        if source_amount <= 0 {
+            return Err(AppError::Validation(
+                "amount must be greater than zero".to_string(),
+            ));
+        }
+
+        let rate = fx_rates.get_rate(source_currency.code(), destination_currency.code()).await?;
+        let destination_amount = Self::convert(source_amount, source_currency, destination_currency, rate)?;
+
+        Ok(FxConversion {
+            source_currency,
+            source_amount,
+            rate,
+            destination_currency,
+            destination_amount,
+            quote_id: None,
+        })
+    }
+
+    pub async fn create_quote(
+        pool: &PgPool,
+        fx_rates: &FxRates,
+        environment: &str,
+        request: CreateFxQuoteRequest,
+    ) -> Result<FxQuote, AppError> {
+        let source_amount = request.source_amount.to_minor_units(request.source_currency)?;
+        let conversion =
+            Self::live_conversion(fx_rates, request.source_currency, request.destination_currency, source_amount)
+                .await?;
+
+        let expires_at = Utc::now() + Self::quote_ttl();
+        let quote = FxRepository::create_quote(pool, request.organization_id, environment, &conversion, expires_at).await?;
+
+        info!(
+            organization_id = %quote.organization_id,
+            fx_quote_id = %quote.id,
+            source_currency = %quote.source_currency,
+            destination_currency = %quote.destination_currency,
+            rate = %quote.rate,
+            "fx_quote_created"
+        );
+
+        Ok(quote)
+    }
+
+    pub async fn get_quote(
+        pool: &PgPool,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<FxQuote, AppError> {
+        FxRepository::find_quote(pool, id, organization_id, environment).await
+    }
+
+    /// Lock an open quote so its rate is honoured for the lock window.
+    pub async fn lock_quote(
+        pool: &PgPool,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<FxQuote, AppError> {
+        let expires_at = Utc::now() + Self::lock_ttl();
+
+        if let Some(quote) = FxRepository::lock_quote(pool, id, organization_id, environment, expires_at).await? {
+            info!(fx_quote_id = %quote.id, expires_at = %quote.expires_at, "fx_quote_locked");
+            return Ok(quote);
+        }
+
+        let quote = FxRepository::find_quote(pool, id, organization_id, environment).await?;
+        match quote.status {
+            FxQuoteStatus::Locked => Err(AppError::BusinessLogic("FX quote is already locked".to_string())),
+            FxQuoteStatus::Used => Err(AppError::BusinessLogic("FX quote has already been used".to_string())),
+            FxQuoteStatus::Open => Err(AppError::BusinessLogic("FX quote has expired".to_string())),
+        }
+    }
+
+    /// Conversion from a client's quote, row-locked until the surrounding transaction commits.
+    pub async fn quoted_conversion(
+        conn: &mut PgConnection,
+        quote_id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+        source_currency: Currency,
+        destination_currency: Currency,
+        source_amount: i64,
+    ) -> Result<FxConversion, AppError> {
+        let quote = FxRepository::find_quote_for_update(&mut *conn, quote_id, environment).await?;
+
+        if quote.organization_id != organization_id {
+            return Err(AppError::Validation(
+                "FX quote must belong to the same organization".to_string(),
+            ));
+        }
+
+        if quote.source_currency != source_currency.code() || quote.destination_currency != destination_currency.code() {
+            return Err(AppError::Validation(
+                "FX quote currencies must match both accounts".to_string(),
+            ));
+        }
+
+        if quote.source_amount != source_amount {
+            return Err(AppError::Validation(
+                "amount must match the FX quote source_amount".to_string(),
+            ));
+        }
+
+        // Used quotes are only valid for a replay of the transfer that used them (see consume_quote)
+        if quote.status != FxQuoteStatus::Used && quote.expires_at <= Utc::now() {
+            return Err(AppError::BusinessLogic("FX quote has expired".to_string()));
+        }
+
+        Ok(FxConversion {
+            source_currency,
+            source_amount: quote.source_amount,
+            rate: quote.rate,
+            destination_currency,
+            destination_amount: quote.destination_amount,
+            quote_id: Some(quote.id),
+        })
+    }
+
+    /// Mark the conversion's quote as used by the transfer intent. Must run in the same
+    /// database transaction as the intent.
+    pub async fn consume_quote(
+        conn: &mut PgConnection,
+        conversion: &FxConversion,
+        transaction: &Transaction,
+    ) -> Result<(), AppError> {
+        let Some(quote_id) = conversion.quote_id else {
+            return Ok(());
+        };
+
+        // Idempotent replay of an intent created with a different (or no) quote: leave this quote untouched
+        if transaction.fx_quote_id != Some(quote_id) {
+            return Ok(());
+        }
+
+        if !FxRepository::mark_quote_used(&mut *conn, quote_id, transaction.id).await? {
+            return Err(AppError::BusinessLogic("FX quote has already been used".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Post a cross-currency transfer as two Ledger legs through the per-currency
+    /// FX clearing accounts: source -> clearing (source currency), clearing -> destination
+    /// (destination currency). Each leg has its own idempotency key so retries are safe.
+    pub async fn post_to_ledger(
+        ledger_grpc: &LedgerGrpc,
+        transaction: &Transaction,
+        environment: &str,
+        correlation_id: &str,
+    ) -> Result<(), AppError> {
+        let (Some(destination_amount), Some(destination_currency)) =
+            (transaction.destination_amount, transaction.destination_currency.clone())
+        else {
+            return Err(AppError::Internal(format!(
+                "transaction {} has no FX details",
+                transaction.id
+            )));
+        };
+
+        ledger_grpc
+            .post_transaction(
+                transaction.organization_id,
+                environment,
+                transaction.from_account_id.to_string(),
+                FX_CLEARING_ACCOUNT.to_string(),
+                transaction.amount,
+                transaction.currency.clone(),
+                transaction.id,
+                format!("{}:fx:source", transaction.idempotency_key),
+                correlation_id.to_string(),
+            )
+            .await?;
+
+        ledger_grpc
+            .post_transaction(
+                transaction.organization_id,
+                environment,
+                FX_CLEARING_ACCOUNT.to_string(),
+                transaction.to_account_id.to_string(),
+                destination_amount,
+                destination_currency,
+                transaction.id,
+                format!("{}:fx:destination", transaction.idempotency_key),
+                correlation_id.to_string(),
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn convert(amount: i64, from: &str, to: &str, rate: &str) -> Result<i64, AppError> {
+        FxService::convert(
+            amount,
+            Currency::from_code(from).unwrap(),
+            Currency::from_code(to).unwrap(),
+            Decimal::from_str(rate).unwrap(),
+        )
+    }
+
+    #[test]
+    fn rounds_half_to_even() {
+        assert_eq!(convert(3, "USD", "EUR", "0.5").unwrap(), 2);
+        assert_eq!(convert(5, "USD", "EUR", "0.5").unwrap(), 2);
+        assert_eq!(convert(7, "USD", "EUR", "0.5").unwrap(), 4);
+        assert_eq!(convert(101, "USD", "EUR", "1.005").unwrap(), 102);
+        assert_eq!(convert(100, "USD", "EUR", "0.92505").unwrap(), 93);
+    }
+
+    #[test]
+    fn scales_between_currency_exponents() {
+        // 1.25 USD at 150.5 = 188.125 JPY
+        assert_eq!(convert(125, "USD", "JPY", "150.5").unwrap(), 188);
+        // 190 JPY at 0.0065 = 1.235 USD; the half cent rounds to the even 1.24
+        assert_eq!(convert(190, "JPY", "USD", "0.0065").unwrap(), 124);
+        assert_eq!(convert(1000, "KWD", "USD", "3.25").unwrap(), 325);
+        assert_eq!(convert(125, "USD", "KWD", "0.3075").unwrap(), 384);
+    }
+
+    #[test]
+    fn rejects_amounts_that_round_to_nothing() {
+        assert!(matches!(convert(1, "USD", "EUR", "0.5"), Err(AppError::Validation(_))));
+        assert!(matches!(convert(1, "JPY", "USD", "0.0049"), Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn rejects_amounts_out_of_range() {
+        assert!(matches!(convert(i64::MAX, "USD", "JPY", "1000"), Err(AppError::Validation(_))));
+    }
+}
@@ -0,0 +1,210 @@
+use chrono::{Duration, NaiveDate, Utc};
+use sqlx::PgPool;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::hash_chain::{checkpoint_message, Chain, CheckpointSigner, GENESIS_HASH};
+use crate::models::{ChainBreak, ChainCheckpoint, ChainVerification, ChainVerificationReport};
+use crate::repositories::{ChainHead, HashChainRepository};
+
+/// Records read per round trip while verifying
+const VERIFY_BATCH_SIZE: i64 = 1000;
+
+/// Days checkpointed per chain per run; older gaps are filled on later runs
+const MAX_CHECKPOINT_DAYS: usize = 366;
+
+pub struct HashChainService;
+
+impl HashChainService {
+    /// Recomputes both chains of the organization from their first record.
+    pub async fn verify(pool: &PgPool, organization_id: Uuid) -> Result<ChainVerificationReport, AppError> {
+        let heads = HashChainRepository::find_heads(pool, Some(organization_id)).await?;
+
+        let mut chains = Vec::with_capacity(Chain::ALL.len());
+        for chain in Chain::ALL {
+            let head = heads.iter().find(|head| head.chain == chain);
+            chains.push(Self::verify_chain(pool, organization_id, chain, head).await?);
+        }
+
+        Ok(ChainVerificationReport {
+            organization_id,
+            valid: chains.iter().all(|chain| chain.valid),
+            chains,
+            verified_at: Utc::now(),
+        })
+    }
+
+    /// Reports for every organization with a chain, including operator audit events (nil UUID).
+    pub async fn verify_all(pool: &PgPool) -> Result<Vec<ChainVerificationReport>, AppError> {
+        let mut organization_ids: Vec<Uuid> = HashChainRepository::find_heads(pool, None)
+            .await?
+            .into_iter()
+            .map(|head| head.organization_id)
+            .collect();
+        organization_ids.dedup();
+
+        let mut reports = Vec::with_capacity(organization_ids.len());
+        for organization_id in organization_ids {
+            reports.push(Self::verify(pool, organization_id).await?);
+        }
+        Ok(reports)
+    }
+
+    async fn verify_chain(
+        pool: &PgPool,
+        organization_id: Uuid,
+        chain: Chain,
+        head: Option<&ChainHead>,
+    ) -> Result<ChainVerification, AppError> {
+        let (head_seq, head_hash) = match head {
+            Some(head) => (head.last_seq, head.last_hash.clone()),
+            None => (0, GENESIS_HASH.to_string()),
+        };
+
+        let mut verification = ChainVerification {
+            chain: chain.as_str(),
+            records: 0,
+            head_seq,
+            head_hash: head_hash.clone(),
+            valid: true,
+            first_break: None,
+        };
+
+        let mut last_seq = 0;
+        let mut last_hash = GENESIS_HASH.to_string();
+
+        loop {
+            let records =
+                HashChainRepository::find_records(pool, organization_id, chain, last_seq, VERIFY_BATCH_SIZE).await?;
+            let batch_len = records.len() as i64;
+
+            for record in records {
+                let chain_break = if record.chain_seq != last_seq + 1 {
+                    Some(("sequence_gap", (last_seq + 1).to_string(), record.chain_seq.to_string()))
+                } else if record.prev_hash != last_hash {
+                    Some(("prev_hash_mismatch", last_hash.clone(), record.prev_hash.clone()))
+                } else {
+                    let computed = record.content.link(&record.prev_hash);
+                    (computed != record.record_hash).then(|| ("hash_mismatch", computed, record.record_hash.clone()))
+                };
+
+                if let Some((reason, expected, actual)) = chain_break {
+                    verification.valid = false;
+                    verification.first_break = Some(ChainBreak {
+                        chain_seq: record.chain_seq,
+                        record_id: Some(record.id),
+                        reason,
+                        expected,
+                        actual,
+                    });
+                    return Ok(verification);
+                }
+
+                verification.records += 1;
+                last_seq = record.chain_seq;
+                last_hash = record.record_hash;
+            }
+
+            if batch_len < VERIFY_BATCH_SIZE {
+                break;
+            }
+        }
+
+        if last_seq != head_seq || last_hash != head_hash {
+            verification.valid = false;
+            verification.first_break = Some(ChainBreak {
+                chain_seq: last_seq + 1,
+                record_id: None,
+                reason: "head_mismatch",
+                expected: format!("{}:{}", head_seq, head_hash),
+                actual: format!("{}:{}", last_seq, last_hash),
+            });
+        }
+
+        Ok(verification)
+    }
+
+    /// Signs the head of every chain as of the end of each completed UTC day not yet
+    /// checkpointed. Returns the number of checkpoints created.
+    pub async fn create_checkpoints(pool: &PgPool, signer: &CheckpointSigner) -> Result<usize, AppError> {
+        let yesterday = Utc::now().date_naive() - Duration::days(1);
+        let public_key = signer.public_key();
+        let mut created = 0;
+
+        for head in HashChainRepository::find_heads(pool, None).await? {
+            let Some(mut date) =
+                HashChainRepository::find_next_checkpoint_date(pool, head.organization_id, head.chain).await?
+            else {
+                continue;
+            };
+
+            for _ in 0..MAX_CHECKPOINT_DAYS {
+                if date > yesterday {
+                    break;
+                }
+                let Some(end_of_day) = date.succ_opt() else {
+                    break;
+                };
+
+                let link = HashChainRepository::find_link_before(
+                    pool,
+                    head.organization_id,
+                    head.chain,
+                    end_of_day.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc(),
+                )
+                .await?;
+
+                if let Some((chain_seq, record_hash)) = link {
+                    let message = checkpoint_message(head.organization_id, head.chain, date, chain_seq, &record_hash);
+                    HashChainRepository::insert_checkpoint(
+                        pool,
+                        head.organization_id,
+                        head.chain,
+                        date,
+                        chain_seq,
+                        &record_hash,
+                        &signer.sign(&message),
+                        &public_key,
+                    )
+                    .await?;
+                    created += 1;
+                }
+
+                date = end_of_day;
+            }
+        }
+
+        Ok(created)
+    }
+
+    pub async fn get_checkpoints(
+        pool: &PgPool,
+        organization_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<ChainCheckpoint>, AppError> {
+        HashChainRepository::find_checkpoints(pool, organization_id, from, to).await
+    }
+}
+
+/// Background job: sign daily checkpoints of every hash chain.
+pub async fn run(pool: PgPool, signer: CheckpointSigner) {
+    let interval_secs = std::env::var("CHECKPOINT_JOB_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(3600);
+
+    info!(interval_secs, public_key = %signer.public_key(), "Hash chain checkpoint job started");
+
+    loop {
+        match HashChainService::create_checkpoints(&pool, &signer).await {
+            Ok(0) => {}
+            Ok(created) => info!(created, "hash_chain_checkpoints_created"),
+            Err(e) => warn!(error = %e, "hash_chain_checkpoint_job_failed"),
+        }
+
+        tokio::time::sleep(std::time::Duration::from_secs(interval_secs)).await;
+    }
+}
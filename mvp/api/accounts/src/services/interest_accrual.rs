@@ -0,0 +1,486 @@
+use chrono::{Datelike, Days, Months, NaiveDate, NaiveTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+use crate::errors::AppError;
+use crate::ledger_grpc::LedgerGrpc;
+use crate::models::{
+    InterestAccrualCandidate, InterestMethod, InterestPostingPeriod, InterestRateConfig, TransactionDetails,
+    TransactionKind, TransactionStatus,
+};
+use crate::repositories::{InterestRepository, TransactionRepository};
+
+/// Day-count basis for daily accrual (Actual/365 Fixed).
+const DAYS_PER_YEAR: i64 = 365;
+
+/// Background job for saving account interest.
+/// Accrues every completed day from end-of-day balances, then posts each completed month
+/// as an `interest` transaction from SYSTEM_INTEREST_EXPENSE. Days and months missed during
+/// downtime are caught up on the next run.
+pub async fn run(pool: PgPool, ledger_grpc: LedgerGrpc) {
+    let interval_secs = std::env::var("INTEREST_ACCRUAL_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(3600);
+
+    info!(interval_secs, "Interest accrual worker started");
+
+    loop {
+        let today = Utc::now().date_naive();
+
+        match InterestRepository::find_accrual_candidates(&pool).await {
+            Ok(candidates) => {
+                for candidate in candidates {
+                    if let Err(e) = accrue_account(&pool, &candidate, today).await {
+                        warn!(account_id = %candidate.account_id, error = %e, "interest_accrual_failed");
+                    }
+                }
+            }
+            Err(e) => warn!(error = %e, "interest_accrual_failed_to_load_accounts"),
+        }
+
+        // Only months that have fully ended are posted
+        let month_start = today.with_day(1).unwrap_or(today);
+        match InterestRepository::find_unposted_periods(&pool, month_start).await {
+            Ok(periods) => {
+                for period in periods {
+                    if let Err(e) = post_period(&pool, &ledger_grpc, &period).await {
+                        warn!(
+                            account_id = %period.account_id,
+                            period_start = %period.period_start,
+                            error = %e,
+                            "interest_posting_failed"
+                        );
+                    }
+                }
+            }
+            Err(e) => warn!(error = %e, "interest_posting_failed_to_load_periods"),
+        }
+
+        tokio::time::sleep(std::time::Duration::from_secs(interval_secs)).await;
+    }
+}
+
+/// Daily interest for an end-of-day balance: (annual_rate, accrued fractional minor units).
+fn daily_accrual(config: &InterestRateConfig, balance: i64, unposted: Decimal) -> (Decimal, Decimal) {
+    let rate = config.rate_for_balance(balance);
+    if balance <= 0 || rate.is_zero() {
+        return (rate, Decimal::ZERO);
+    }
+
+    let base = match config.interest_method {
+        InterestMethod::Simple => Decimal::from(balance),
+        InterestMethod::Compound => Decimal::from(balance) + unposted,
+    };
+
+    (rate, base * rate / Decimal::from(DAYS_PER_YEAR))
+}
+
+/// Minor units posted for a month of accruals (rounded half-even).
+fn posting_amount(accrued_total: Decimal) -> Result<i64, AppError> {
+    accrued_total
+        .round_dp_with_strategy(0, RoundingStrategy::MidpointNearestEven)
+        .to_i64()
+        .ok_or_else(|| AppError::Internal("accrued interest out of range".to_string()))
+}
+
+/// Accrue every completed day (up to and excluding `today`) that has not been accrued yet.
+async fn accrue_account(
+    pool: &PgPool,
+    candidate: &InterestAccrualCandidate,
+    today: NaiveDate,
+) -> Result<(), AppError> {
+    let mut day = match candidate.last_accrual_date {
+        Some(last) => last + Days::new(1),
+        None => candidate.start_date,
+    };
+
+    if day >= today {
+        return Ok(());
+    }
+
+    // Re-read per run so rate changes apply from the next accrued day
+    let config = match InterestRepository::find_config(
+        pool,
+        candidate.organization_id,
+        &candidate.environment,
+        &candidate.currency,
+    )
+    .await?
+    {
+        Some(config) => config,
+        None => return Ok(()),
+    };
+
+    while day < today {
+        let next_day = day + Days::new(1);
+        let cutoff = next_day.and_time(NaiveTime::MIN).and_utc();
+
+        let mut tx = pool.begin().await?;
+        let balance = TransactionRepository::balance_as_of(&mut *tx, candidate.account_id, cutoff).await?;
+        let unposted = InterestRepository::unposted_total(&mut *tx, candidate.account_id).await?;
+        let (rate, accrued) = daily_accrual(&config, balance, unposted);
+
+        InterestRepository::insert_accrual(
+            &mut *tx,
+            candidate,
+            day,
+            balance,
+            rate,
+            config.interest_method,
+            accrued,
+        )
+        .await?;
+        tx.commit().await?;
+
+        day = next_day;
+    }
+
+    Ok(())
+}
+
+/// Post a completed month of accruals as one `interest` transaction (rounded half-even to minor units).
+async fn post_period(
+    pool: &PgPool,
+    ledger_grpc: &LedgerGrpc,
+    period: &InterestPostingPeriod,
+) -> Result<(), AppError> {
+    let period_end = period
+        .period_start
+        .checked_add_months(Months::new(1))
+        .ok_or_else(|| AppError::Internal("interest period out of range".to_string()))?;
+
+    let amount = posting_amount(period.accrued_total)?;
+
+    let mut tx = pool.begin().await?;
+
+    let transaction = if amount > 0 {
+        // One intent per account per month keeps posting idempotent across restarts
+        let idempotency_key = format!(
+            "interest:{}:{}",
+            period.account_id,
+            period.period_start.format("%Y-%m")
+        );
+
+        Some(
+            TransactionRepository::create_or_get_by_idempotency(
+                &mut *tx,
+                period.organization_id,
+                period.account_id,
+                period.account_id,
+                amount,
+                &period.currency,
+                TransactionKind::Interest,
+                &idempotency_key,
+                Some(&period.environment),
+                &TransactionDetails::default(),
+            )
+            .await?,
+        )
+    } else {
+        None
+    };
+
+    InterestRepository::mark_posted(
+        &mut *tx,
+        period.account_id,
+        period.period_start,
+        period_end,
+        transaction.as_ref().map(|t| t.id),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    info!(
+        account_id = %period.account_id,
+        period_start = %period.period_start,
+        accrued_total = %period.accrued_total,
+        amount,
+        "interest_period_posted"
+    );
+
+    let transaction = match transaction {
+        Some(transaction) if transaction.status == TransactionStatus::Pending => transaction,
+        _ => return Ok(()),
+    };
+
+    // Attempt to post to Ledger via gRPC (eventual consistency: the retry worker picks up pending intents)
+    let post_result = ledger_grpc
+        .post_transaction(
+            period.organization_id,
+            &period.environment,
+            "SYSTEM_INTEREST_EXPENSE".to_string(),
+            period.account_id.to_string(),
+            transaction.amount,
+            transaction.currency.clone(),
+            transaction.id,
+            transaction.idempotency_key.clone(),
+            transaction.id.to_string(),
+        )
+        .await;
+
+    match post_result {
+        Ok(()) => {
+            TransactionRepository::update_status(pool, transaction.id, TransactionStatus::Posted, None).await?;
+        }
+        Err(e) => {
+            let reason = format!("{}", e);
+            warn!(
+                transaction_id = %transaction.id,
+                error = %reason,
+                "Ledger gRPC post failed; leaving transaction pending"
+            );
+            TransactionRepository::update_status(
+                pool,
+                transaction.id,
+                TransactionStatus::Pending,
+                Some(&reason),
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+    use rust_decimal_macros::dec;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::models::{Account, AccountType, InterestRateTier};
+    use crate::testing::{self, FakeLedger, ENVIRONMENT};
+
+    fn date(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    fn tiers(tiers: &[(i64, Decimal)]) -> Vec<InterestRateTier> {
+        tiers
+            .iter()
+            .map(|&(min_balance, annual_rate)| InterestRateTier { min_balance, annual_rate })
+            .collect()
+    }
+
+    fn config(interest_method: InterestMethod, rates: &[(i64, Decimal)]) -> InterestRateConfig {
+        InterestRateConfig {
+            id: Uuid::new_v4(),
+            organization_id: Uuid::new_v4(),
+            environment: ENVIRONMENT.to_string(),
+            currency: "USD".to_string(),
+            interest_method,
+            tiers: tiers(rates),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn simple_interest_ignores_unposted_interest() {
+        let config = config(InterestMethod::Simple, &[(0, dec!(0.0365)), (1_000_000, dec!(0.073))]);
+
+        assert_eq!(daily_accrual(&config, 500_000, dec!(123.4)), (dec!(0.0365), dec!(50)));
+        assert_eq!(daily_accrual(&config, 1_100_000, Decimal::ZERO), (dec!(0.073), dec!(220)));
+        assert_eq!(daily_accrual(&config, 0, dec!(10)).1, Decimal::ZERO);
+        assert_eq!(daily_accrual(&config, -500, Decimal::ZERO).1, Decimal::ZERO);
+    }
+
+    #[test]
+    fn compound_interest_accrues_on_unposted_interest() {
+        let config = config(InterestMethod::Compound, &[(0, dec!(0.365))]);
+
+        assert_eq!(daily_accrual(&config, 1_000_000, Decimal::ZERO).1, dec!(1000));
+        assert_eq!(daily_accrual(&config, 1_000_000, dec!(1000)).1, dec!(1001));
+        assert_eq!(daily_accrual(&config, 1_000_000, dec!(2001)).1, dec!(1002.001));
+        // Balances below every tier earn nothing, whatever has accrued
+        let tiered = self::config(InterestMethod::Compound, &[(100, dec!(0.365))]);
+        assert_eq!(daily_accrual(&tiered, 99, dec!(5)), (Decimal::ZERO, Decimal::ZERO));
+    }
+
+    #[test]
+    fn monthly_totals_round_half_to_even() {
+        assert_eq!(posting_amount(dec!(100.5)).unwrap(), 100);
+        assert_eq!(posting_amount(dec!(101.5)).unwrap(), 102);
+        assert_eq!(posting_amount(dec!(101.51)).unwrap(), 102);
+        assert_eq!(posting_amount(dec!(0.4)).unwrap(), 0);
+    }
+
+    /// A saving account and rate configuration of a new organization, both created on `since`.
+    async fn saving_account(
+        pool: &PgPool,
+        interest_method: InterestMethod,
+        rates: &[(i64, Decimal)],
+        since: &str,
+    ) -> Account {
+        let organization_id = Uuid::new_v4();
+        let account = testing::account(pool, organization_id, AccountType::Saving, "USD").await;
+        let config =
+            InterestRepository::upsert_config(pool, organization_id, ENVIRONMENT, "USD", interest_method, &tiers(rates))
+                .await
+                .unwrap();
+
+        let since: DateTime<Utc> = format!("{}T09:00:00Z", since).parse().unwrap();
+        sqlx::query("UPDATE accounts SET created_at = $2 WHERE id = $1")
+            .bind(account.id)
+            .bind(since)
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE interest_rate_configs SET created_at = $2 WHERE id = $1")
+            .bind(config.id)
+            .bind(since)
+            .execute(pool)
+            .await
+            .unwrap();
+        account
+    }
+
+    async fn deposit(pool: &PgPool, account: &Account, amount: i64, at: &str, posted: bool) {
+        let intent =
+            testing::intent(pool, account, account, TransactionKind::Deposit, amount, &Uuid::new_v4().to_string()).await;
+        if posted {
+            TransactionRepository::update_status(pool, intent.id, TransactionStatus::Posted, None).await.unwrap();
+        }
+        sqlx::query("UPDATE transactions SET created_at = $2 WHERE id = $1")
+            .bind(intent.id)
+            .bind(at.parse::<DateTime<Utc>>().unwrap())
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    /// Accrues every candidate as the worker does on `today`.
+    async fn accrue(pool: &PgPool, today: &str) {
+        for candidate in InterestRepository::find_accrual_candidates(pool).await.unwrap() {
+            accrue_account(pool, &candidate, date(today)).await.unwrap();
+        }
+    }
+
+    async fn accruals(pool: &PgPool, account: &Account) -> Vec<(NaiveDate, i64, Decimal, Decimal)> {
+        sqlx::query_as(
+            r#"
+            SELECT accrual_date, balance, annual_rate, accrued_amount
+            FROM interest_accruals
+            WHERE account_id = $1
+            ORDER BY accrual_date
+            "#,
+        )
+        .bind(account.id)
+        .fetch_all(pool)
+        .await
+        .unwrap()
+    }
+
+    #[sqlx::test(migrations = "./migrations_accounts")]
+    #[ignore = "needs Postgres: DATABASE_URL=... cargo test -- --ignored"]
+    async fn accrues_each_missed_day_once_from_its_end_of_day_balance(pool: PgPool) {
+        let account =
+            saving_account(&pool, InterestMethod::Simple, &[(0, dec!(0.0365)), (1_000_000, dec!(0.073))], "2026-03-30")
+                .await;
+        deposit(&pool, &account, 500_000, "2026-03-30T10:00:00Z", true).await;
+        // Pending intents do not count; the last second of a day does
+        deposit(&pool, &account, 9_000_000, "2026-03-31T12:00:00Z", false).await;
+        deposit(&pool, &account, 600_000, "2026-04-01T23:59:59Z", true).await;
+
+        // Nothing ran since the account was opened: every completed day is caught up
+        accrue(&pool, "2026-04-03").await;
+        let expected = vec![
+            (date("2026-03-30"), 500_000, dec!(0.0365), dec!(50)),
+            (date("2026-03-31"), 500_000, dec!(0.0365), dec!(50)),
+            (date("2026-04-01"), 1_100_000, dec!(0.073), dec!(220)),
+            (date("2026-04-02"), 1_100_000, dec!(0.073), dec!(220)),
+        ];
+        assert_eq!(accruals(&pool, &account).await, expected);
+
+        // Running again the same day, even from a stale candidate, accrues nothing twice
+        accrue(&pool, "2026-04-03").await;
+        let stale = InterestAccrualCandidate {
+            account_id: account.id,
+            organization_id: account.organization_id.unwrap(),
+            environment: ENVIRONMENT.to_string(),
+            currency: "USD".to_string(),
+            start_date: date("2026-03-30"),
+            last_accrual_date: None,
+        };
+        accrue_account(&pool, &stale, date("2026-04-03")).await.unwrap();
+        assert_eq!(accruals(&pool, &account).await, expected);
+
+        accrue(&pool, "2026-04-04").await;
+        assert_eq!(accruals(&pool, &account).await.len(), 5);
+    }
+
+    #[sqlx::test(migrations = "./migrations_accounts")]
+    #[ignore = "needs Postgres: DATABASE_URL=... cargo test -- --ignored"]
+    async fn compound_interest_accrues_on_interest_not_yet_posted(pool: PgPool) {
+        let simple = saving_account(&pool, InterestMethod::Simple, &[(0, dec!(0.365))], "2026-03-01").await;
+        let compound = saving_account(&pool, InterestMethod::Compound, &[(0, dec!(0.365))], "2026-03-01").await;
+        deposit(&pool, &simple, 1_000_000, "2026-03-01T10:00:00Z", true).await;
+        deposit(&pool, &compound, 1_000_000, "2026-03-01T10:00:00Z", true).await;
+
+        accrue(&pool, "2026-03-04").await;
+
+        let amounts = |accruals: Vec<(NaiveDate, i64, Decimal, Decimal)>| -> Vec<Decimal> {
+            accruals.into_iter().map(|(_, _, _, amount)| amount).collect()
+        };
+        assert_eq!(amounts(accruals(&pool, &simple).await), [dec!(1000), dec!(1000), dec!(1000)]);
+        assert_eq!(amounts(accruals(&pool, &compound).await), [dec!(1000), dec!(1001), dec!(1002.001)]);
+    }
+
+    #[sqlx::test(migrations = "./migrations_accounts")]
+    #[ignore = "needs Postgres: DATABASE_URL=... cargo test -- --ignored"]
+    async fn posts_each_completed_month_once(pool: PgPool) {
+        let (ledger, ledger_grpc) = FakeLedger::start().await;
+        let account = saving_account(&pool, InterestMethod::Simple, &[(0, dec!(0.0365))], "2026-03-30").await;
+        deposit(&pool, &account, 500_000, "2026-03-30T10:00:00Z", true).await;
+        accrue(&pool, "2026-04-03").await;
+
+        // Only March has ended
+        let periods = InterestRepository::find_unposted_periods(&pool, date("2026-04-01")).await.unwrap();
+        assert_eq!(periods.len(), 1);
+        assert_eq!((periods[0].period_start, periods[0].accrued_total), (date("2026-03-01"), dec!(100)));
+        post_period(&pool, &ledger_grpc, &periods[0]).await.unwrap();
+
+        let key = format!("interest:{}:2026-03", account.id);
+        let organization_id = account.organization_id.unwrap();
+        let interest = TransactionRepository::find_by_idempotency_key(&pool, organization_id, ENVIRONMENT, &key)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!((interest.amount, interest.status), (100, TransactionStatus::Posted));
+        assert_eq!(ledger.balance(account.id), 100);
+
+        let posted: Vec<(NaiveDate, Option<Uuid>)> = sqlx::query_as(
+            "SELECT accrual_date, transaction_id FROM interest_accruals WHERE account_id = $1 ORDER BY accrual_date",
+        )
+        .bind(account.id)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            posted,
+            [
+                (date("2026-03-30"), Some(interest.id)),
+                (date("2026-03-31"), Some(interest.id)),
+                (date("2026-04-01"), None),
+                (date("2026-04-02"), None),
+            ]
+        );
+
+        // Running again in the same month finds nothing to post; posting the period again
+        // reuses its transaction
+        assert!(InterestRepository::find_unposted_periods(&pool, date("2026-04-01")).await.unwrap().is_empty());
+        post_period(&pool, &ledger_grpc, &periods[0]).await.unwrap();
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM transactions WHERE transaction_kind = 'interest'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(ledger.balance(account.id), 100);
+    }
+}
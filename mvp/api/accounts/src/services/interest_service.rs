@@ -0,0 +1,95 @@
+use std::collections::HashSet;
+
+use crate::errors::AppError;
+use crate::models::{
+    AccountType, InterestRateConfig, PaginatedInterestAccrualsResponse, UpsertInterestRateConfigRequest,
+};
+use crate::repositories::{AccountRepository, InterestRepository};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use tracing::info;
+use uuid::Uuid;
+
+pub struct InterestService;
+
+impl InterestService {
+    pub async fn set_rate_config(
+        pool: &PgPool,
+        environment: &str,
+        request: UpsertInterestRateConfigRequest,
+    ) -> Result<InterestRateConfig, AppError> {
+        if request.tiers.is_empty() {
+            return Err(AppError::Validation("at least one interest rate tier is required".to_string()));
+        }
+
+        let mut seen = HashSet::new();
+        for tier in &request.tiers {
+            if tier.min_balance < 0 {
+                return Err(AppError::Validation("tier min_balance must not be negative".to_string()));
+            }
+            if tier.annual_rate < Decimal::ZERO || tier.annual_rate > Decimal::ONE {
+                return Err(AppError::Validation(
+                    "tier annual_rate must be a fraction between 0 and 1".to_string(),
+                ));
+            }
+            if !seen.insert(tier.min_balance) {
+                return Err(AppError::Validation("tier min_balance values must be unique".to_string()));
+            }
+        }
+
+        let config = InterestRepository::upsert_config(
+            pool,
+            request.organization_id,
+            environment,
+            request.currency.code(),
+            request.interest_method,
+            &request.tiers,
+        )
+        .await?;
+
+        info!(
+            organization_id = %config.organization_id,
+            environment = %config.environment,
+            currency = %config.currency,
+            interest_method = ?config.interest_method,
+            tiers = config.tiers.len(),
+            "interest_rate_config_updated"
+        );
+
+        Ok(config)
+    }
+
+    pub async fn get_rate_configs(
+        pool: &PgPool,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<Vec<InterestRateConfig>, AppError> {
+        InterestRepository::find_configs_by_organization(pool, organization_id, environment).await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_account_accruals(
+        pool: &PgPool,
+        account_id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        page: u32,
+        per_page: u32,
+    ) -> Result<PaginatedInterestAccrualsResponse, AppError> {
+        // Verify account exists in the correct environment before fetching accruals
+        let account =
+            AccountRepository::find_by_id_for_organization(pool, account_id, organization_id, environment).await?;
+
+        if account.account_type != AccountType::Saving {
+            return Err(AppError::Validation("interest accrues on saving accounts only".to_string()));
+        }
+
+        let (data, pagination) =
+            InterestRepository::find_by_account_id_paginated(pool, account_id, from, to, page, per_page).await?;
+
+        Ok(PaginatedInterestAccrualsResponse { data, pagination })
+    }
+}
@@ -0,0 +1,296 @@
+use crate::errors::AppError;
+use crate::ledger_grpc::LedgerGrpc;
+use crate::models::{Account, CreateInterorgAgreementRequest, InterorgAgreement, Transaction};
+use crate::repositories::{AccountRepository, InterorgRepository};
+use sqlx::{PgConnection, PgPool};
+use tracing::info;
+use uuid::Uuid;
+
+/// Ledger external account that bridges the two organizations of a cross-organization
+/// transfer. Each organization has its own clearing account; the payer's is credited and
+/// the payee's is debited, so both organizations' books stay balanced.
+pub const INTERORG_CLEARING_ACCOUNT: &str = "SYSTEM_INTERORG_CLEARING";
+
+pub struct InterorgService;
+
+impl InterorgService {
+    pub async fn create_agreement(
+        pool: &PgPool,
+        environment: &str,
+        request: CreateInterorgAgreementRequest,
+    ) -> Result<InterorgAgreement, AppError> {
+        if request.max_amount_per_transfer.is_some_and(|v| v <= 0)
+            || request.daily_amount.is_some_and(|v| v <= 0)
+        {
+            return Err(AppError::Validation("agreement limits must be greater than zero".to_string()));
+        }
+
+        // The payer learns nothing about accounts outside its organization: the agreement stays
+        // pending, whether or not the account exists, until the organization owning it accepts
+        match AccountRepository::find_by_id_for_organization(
+            pool,
+            request.counterparty_account_id,
+            request.organization_id,
+            environment,
+        )
+        .await
+        {
+            Ok(_) => {
+                return Err(AppError::Validation(
+                    "counterparty account must belong to a different organization".to_string(),
+                ))
+            }
+            Err(AppError::NotFound(_)) => {}
+            Err(e) => return Err(e),
+        }
+
+        if InterorgRepository::find_open(
+            pool,
+            environment,
+            request.organization_id,
+            request.counterparty_account_id,
+        )
+        .await?
+        .is_some()
+        {
+            return Err(AppError::BusinessLogic(
+                "an agreement already exists for this counterparty account".to_string(),
+            ));
+        }
+
+        let agreement = InterorgRepository::create(pool, environment, &request).await?;
+
+        info!(
+            organization_id = %agreement.organization_id,
+            counterparty_account_id = %agreement.counterparty_account_id,
+            agreement_id = %agreement.id,
+            "interorg_agreement_created"
+        );
+
+        Ok(agreement)
+    }
+
+    /// Accept a pending agreement naming one of the organization's accounts. Agreements for
+    /// other organizations' accounts are reported as not found.
+    pub async fn accept_agreement(
+        pool: &PgPool,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<InterorgAgreement, AppError> {
+        let mut tx = pool.begin().await?;
+
+        let pending =
+            InterorgRepository::find_pending_for_counterparty_for_update(&mut *tx, id, organization_id, environment)
+                .await?;
+        AccountRepository::lock_active(&mut tx, &[pending.counterparty_account_id]).await?;
+        let agreement = InterorgRepository::accept(&mut *tx, pending.id, organization_id).await?;
+
+        tx.commit().await?;
+
+        info!(
+            organization_id = %agreement.organization_id,
+            counterparty_organization_id = %organization_id,
+            counterparty_account_id = %agreement.counterparty_account_id,
+            agreement_id = %agreement.id,
+            "interorg_agreement_accepted"
+        );
+
+        Ok(agreement)
+    }
+
+    pub async fn get_agreements(
+        pool: &PgPool,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<Vec<InterorgAgreement>, AppError> {
+        InterorgRepository::find_by_organization(pool, organization_id, environment).await
+    }
+
+    pub async fn revoke_agreement(
+        pool: &PgPool,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<InterorgAgreement, AppError> {
+        let agreement = InterorgRepository::revoke(pool, id, organization_id, environment).await?;
+        info!(agreement_id = %agreement.id, "interorg_agreement_revoked");
+        Ok(agreement)
+    }
+
+    /// Check that a transfer from `from_account` to another organization's `to_account` is
+    /// covered by an active agreement and within its limits. Must run in the same database
+    /// transaction as the intent: the agreement row stays locked until it commits.
+    pub async fn authorize_transfer(
+        conn: &mut PgConnection,
+        environment: &str,
+        from_account: &Account,
+        to_account: &Account,
+        amount: i64,
+        idempotency_key: &str,
+    ) -> Result<InterorgAgreement, AppError> {
+        let organization_id = from_account.organization_id.ok_or_else(|| {
+            AppError::Validation("Account does not belong to an organization".to_string())
+        })?;
+
+        let agreement = InterorgRepository::find_active_for_update(
+            &mut *conn,
+            environment,
+            organization_id,
+            to_account.id,
+        )
+        .await?
+        .ok_or_else(|| {
+            AppError::BusinessLogic(
+                "no active inter-organization agreement covers the destination account".to_string(),
+            )
+        })?;
+
+        if from_account.currency()? != to_account.currency()? {
+            return Err(AppError::BusinessLogic(
+                "cross-organization transfers must be in a single currency".to_string(),
+            ));
+        }
+
+        if let Some(max) = agreement.max_amount_per_transfer {
+            if amount > max {
+                return Err(AppError::BusinessLogic(format!(
+                    "amount exceeds the agreement's per-transfer limit of {}",
+                    max
+                )));
+            }
+        }
+
+        if let Some(daily) = agreement.daily_amount {
+            let used = InterorgRepository::transferred_today(&mut *conn, agreement.id, idempotency_key).await?;
+            if used + amount > daily {
+                return Err(AppError::BusinessLogic(format!(
+                    "amount exceeds the agreement's daily limit of {} ({} already transferred today)",
+                    daily, used
+                )));
+            }
+        }
+
+        Ok(agreement)
+    }
+
+    /// Post a cross-organization transfer as two ledger transactions, one in each organization,
+    /// through their clearing accounts. Each leg has its own idempotency key so a retry after a
+    /// partial failure only re-posts what is missing.
+    pub async fn post_to_ledger(
+        ledger_grpc: &LedgerGrpc,
+        transaction: &Transaction,
+        environment: &str,
+        correlation_id: &str,
+    ) -> Result<(), AppError> {
+        let Some(counterparty_organization_id) = transaction.counterparty_organization_id else {
+            return Err(AppError::Internal(format!(
+                "transaction {} has no counterparty organization",
+                transaction.id
+            )));
+        };
+
+        ledger_grpc
+            .post_transaction(
+                transaction.organization_id,
+                environment,
+                transaction.from_account_id.to_string(),
+                INTERORG_CLEARING_ACCOUNT.to_string(),
+                transaction.amount,
+                transaction.currency.clone(),
+                transaction.id,
+                format!("{}:interorg:source", transaction.idempotency_key),
+                correlation_id.to_string(),
+            )
+            .await?;
+
+        ledger_grpc
+            .post_transaction(
+                counterparty_organization_id,
+                environment,
+                INTERORG_CLEARING_ACCOUNT.to_string(),
+                transaction.to_account_id.to_string(),
+                transaction.amount,
+                transaction.currency.clone(),
+                transaction.id,
+                format!("{}:interorg:destination", transaction.idempotency_key),
+                correlation_id.to_string(),
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AccountType, InterorgAgreementStatus};
+    use crate::testing;
+
+    fn request(organization_id: Uuid, counterparty_account_id: Uuid) -> CreateInterorgAgreementRequest {
+        CreateInterorgAgreementRequest {
+            organization_id,
+            counterparty_account_id,
+            max_amount_per_transfer: None,
+            daily_amount: None,
+        }
+    }
+
+    fn not_found(result: Result<impl std::fmt::Debug, AppError>) -> String {
+        match result {
+            Err(AppError::NotFound(message)) => message,
+            other => panic!("expected not found, got {:?}", other),
+        }
+    }
+
+    #[sqlx::test(migrations = "./migrations_accounts")]
+    #[ignore = "needs Postgres: DATABASE_URL=... cargo test -- --ignored"]
+    async fn agreements_stay_pending_until_the_counterparty_accepts(pool: PgPool) {
+        let (payer, payee, other) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let counterparty_account = testing::account(&pool, payee, AccountType::Checking, "USD").await;
+        let env = testing::ENVIRONMENT;
+
+        // An existing account and an unknown one look the same to the payer
+        let pending = InterorgService::create_agreement(&pool, env, request(payer, counterparty_account.id))
+            .await
+            .unwrap();
+        let unknown = InterorgService::create_agreement(&pool, env, request(payer, Uuid::new_v4()))
+            .await
+            .unwrap();
+        for agreement in [&pending, &unknown] {
+            assert_eq!(agreement.status, InterorgAgreementStatus::Pending);
+            assert_eq!(agreement.counterparty_organization_id, None);
+        }
+
+        // Pending agreements don't allow transfers
+        let message = not_found(
+            AccountRepository::find_transfer_destination(&pool, counterparty_account.id, payer, env).await,
+        );
+        assert_eq!(message, format!("Account with id {} not found in environment {}", counterparty_account.id, env));
+
+        // Only the organization owning the counterparty account can accept
+        not_found(InterorgService::accept_agreement(&pool, pending.id, payer, env).await);
+        not_found(InterorgService::accept_agreement(&pool, pending.id, other, env).await);
+        not_found(InterorgService::accept_agreement(&pool, unknown.id, payee, env).await);
+
+        let accepted = InterorgService::accept_agreement(&pool, pending.id, payee, env).await.unwrap();
+        assert_eq!(accepted.status, InterorgAgreementStatus::Active);
+        assert_eq!(accepted.counterparty_organization_id, Some(payee));
+        assert!(accepted.accepted_at.is_some());
+
+        let destination = AccountRepository::find_transfer_destination(&pool, counterparty_account.id, payer, env)
+            .await
+            .unwrap();
+        assert_eq!(destination.id, counterparty_account.id);
+        not_found(AccountRepository::find_transfer_destination(&pool, counterparty_account.id, other, env).await);
+    }
+
+    #[sqlx::test(migrations = "./migrations_accounts")]
+    #[ignore = "needs Postgres: DATABASE_URL=... cargo test -- --ignored"]
+    async fn agreements_cannot_name_the_payers_own_account(pool: PgPool) {
+        let payer = Uuid::new_v4();
+        let own = testing::account(&pool, payer, AccountType::Checking, "USD").await;
+
+        let result = InterorgService::create_agreement(&pool, testing::ENVIRONMENT, request(payer, own.id)).await;
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+}
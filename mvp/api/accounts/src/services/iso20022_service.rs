@@ -0,0 +1,187 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Days, NaiveDate, NaiveTime, Utc};
+use sqlx::PgPool;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::iso20022::{self, CamtStatement, PainCreditTransfer, PainPaymentInfo};
+use crate::ledger_grpc::LedgerGrpc;
+use crate::models::PayoutStatus;
+use crate::repositories::{AccountRepository, PayeeRepository, PayoutRepository, TransactionRepository};
+use crate::services::AccountService;
+
+/// Longest date range one export may cover
+const MAX_EXPORT_DAYS: u64 = 92;
+
+pub struct Iso20022Service;
+
+impl Iso20022Service {
+    /// camt.053 document with one end-of-day statement per day in `[from, to]`.
+    /// Balances are anchored on the account's current Ledger balance and rolled back through
+    /// the posted movements in `transactions` (Ledger balances cannot be queried retroactively).
+    pub async fn camt053(
+        pool: &PgPool,
+        ledger_grpc: &LedgerGrpc,
+        account_id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<String, AppError> {
+        let account =
+            AccountRepository::find_by_id_for_organization(pool, account_id, organization_id, environment).await?;
+        validate_range(from, to, true)?;
+
+        let currency = account.currency()?;
+        let range_start = start_of_day(from);
+        let range_end = start_of_day(to + Days::new(1));
+
+        // Anchor: balance at the end of the range = Ledger balance now - movements since
+        let now = Utc::now();
+        let movements_since_range =
+            TransactionRepository::posted_movements(pool, account_id, range_end, now + chrono::Duration::days(1))
+                .await?
+                .iter()
+                .map(|m| m.amount)
+                .sum::<i64>();
+        let closing_balance = match AccountService::ledger_balance(ledger_grpc, &account, environment).await {
+            Ok(ledger_balance) => ledger_balance - movements_since_range,
+            Err(e) => {
+                warn!(account_id = %account_id, error = %e, "Ledger balance unavailable; using posted intents for camt.053");
+                TransactionRepository::balance_as_of(pool, account_id, range_end).await?
+            }
+        };
+
+        let movements = TransactionRepository::posted_movements(pool, account_id, range_start, range_end).await?;
+        let mut balance = closing_balance - movements.iter().map(|m| m.amount).sum::<i64>();
+
+        let mut statements = Vec::new();
+        let mut day = from;
+        while day <= to {
+            let day_start = start_of_day(day);
+            let day_end = start_of_day(day + Days::new(1));
+            let entries: Vec<_> = movements
+                .iter()
+                .filter(|m| m.booked_at >= day_start && m.booked_at < day_end)
+                .cloned()
+                .collect();
+
+            let opening_balance = balance;
+            balance += entries.iter().map(|m| m.amount).sum::<i64>();
+
+            statements.push(CamtStatement {
+                id: format!("{}-{}", account.account_number, day.format("%Y%m%d")),
+                sequence_number: day.signed_duration_since(NaiveDate::default()).num_days(),
+                from: day_start,
+                to: day_end - chrono::Duration::seconds(1),
+                account_number: account.account_number.clone(),
+                currency,
+                opening_balance,
+                closing_balance: balance,
+                entries,
+            });
+
+            day = day + Days::new(1);
+        }
+
+        let message_id = format!("CAMT053-{}-{}", account.account_number, now.format("%Y%m%d%H%M%S"));
+        Ok(iso20022::camt053(&message_id, now, &statements))
+    }
+
+    /// pain.001 document of the account's payouts created in `[from, to]`, one payment
+    /// information block per day. Returned payouts are left out; a range without payouts is
+    /// not found (the schema requires at least one payment information block).
+    pub async fn pain001(
+        pool: &PgPool,
+        account_id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<String, AppError> {
+        let account =
+            AccountRepository::find_by_id_for_organization(pool, account_id, organization_id, environment).await?;
+        validate_range(from, to, false)?;
+
+        let currency = account.currency()?;
+        let range_start = start_of_day(from);
+        let range_end = start_of_day(to + Days::new(1));
+        let debtor_id = account
+            .organization_id
+            .map(|id| id.simple().to_string())
+            .unwrap_or_else(|| account.user_id.simple().to_string());
+
+        let payouts = PayoutRepository::find_by_account(pool, account_id, environment).await?;
+        let mut by_day: BTreeMap<NaiveDate, Vec<PainCreditTransfer>> = BTreeMap::new();
+
+        for payout in payouts.into_iter().rev() {
+            if payout.created_at < range_start
+                || payout.created_at >= range_end
+                || payout.status == PayoutStatus::Returned
+            {
+                continue;
+            }
+
+            let payee = PayeeRepository::find_by_id(pool, payout.payee_id, organization_id, environment).await?;
+            by_day.entry(payout.created_at.date_naive()).or_default().push(PainCreditTransfer {
+                end_to_end_id: payout.id.simple().to_string(),
+                amount: payout.amount,
+                creditor_name: payee.name,
+                creditor_routing_number: payee.routing_number,
+                creditor_account_number: payee.account_number,
+                remittance_information: format!("PAYOUT {}", payout.id),
+            });
+        }
+
+        let payment_infos: Vec<PainPaymentInfo> = by_day
+            .into_iter()
+            .map(|(day, transfers)| PainPaymentInfo {
+                id: format!("{}-{}", account.account_number, day.format("%Y%m%d")),
+                requested_execution_date: day,
+                debtor_id: debtor_id.clone(),
+                debtor_account_number: account.account_number.clone(),
+                currency,
+                transfers,
+            })
+            .collect();
+
+        if payment_infos.is_empty() {
+            return Err(AppError::NotFound(format!(
+                "Account {} has no payouts from {} to {}",
+                account_id, from, to
+            )));
+        }
+
+        let now = Utc::now();
+        let message_id = format!("PAIN001-{}-{}", account.account_number, now.format("%Y%m%d%H%M%S"));
+        Ok(iso20022::pain001(&message_id, now, &debtor_id, &payment_infos))
+    }
+}
+
+/// End-of-day statements only cover completed days.
+fn validate_range(from: NaiveDate, to: NaiveDate, completed_days_only: bool) -> Result<(), AppError> {
+    if from > to {
+        return Err(AppError::Validation("from must not be after to".to_string()));
+    }
+
+    if to.signed_duration_since(from).num_days() >= MAX_EXPORT_DAYS as i64 {
+        return Err(AppError::Validation(format!(
+            "date range must not exceed {} days",
+            MAX_EXPORT_DAYS
+        )));
+    }
+
+    if completed_days_only && to >= Utc::now().date_naive() {
+        return Err(AppError::Validation(
+            "statements are only available for completed days (to must be before today)".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn start_of_day(day: NaiveDate) -> DateTime<Utc> {
+    day.and_time(NaiveTime::MIN).and_utc()
+}
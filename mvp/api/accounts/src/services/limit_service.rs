@@ -0,0 +1,333 @@
+use chrono::{DateTime, Datelike, Days, Months, NaiveTime, Utc};
+use sqlx::{PgConnection, PgPool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::models::{
+    Account, LimitType, LimitViolation, TransactionKind, TransactionLimit, UpsertTransactionLimitRequest,
+};
+use crate::repositories::{AccountRepository, LimitRepository, TransactionRepository};
+
+pub struct LimitService;
+
+impl LimitService {
+    pub async fn set_limit(
+        pool: &PgPool,
+        environment: &str,
+        request: UpsertTransactionLimitRequest,
+    ) -> Result<TransactionLimit, AppError> {
+        if request.account_id.is_some() && request.account_type.is_some() {
+            return Err(AppError::Validation(
+                "set either account_id or account_type, not both".to_string(),
+            ));
+        }
+
+        if matches!(
+            request.transaction_kind,
+            Some(TransactionKind::Deposit) | Some(TransactionKind::Interest)
+        ) {
+            return Err(AppError::Validation(
+                "limits can only be set on withdraw or transfer".to_string(),
+            ));
+        }
+
+        let values = [
+            request.max_amount_per_transaction,
+            request.daily_amount,
+            request.monthly_amount,
+            request.daily_count,
+            request.monthly_count,
+        ];
+        if values.iter().all(Option::is_none) {
+            return Err(AppError::Validation("at least one limit value is required".to_string()));
+        }
+        if values.iter().flatten().any(|v| *v < 0) {
+            return Err(AppError::Validation("limit values must not be negative".to_string()));
+        }
+
+        if let Some(account_id) = request.account_id {
+            let account =
+                AccountRepository::find_by_id_for_organization(pool, account_id, request.organization_id, environment)
+                    .await?;
+            if account.currency()? != request.currency {
+                return Err(AppError::Validation(
+                    "currency must match the account currency".to_string(),
+                ));
+            }
+        }
+
+        let limit = LimitRepository::upsert(pool, environment, &request).await?;
+
+        info!(
+            organization_id = %limit.organization_id,
+            transaction_limit_id = %limit.id,
+            scope = ?limit.scope,
+            currency = %limit.currency,
+            "transaction_limit_set"
+        );
+
+        Ok(limit)
+    }
+
+    pub async fn get_limits(
+        pool: &PgPool,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<Vec<TransactionLimit>, AppError> {
+        LimitRepository::find_by_organization(pool, organization_id, environment).await
+    }
+
+    pub async fn delete_limit(
+        pool: &PgPool,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<(), AppError> {
+        LimitRepository::delete(pool, id, organization_id, environment).await?;
+        info!(transaction_limit_id = %id, "transaction_limit_deleted");
+        Ok(())
+    }
+
+    /// Check every limit covering an outgoing transaction from `account`. Must run in the
+    /// database transaction that creates the intent: matching limits are advisory-locked until
+    /// commit, so concurrent requests are checked one at a time against committed usage.
+    /// Replays of an existing idempotency key are not re-checked.
+    pub async fn enforce(
+        conn: &mut PgConnection,
+        account: &Account,
+        environment: &str,
+        transaction_kind: TransactionKind,
+        amount: i64,
+        idempotency_key: &str,
+    ) -> Result<(), AppError> {
+        let organization_id = account
+            .organization_id
+            .ok_or_else(|| AppError::Validation("organization_id is required".to_string()))?;
+
+        if TransactionRepository::exists_by_idempotency(&mut *conn, organization_id, Some(environment), idempotency_key)
+            .await?
+        {
+            return Ok(());
+        }
+
+        let currency = account.currency()?;
+        let limits = LimitRepository::find_applicable(
+            &mut *conn,
+            organization_id,
+            environment,
+            currency.code(),
+            account.id,
+            account.account_type,
+            transaction_kind,
+        )
+        .await?;
+
+        let now = Utc::now();
+        let (day_start, day_end) = day_window(now);
+        let (month_start, month_end) = month_window(now);
+
+        for limit in &limits {
+            LimitRepository::lock(&mut *conn, limit.id).await?;
+
+            if let Some(max) = limit.max_amount_per_transaction {
+                if amount > max {
+                    return Err(violation(limit, LimitType::MaxAmountPerTransaction, max, 0, amount, None));
+                }
+            }
+
+            if limit.daily_amount.is_some() || limit.daily_count.is_some() {
+                let (total, count) = LimitRepository::usage_since(&mut *conn, limit, day_start).await?;
+                if let Some(max) = limit.daily_amount {
+                    if total.saturating_add(amount) > max {
+                        return Err(violation(limit, LimitType::DailyAmount, max, total, amount, Some(day_end)));
+                    }
+                }
+                if let Some(max) = limit.daily_count {
+                    if count + 1 > max {
+                        return Err(violation(limit, LimitType::DailyCount, max, count, 1, Some(day_end)));
+                    }
+                }
+            }
+
+            if limit.monthly_amount.is_some() || limit.monthly_count.is_some() {
+                let (total, count) = LimitRepository::usage_since(&mut *conn, limit, month_start).await?;
+                if let Some(max) = limit.monthly_amount {
+                    if total.saturating_add(amount) > max {
+                        return Err(violation(limit, LimitType::MonthlyAmount, max, total, amount, Some(month_end)));
+                    }
+                }
+                if let Some(max) = limit.monthly_count {
+                    if count + 1 > max {
+                        return Err(violation(limit, LimitType::MonthlyCount, max, count, 1, Some(month_end)));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn violation(
+    limit: &TransactionLimit,
+    limit_type: LimitType,
+    max: i64,
+    used: i64,
+    requested: i64,
+    resets_at: Option<DateTime<Utc>>,
+) -> AppError {
+    warn!(
+        organization_id = %limit.organization_id,
+        transaction_limit_id = %limit.id,
+        limit_type = limit_type.as_str(),
+        limit = max,
+        used = used,
+        requested = requested,
+        "transaction_limit_exceeded"
+    );
+
+    AppError::LimitExceeded(Box::new(LimitViolation {
+        limit_id: limit.id,
+        scope: limit.scope,
+        limit_type,
+        limit: max,
+        used,
+        requested,
+        currency: limit.currency.clone(),
+        resets_at,
+    }))
+}
+
+/// Current UTC day: [00:00 today, 00:00 tomorrow)
+fn day_window(now: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+    let today = now.date_naive();
+    let tomorrow = today.checked_add_days(Days::new(1)).unwrap_or(today);
+    (
+        today.and_time(NaiveTime::MIN).and_utc(),
+        tomorrow.and_time(NaiveTime::MIN).and_utc(),
+    )
+}
+
+/// Current UTC month: [00:00 on the 1st, 00:00 on the 1st of next month)
+fn month_window(now: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+    let first = now.date_naive().with_day(1).unwrap_or(now.date_naive());
+    let next = first.checked_add_months(Months::new(1)).unwrap_or(first);
+    (
+        first.and_time(NaiveTime::MIN).and_utc(),
+        next.and_time(NaiveTime::MIN).and_utc(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AccountType, Currency, Transaction, TransactionStatus};
+    use crate::testing;
+    use chrono::TimeZone;
+
+    fn at(year: i32, month: u32, day: u32, hour: u32, min: u32, sec: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, min, sec).unwrap()
+    }
+
+    fn daily_count_limit(organization_id: Uuid, daily_count: i64) -> UpsertTransactionLimitRequest {
+        UpsertTransactionLimitRequest {
+            organization_id,
+            account_id: None,
+            account_type: None,
+            transaction_kind: None,
+            currency: Currency::from_code("USD").unwrap(),
+            max_amount_per_transaction: None,
+            daily_amount: None,
+            monthly_amount: None,
+            daily_count: Some(daily_count),
+            monthly_count: None,
+        }
+    }
+
+    /// Enforce the limits and create the intent in one transaction, as the withdraw and transfer paths do.
+    async fn transfer(
+        pool: &PgPool,
+        from: &Account,
+        to: &Account,
+        amount: i64,
+        idempotency_key: &str,
+    ) -> Result<Transaction, AppError> {
+        let mut tx = pool.begin().await.unwrap();
+        LimitService::enforce(&mut tx, from, testing::ENVIRONMENT, TransactionKind::Transfer, amount, idempotency_key)
+            .await?;
+        let transaction =
+            testing::intent(&mut *tx, from, to, TransactionKind::Transfer, amount, idempotency_key).await;
+        tx.commit().await.unwrap();
+        Ok(transaction)
+    }
+
+    #[test]
+    fn day_window_runs_from_midnight_to_midnight() {
+        let start = at(2026, 10, 18, 0, 0, 0);
+        let end = at(2026, 10, 19, 0, 0, 0);
+
+        assert_eq!(day_window(start), (start, end));
+        assert_eq!(day_window(at(2026, 10, 18, 23, 59, 59)), (start, end));
+        assert_eq!(day_window(end).0, end);
+    }
+
+    #[test]
+    fn month_window_rolls_over_month_and_year_ends() {
+        assert_eq!(
+            month_window(at(2026, 10, 18, 12, 0, 0)),
+            (at(2026, 10, 1, 0, 0, 0), at(2026, 11, 1, 0, 0, 0))
+        );
+        assert_eq!(
+            month_window(at(2026, 12, 31, 23, 59, 59)),
+            (at(2026, 12, 1, 0, 0, 0), at(2027, 1, 1, 0, 0, 0))
+        );
+        assert_eq!(
+            month_window(at(2028, 2, 29, 8, 0, 0)),
+            (at(2028, 2, 1, 0, 0, 0), at(2028, 3, 1, 0, 0, 0))
+        );
+        assert_eq!(month_window(at(2026, 11, 1, 0, 0, 0)).0, at(2026, 11, 1, 0, 0, 0));
+    }
+
+    #[sqlx::test(migrations = "./migrations_accounts")]
+    #[ignore = "needs Postgres: DATABASE_URL=... cargo test -- --ignored"]
+    async fn rejects_with_the_window_reset_and_skips_replays(pool: PgPool) {
+        let organization_id = Uuid::new_v4();
+        let from = testing::account(&pool, organization_id, AccountType::Checking, "USD").await;
+        let to = testing::account(&pool, organization_id, AccountType::Checking, "USD").await;
+        LimitService::set_limit(&pool, testing::ENVIRONMENT, daily_count_limit(organization_id, 1))
+            .await
+            .unwrap();
+
+        transfer(&pool, &from, &to, 500, "limit-first").await.unwrap();
+
+        let Err(AppError::LimitExceeded(violation)) = transfer(&pool, &from, &to, 500, "limit-second").await else {
+            panic!("second transfer of the day should exceed the daily count");
+        };
+        assert_eq!(violation.limit_type, LimitType::DailyCount);
+        assert_eq!((violation.used, violation.requested), (1, 1));
+        assert_eq!(violation.resets_at, Some(day_window(Utc::now()).1));
+
+        // The replay returns the original intent without counting it again
+        let replay = transfer(&pool, &from, &to, 500, "limit-first").await.unwrap();
+        assert!(replay.replayed);
+    }
+
+    #[sqlx::test(migrations = "./migrations_accounts")]
+    #[ignore = "needs Postgres: DATABASE_URL=... cargo test -- --ignored"]
+    async fn failed_intents_do_not_count(pool: PgPool) {
+        let organization_id = Uuid::new_v4();
+        let from = testing::account(&pool, organization_id, AccountType::Checking, "USD").await;
+        let to = testing::account(&pool, organization_id, AccountType::Checking, "USD").await;
+        LimitService::set_limit(&pool, testing::ENVIRONMENT, daily_count_limit(organization_id, 1))
+            .await
+            .unwrap();
+
+        let first = transfer(&pool, &from, &to, 500, "limit-failed").await.unwrap();
+        TransactionRepository::update_status(&pool, first.id, TransactionStatus::Failed, Some("declined"))
+            .await
+            .unwrap();
+
+        transfer(&pool, &from, &to, 500, "limit-after-failure").await.unwrap();
+    }
+}
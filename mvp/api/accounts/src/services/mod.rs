@@ -1,6 +1,37 @@
 pub mod account_service;
+pub mod account_closure_service;
 pub mod transaction_service;
 pub mod transaction_retry;
+pub mod savings_withdraw;
+pub mod interest_service;
+pub mod interest_accrual;
+pub mod fee_service;
+pub mod fx_service;
+pub mod limit_service;
+pub mod interorg_service;
+pub mod iso20022_service;
+pub mod nacha_service;
+pub mod payout_service;
+pub mod payout_worker;
+pub mod statement_service;
+pub mod webhook_service;
+pub mod event_stream_service;
+pub mod audit_service;
+pub mod hash_chain_service;
 
 pub use account_service::AccountService;
+pub use account_closure_service::AccountClosureService;
 pub use transaction_service::TransactionService;
+pub use interest_service::InterestService;
+pub use fee_service::FeeService;
+pub use fx_service::FxService;
+pub use limit_service::LimitService;
+pub use interorg_service::InterorgService;
+pub use iso20022_service::Iso20022Service;
+pub use nacha_service::NachaService;
+pub use payout_service::PayoutService;
+pub use statement_service::StatementService;
+pub use webhook_service::WebhookService;
+pub use event_stream_service::EventStreamService;
+pub use audit_service::AuditService;
+pub use hash_chain_service::HashChainService;
@@ -0,0 +1,245 @@
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+use sqlx::PgPool;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::ledger_grpc::LedgerGrpc;
+use crate::models::{BankAccountType, NachaFile, NachaReturnsResult, Payout};
+use crate::nacha::{self, NachaBatch, NachaEntry, MAX_ENTRY_AMOUNT};
+use crate::payout_rail::{NachaPayoutRail, PayoutRail, PayoutRails};
+use crate::repositories::{NachaRepository, NewNachaFile, PayeeRepository, PayoutRepository, TransactionRepository};
+use crate::services::PayoutService;
+
+pub struct NachaService;
+
+impl NachaService {
+    /// Write the NACHA file for the current settlement window: every posted payout still
+    /// pending on the NACHA rail, one batch per organization. Included payouts are marked
+    /// submitted (rail reference = trace number) and their withdraw intents record the file
+    /// and trace number. Returns None when nothing is ready.
+    pub async fn generate_file(
+        pool: &PgPool,
+        rail: &NachaPayoutRail,
+        environment: &str,
+    ) -> Result<Option<NachaFile>, AppError> {
+        let window_end = Utc::now();
+        let mut tx = pool.begin().await?;
+
+        NachaRepository::lock_environment(&mut *tx, environment).await?;
+
+        let payouts = PayoutRepository::find_ready_for_file(&mut *tx, environment, rail.name(), window_end).await?;
+        if payouts.is_empty() {
+            return Ok(None);
+        }
+
+        let mut batches: BTreeMap<Uuid, NachaBatch> = BTreeMap::new();
+        let mut included: Vec<(Payout, String)> = Vec::new();
+
+        for payout in payouts {
+            if payout.amount > MAX_ENTRY_AMOUNT {
+                warn!(payout_id = %payout.id, amount = payout.amount, "Payout exceeds the NACHA entry amount limit; skipping");
+                continue;
+            }
+
+            let payee =
+                PayeeRepository::find_by_id(&mut *tx, payout.payee_id, payout.organization_id, environment).await?;
+            let trace_number = rail.config.trace_number(NachaRepository::next_trace_sequence(&mut *tx).await?);
+
+            batches
+                .entry(payout.organization_id)
+                .or_insert_with(|| NachaBatch {
+                    company_discretionary_data: payout.organization_id.simple().to_string(),
+                    entries: Vec::new(),
+                })
+                .entries
+                .push(NachaEntry {
+                    transaction_code: match payee.bank_account_type {
+                        BankAccountType::Checking => "22",
+                        BankAccountType::Savings => "32",
+                    },
+                    routing_number: payee.routing_number,
+                    account_number: payee.account_number,
+                    amount: payout.amount,
+                    individual_id: payout.id.simple().to_string(),
+                    individual_name: payee.name,
+                    payment_info: format!("PAYOUT {}", payout.id),
+                    trace_number: trace_number.clone(),
+                });
+
+            included.push((payout, trace_number));
+        }
+
+        if included.is_empty() {
+            return Ok(None);
+        }
+
+        let created_at = Utc::now();
+        let file_creation_date = created_at.date_naive();
+        let files_today = NachaRepository::count_files_for_date(&mut *tx, environment, file_creation_date).await?;
+        let file_id_modifier = nacha::file_id_modifier(files_today).ok_or_else(|| {
+            AppError::BusinessLogic(format!(
+                "the daily limit of NACHA files for {} has been reached",
+                file_creation_date
+            ))
+        })?;
+
+        let batches: Vec<NachaBatch> = batches.into_values().collect();
+        let contents = nacha::build_file(
+            &rail.config,
+            &batches,
+            created_at,
+            file_id_modifier,
+            nacha::next_business_day(file_creation_date),
+        )?;
+
+        let file_name = format!(
+            "{}-{}-{}.ach",
+            environment,
+            created_at.format("%Y%m%d"),
+            file_id_modifier
+        );
+        let file_path = rail.config.output_dir.join(&file_name);
+        let file_path_str = file_path.to_string_lossy().to_string();
+
+        let file = NachaRepository::create(
+            &mut *tx,
+            &NewNachaFile {
+                environment,
+                file_name: &file_name,
+                file_path: &file_path_str,
+                file_creation_date,
+                file_id_modifier,
+                window_end,
+            },
+            &contents,
+        )
+        .await?;
+
+        for (payout, trace_number) in &included {
+            TransactionRepository::mark_nacha_entry(&mut *tx, payout.transaction_id, file.id, trace_number).await?;
+            PayoutRepository::mark_submitted(&mut *tx, payout.id, trace_number).await?;
+        }
+
+        // Written under a temporary name so a file only appears once the database agrees it exists
+        let temp_path = file_path.with_extension("ach.tmp");
+        tokio::fs::create_dir_all(&rail.config.output_dir)
+            .await
+            .map_err(|e| AppError::Internal(format!("failed to create NACHA output directory: {}", e)))?;
+        tokio::fs::write(&temp_path, contents.contents.as_bytes())
+            .await
+            .map_err(|e| AppError::Internal(format!("failed to write NACHA file {}: {}", file_name, e)))?;
+
+        if let Err(e) = tx.commit().await {
+            let _ = tokio::fs::remove_file(&temp_path).await;
+            return Err(e.into());
+        }
+
+        tokio::fs::rename(&temp_path, &file_path)
+            .await
+            .map_err(|e| AppError::Internal(format!("failed to finalize NACHA file {}: {}", file_name, e)))?;
+
+        info!(
+            nacha_file_id = %file.id,
+            file_name = %file.file_name,
+            batch_count = file.batch_count,
+            entry_count = included.len(),
+            total_credit_amount = file.total_credit_amount,
+            "nacha_file_generated"
+        );
+
+        Ok(Some(file))
+    }
+
+    pub async fn get_files(pool: &PgPool, environment: &str) -> Result<Vec<NachaFile>, AppError> {
+        NachaRepository::find_recent(pool, environment, 100).await
+    }
+
+    /// File record and its contents as written to the output directory.
+    pub async fn get_file_contents(pool: &PgPool, id: Uuid, environment: &str) -> Result<(NachaFile, String), AppError> {
+        let file = NachaRepository::find_by_id(pool, id, environment).await?;
+        let contents = tokio::fs::read_to_string(&file.file_path)
+            .await
+            .map_err(|e| AppError::Internal(format!("failed to read NACHA file {}: {}", file.file_name, e)))?;
+        Ok((file, contents))
+    }
+
+    /// Apply a NACHA return file: each returned entry is matched to its withdraw intent by
+    /// original trace number and the payout is returned (crediting the account back).
+    pub async fn process_return_file(
+        pool: &PgPool,
+        ledger_grpc: &LedgerGrpc,
+        environment: &str,
+        contents: &str,
+    ) -> Result<NachaReturnsResult, AppError> {
+        let returns = nacha::parse_returns(contents)?;
+
+        let mut result = NachaReturnsResult {
+            returned: Vec::new(),
+            unmatched_trace_numbers: Vec::new(),
+        };
+
+        for entry in returns {
+            let payout = match TransactionRepository::find_by_nacha_trace_number(
+                pool,
+                environment,
+                &entry.original_trace_number,
+            )
+            .await?
+            {
+                Some(transaction) => PayoutRepository::find_by_transaction_id(pool, transaction.id).await?,
+                None => None,
+            };
+
+            let Some(payout) = payout.filter(|p| p.amount == entry.amount) else {
+                warn!(
+                    trace_number = %entry.original_trace_number,
+                    return_code = %entry.return_code,
+                    "NACHA return does not match a payout"
+                );
+                result.unmatched_trace_numbers.push(entry.original_trace_number);
+                continue;
+            };
+
+            let payout = PayoutService::record_return(
+                pool,
+                ledger_grpc,
+                payout.id,
+                &entry.return_code,
+                nacha::return_reason(&entry.return_code),
+            )
+            .await?;
+            result.returned.push(payout);
+        }
+
+        Ok(result)
+    }
+}
+
+/// Background job producing one NACHA file per environment each settlement window
+/// (NACHA_FILE_INTERVAL_SECS). Only runs when PAYOUT_RAIL=nacha.
+pub async fn run(pool: PgPool, payout_rail: PayoutRails) {
+    let Ok(rail) = payout_rail.nacha() else {
+        return;
+    };
+
+    let interval_secs = std::env::var("NACHA_FILE_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(3600);
+
+    info!(interval_secs, "NACHA file worker started");
+
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(interval_secs)).await;
+
+        for environment in ["sandbox", "production"] {
+            if let Err(e) = NachaService::generate_file(&pool, rail, environment).await {
+                warn!(environment, error = %e, "nacha_file_generation_failed");
+            }
+        }
+    }
+}
@@ -0,0 +1,360 @@
+use crate::errors::AppError;
+use crate::ledger_grpc::LedgerGrpc;
+use crate::models::{
+    AccountStatus, CreatePayeeRequest, CreatePayoutRequest, Payee, PayeeStatus, Payout, PayoutStatus, Transaction,
+    TransactionDetails, TransactionKind, TransactionStatus,
+};
+use crate::payout_rail::{PayoutRail, PayoutRails, RailStatus};
+use crate::repositories::{
+    AccountClosureRepository, AccountRepository, PayeeRepository, PayoutRepository, TransactionRepository,
+};
+use crate::services::AccountService;
+use crate::utils::is_valid_routing_number;
+use sqlx::{PgConnection, PgPool};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+pub struct PayoutService;
+
+impl PayoutService {
+    pub async fn create_payee(
+        pool: &PgPool,
+        environment: &str,
+        request: CreatePayeeRequest,
+    ) -> Result<Payee, AppError> {
+        if request.name.trim().is_empty() {
+            return Err(AppError::Validation("name is required".to_string()));
+        }
+
+        if !is_valid_routing_number(&request.routing_number) {
+            return Err(AppError::Validation("routing_number is not a valid ABA routing number".to_string()));
+        }
+
+        if !(4..=17).contains(&request.account_number.len())
+            || !request.account_number.bytes().all(|b| b.is_ascii_digit())
+        {
+            return Err(AppError::Validation("account_number must be 4 to 17 digits".to_string()));
+        }
+
+        let payee = PayeeRepository::create(pool, environment, &request).await?;
+
+        info!(
+            organization_id = %payee.organization_id,
+            user_id = %payee.user_id,
+            payee_id = %payee.id,
+            "payee_created"
+        );
+
+        Ok(payee)
+    }
+
+    pub async fn get_payees(
+        pool: &PgPool,
+        organization_id: Uuid,
+        user_id: Option<Uuid>,
+        environment: &str,
+    ) -> Result<Vec<Payee>, AppError> {
+        PayeeRepository::find_by_organization(pool, organization_id, user_id, environment).await
+    }
+
+    pub async fn deactivate_payee(
+        pool: &PgPool,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<Payee, AppError> {
+        let payee = PayeeRepository::deactivate(pool, id, organization_id, environment).await?;
+        info!(payee_id = %payee.id, "payee_deactivated");
+        Ok(payee)
+    }
+
+    /// Debit the account with a withdraw intent and send the funds to one of the owner's payees.
+    /// The Idempotency-Key identifies the payout: replays return the original payout.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_payout(
+        pool: &PgPool,
+        ledger_grpc: &LedgerGrpc,
+        payout_rail: &PayoutRails,
+        account_id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+        request: CreatePayoutRequest,
+        idempotency_key: &str,
+    ) -> Result<(Payout, Transaction), AppError> {
+        let account =
+            AccountRepository::find_by_id_for_organization(pool, account_id, organization_id, environment).await?;
+        let payee = PayeeRepository::find_by_id(pool, request.payee_id, organization_id, environment).await?;
+
+        if account.user_id != payee.user_id {
+            return Err(AppError::Validation("payee must belong to the account owner".to_string()));
+        }
+
+        if payee.status != PayeeStatus::Active {
+            return Err(AppError::BusinessLogic("payee is not active".to_string()));
+        }
+
+        let currency = account.currency()?;
+        if !payout_rail.supports_currency(currency.code()) {
+            return Err(AppError::BusinessLogic(format!(
+                "the {} payout rail does not support {}",
+                payout_rail.name(),
+                currency.code()
+            )));
+        }
+
+        let amount = request.amount.to_minor_units(currency)?;
+
+        // The intent and the payout commit together, so a debit never exists without its payout
+        let mut tx = pool.begin().await?;
+        let transaction = AccountService::create_withdraw_intent(
+            &mut tx,
+            &account,
+            environment,
+            amount,
+            idempotency_key,
+            &TransactionDetails::default(),
+        )
+        .await?;
+
+        if transaction.transaction_kind != TransactionKind::Withdraw || transaction.from_account_id != account_id {
+            return Err(AppError::IdempotencyConflict(format!(
+                "Idempotency-Key '{}' was already used for a different request",
+                idempotency_key
+            )));
+        }
+
+        let payout = PayoutRepository::create_or_get(&mut *tx, &transaction, &payee, payout_rail.name()).await?;
+        tx.commit().await?;
+
+        let transaction = AccountService::post_withdraw(pool, ledger_grpc, environment, transaction, None).await?;
+
+        info!(
+            organization_id = %payout.organization_id,
+            payout_id = %payout.id,
+            transaction_id = %transaction.id,
+            payee_id = %payee.id,
+            status = ?payout.status,
+            "payout_created"
+        );
+
+        // Funds leave only once the debit is in the Ledger; otherwise the worker submits later
+        let payout = if payout.status == PayoutStatus::Pending
+            && transaction.status == TransactionStatus::Posted
+            && payout_rail.submits_individually()
+        {
+            Self::submit(pool, payout_rail, payout, &payee).await?
+        } else {
+            payout
+        };
+
+        Ok((payout, transaction))
+    }
+
+    pub async fn get_payout(
+        pool: &PgPool,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<Payout, AppError> {
+        PayoutRepository::find_by_id(pool, id, organization_id, environment).await
+    }
+
+    pub async fn get_account_payouts(
+        pool: &PgPool,
+        account_id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<Vec<Payout>, AppError> {
+        let _account =
+            AccountRepository::find_by_id_for_organization(pool, account_id, organization_id, environment).await?;
+        PayoutRepository::find_by_account(pool, account_id, environment).await
+    }
+
+    /// Hand a pending payout to the rail. Rail errors are recorded and the payout stays pending.
+    pub async fn submit(
+        pool: &PgPool,
+        payout_rail: &PayoutRails,
+        payout: Payout,
+        payee: &Payee,
+    ) -> Result<Payout, AppError> {
+        match payout_rail.submit(&payout, payee).await {
+            Ok(rail_reference) => {
+                let Some(submitted) = PayoutRepository::mark_submitted(pool, payout.id, &rail_reference).await? else {
+                    return PayoutRepository::find_by_id(
+                        pool,
+                        payout.id,
+                        payout.organization_id,
+                        &payout.environment,
+                    )
+                    .await;
+                };
+                info!(payout_id = %submitted.id, rail_reference = %rail_reference, "payout_submitted");
+                Ok(submitted)
+            }
+            Err(e) => {
+                let reason = format!("{}", e);
+                warn!(payout_id = %payout.id, error = %reason, "Payout rail submission failed; leaving payout pending");
+                PayoutRepository::record_failure(pool, payout.id, &reason).await
+            }
+        }
+    }
+
+    /// Move a payout forward from its current state: submit it once its debit is posted,
+    /// or apply the rail's settlement/return outcome once submitted.
+    pub async fn process(
+        pool: &PgPool,
+        ledger_grpc: &LedgerGrpc,
+        payout_rail: &PayoutRails,
+        payout: Payout,
+    ) -> Result<Payout, AppError> {
+        let payee =
+            PayeeRepository::find_by_id(pool, payout.payee_id, payout.organization_id, &payout.environment).await?;
+
+        match payout.status {
+            PayoutStatus::Pending => {
+                let transaction = TransactionRepository::find_by_id(pool, payout.transaction_id).await?;
+                if transaction.status != TransactionStatus::Posted || !payout_rail.submits_individually() {
+                    return Ok(payout);
+                }
+                Self::submit(pool, payout_rail, payout, &payee).await
+            }
+            PayoutStatus::Submitted => match payout_rail.status(&payout, &payee).await? {
+                RailStatus::Submitted => Ok(payout),
+                RailStatus::Settled => {
+                    let Some(settled) = PayoutRepository::mark_settled(pool, payout.id).await? else {
+                        return PayoutRepository::find_by_id(
+                            pool,
+                            payout.id,
+                            payout.organization_id,
+                            &payout.environment,
+                        )
+                        .await;
+                    };
+                    info!(payout_id = %settled.id, "payout_settled");
+                    Ok(settled)
+                }
+                RailStatus::Returned { code, reason } => {
+                    Self::record_return(pool, ledger_grpc, payout.id, &code, &reason).await
+                }
+            },
+            PayoutStatus::Settled | PayoutStatus::Returned => Ok(payout),
+        }
+    }
+
+    /// Record a return from the receiving bank and credit the payout amount back to the account
+    /// (SYSTEM_CASH_CONTROL -> account), or to the account it was swept into if it has closed
+    /// since (see `return_account`). Recording the same return again is a no-op.
+    pub async fn record_return(
+        pool: &PgPool,
+        ledger_grpc: &LedgerGrpc,
+        payout_id: Uuid,
+        return_code: &str,
+        return_reason: &str,
+    ) -> Result<Payout, AppError> {
+        let mut tx = pool.begin().await?;
+
+        let payout = PayoutRepository::find_by_id_for_update(&mut *tx, payout_id).await?;
+        match payout.status {
+            PayoutStatus::Returned => return Ok(payout),
+            PayoutStatus::Submitted | PayoutStatus::Settled => {}
+            PayoutStatus::Pending => {
+                return Err(AppError::BusinessLogic(format!(
+                    "payout {} was never submitted and cannot be returned",
+                    payout_id
+                )));
+            }
+        }
+
+        let account_id = Self::return_account(&mut tx, &payout).await?;
+        let credit = TransactionRepository::create_or_get_by_idempotency(
+            &mut *tx,
+            payout.organization_id,
+            account_id,
+            account_id,
+            payout.amount,
+            &payout.currency,
+            TransactionKind::Deposit,
+            &format!("payout-return:{}", payout.id),
+            Some(&payout.environment),
+            &TransactionDetails::default(),
+        )
+        .await?;
+
+        let payout = PayoutRepository::mark_returned(&mut *tx, payout.id, credit.id, return_code, return_reason).await?;
+
+        tx.commit().await?;
+
+        info!(
+            payout_id = %payout.id,
+            return_code = %return_code,
+            transaction_id = %credit.id,
+            account_id = %account_id,
+            "payout_returned"
+        );
+
+        // Credit back via the Ledger; failures leave the credit pending for the retry worker
+        let post_result = ledger_grpc
+            .post_transaction(
+                payout.organization_id,
+                &payout.environment,
+                "SYSTEM_CASH_CONTROL".to_string(),
+                account_id.to_string(),
+                credit.amount,
+                credit.currency.clone(),
+                credit.id,
+                credit.idempotency_key.clone(),
+                credit.id.to_string(),
+            )
+            .await;
+
+        match post_result {
+            Ok(()) => {
+                TransactionRepository::update_status(pool, credit.id, TransactionStatus::Posted, None).await?;
+            }
+            Err(e) => {
+                let reason = format!("{}", e);
+                warn!(
+                    transaction_id = %credit.id,
+                    error = %reason,
+                    "Ledger gRPC post failed; leaving transaction pending"
+                );
+                TransactionRepository::update_status(pool, credit.id, TransactionStatus::Pending, Some(&reason))
+                    .await?;
+            }
+        }
+
+        Ok(payout)
+    }
+
+    /// Account credited with a returned payout, locked against status changes until the return
+    /// commits. A closed account does not take new funds: the return goes to the account its
+    /// closure swept into, and only without one (or if that closed too) to the closed account,
+    /// which then needs manual handling. A closing account takes it and sweeps it again.
+    async fn return_account(conn: &mut PgConnection, payout: &Payout) -> Result<Uuid, AppError> {
+        if AccountRepository::lock_status(&mut *conn, payout.account_id).await? != AccountStatus::Closed {
+            return Ok(payout.account_id);
+        }
+
+        let sweep_to_account_id = AccountClosureRepository::find_by_account(&mut *conn, payout.account_id)
+            .await?
+            .and_then(|closure| closure.sweep_to_account_id);
+        if let Some(sweep_to_account_id) = sweep_to_account_id {
+            if AccountRepository::lock_status(&mut *conn, sweep_to_account_id).await? != AccountStatus::Closed {
+                info!(
+                    payout_id = %payout.id,
+                    account_id = %payout.account_id,
+                    sweep_to_account_id = %sweep_to_account_id,
+                    "payout_return_redirected_to_sweep_account"
+                );
+                return Ok(sweep_to_account_id);
+            }
+        }
+
+        error!(
+            payout_id = %payout.id,
+            account_id = %payout.account_id,
+            "payout_returned_to_closed_account; needs manual handling"
+        );
+        Ok(payout.account_id)
+    }
+}
@@ -0,0 +1,39 @@
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+use crate::ledger_grpc::LedgerGrpc;
+use crate::payout_rail::PayoutRails;
+use crate::repositories::PayoutRepository;
+use crate::services::PayoutService;
+
+/// Background worker that moves payouts through the rail: submits pending payouts once their
+/// debit is posted to the Ledger, then polls submitted payouts until they settle or are returned.
+pub async fn run(pool: PgPool, ledger_grpc: LedgerGrpc, payout_rail: PayoutRails) {
+    let interval_secs = std::env::var("PAYOUT_WORKER_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(30);
+
+    info!(interval_secs, "Payout worker started");
+
+    loop {
+        let in_flight = match PayoutRepository::find_in_flight(&pool, 200).await {
+            Ok(payouts) => payouts,
+            Err(e) => {
+                warn!(error = %e, "payout_worker_failed_to_load_payouts");
+                tokio::time::sleep(std::time::Duration::from_secs(interval_secs)).await;
+                continue;
+            }
+        };
+
+        for payout in in_flight {
+            let payout_id = payout.id;
+            if let Err(e) = PayoutService::process(&pool, &ledger_grpc, &payout_rail, payout).await {
+                warn!(payout_id = %payout_id, error = %e, "payout_worker_process_failed");
+            }
+        }
+
+        tokio::time::sleep(std::time::Duration::from_secs(interval_secs)).await;
+    }
+}
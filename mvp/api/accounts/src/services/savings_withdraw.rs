@@ -0,0 +1,375 @@
+use chrono::{Months, NaiveDate, Utc};
+use sqlx::PgPool;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::ledger_grpc::LedgerGrpc;
+use crate::models::{FixedSavingsPlanStatus, Transaction, TransactionDetails, TransactionKind, TransactionStatus};
+use crate::repositories::{AccountRepository, FixedSavingsRepository, TransactionRepository};
+
+/// Background scheduler for auto-withdraw fixed savings plans.
+/// Each due installment moves `monthly_withdraw_amount` from the saving account back to the
+/// owner's checking account. Installments missed during downtime are caught up one by one.
+pub async fn run(pool: PgPool, ledger_grpc: LedgerGrpc) {
+    let interval_secs = std::env::var("SAVINGS_WITHDRAW_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(60);
+
+    info!(interval_secs, "Savings auto-withdraw worker started");
+
+    loop {
+        let today = Utc::now().date_naive();
+
+        let due = match FixedSavingsRepository::find_due_auto_withdraw_ids(&pool, today, 200).await {
+            Ok(ids) => ids,
+            Err(e) => {
+                warn!(error = %e, "savings_withdraw_failed_to_load_due_plans");
+                tokio::time::sleep(std::time::Duration::from_secs(interval_secs)).await;
+                continue;
+            }
+        };
+
+        for plan_id in due {
+            // Catch up every installment that is due as of today before moving on.
+            loop {
+                match process_installment(&pool, &ledger_grpc, plan_id, today).await {
+                    Ok(Some(_)) => continue,
+                    Ok(None) => break,
+                    Err(e) => {
+                        warn!(plan_id = %plan_id, error = %e, "savings_withdraw_installment_failed");
+                        break;
+                    }
+                }
+            }
+        }
+
+        tokio::time::sleep(std::time::Duration::from_secs(interval_secs)).await;
+    }
+}
+
+/// Process the next due installment of a plan.
+/// Returns None when the plan has nothing due (or is being processed by another worker), or
+/// when it cannot be paid out and was suspended.
+async fn process_installment(
+    pool: &PgPool,
+    ledger_grpc: &LedgerGrpc,
+    plan_id: Uuid,
+    as_of: NaiveDate,
+) -> Result<Option<Transaction>, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let plan = match FixedSavingsRepository::lock_due_auto_withdraw(&mut *tx, plan_id, as_of).await? {
+        Some(plan) => plan,
+        None => return Ok(None),
+    };
+
+    // Locked query guarantees next_withdraw_date is set and <= as_of
+    let withdraw_date = match plan.next_withdraw_date {
+        Some(date) => date,
+        None => return Ok(None),
+    };
+
+    let monthly_amount = match plan.monthly_withdraw_amount {
+        Some(amount) if amount > 0 => amount,
+        _ => return suspend(tx, plan.id, "monthly_withdraw_amount is required").await,
+    };
+
+    let checking = match AccountRepository::find_owner_checking_account(&mut *tx, plan.account_id, &plan.currency).await? {
+        Some(account) => account,
+        None => {
+            let reason = format!(
+                "saving account inactive or owner has no active {} checking account",
+                plan.currency
+            );
+            return suspend(tx, plan.id, &reason).await;
+        }
+    };
+
+    let organization_id = checking
+        .organization_id
+        .ok_or_else(|| AppError::Validation("organization_id is required".to_string()))?;
+    let environment = checking
+        .environment
+        .clone()
+        .unwrap_or_else(|| "sandbox".to_string());
+
+    let amount = monthly_amount.min(plan.current_balance);
+    let remaining = plan.current_balance - amount;
+
+    // One intent per plan installment: the withdraw date makes the key stable across retries
+    let idempotency_key = format!("savings-plan:{}:{}", plan.id, withdraw_date);
+
+    let transaction = if amount > 0 {
+        Some(
+            TransactionRepository::create_or_get_by_idempotency(
+                &mut *tx,
+                organization_id,
+                plan.account_id,
+                checking.id,
+                amount,
+                &plan.currency,
+                TransactionKind::Transfer,
+                &idempotency_key,
+                Some(&environment),
+                &TransactionDetails::default(),
+            )
+            .await?,
+        )
+    } else {
+        None
+    };
+
+    let (next_withdraw_date, status) = if remaining == 0 {
+        (None, FixedSavingsPlanStatus::Completed)
+    } else {
+        let next = withdraw_date
+            .checked_add_months(Months::new(1))
+            .ok_or_else(|| AppError::Internal("next_withdraw_date out of range".to_string()))?;
+        (Some(next), FixedSavingsPlanStatus::Active)
+    };
+
+    let plan = FixedSavingsRepository::record_withdrawal(
+        &mut *tx,
+        plan.id,
+        remaining,
+        next_withdraw_date,
+        status,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    info!(
+        plan_id = %plan.id,
+        withdraw_date = %withdraw_date,
+        amount,
+        current_balance = plan.current_balance,
+        status = ?plan.status,
+        "savings_withdraw_installment_processed"
+    );
+
+    let transaction = match transaction {
+        Some(transaction) => transaction,
+        // Empty plan: nothing to move, the plan is now completed
+        None => return Ok(None),
+    };
+
+    // Attempt to post to Ledger via gRPC (eventual consistency: the retry worker picks up pending intents)
+    if transaction.status == TransactionStatus::Pending {
+        let post_result = ledger_grpc
+            .post_transaction(
+                organization_id,
+                &environment,
+                transaction.from_account_id.to_string(),
+                transaction.to_account_id.to_string(),
+                transaction.amount,
+                transaction.currency.clone(),
+                transaction.id,
+                transaction.idempotency_key.clone(),
+                transaction.id.to_string(),
+            )
+            .await;
+
+        match post_result {
+            Ok(()) => {
+                TransactionRepository::update_status(pool, transaction.id, TransactionStatus::Posted, None).await?;
+            }
+            Err(e) => {
+                let reason = format!("{}", e);
+                warn!(
+                    transaction_id = %transaction.id,
+                    error = %reason,
+                    "Ledger gRPC post failed; leaving transaction pending"
+                );
+                TransactionRepository::update_status(
+                    pool,
+                    transaction.id,
+                    TransactionStatus::Pending,
+                    Some(&reason),
+                )
+                .await?;
+            }
+        }
+    }
+
+    Ok(Some(transaction))
+}
+
+/// Suspend a plan the scheduler cannot pay out, so it is not retried (and logged) on every run.
+async fn suspend(
+    mut tx: sqlx::Transaction<'_, sqlx::Postgres>,
+    plan_id: Uuid,
+    reason: &str,
+) -> Result<Option<Transaction>, AppError> {
+    FixedSavingsRepository::suspend(&mut *tx, plan_id, reason).await?;
+    tx.commit().await?;
+
+    warn!(plan_id = %plan_id, reason = %reason, "savings_withdraw_plan_suspended");
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Account, AccountType, Metadata};
+    use crate::testing::{self, FakeLedger, ENVIRONMENT};
+    use crate::utils::generate_account_number;
+
+    fn date(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    /// The owner's checking account for the saving account.
+    async fn checking(pool: &PgPool, saving: &Account) -> Account {
+        let account_number = generate_account_number(pool, 12).await.unwrap();
+        AccountRepository::create(
+            pool,
+            &account_number,
+            AccountType::Checking,
+            saving.organization_id,
+            ENVIRONMENT,
+            saving.user_id,
+            "USD",
+            None,
+            &Metadata::new(),
+        )
+        .await
+        .unwrap()
+    }
+
+    async fn plan(pool: &PgPool, saving: &Account, balance: i64, monthly: i64, next: NaiveDate) -> Uuid {
+        sqlx::query_scalar(
+            r#"
+            INSERT INTO fixed_savings_plans (
+                account_id, plan_type, initial_amount, current_balance, currency,
+                monthly_withdraw_amount, next_withdraw_date
+            )
+            VALUES ($1, 'auto_withdraw', $2, $2, 'USD', $3, $4)
+            RETURNING id
+            "#,
+        )
+        .bind(saving.id)
+        .bind(balance)
+        .bind(monthly)
+        .bind(next)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    async fn find_plan(pool: &PgPool, id: Uuid) -> (i64, Option<NaiveDate>, String, Option<String>) {
+        sqlx::query_as(
+            "SELECT current_balance, next_withdraw_date, status, suspended_reason FROM fixed_savings_plans WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    /// Every installment due as of the date, as the scheduler runs them.
+    async fn run_due(pool: &PgPool, ledger_grpc: &LedgerGrpc, plan_id: Uuid, as_of: NaiveDate) -> Vec<Transaction> {
+        let mut transactions = Vec::new();
+        while let Some(transaction) = process_installment(pool, ledger_grpc, plan_id, as_of).await.unwrap() {
+            transactions.push(transaction);
+        }
+        transactions
+    }
+
+    #[sqlx::test(migrations = "./migrations_accounts")]
+    #[ignore = "needs Postgres: DATABASE_URL=... cargo test -- --ignored"]
+    async fn catches_up_missed_installments_until_the_plan_is_exhausted(pool: PgPool) {
+        let (ledger, ledger_grpc) = FakeLedger::start().await;
+        let saving = testing::account(&pool, Uuid::new_v4(), AccountType::Saving, "USD").await;
+        let checking = checking(&pool, &saving).await;
+        let plan_id = plan(&pool, &saving, 250, 100, date("2026-01-31")).await;
+
+        // Two installments were missed; the next one is not due yet
+        let transactions = run_due(&pool, &ledger_grpc, plan_id, date("2026-03-27")).await;
+        let keys: Vec<&str> = transactions.iter().map(|t| t.idempotency_key.as_str()).collect();
+        assert_eq!(
+            keys,
+            [format!("savings-plan:{}:2026-01-31", plan_id), format!("savings-plan:{}:2026-02-28", plan_id)]
+        );
+        assert!(transactions.iter().all(|t| t.amount == 100 && t.to_account_id == checking.id));
+        assert_eq!(find_plan(&pool, plan_id).await, (50, Some(date("2026-03-28")), "active".to_string(), None));
+
+        // The last installment pays out what is left and completes the plan
+        let transactions = run_due(&pool, &ledger_grpc, plan_id, date("2026-03-28")).await;
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].amount, 50);
+        assert_eq!(find_plan(&pool, plan_id).await, (0, None, "completed".to_string(), None));
+        assert!(run_due(&pool, &ledger_grpc, plan_id, date("2026-12-31")).await.is_empty());
+
+        assert!(TransactionRepository::find_pending_by_account(&pool, saving.id).await.unwrap().is_empty());
+        assert_eq!(ledger.balance(checking.id), 250);
+    }
+
+    #[sqlx::test(migrations = "./migrations_accounts")]
+    #[ignore = "needs Postgres: DATABASE_URL=... cargo test -- --ignored"]
+    async fn rerunning_an_installment_reuses_its_transaction(pool: PgPool) {
+        let (ledger, ledger_grpc) = FakeLedger::start().await;
+        let saving = testing::account(&pool, Uuid::new_v4(), AccountType::Saving, "USD").await;
+        let checking = checking(&pool, &saving).await;
+        let plan_id = plan(&pool, &saving, 300, 100, date("2026-01-15")).await;
+
+        let first = process_installment(&pool, &ledger_grpc, plan_id, date("2026-01-15")).await.unwrap().unwrap();
+
+        // The plan is put back as it was before the installment and the installment runs again
+        sqlx::query("UPDATE fixed_savings_plans SET current_balance = 300, next_withdraw_date = '2026-01-15' WHERE id = $1")
+            .bind(plan_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let second = process_installment(&pool, &ledger_grpc, plan_id, date("2026-01-15")).await.unwrap().unwrap();
+
+        assert_eq!(second.id, first.id);
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM transactions WHERE idempotency_key = $1")
+            .bind(&first.idempotency_key)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(ledger.balance(checking.id), 100);
+    }
+
+    #[sqlx::test(migrations = "./migrations_accounts")]
+    #[ignore = "needs Postgres: DATABASE_URL=... cargo test -- --ignored"]
+    async fn plans_without_a_destination_are_suspended(pool: PgPool) {
+        let (_ledger, ledger_grpc) = FakeLedger::start().await;
+        let saving = testing::account(&pool, Uuid::new_v4(), AccountType::Saving, "USD").await;
+        let plan_id = plan(&pool, &saving, 300, 100, date("2026-01-15")).await;
+
+        assert!(process_installment(&pool, &ledger_grpc, plan_id, date("2026-02-20")).await.unwrap().is_none());
+        let (balance, next, status, reason) = find_plan(&pool, plan_id).await;
+        assert_eq!((balance, next, status.as_str()), (300, Some(date("2026-01-15")), "suspended"));
+        assert!(reason.unwrap().contains("no active USD checking account"));
+        let due = FixedSavingsRepository::find_due_auto_withdraw_ids(&pool, date("2026-02-20"), 10).await.unwrap();
+        assert!(!due.contains(&plan_id));
+
+        // Reactivated once the owner has a checking account, it catches up
+        checking(&pool, &saving).await;
+        sqlx::query("UPDATE fixed_savings_plans SET status = 'active', suspended_reason = NULL WHERE id = $1")
+            .bind(plan_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(run_due(&pool, &ledger_grpc, plan_id, date("2026-02-20")).await.len(), 2);
+
+        // Plans the scheduler could never pay out are rejected
+        let err = sqlx::query(
+            r#"
+            INSERT INTO fixed_savings_plans (account_id, plan_type, initial_amount, current_balance, currency)
+            VALUES ($1, 'auto_withdraw', 100, 100, 'USD')
+            "#,
+        )
+        .bind(saving.id)
+        .execute(&pool)
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("fixed_savings_plans_auto_withdraw_check"), "{}", err);
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, NaiveTime, Utc};
+use sqlx::PgPool;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::models::{Account, AccountStatement, StatementKindTotal, StatementLine, StatementSummary};
+use crate::repositories::{AccountRepository, StatementRepository, TransactionRepository};
+
+/// Longest period an ad-hoc statement may cover
+const MAX_STATEMENT_DAYS: i64 = 366;
+
+pub struct StatementService;
+
+impl StatementService {
+    /// Statement for `[from, to]` (whole UTC days). A completed calendar month is stored on
+    /// first request and served from storage from then on; any other period (or a month that
+    /// still has pending intents) is generated on the fly and not stored.
+    pub async fn get_statement(
+        pool: &PgPool,
+        account_id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<AccountStatement, AppError> {
+        let account =
+            AccountRepository::find_by_id_for_organization(pool, account_id, organization_id, environment).await?;
+
+        if from > to {
+            return Err(AppError::Validation("from must not be after to".to_string()));
+        }
+        if to.signed_duration_since(from).num_days() >= MAX_STATEMENT_DAYS {
+            return Err(AppError::Validation(format!(
+                "statement period must not exceed {} days",
+                MAX_STATEMENT_DAYS
+            )));
+        }
+
+        if is_completed_month(from, to, Utc::now().date_naive()) {
+            if let Some(stored) =
+                StatementRepository::find_by_period(pool, account_id, environment, from, to).await?
+            {
+                return Ok(stored);
+            }
+            return Self::generate_and_store(pool, &account, environment, from, to).await;
+        }
+
+        Self::build(pool, &account, environment, from, to).await
+    }
+
+    /// Monthly statement for `YYYY-MM`.
+    pub async fn get_monthly_statement(
+        pool: &PgPool,
+        account_id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+        period: &str,
+    ) -> Result<AccountStatement, AppError> {
+        let (from, to) = parse_month(period)?;
+        Self::get_statement(pool, account_id, organization_id, environment, from, to).await
+    }
+
+    pub async fn list_statements(
+        pool: &PgPool,
+        account_id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<Vec<StatementSummary>, AppError> {
+        AccountRepository::find_by_id_for_organization(pool, account_id, organization_id, environment).await?;
+        StatementRepository::find_by_account(pool, account_id, environment).await
+    }
+
+    /// Generate the previous month's statement for every account that does not have one yet.
+    /// Returns how many statements were stored.
+    pub async fn store_previous_month(pool: &PgPool) -> Result<usize, AppError> {
+        let today = Utc::now().date_naive();
+        let this_month = today.with_day(1).unwrap_or(today);
+        let period_start = this_month - Months::new(1);
+        let period_end = this_month - Days::new(1);
+
+        let accounts = StatementRepository::find_accounts_missing_statement(
+            pool,
+            period_start,
+            period_end,
+            start_of_day(this_month),
+            500,
+        )
+        .await?;
+
+        let mut stored = 0;
+        for (account_id, environment) in accounts {
+            let account = AccountRepository::find_by_id(pool, account_id, &environment).await?;
+            match Self::generate_and_store(pool, &account, &environment, period_start, period_end).await {
+                Ok(statement) if statement.id.is_some() => stored += 1,
+                Ok(_) => {}
+                Err(e) => warn!(account_id = %account_id, error = %e, "monthly_statement_failed"),
+            }
+        }
+
+        Ok(stored)
+    }
+
+    /// Store the statement unless the period still has pending intents (then it is returned unstored).
+    async fn generate_and_store(
+        pool: &PgPool,
+        account: &Account,
+        environment: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<AccountStatement, AppError> {
+        let statement = Self::build(pool, account, environment, from, to).await?;
+
+        let pending = StatementRepository::count_pending_movements(
+            pool,
+            account.id,
+            start_of_day(from),
+            start_of_day(to + Days::new(1)),
+        )
+        .await?;
+        if pending > 0 {
+            return Ok(statement);
+        }
+
+        StatementRepository::create_or_get(pool, &statement).await
+    }
+
+    /// Opening balance, every posted movement with the running balance, totals by kind
+    /// and the closing balance, from posted intents and fee legs.
+    async fn build(
+        pool: &PgPool,
+        account: &Account,
+        environment: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<AccountStatement, AppError> {
+        let currency = account.currency()?;
+        let period_start = start_of_day(from);
+        let period_end = start_of_day(to + Days::new(1));
+
+        let opening_balance = TransactionRepository::balance_as_of(pool, account.id, period_start).await?;
+        let movements = TransactionRepository::posted_movements(pool, account.id, period_start, period_end).await?;
+
+        let mut balance = opening_balance;
+        let mut total_credits = 0;
+        let mut total_debits = 0;
+        let mut totals: BTreeMap<_, StatementKindTotal> = BTreeMap::new();
+        let mut lines = Vec::with_capacity(movements.len());
+
+        for movement in movements {
+            balance += movement.amount;
+
+            let total = totals.entry(movement.kind).or_insert_with(|| StatementKindTotal {
+                kind: movement.kind,
+                count: 0,
+                credits: 0,
+                debits: 0,
+            });
+            total.count += 1;
+            if movement.amount >= 0 {
+                total.credits += movement.amount;
+                total_credits += movement.amount;
+            } else {
+                total.debits -= movement.amount;
+                total_debits -= movement.amount;
+            }
+
+            lines.push(StatementLine {
+                movement,
+                running_balance: balance,
+            });
+        }
+
+        Ok(AccountStatement {
+            id: None,
+            account_id: account.id,
+            organization_id: account.organization_id,
+            environment: environment.to_string(),
+            period_start: from,
+            period_end: to,
+            currency: currency.code().to_string(),
+            opening_balance,
+            closing_balance: balance,
+            total_credits,
+            total_debits,
+            totals: totals.into_values().collect(),
+            lines,
+            created_at: Utc::now(),
+        })
+    }
+}
+
+/// `[from, to]` is exactly one calendar month that ended before `today`.
+fn is_completed_month(from: NaiveDate, to: NaiveDate, today: NaiveDate) -> bool {
+    from.day() == 1 && from + Months::new(1) - Days::new(1) == to && to < today
+}
+
+fn parse_month(period: &str) -> Result<(NaiveDate, NaiveDate), AppError> {
+    let from = NaiveDate::parse_from_str(&format!("{}-01", period), "%Y-%m-%d")
+        .map_err(|_| AppError::Validation("period must be a month in YYYY-MM format".to_string()))?;
+    Ok((from, from + Months::new(1) - Days::new(1)))
+}
+
+fn start_of_day(day: NaiveDate) -> DateTime<Utc> {
+    day.and_time(NaiveTime::MIN).and_utc()
+}
+
+/// Background job storing last month's statements once the month has ended
+/// (STATEMENT_JOB_INTERVAL_SECS).
+pub async fn run(pool: PgPool) {
+    let interval_secs = std::env::var("STATEMENT_JOB_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(3600);
+
+    info!(interval_secs, "Statement job started");
+
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(interval_secs)).await;
+
+        match StatementService::store_previous_month(&pool).await {
+            Ok(0) => {}
+            Ok(stored) => info!(stored, "monthly_statements_stored"),
+            Err(e) => warn!(error = %e, "monthly_statement_job_failed"),
+        }
+    }
+}
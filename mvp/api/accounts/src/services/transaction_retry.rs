@@ -2,9 +2,11 @@ use chrono::Duration;
 use sqlx::PgPool;
 use tracing::{info, warn};
 
+use crate::errors::AppError;
 use crate::ledger_grpc::LedgerGrpc;
-use crate::models::{TransactionKind, TransactionStatus};
-use crate::repositories::{AccountRepository, TransactionRepository};
+use crate::models::{Transaction, TransactionKind, TransactionStatus};
+use crate::repositories::{AccountRepository, FeeRepository, TransactionRepository};
+use crate::services::{FeeService, FxService, InterorgService};
 
 /// Best-effort background retry loop that posts pending transactions to the Ledger via gRPC.
 /// Eventual consistency: transactions remain pending until Ledger accepts them.
@@ -31,66 +33,29 @@ pub async fn run(pool: PgPool, ledger_grpc: LedgerGrpc) {
         };
 
         for tx in pending {
-            // Use environment from transaction if available, otherwise fallback to account lookup
+            // Use environment from transaction if available; legacy transactions take it from
+            // their account and are left pending rather than posted to a guessed environment
             let environment = if let Some(ref env) = tx.environment {
                 env.clone()
             } else {
-                // Legacy transaction: determine environment from account record
-                // Try sandbox first, then production if not found
-                let account = match AccountRepository::find_by_id(&pool, tx.from_account_id, "sandbox").await {
-                    Ok(a) => a,
-                    Err(_) => {
-                        // Try production if sandbox fails
-                        match AccountRepository::find_by_id(&pool, tx.from_account_id, "production").await {
-                            Ok(a) => a,
-                            Err(e) => {
-                                warn!(
-                                    transaction_id = %tx.id,
-                                    error = %e,
-                                    "retry_worker_missing_account; leaving pending"
-                                );
-                                continue;
-                            }
-                        }
+                match AccountRepository::find_environment(&pool, tx.from_account_id).await {
+                    Ok(Some(env)) => env,
+                    Ok(None) => {
+                        warn!(transaction_id = %tx.id, "retry_worker_unknown_environment; leaving pending");
+                        continue;
                     }
-                };
-                
-                account
-                    .environment
-                    .clone()
-                    .unwrap_or_else(|| "sandbox".to_string())
-            };
-
-            let (source_external, dest_external) = match tx.transaction_kind {
-                TransactionKind::Transfer => (
-                    tx.from_account_id.to_string(),
-                    tx.to_account_id.to_string(),
-                ),
-                TransactionKind::Deposit => (
-                    "SYSTEM_CASH_CONTROL".to_string(),
-                    tx.to_account_id.to_string(),
-                ),
-                TransactionKind::Withdraw => (
-                    tx.from_account_id.to_string(),
-                    "SYSTEM_CASH_CONTROL".to_string(),
-                ),
+                    Err(e) => {
+                        warn!(
+                            transaction_id = %tx.id,
+                            error = %e,
+                            "retry_worker_missing_account; leaving pending"
+                        );
+                        continue;
+                    }
+                }
             };
 
-            let post_result = ledger_grpc
-                .post_transaction(
-                    tx.organization_id,
-                    &environment,
-                    source_external,
-                    dest_external,
-                    tx.amount,
-                    tx.currency.clone(),
-                    tx.id,
-                    tx.idempotency_key.clone(),
-                    tx.id.to_string(),
-                )
-                .await;
-
-            match post_result {
+            match post_to_ledger(&ledger_grpc, &tx, &environment).await {
                 Ok(()) => {
                     let _ = TransactionRepository::update_status(
                         &pool,
@@ -102,39 +67,98 @@ pub async fn run(pool: PgPool, ledger_grpc: LedgerGrpc) {
                 }
                 Err(e) => {
                     let reason = format!("{}", e);
-                    // If the error is a duplicate key violation for idempotency_key, the transaction
-                    // was already posted to the ledger. This can happen due to race conditions
-                    // between the ledger's existence check and transaction creation.
-                    // Treat this as success since the transaction is already in the ledger.
-                    let reason_lower = reason.to_lowercase();
-                    if (reason_lower.contains("duplicate key") || reason_lower.contains("uniqueviolation"))
-                        && reason_lower.contains("idempotency") {
-                        info!(
-                            transaction_id = %tx.id,
-                            idempotency_key = %tx.idempotency_key,
-                            "Transaction already posted to ledger (duplicate idempotency key), marking as posted"
-                        );
-                        let _ = TransactionRepository::update_status(
-                            &pool,
-                            tx.id,
-                            TransactionStatus::Posted,
-                            None,
-                        )
-                        .await;
-                    } else {
-                        let _ = TransactionRepository::update_status(
-                            &pool,
-                            tx.id,
-                            TransactionStatus::Pending,
-                            Some(&reason),
-                        )
-                        .await;
+                    let _ = TransactionRepository::update_status(
+                        &pool,
+                        tx.id,
+                        TransactionStatus::Pending,
+                        Some(&reason),
+                    )
+                    .await;
+                }
+            }
+        }
+
+        // Fee legs of posted transactions that could not be posted inline
+        match FeeRepository::find_pending_with_posted_parent(&pool, Duration::seconds(2), 200).await {
+            Ok(fees) => {
+                for fee in fees {
+                    let correlation_id = fee.transaction_id.to_string();
+                    if let Err(e) = FeeService::post_fee(&pool, &ledger_grpc, &fee, &correlation_id).await {
+                        warn!(fee_id = %fee.id, error = %e, "retry_worker_fee_post_failed");
                     }
                 }
             }
+            Err(e) => warn!(error = %e, "retry_worker_failed_to_load_pending_fees"),
         }
 
         tokio::time::sleep(std::time::Duration::from_secs(3)).await;
     }
 }
 
+
+/// Posts an intent to the Ledger: one leg between its accounts (or the system cash and
+/// interest accounts), or two legs via clearing accounts for cross-currency and
+/// cross-organization transfers. The intent's id is the correlation id.
+pub async fn post_to_ledger(ledger_grpc: &LedgerGrpc, tx: &Transaction, environment: &str) -> Result<(), AppError> {
+    let (source_external, dest_external) = match tx.transaction_kind {
+        TransactionKind::Transfer => (
+            tx.from_account_id.to_string(),
+            tx.to_account_id.to_string(),
+        ),
+        TransactionKind::Deposit => (
+            "SYSTEM_CASH_CONTROL".to_string(),
+            tx.to_account_id.to_string(),
+        ),
+        TransactionKind::Withdraw => (
+            tx.from_account_id.to_string(),
+            "SYSTEM_CASH_CONTROL".to_string(),
+        ),
+        TransactionKind::Interest => (
+            "SYSTEM_INTEREST_EXPENSE".to_string(),
+            tx.to_account_id.to_string(),
+        ),
+    };
+
+    // Cross-currency and cross-organization transfers post as two legs via clearing accounts
+    let post_result = if tx.is_cross_organization() {
+        InterorgService::post_to_ledger(ledger_grpc, tx, environment, &tx.id.to_string()).await
+    } else if tx.is_cross_currency() {
+        FxService::post_to_ledger(ledger_grpc, tx, environment, &tx.id.to_string()).await
+    } else {
+        ledger_grpc
+            .post_transaction(
+                tx.organization_id,
+                environment,
+                source_external,
+                dest_external,
+                tx.amount,
+                tx.currency.clone(),
+                tx.id,
+                tx.idempotency_key.clone(),
+                tx.id.to_string(),
+            )
+            .await
+    };
+
+    match post_result {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            // If the error is a duplicate key violation for idempotency_key, the transaction
+            // was already posted to the ledger. This can happen due to race conditions
+            // between the ledger's existence check and transaction creation.
+            // Treat this as success since the transaction is already in the ledger.
+            let reason_lower = format!("{}", e).to_lowercase();
+            if (reason_lower.contains("duplicate key") || reason_lower.contains("uniqueviolation"))
+                && reason_lower.contains("idempotency") {
+                info!(
+                    transaction_id = %tx.id,
+                    idempotency_key = %tx.idempotency_key,
+                    "Transaction already posted to ledger (duplicate idempotency key), marking as posted"
+                );
+                Ok(())
+            } else {
+                Err(e)
+            }
+        }
+    }
+}
@@ -1,7 +1,10 @@
 use crate::errors::AppError;
+use crate::fx::FxRates;
 use crate::ledger::{LedgerAdapter, NoopLedgerAdapter};
-use crate::models::{CreateTransactionRequest, Transaction, TransactionKind};
+use crate::models::{CreateTransactionRequest, Transaction, TransactionFilter, TransactionKind};
 use crate::repositories::{AccountRepository, TransactionRepository};
+use crate::services::{FeeService, FxService, InterorgService, LimitService};
+use crate::utils::pagination::PageRequest;
 use sqlx::PgPool;
 use tracing::info;
 use uuid::Uuid;
@@ -11,64 +14,132 @@ pub struct TransactionService;
 impl TransactionService {
     pub async fn create_transaction(
         pool: &PgPool,
+        organization_id: Uuid,
         request: CreateTransactionRequest,
         environment: &str,
         idempotency_key: &str,
+        fx_rates: &FxRates,
     ) -> Result<Transaction, AppError> {
         if idempotency_key.trim().is_empty() {
             return Err(AppError::Validation("Idempotency-Key header is required".to_string()));
         }
+        let details = request.details.normalized()?;
 
-        let from_account = AccountRepository::find_by_id(pool, request.from_account_id, environment).await?;
-        let to_account = AccountRepository::find_by_id(pool, request.to_account_id, environment).await?;
+        let from_account =
+            AccountRepository::find_by_id_for_organization(pool, request.from_account_id, organization_id, environment)
+                .await?;
+        // The destination may belong to another organization that accepted an agreement
+        let to_account =
+            AccountRepository::find_transfer_destination(pool, request.to_account_id, organization_id, environment).await?;
 
-        let from_org = from_account
-            .organization_id
-            .ok_or_else(|| AppError::Validation("organization_id is required".to_string()))?;
+        let from_org = organization_id;
         let to_org = to_account
             .organization_id
             .ok_or_else(|| AppError::Validation("organization_id is required".to_string()))?;
 
-        if from_org != to_org {
+        let from_currency = from_account.currency()?;
+        let to_currency = to_account.currency()?;
+
+        let amount = request.amount.to_minor_units(request.currency)?;
+
+        if request.currency != from_currency {
             return Err(AppError::Validation(
-                "accounts must belong to the same organization".to_string(),
+                "currency must match the source account".to_string(),
             ));
         }
 
-        let from_currency = from_account
-            .currency
-            .clone()
-            .unwrap_or_else(|| "USD".to_string());
-        let to_currency = to_account
-            .currency
-            .clone()
-            .unwrap_or_else(|| "USD".to_string());
-
-        if request.currency != from_currency || request.currency != to_currency {
+        if from_currency == to_currency && request.fx_quote_id.is_some() {
             return Err(AppError::Validation(
-                "currency must match both accounts".to_string(),
+                "fx_quote_id only applies to transfers between different currencies".to_string(),
             ));
         }
 
-        if request.amount <= 0 {
+        if amount <= 0 {
             return Err(AppError::Validation(
                 "amount must be greater than zero".to_string(),
             ));
         }
 
         let mut tx = pool.begin().await?;
-        let transaction = TransactionRepository::create_or_get_by_idempotency(
-            &mut *tx,
-            from_org,
-            request.from_account_id,
-            request.to_account_id,
-            request.amount,
-            &request.currency,
-            TransactionKind::Transfer,
-            idempotency_key,
-            Some(environment), // Always pass environment for new transactions
-        )
-        .await?;
+
+        // Both accounts stay active until the intent commits
+        AccountRepository::lock_active(&mut tx, &[request.from_account_id, request.to_account_id]).await?;
+
+        // Velocity and amount limits are checked under lock in the same transaction as the intent
+        LimitService::enforce(&mut tx, &from_account, environment, TransactionKind::Transfer, amount, idempotency_key)
+            .await?;
+
+        let transaction = if from_org != to_org {
+            // Cross-organization transfers need an active agreement covering the destination account
+            let agreement = InterorgService::authorize_transfer(
+                &mut tx,
+                environment,
+                &from_account,
+                &to_account,
+                amount,
+                idempotency_key,
+            )
+            .await?;
+
+            TransactionRepository::create_or_get_interorg_by_idempotency(
+                &mut *tx,
+                request.from_account_id,
+                request.to_account_id,
+                amount,
+                from_currency.code(),
+                &agreement,
+                idempotency_key,
+                &details,
+            )
+            .await?
+        } else if from_currency == to_currency {
+            TransactionRepository::create_or_get_by_idempotency(
+                &mut *tx,
+                from_org,
+                request.from_account_id,
+                request.to_account_id,
+                amount,
+                request.currency.code(),
+                TransactionKind::Transfer,
+                idempotency_key,
+                Some(environment), // Always pass environment for new transactions
+                &details,
+            )
+            .await?
+        } else {
+            let conversion = match request.fx_quote_id {
+                Some(quote_id) => {
+                    FxService::quoted_conversion(
+                        &mut tx,
+                        quote_id,
+                        from_org,
+                        environment,
+                        from_currency,
+                        to_currency,
+                        amount,
+                    )
+                    .await?
+                }
+                None => FxService::live_conversion(fx_rates, from_currency, to_currency, amount).await?,
+            };
+
+            let transaction = TransactionRepository::create_or_get_cross_currency_by_idempotency(
+                &mut *tx,
+                from_org,
+                request.from_account_id,
+                request.to_account_id,
+                &conversion,
+                idempotency_key,
+                Some(environment),
+                &details,
+            )
+            .await?;
+
+            FxService::consume_quote(&mut tx, &conversion, &transaction).await?;
+            transaction
+        };
+
+        FeeService::apply_fees(&mut tx, &transaction, request.from_account_id, from_account.account_type).await?;
 
         tx.commit().await?;
 
@@ -90,12 +161,18 @@ impl TransactionService {
         Ok(transaction)
     }
 
-    pub async fn get_transaction(pool: &PgPool, id: Uuid, environment: &str) -> Result<Transaction, AppError> {
+    pub async fn get_transaction(
+        pool: &PgPool,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<Transaction, AppError> {
         // Transactions don't have environment column, but we verify the account is in the correct environment
         // by checking the account exists in that environment first
-        let transaction = TransactionRepository::find_by_id(pool, id).await?;
+        let transaction = TransactionRepository::find_by_id_for_organization(pool, id, organization_id).await?;
         
-        // Verify the from_account is in the correct environment
+        // Verify the from_account is in the correct environment (it is the counterparty's
+        // account when the organization received an inter-organization transfer)
         let _account = AccountRepository::find_by_id(pool, transaction.from_account_id, environment).await?;
         
         Ok(transaction)
@@ -104,29 +181,32 @@ impl TransactionService {
     pub async fn get_account_transactions(
         pool: &PgPool,
         account_id: Uuid,
+        organization_id: Uuid,
         environment: &str,
+        filter: &TransactionFilter,
         limit: Option<i64>,
     ) -> Result<Vec<Transaction>, AppError> {
-        // Verify account exists in the correct environment before fetching transactions
-        let _account = AccountRepository::find_by_id(pool, account_id, environment).await?;
+        // Verify the organization's account exists in the correct environment before fetching transactions
+        let _account =
+            AccountRepository::find_by_id_for_organization(pool, account_id, organization_id, environment).await?;
         
         // Filter by environment, but include legacy transactions (NULL environment)
-        TransactionRepository::find_by_account_id(pool, account_id, limit, Some(environment)).await
+        TransactionRepository::find_by_account_id(pool, account_id, limit, Some(environment), filter).await
     }
 
     pub async fn get_transactions_by_organization_paginated(
         pool: &PgPool,
         organization_id: Uuid,
         environment: &str,
-        page: u32,
-        per_page: u32,
+        filter: &TransactionFilter,
+        page: &PageRequest,
     ) -> Result<(Vec<Transaction>, crate::models::PaginationMeta), AppError> {
         TransactionRepository::find_by_organization_id_paginated(
             pool,
             organization_id,
             environment,
+            filter,
             page,
-            per_page,
         )
         .await
     }
@@ -0,0 +1,226 @@
+use chrono::Utc;
+use sqlx::PgPool;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::models::{
+    CreateWebhookEndpointRequest, CreatedWebhookEndpoint, WebhookDelivery, WebhookDeliveryDetail,
+    WebhookDeliveryStatus, WebhookEndpoint,
+};
+use crate::repositories::{DueDelivery, WebhookRepository};
+use crate::webhook::{self, AttemptOutcome, WebhookClient, EVENT_TYPES};
+
+/// Seconds a claimed delivery is reserved for the worker that claimed it
+const DELIVERY_LEASE_SECS: i64 = 300;
+
+pub struct WebhookService;
+
+impl WebhookService {
+    pub async fn create_endpoint(
+        pool: &PgPool,
+        environment: &str,
+        request: CreateWebhookEndpointRequest,
+    ) -> Result<CreatedWebhookEndpoint, AppError> {
+        let url = request.url.trim();
+        webhook::validate_url(url, environment).await?;
+
+        let mut event_types: Vec<String> = Vec::new();
+        for event_type in request.event_types {
+            let event_type = event_type.trim().to_lowercase();
+            if !EVENT_TYPES.contains(&event_type.as_str()) {
+                return Err(AppError::Validation(format!(
+                    "unknown event type '{}' (expected one of {})",
+                    event_type,
+                    EVENT_TYPES.join(", ")
+                )));
+            }
+            if !event_types.contains(&event_type) {
+                event_types.push(event_type);
+            }
+        }
+
+        let secret = webhook::generate_secret();
+        let endpoint = WebhookRepository::create_endpoint(
+            pool,
+            request.organization_id,
+            environment,
+            url,
+            request.description.as_deref().map(str::trim).filter(|d| !d.is_empty()),
+            &secret,
+            &event_types,
+        )
+        .await?;
+
+        Ok(CreatedWebhookEndpoint { endpoint, secret })
+    }
+
+    pub async fn get_endpoints(
+        pool: &PgPool,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<Vec<WebhookEndpoint>, AppError> {
+        WebhookRepository::find_endpoints(pool, organization_id, environment).await
+    }
+
+    pub async fn disable_endpoint(
+        pool: &PgPool,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<WebhookEndpoint, AppError> {
+        WebhookRepository::disable_endpoint(pool, id, organization_id, environment).await
+    }
+
+    pub async fn get_deliveries(
+        pool: &PgPool,
+        endpoint_id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<Vec<WebhookDelivery>, AppError> {
+        WebhookRepository::find_endpoint(pool, endpoint_id, organization_id, environment).await?;
+        WebhookRepository::find_deliveries_by_endpoint(pool, endpoint_id, 100).await
+    }
+
+    pub async fn get_delivery(
+        pool: &PgPool,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<WebhookDeliveryDetail, AppError> {
+        let (delivery, payload) = WebhookRepository::find_delivery(pool, id, organization_id, environment).await?;
+        let attempts = WebhookRepository::find_attempts(pool, id).await?;
+        Ok(WebhookDeliveryDetail {
+            delivery,
+            payload,
+            attempts,
+        })
+    }
+
+    /// Send a delivery again now, whatever its status. The attempt is logged like any other;
+    /// a success marks the delivery succeeded.
+    pub async fn redeliver(
+        pool: &PgPool,
+        client: &WebhookClient,
+        id: Uuid,
+        organization_id: Uuid,
+        environment: &str,
+    ) -> Result<WebhookDeliveryDetail, AppError> {
+        let delivery = WebhookRepository::find_for_delivery(pool, id, organization_id, environment).await?;
+        if !delivery.endpoint_active {
+            return Err(AppError::BusinessLogic(
+                "webhook endpoint is disabled; deliveries cannot be resent".to_string(),
+            ));
+        }
+
+        Self::attempt(pool, client, delivery).await?;
+        Self::get_delivery(pool, id, organization_id, environment).await
+    }
+
+    /// Create deliveries for new events, then attempt every due delivery.
+    pub async fn process_due(pool: &PgPool, client: &WebhookClient, batch_size: i64) -> Result<(), AppError> {
+        let created = WebhookRepository::fan_out_events(pool, batch_size).await?;
+        if created > 0 {
+            info!(deliveries = created, "webhook_deliveries_created");
+        }
+
+        for delivery in WebhookRepository::claim_due_deliveries(pool, batch_size, DELIVERY_LEASE_SECS).await? {
+            let delivery_id = delivery.id;
+            if let Err(e) = Self::attempt(pool, client, delivery).await {
+                warn!(delivery_id = %delivery_id, error = %e, "webhook_delivery_attempt_failed");
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn attempt(pool: &PgPool, client: &WebhookClient, delivery: DueDelivery) -> Result<WebhookDelivery, AppError> {
+        let attempt_number = delivery.attempt_count + 1;
+
+        let outcome = if delivery.endpoint_active {
+            let body = serde_json::json!({
+                "id": delivery.event_id,
+                "type": delivery.event_type,
+                "created_at": delivery.event_created_at,
+                "data": delivery.payload,
+            })
+            .to_string();
+
+            client
+                .send(
+                    &delivery.url,
+                    &delivery.secret,
+                    &delivery.event_id.to_string(),
+                    &delivery.event_type,
+                    &body,
+                )
+                .await
+        } else {
+            AttemptOutcome {
+                response_status: None,
+                error: Some("webhook endpoint is disabled".to_string()),
+                duration_ms: 0,
+            }
+        };
+
+        let now = Utc::now();
+        let (status, next_attempt_at) = if outcome.succeeded() {
+            (WebhookDeliveryStatus::Succeeded, now)
+        } else if !delivery.endpoint_active || attempt_number >= max_attempts() {
+            (WebhookDeliveryStatus::Failed, now)
+        } else {
+            (WebhookDeliveryStatus::Pending, now + webhook::retry_delay(attempt_number))
+        };
+
+        let updated =
+            WebhookRepository::record_attempt(pool, delivery.id, attempt_number, &outcome, status, next_attempt_at)
+                .await?;
+
+        match status {
+            WebhookDeliveryStatus::Succeeded => info!(
+                delivery_id = %delivery.id,
+                event_type = %delivery.event_type,
+                attempt_number,
+                "webhook_delivered"
+            ),
+            _ => warn!(
+                delivery_id = %delivery.id,
+                event_type = %delivery.event_type,
+                attempt_number,
+                error = outcome.error.as_deref().unwrap_or_default(),
+                "webhook_delivery_failed"
+            ),
+        }
+
+        Ok(updated)
+    }
+}
+
+/// Attempts before a delivery is given up (WEBHOOK_MAX_ATTEMPTS)
+fn max_attempts() -> i32 {
+    std::env::var("WEBHOOK_MAX_ATTEMPTS")
+        .ok()
+        .and_then(|v| v.parse::<i32>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(8)
+}
+
+/// Background worker fanning recorded events out to subscribed endpoints and sending due
+/// deliveries with exponential backoff (WEBHOOK_WORKER_INTERVAL_SECS).
+pub async fn run(pool: PgPool, client: WebhookClient) {
+    let interval_secs = std::env::var("WEBHOOK_WORKER_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(5);
+
+    info!(interval_secs, "Webhook worker started");
+
+    loop {
+        if let Err(e) = WebhookService::process_due(&pool, &client, 100).await {
+            warn!(error = %e, "webhook_worker_failed");
+        }
+
+        tokio::time::sleep(std::time::Duration::from_secs(interval_secs)).await;
+    }
+}
@@ -0,0 +1,178 @@
+//! Fixtures for tests that run against Postgres. Those tests are `#[ignore]`d and run with
+//! `DATABASE_URL=postgres://... cargo test -- --ignored`; `#[sqlx::test]` gives each one a
+//! fresh database with the accounts migrations applied.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use sqlx::PgPool;
+use tonic::transport::server::TcpIncoming;
+use tonic::{Request, Response, Status};
+use uuid::Uuid;
+
+use crate::auth::Authenticator;
+use crate::environment::EnvironmentMode;
+use crate::event_stream::EventNotifier;
+use crate::fx::{DbFxRateProvider, FxRates};
+use crate::grpc::ledger_proto::ledger_service_server::{LedgerService, LedgerServiceServer};
+use crate::grpc::ledger_proto::{
+    GetAccountBalanceRequest, GetAccountBalanceResponse, PostTransactionRequest, PostTransactionResponse,
+};
+use crate::ledger_grpc::LedgerGrpc;
+use crate::middleware::rate_limit::RateLimiter;
+use crate::models::{Account, AccountType, Metadata, Transaction, TransactionDetails, TransactionKind};
+use crate::payout_rail::{PayoutRails, SimulatedPayoutRail};
+use crate::repositories::{AccountRepository, TransactionRepository};
+use crate::routes::api::AppState;
+use crate::utils::generate_account_number;
+use crate::webhook::WebhookClient;
+
+pub const ENVIRONMENT: &str = "sandbox";
+
+/// An active account of the organization.
+pub async fn account(pool: &PgPool, organization_id: Uuid, account_type: AccountType, currency: &str) -> Account {
+    let account_number = generate_account_number(pool, 12).await.unwrap();
+    AccountRepository::create(
+        pool,
+        &account_number,
+        account_type,
+        Some(organization_id),
+        ENVIRONMENT,
+        Uuid::new_v4(),
+        currency,
+        None,
+        &Metadata::new(),
+    )
+    .await
+    .unwrap()
+}
+
+/// A pending intent from `from` to `to`, or the one already created with the key.
+pub async fn intent(
+    executor: impl sqlx::Executor<'_, Database = sqlx::Postgres>,
+    from: &Account,
+    to: &Account,
+    kind: TransactionKind,
+    amount: i64,
+    idempotency_key: &str,
+) -> Transaction {
+    TransactionRepository::create_or_get_by_idempotency(
+        executor,
+        from.organization_id.unwrap(),
+        from.id,
+        to.id,
+        amount,
+        from.currency().unwrap().code(),
+        kind,
+        idempotency_key,
+        Some(ENVIRONMENT),
+        &TransactionDetails::default(),
+    )
+    .await
+    .unwrap()
+}
+
+/// Application state for middleware and handler tests. Nothing listens on the Ledger
+/// endpoint, so calls to it fail.
+pub fn state(pool: PgPool) -> AppState {
+    AppState {
+        pool: pool.clone(),
+        authenticator: Authenticator::local("test_secret", EnvironmentMode::Strict),
+        ledger_grpc: LedgerGrpc::new("http://127.0.0.1:1".to_string()),
+        fx_rates: FxRates::Database(DbFxRateProvider::new(pool)),
+        payout_rail: PayoutRails::Simulated(SimulatedPayoutRail::new(chrono::Duration::seconds(60))),
+        webhook_client: WebhookClient::from_env().unwrap(),
+        event_notifier: EventNotifier::new(),
+        rate_limiter: RateLimiter::from_env(),
+    }
+}
+
+/// In-memory Ledger serving the gRPC API on a local port. Each posted transaction moves its
+/// amount between the balances of its external accounts, once per idempotency key; while
+/// unavailable every call fails as if the Ledger could not be reached.
+#[derive(Clone, Default)]
+pub struct FakeLedger {
+    balances: Arc<Mutex<HashMap<String, i64>>>,
+    posted: Arc<Mutex<HashSet<String>>>,
+    unavailable: Arc<AtomicBool>,
+}
+
+impl FakeLedger {
+    /// Starts the Ledger and returns it with a client pointing at it.
+    pub async fn start() -> (Self, LedgerGrpc) {
+        let ledger = Self::default();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let incoming = TcpIncoming::from_listener(listener, true, None).unwrap();
+
+        let service = LedgerServiceServer::new(ledger.clone());
+        tokio::spawn(async move {
+            tonic::transport::Server::builder()
+                .add_service(service)
+                .serve_with_incoming(incoming)
+                .await
+                .unwrap();
+        });
+
+        (ledger, LedgerGrpc::new(endpoint))
+    }
+
+    pub fn balance(&self, account_id: Uuid) -> i64 {
+        self.balances.lock().unwrap().get(&account_id.to_string()).copied().unwrap_or(0)
+    }
+
+    /// Moves funds into the account outside the Accounts service.
+    pub fn credit(&self, account_id: Uuid, amount: i64) {
+        *self.balances.lock().unwrap().entry(account_id.to_string()).or_default() += amount;
+    }
+
+    pub fn set_available(&self, available: bool) {
+        self.unavailable.store(!available, Ordering::SeqCst);
+    }
+
+    fn is_available(&self) -> bool {
+        !self.unavailable.load(Ordering::SeqCst)
+    }
+}
+
+#[tonic::async_trait]
+impl LedgerService for FakeLedger {
+    async fn post_transaction(
+        &self,
+        request: Request<PostTransactionRequest>,
+    ) -> Result<Response<PostTransactionResponse>, Status> {
+        if !self.is_available() {
+            return Err(Status::unavailable("ledger unavailable"));
+        }
+        let request = request.into_inner();
+
+        if self.posted.lock().unwrap().insert(request.idempotency_key) {
+            let mut balances = self.balances.lock().unwrap();
+            *balances.entry(request.source_external_account_id).or_default() -= request.amount;
+            *balances.entry(request.destination_external_account_id).or_default() += request.amount;
+        }
+
+        Ok(Response::new(PostTransactionResponse {
+            status: "posted".to_string(),
+            ledger_transaction_id: Uuid::new_v4().to_string(),
+            failure_reason: String::new(),
+        }))
+    }
+
+    async fn get_account_balance(
+        &self,
+        request: Request<GetAccountBalanceRequest>,
+    ) -> Result<Response<GetAccountBalanceResponse>, Status> {
+        if !self.is_available() {
+            return Err(Status::unavailable("ledger unavailable"));
+        }
+        let request = request.into_inner();
+        let balance = self.balances.lock().unwrap().get(&request.external_account_id).copied().unwrap_or(0);
+
+        Ok(Response::new(GetAccountBalanceResponse {
+            balance: balance.to_string(),
+            currency: request.currency,
+        }))
+    }
+}
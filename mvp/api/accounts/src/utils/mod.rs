@@ -1,3 +1,6 @@
 pub mod account_number;
+pub mod pagination;
+pub mod routing_number;
 
 pub use account_number::generate_account_number;
+pub use routing_number::is_valid_routing_number;
@@ -0,0 +1,252 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::errors::AppError;
+
+/// Which side of the cursor row a page lies on, in listing order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorDirection {
+    After,
+    Before,
+}
+
+/// Opaque keyset cursor over listings ordered by `(created_at, id)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub direction: CursorDirection,
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        let direction = match self.direction {
+            CursorDirection::After => 'a',
+            CursorDirection::Before => 'b',
+        };
+        let raw = format!("{}|{}|{}", direction, self.created_at.timestamp_micros(), self.id);
+        URL_SAFE_NO_PAD.encode(raw)
+    }
+
+    pub fn decode(token: &str) -> Result<Cursor, AppError> {
+        let invalid = || AppError::Validation("invalid cursor".to_string());
+
+        let raw = URL_SAFE_NO_PAD.decode(token.trim()).map_err(|_| invalid())?;
+        let raw = String::from_utf8(raw).map_err(|_| invalid())?;
+        let mut parts = raw.splitn(3, '|');
+
+        let direction = match parts.next() {
+            Some("a") => CursorDirection::After,
+            Some("b") => CursorDirection::Before,
+            _ => return Err(invalid()),
+        };
+        let created_at = parts
+            .next()
+            .and_then(|micros| micros.parse::<i64>().ok())
+            .and_then(DateTime::from_timestamp_micros)
+            .ok_or_else(invalid)?;
+        let id = parts
+            .next()
+            .and_then(|id| Uuid::parse_str(id).ok())
+            .ok_or_else(invalid)?;
+
+        Ok(Cursor {
+            direction,
+            created_at,
+            id,
+        })
+    }
+
+    /// Row comparison selecting the rows of this page: `(created_at, id) <op> (cursor)`.
+    pub fn comparison(&self, descending: bool) -> &'static str {
+        match (self.direction, descending) {
+            (CursorDirection::After, true) | (CursorDirection::Before, false) => "<",
+            (CursorDirection::After, false) | (CursorDirection::Before, true) => ">",
+        }
+    }
+
+    /// Pages before the cursor are scanned against the listing order (nearest rows first).
+    pub fn scan_descending(&self, descending: bool) -> bool {
+        match self.direction {
+            CursorDirection::After => descending,
+            CursorDirection::Before => !descending,
+        }
+    }
+}
+
+/// Page-number pagination (with COUNT) or keyset pagination from a cursor (without).
+#[derive(Debug, Clone, Copy)]
+pub enum PageRequest {
+    Page { page: u32, per_page: u32 },
+    Cursor { cursor: Cursor, per_page: u32 },
+}
+
+impl PageRequest {
+    /// A cursor takes precedence over `page`.
+    pub fn from_query(page: Option<u32>, per_page: Option<u32>, cursor: Option<&str>) -> Result<PageRequest, AppError> {
+        let per_page = per_page.unwrap_or(10).clamp(1, 100);
+
+        match cursor.filter(|c| !c.trim().is_empty()) {
+            Some(token) => Ok(PageRequest::Cursor {
+                cursor: Cursor::decode(token)?,
+                per_page,
+            }),
+            None => Ok(PageRequest::Page {
+                page: page.unwrap_or(1).max(1),
+                per_page,
+            }),
+        }
+    }
+
+    pub fn per_page(&self) -> u32 {
+        match self {
+            PageRequest::Page { per_page, .. } | PageRequest::Cursor { per_page, .. } => *per_page,
+        }
+    }
+}
+
+/// Rows of a keyset page fetched with `LIMIT per_page + 1` in scan order: drops the probe
+/// row, restores listing order and returns `(rows, next_cursor, prev_cursor)`.
+pub fn keyset_page<T>(
+    mut rows: Vec<T>,
+    cursor: &Cursor,
+    per_page: u32,
+    key: impl Fn(&T) -> (DateTime<Utc>, Uuid),
+) -> (Vec<T>, Option<String>, Option<String>) {
+    let has_more = rows.len() > per_page as usize;
+    rows.truncate(per_page as usize);
+    if cursor.direction == CursorDirection::Before {
+        rows.reverse();
+    }
+
+    let (more_after, more_before) = match cursor.direction {
+        CursorDirection::After => (has_more, true),
+        CursorDirection::Before => (true, has_more),
+    };
+    let (next_cursor, prev_cursor) = edge_cursors(&rows, more_after, more_before, key);
+    (rows, next_cursor, prev_cursor)
+}
+
+/// Cursors pointing after the last and before the first row of a page.
+pub fn edge_cursors<T>(
+    rows: &[T],
+    more_after: bool,
+    more_before: bool,
+    key: impl Fn(&T) -> (DateTime<Utc>, Uuid),
+) -> (Option<String>, Option<String>) {
+    let cursor = |row: &T, direction| {
+        let (created_at, id) = key(row);
+        Cursor {
+            direction,
+            created_at,
+            id,
+        }
+        .encode()
+    };
+
+    let next_cursor = rows
+        .last()
+        .filter(|_| more_after)
+        .map(|row| cursor(row, CursorDirection::After));
+    let prev_cursor = rows
+        .first()
+        .filter(|_| more_before)
+        .map(|row| cursor(row, CursorDirection::Before));
+    (next_cursor, prev_cursor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(micros: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp_micros(micros).unwrap()
+    }
+
+    fn cursor(direction: CursorDirection) -> Cursor {
+        Cursor {
+            direction,
+            created_at: at(1_760_000_000_123_456),
+            id: Uuid::new_v4(),
+        }
+    }
+
+    #[test]
+    fn cursors_round_trip_with_microsecond_precision() {
+        for direction in [CursorDirection::After, CursorDirection::Before] {
+            let original = cursor(direction);
+            let token = original.encode();
+
+            assert!(token.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_'));
+            assert_eq!(Cursor::decode(&token).unwrap(), original);
+            assert_eq!(Cursor::decode(&format!(" {} ", token)).unwrap(), original);
+        }
+    }
+
+    #[test]
+    fn rejects_tampered_cursors() {
+        let id = Uuid::new_v4();
+        let tokens = [
+            "not base64!".to_string(),
+            URL_SAFE_NO_PAD.encode(format!("x|1|{}", id)),
+            URL_SAFE_NO_PAD.encode(format!("a|soon|{}", id)),
+            URL_SAFE_NO_PAD.encode("a|1|not-a-uuid"),
+            URL_SAFE_NO_PAD.encode("a|1"),
+            URL_SAFE_NO_PAD.encode(format!("a|{}|{}", i64::MAX, id)),
+            URL_SAFE_NO_PAD.encode([0xff, 0xfe]),
+        ];
+
+        for token in tokens {
+            assert!(matches!(Cursor::decode(&token), Err(AppError::Validation(_))), "{}", token);
+        }
+    }
+
+    #[test]
+    fn comparison_follows_direction_and_order() {
+        let after = cursor(CursorDirection::After);
+        let before = cursor(CursorDirection::Before);
+
+        assert_eq!(after.comparison(true), "<");
+        assert_eq!(after.comparison(false), ">");
+        assert_eq!(before.comparison(true), ">");
+        assert_eq!(before.comparison(false), "<");
+        assert!(after.scan_descending(true));
+        assert!(!before.scan_descending(true));
+    }
+
+    #[test]
+    fn page_request_prefers_the_cursor_and_clamps_per_page() {
+        let token = cursor(CursorDirection::After).encode();
+
+        assert!(matches!(
+            PageRequest::from_query(Some(3), Some(500), Some(&token)).unwrap(),
+            PageRequest::Cursor { per_page: 100, .. }
+        ));
+        assert!(matches!(
+            PageRequest::from_query(Some(0), Some(0), Some("  ")).unwrap(),
+            PageRequest::Page { page: 1, per_page: 1 }
+        ));
+        assert!(PageRequest::from_query(None, None, Some("garbage")).is_err());
+    }
+
+    #[test]
+    fn keyset_page_drops_the_probe_row_and_restores_order() {
+        let key = |row: &(i64, Uuid)| (at(row.0), row.1);
+        let rows: Vec<(i64, Uuid)> = (1..=4).map(|n| (n, Uuid::new_v4())).collect();
+
+        // Scanned backwards from a `before` cursor: nearest rows first, one extra probe row
+        let scanned: Vec<_> = rows.iter().rev().cloned().collect();
+        let (page, next, prev) = keyset_page(scanned, &cursor(CursorDirection::Before), 3, key);
+        assert_eq!(page, rows[1..].to_vec());
+        assert!(next.is_some());
+        let prev = Cursor::decode(&prev.unwrap()).unwrap();
+        assert_eq!((prev.direction, prev.id), (CursorDirection::Before, rows[1].1));
+
+        let (page, next, prev) = keyset_page(rows[..2].to_vec(), &cursor(CursorDirection::After), 3, key);
+        assert_eq!(page.len(), 2);
+        assert!(next.is_none());
+        assert!(prev.is_some());
+    }
+}
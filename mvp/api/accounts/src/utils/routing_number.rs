@@ -0,0 +1,15 @@
+/// Validate a 9-digit ABA routing transit number (weights 3-7-1, sum divisible by 10).
+pub fn is_valid_routing_number(routing_number: &str) -> bool {
+    if routing_number.len() != 9 || !routing_number.bytes().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+
+    let checksum: u32 = routing_number
+        .bytes()
+        .map(|b| u32::from(b - b'0'))
+        .zip([3, 7, 1, 3, 7, 1, 3, 7, 1])
+        .map(|(digit, weight)| digit * weight)
+        .sum();
+
+    checksum.is_multiple_of(10)
+}
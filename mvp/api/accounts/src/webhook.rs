@@ -0,0 +1,290 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+
+use crate::errors::AppError;
+
+pub const SIGNATURE_HEADER: &str = "Rails-Webhook-Signature";
+pub const TIMESTAMP_HEADER: &str = "Rails-Webhook-Timestamp";
+pub const EVENT_ID_HEADER: &str = "Rails-Webhook-Event-Id";
+pub const EVENT_TYPE_HEADER: &str = "Rails-Webhook-Event-Type";
+
+/// Event types an endpoint can subscribe to.
+pub const EVENT_TYPES: &[&str] = &[
+    "transaction.created",
+    "transaction.posted",
+    "transaction.failed",
+    "account.status_changed",
+];
+
+/// New endpoint signing secret: `whsec_` followed by 32 random bytes in hex.
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    format!("whsec_{}", hex::encode(bytes))
+}
+
+/// Hex HMAC-SHA256 of `{timestamp}.{body}` under the endpoint secret. Receivers recompute it
+/// from the timestamp header and the raw body, and reject stale timestamps.
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    format!("v1={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Delay before attempt `attempt + 1`: 30s doubling per attempt, capped at 6 hours.
+pub fn retry_delay(attempt: i32) -> chrono::Duration {
+    let exponent = attempt.saturating_sub(1).clamp(0, 20) as u32;
+    chrono::Duration::seconds((30i64 << exponent).min(6 * 3600))
+}
+
+/// Endpoint URLs must be http(s) and resolve to public addresses; plain http is only accepted
+/// in sandbox.
+pub async fn validate_url(url: &str, environment: &str) -> Result<(), AppError> {
+    let parsed = reqwest::Url::parse(url).map_err(|_| AppError::Validation("url is not a valid URL".to_string()))?;
+
+    match parsed.scheme() {
+        "https" => {}
+        "http" if environment == "sandbox" => {}
+        "http" => return Err(AppError::Validation("production webhook endpoints must use https".to_string())),
+        _ => return Err(AppError::Validation("url must be an http(s) URL".to_string())),
+    }
+
+    check_destination(&parsed).await
+}
+
+/// Webhooks are sent to private networks only when WEBHOOK_ALLOW_PRIVATE_NETWORKS=true
+/// (local development receivers).
+fn allow_private_networks() -> bool {
+    std::env::var("WEBHOOK_ALLOW_PRIVATE_NETWORKS").is_ok_and(|v| v == "true")
+}
+
+/// Reject URLs whose host is, or resolves to, an address that is not publicly routable, so an
+/// endpoint cannot reach the service's own network or the cloud metadata service.
+async fn check_destination(url: &reqwest::Url) -> Result<(), AppError> {
+    if allow_private_networks() {
+        return Ok(());
+    }
+
+    let host = url
+        .host_str()
+        .ok_or_else(|| AppError::Validation("url must have a host".to_string()))?;
+    let port = url.port_or_known_default().unwrap_or(443);
+
+    let addresses: Vec<IpAddr> = match host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+        Ok(ip) => vec![ip],
+        Err(_) => tokio::net::lookup_host((host, port))
+            .await
+            .map_err(|_| AppError::Validation(format!("url host {} could not be resolved", host)))?
+            .map(|address| address.ip())
+            .collect(),
+    };
+
+    if addresses.is_empty() || !addresses.iter().all(|ip| is_public(*ip)) {
+        return Err(AppError::Validation(
+            "url must not point to a loopback, private, link-local or metadata address".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// False for loopback, private, shared (CGNAT), link-local (which includes the 169.254.169.254
+/// metadata service), unspecified, broadcast, multicast and documentation addresses.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_public_v4(mapped),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        || a == 0
+        || (a == 100 && (64..128).contains(&b))
+        || (a == 192 && b == 0 && c == 0)
+        || a >= 240)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        || (first & 0xfe00) == 0xfc00 // unique local, fd00:ec2::254 metadata
+        || (first & 0xffc0) == 0xfe80 // link-local
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8) // documentation
+        || ip.segments()[..6] == [0, 0, 0, 0, 0, 0]) // IPv4-compatible
+}
+
+/// DNS resolver for deliveries that refuses hosts resolving to non-public addresses, checked
+/// on the addresses actually connected to so a host cannot be re-pointed after registration.
+struct PublicResolver;
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            let addresses: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0)).await?.collect();
+            if addresses.is_empty() || !addresses.iter().all(|address| is_public(address.ip())) {
+                return Err(format!("{} does not resolve to a public address", name.as_str()).into());
+            }
+            let addresses: reqwest::dns::Addrs = Box::new(addresses.into_iter());
+            Ok(addresses)
+        })
+    }
+}
+
+/// Result of one HTTP delivery attempt.
+pub struct AttemptOutcome {
+    pub response_status: Option<u16>,
+    pub error: Option<String>,
+    pub duration_ms: i64,
+}
+
+impl AttemptOutcome {
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// HTTP client for webhook deliveries (WEBHOOK_TIMEOUT_SECS per request). Only public
+/// addresses are connected to, unless WEBHOOK_ALLOW_PRIVATE_NETWORKS=true.
+#[derive(Clone)]
+pub struct WebhookClient {
+    client: reqwest::Client,
+}
+
+impl WebhookClient {
+    pub fn from_env() -> Result<Self, AppError> {
+        let timeout_secs = std::env::var("WEBHOOK_TIMEOUT_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(10);
+
+        let mut builder = reqwest::Client::builder()
+            .timeout(Duration::from_secs(timeout_secs))
+            .redirect(reqwest::redirect::Policy::none());
+        if !allow_private_networks() {
+            builder = builder.dns_resolver(Arc::new(PublicResolver));
+        }
+        let client = builder
+            .build()
+            .map_err(|e| AppError::Internal(format!("failed to build webhook HTTP client: {}", e)))?;
+
+        Ok(Self { client })
+    }
+
+    /// POST the signed body; any 2xx response is a success. Only the status of other responses
+    /// is recorded: their bodies are never read into the delivery log.
+    pub async fn send(&self, url: &str, secret: &str, event_id: &str, event_type: &str, body: &str) -> AttemptOutcome {
+        let timestamp = chrono::Utc::now().timestamp();
+        let started = Instant::now();
+
+        // Literal IP hosts bypass the resolver, so the destination is checked here as well
+        let destination = match reqwest::Url::parse(url) {
+            Ok(parsed) => check_destination(&parsed).await,
+            Err(_) => Err(AppError::Validation("url is not a valid URL".to_string())),
+        };
+        if let Err(AppError::Validation(message)) = destination {
+            return AttemptOutcome {
+                response_status: None,
+                error: Some(message),
+                duration_ms: started.elapsed().as_millis() as i64,
+            };
+        }
+
+        let result = self
+            .client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, sign(secret, timestamp, body))
+            .header(EVENT_ID_HEADER, event_id)
+            .header(EVENT_TYPE_HEADER, event_type)
+            .body(body.to_string())
+            .send()
+            .await;
+
+        let (response_status, error) = match result {
+            Ok(response) if response.status().is_success() => (Some(response.status().as_u16()), None),
+            Ok(response) => {
+                let status = response.status().as_u16();
+                (Some(status), Some(format!("HTTP {}", status)))
+            }
+            Err(e) => (None, Some(e.to_string())),
+        };
+
+        AttemptOutcome {
+            response_status,
+            error,
+            duration_ms: started.elapsed().as_millis() as i64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_public_addresses_are_allowed() {
+        let blocked = [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "255.255.255.255",
+            "::1",
+            "::",
+            "fd00:ec2::254",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "::ffff:169.254.169.254",
+        ];
+        for ip in blocked {
+            assert!(!is_public(ip.parse().unwrap()), "{}", ip);
+        }
+
+        for ip in ["93.184.216.34", "8.8.8.8", "2606:4700:4700::1111"] {
+            assert!(is_public(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_urls_pointing_at_internal_addresses() {
+        for url in [
+            "http://169.254.169.254/latest/meta-data",
+            "https://127.0.0.1:8080/webhooks",
+            "https://[::ffff:10.0.0.1]/webhooks",
+            "https://localhost/webhooks",
+        ] {
+            assert!(matches!(validate_url(url, "sandbox").await, Err(AppError::Validation(_))), "{}", url);
+        }
+
+        assert!(validate_url("https://93.184.216.34/webhooks", "production").await.is_ok());
+        assert!(matches!(
+            validate_url("http://93.184.216.34/webhooks", "production").await,
+            Err(AppError::Validation(_))
+        ));
+    }
+}
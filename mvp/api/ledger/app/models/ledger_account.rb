@@ -75,6 +75,13 @@ class LedgerAccount < ApplicationRecord
         external_account_id: 'SYSTEM_FEE_INCOME',
         currency: currency,
         account_type: 'income'
+      ),
+      interest_expense: resolve(
+        organization_id: organization_id,
+        environment: environment,
+        external_account_id: 'SYSTEM_INTEREST_EXPENSE',
+        currency: currency,
+        account_type: 'expense'
       )
     }
   end
@@ -135,6 +135,9 @@ class LedgerPoster
   end
 
   def determine_source_account_type
+    return 'expense' if @source_external_account_id == 'SYSTEM_INTEREST_EXPENSE'
+    return 'liability' if %w[SYSTEM_FX_CLEARING SYSTEM_INTERORG_CLEARING].include?(@source_external_account_id)
+
     if @is_deposit || @source_external_account_id.start_with?('SYSTEM_')
       'asset'
     else
@@ -149,6 +152,13 @@ class LedgerPoster
         'asset'
       when 'SYSTEM_FEE_INCOME'
         'income'
+      when 'SYSTEM_FX_CLEARING'
+        # Per-currency FX position; cross-currency transfers post
+        # source -> SYSTEM_FX_CLEARING and SYSTEM_FX_CLEARING -> destination.
+        'liability'
+      when 'SYSTEM_INTERORG_CLEARING'
+        # Amount owed to/from another organization for cross-organization transfers.
+        'liability'
       else
         'asset'
       end
@@ -245,7 +255,10 @@ class LedgerPoster
     # Accounts service uses:
     # - deposit:  SYSTEM_CASH_CONTROL -> user_account
     # - withdraw: user_account -> SYSTEM_CASH_CONTROL
-    if source_is_cash && !dest_is_cash
+    # - interest: SYSTEM_INTEREST_EXPENSE -> user_account
+    if source_account.account_type == 'expense'
+      :expense
+    elsif source_is_cash && !dest_is_cash
       :deposit
     elsif dest_is_cash && !source_is_cash
       :withdraw
@@ -264,6 +277,9 @@ class LedgerPoster
     when :withdraw
       # Withdrawal decreases both cash (asset) and customer liability.
       :decrease
+    when :expense
+      # Interest paid out increases both the expense and customer liability.
+      :increase
     else
       raise PostingError, "Unknown operation"
     end
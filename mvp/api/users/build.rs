@@ -2,5 +2,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     tonic_build::configure()
         .build_client(true)
         .compile_protos(&["proto/accounts.proto"], &["proto"])?;
+    tonic_build::configure()
+        .build_client(false)
+        .build_server(true)
+        .compile_protos(&["proto/auth.proto"], &["proto"])?;
     Ok(())
 }
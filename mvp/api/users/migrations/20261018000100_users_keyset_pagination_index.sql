@@ -0,0 +1,5 @@
+-- Keyset (cursor) pagination of user listings seeks on (created_at, id)
+-- within a business environment.
+CREATE INDEX IF NOT EXISTS users_business_env_created_at_id_idx
+    ON users(business_id, environment_id, created_at, id)
+    WHERE status = 'active';
@@ -0,0 +1,22 @@
+-- Responses cached per (tenant, Idempotency-Key) for mutating endpoints that have no
+-- idempotency of their own. A row without a response is a request still in flight;
+-- its lock expires so a crashed request can be retried. The accounts service creates
+-- the same table (tenants are prefixed with the service name). Stored bodies can hold
+-- secrets shown once (new API keys, webhook signing secrets); rows are purged after expiry.
+
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    tenant VARCHAR(255) NOT NULL,
+    idempotency_key VARCHAR(255) NOT NULL,
+    -- SHA-256 (hex) of method, path and body of the first request
+    request_fingerprint VARCHAR(64) NOT NULL,
+    response_status INTEGER,
+    response_content_type VARCHAR(255),
+    response_body BYTEA,
+    locked_until TIMESTAMP WITH TIME ZONE NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (tenant, idempotency_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_expires_at
+    ON idempotency_keys(expires_at);
@@ -0,0 +1,15 @@
+-- Per-organization request budgets, overriding the service defaults for one route class.
+-- A token bucket per caller holds up to `burst` requests and refills at
+-- `requests_per_minute`. The accounts service creates the same table; `organization_id` is
+-- the business id and each service reads the classes it serves.
+
+CREATE TABLE IF NOT EXISTS rate_limits (
+    organization_id UUID NOT NULL,
+    route_class VARCHAR(20) NOT NULL
+        CHECK (route_class IN ('auth', 'read', 'write', 'money_movement')),
+    requests_per_minute INTEGER NOT NULL CHECK (requests_per_minute > 0),
+    burst INTEGER NOT NULL CHECK (burst > 0),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (organization_id, route_class)
+);
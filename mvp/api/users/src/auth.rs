@@ -29,8 +29,7 @@ struct JwtClaims {
     sub: String,
     #[allow(dead_code)]
     exp: i64, // Used by jsonwebtoken for validation, but not explicitly read
-    #[allow(dead_code)]
-    env: Option<String>, // Reserved for future use
+    env: Option<String>,
 }
 
 #[async_trait]
@@ -38,6 +37,11 @@ impl FromRequestParts<AppState> for AuthContext {
     type Rejection = AppError;
 
     async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        // The rate limiter may already have authenticated the request
+        if let Some(ctx) = parts.extensions.get::<AuthContext>() {
+            return Ok(ctx.clone());
+        }
+
         let environment_id_from_uuid_header: Option<Uuid> = parts
             .headers
             .get(ENVIRONMENT_ID_HEADER)
@@ -151,15 +155,7 @@ impl FromRequestParts<AppState> for AuthContext {
             .strip_prefix("Bearer ")
             .ok_or(AppError::Unauthorized)?;
 
-        let secret = std::env::var("JWT_SECRET").unwrap_or_else(|_| "dev_secret".to_string());
-        let decoded = decode::<JwtClaims>(
-            token,
-            &DecodingKey::from_secret(secret.as_bytes()),
-            &Validation::default(),
-        )
-        .map_err(|_| AppError::Unauthorized)?;
-
-        let user_id = Uuid::parse_str(&decoded.claims.sub).map_err(|_| AppError::Unauthorized)?;
+        let user_id = decode_access_token(token)?.user_id;
 
         // First, try to find user in the requested environment
         let rec = sqlx::query(
@@ -214,6 +210,27 @@ impl FromRequestParts<AppState> for AuthContext {
     }
 }
 
+/// Claims of a valid (signature and expiry checked) access token.
+pub(crate) struct AccessToken {
+    pub user_id: Uuid,
+    pub environment_id: Option<Uuid>,
+}
+
+pub(crate) fn decode_access_token(token: &str) -> Result<AccessToken, AppError> {
+    let secret = crate::config::jwt_secret().ok_or(AppError::Internal)?;
+    let decoded = decode::<JwtClaims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &Validation::default(),
+    )
+    .map_err(|_| AppError::Unauthorized)?;
+
+    Ok(AccessToken {
+        user_id: Uuid::parse_str(&decoded.claims.sub).map_err(|_| AppError::Unauthorized)?,
+        environment_id: decoded.claims.env.as_deref().and_then(|env| Uuid::parse_str(env).ok()),
+    })
+}
+
 pub(crate) fn hash_api_key(api_key_plain: &str) -> Result<String, AppError> {
     let secret = std::env::var("API_KEY_HASH_SECRET")
         .unwrap_or_else(|_| "dev_api_key_hash_secret".to_string());
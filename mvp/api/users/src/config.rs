@@ -1,10 +1,27 @@
+/// Signs access tokens when JWT_SECRET is unset and ALLOW_DEV_JWT_SECRET=true
+const DEV_JWT_SECRET: &str = "dev_secret";
+
 #[derive(Debug, Clone)]
 pub struct Config {
     pub database_url: String,
     pub server_addr: String,
+    pub grpc_addr: String,
     pub accounts_grpc_url: String,
     pub sentry_dsn: Option<String>,
     pub environment: String,
+    /// Access tokens are signed with the development secret
+    pub dev_jwt_secret: bool,
+}
+
+/// Secret access tokens are signed and verified with: JWT_SECRET, or the development secret
+/// when ALLOW_DEV_JWT_SECRET=true. Tokens signed with a guessable secret could be forged for
+/// any user, so there is no other fallback.
+pub fn jwt_secret() -> Option<String> {
+    match std::env::var("JWT_SECRET").ok().filter(|v| !v.trim().is_empty()) {
+        Some(secret) => Some(secret),
+        None if std::env::var("ALLOW_DEV_JWT_SECRET").is_ok_and(|v| v == "true") => Some(DEV_JWT_SECRET.to_string()),
+        None => None,
+    }
 }
 
 pub fn load() -> Result<Config, anyhow::Error> {
@@ -27,18 +44,31 @@ pub fn load() -> Result<Config, anyhow::Error> {
         std::env::var("SERVER_ADDR").unwrap_or_else(|_| "0.0.0.0:8080".to_string())
     };
 
+    // Token introspection for other services (AuthService)
+    let grpc_port = std::env::var("GRPC_PORT")
+        .ok()
+        .and_then(|p| p.parse::<u16>().ok())
+        .unwrap_or(50051);
+    let grpc_addr = format!("{}:{}", host, grpc_port);
+
     let accounts_grpc_url = std::env::var("ACCOUNTS_GRPC_URL")
         .unwrap_or_else(|_| "http://localhost:50052".to_string());
     
     let sentry_dsn = std::env::var("SENTRY_DSN").ok();
     let environment = std::env::var("ENVIRONMENT")
         .unwrap_or_else(|_| "development".to_string());
+
+    let jwt_secret = jwt_secret().ok_or_else(|| anyhow::anyhow!(
+        "JWT_SECRET environment variable is required (ALLOW_DEV_JWT_SECRET=true uses the development secret)"
+    ))?;
     
     Ok(Config {
         database_url,
         server_addr,
+        grpc_addr,
         accounts_grpc_url,
         sentry_dsn,
         environment,
+        dev_jwt_secret: jwt_secret == DEV_JWT_SECRET,
     })
 }
@@ -11,6 +11,12 @@ pub enum AppError {
     UnrecognizedSource,
     #[error("Bad request: {0}")]
     BadRequest(String),
+    #[error("Conflict: {0}")]
+    Conflict(String),
+    #[error("Idempotency conflict: {0}")]
+    IdempotencyConflict(String),
+    #[error("Too many requests: {0}")]
+    RateLimited(String),
     #[error("Internal server error")]
     Internal,
 }
@@ -22,6 +28,11 @@ impl IntoResponse for AppError {
             AppError::Forbidden => (StatusCode::FORBIDDEN, "forbidden", None, false),
             AppError::UnrecognizedSource => (StatusCode::FORBIDDEN, "unrecognized_source", None, true), // Security issue
             AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", Some(msg.clone()), false),
+            AppError::Conflict(msg) => (StatusCode::CONFLICT, "conflict", Some(msg.clone()), false),
+            AppError::IdempotencyConflict(msg) => {
+                (StatusCode::UNPROCESSABLE_ENTITY, "idempotency_conflict", Some(msg.clone()), false)
+            }
+            AppError::RateLimited(msg) => (StatusCode::TOO_MANY_REQUESTS, "rate_limited", Some(msg.clone()), false),
             AppError::Internal => (StatusCode::INTERNAL_SERVER_ERROR, "internal", None, true), // Always report internal errors
         };
         
@@ -0,0 +1,210 @@
+//! Idempotency-Key support for mutating endpoints, keyed per business environment.
+
+use axum::body::{to_bytes, Body};
+use axum::extract::{FromRequestParts, Request, State};
+use axum::http::{header, HeaderValue, Method, StatusCode, Uri};
+use axum::middleware::Next;
+use axum::response::Response;
+use sha2::{Digest, Sha256};
+use sqlx::Row;
+
+use crate::auth::AuthContext;
+use crate::db::Db;
+use crate::error::AppError;
+use crate::routes::AppState;
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// Request and response bodies above this are not handled (matches axum's default body limit)
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// The first request with a key runs and its response is stored for IDEMPOTENCY_KEY_TTL_SECS;
+/// retries get the stored response with `Idempotent-Replayed: true`. A retry while the first
+/// request is still running gets 409, a key reused for a different request 422. 5xx responses
+/// are not stored, so the request can be retried with the same key. Requests without the
+/// header are passed through.
+pub async fn idempotency_middleware(
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    if !matches!(*req.method(), Method::POST | Method::PUT | Method::PATCH | Method::DELETE) {
+        return Ok(next.run(req).await);
+    }
+
+    let Some(key) = req
+        .headers()
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+    else {
+        return Ok(next.run(req).await);
+    };
+
+    if key.len() > 255 {
+        return Err(AppError::BadRequest("Idempotency-Key must be at most 255 characters".to_string()));
+    }
+
+    let (mut parts, body) = req.into_parts();
+    let ctx = AuthContext::from_request_parts(&mut parts, &state).await?;
+    let tenant = format!("users:{}:{}", ctx.business_id, ctx.environment_id);
+
+    let body = to_bytes(body, MAX_BODY_BYTES)
+        .await
+        .map_err(|_| AppError::BadRequest("Request body is too large".to_string()))?;
+    let fingerprint = request_fingerprint(&parts.method, &parts.uri, &body);
+
+    if !acquire(&state.db, &tenant, &key, &fingerprint).await? {
+        let row = sqlx::query(
+            "SELECT request_fingerprint, response_status, response_content_type, response_body \
+             FROM idempotency_keys WHERE tenant = $1 AND idempotency_key = $2 AND expires_at > NOW()",
+        )
+        .bind(&tenant)
+        .bind(&key)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|_| AppError::Internal)?
+        // Expired or released between the two queries
+        .ok_or_else(|| AppError::Conflict("A request with this Idempotency-Key is being retried; try again".to_string()))?;
+
+        let stored_fingerprint: String = row.get("request_fingerprint");
+        if stored_fingerprint != fingerprint {
+            return Err(AppError::IdempotencyConflict(format!(
+                "Idempotency-Key '{}' was already used for a different request",
+                key
+            )));
+        }
+
+        let status: Option<i32> = row.get("response_status");
+        let status = status.ok_or_else(|| {
+            AppError::Conflict("A request with this Idempotency-Key is still being processed".to_string())
+        })?;
+
+        tracing::info!(idempotency_key = %key, status, "idempotent_replay");
+        let mut response = Response::new(Body::from(
+            row.get::<Option<Vec<u8>>, _>("response_body").unwrap_or_default(),
+        ));
+        *response.status_mut() = StatusCode::from_u16(status as u16).unwrap_or(StatusCode::OK);
+        if let Some(content_type) = row
+            .get::<Option<String>, _>("response_content_type")
+            .and_then(|v| HeaderValue::from_str(&v).ok())
+        {
+            response.headers_mut().insert(header::CONTENT_TYPE, content_type);
+        }
+        response
+            .headers_mut()
+            .insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+        return Ok(response);
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+
+    if response.status().is_server_error() {
+        // Let the request be retried with the same key
+        if let Err(e) = sqlx::query(
+            "DELETE FROM idempotency_keys WHERE tenant = $1 AND idempotency_key = $2 AND response_status IS NULL",
+        )
+        .bind(&tenant)
+        .bind(&key)
+        .execute(&state.db)
+        .await
+        {
+            tracing::warn!(idempotency_key = %key, error = %e, "idempotency_key_release_failed");
+        }
+        return Ok(response);
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = to_bytes(body, MAX_BODY_BYTES).await.map_err(|_| AppError::Internal)?;
+    let content_type = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    // The request has taken effect; a failure to store it only means a retry runs it again
+    // once the lock expires
+    if let Err(e) = sqlx::query(
+        "UPDATE idempotency_keys \
+         SET response_status = $3, response_content_type = $4, response_body = $5, locked_until = NOW() \
+         WHERE tenant = $1 AND idempotency_key = $2",
+    )
+    .bind(&tenant)
+    .bind(&key)
+    .bind(parts.status.as_u16() as i32)
+    .bind(&content_type)
+    .bind(body.as_ref())
+    .execute(&state.db)
+    .await
+    {
+        tracing::warn!(idempotency_key = %key, error = %e, "idempotency_response_store_failed");
+    }
+
+    Ok(Response::from_parts(parts, Body::from(body)))
+}
+
+/// Take the key for a request: inserts it, or takes over an expired key or a stale lock of
+/// the same request. Returns false when the key is held by another record.
+async fn acquire(db: &Db, tenant: &str, key: &str, fingerprint: &str) -> Result<bool, AppError> {
+    let row = sqlx::query(
+        "INSERT INTO idempotency_keys (tenant, idempotency_key, request_fingerprint, locked_until, expires_at) \
+         VALUES ($1, $2, $3, NOW() + make_interval(secs => $4), NOW() + make_interval(secs => $5)) \
+         ON CONFLICT (tenant, idempotency_key) DO UPDATE \
+         SET request_fingerprint = EXCLUDED.request_fingerprint, response_status = NULL, \
+             response_content_type = NULL, response_body = NULL, locked_until = EXCLUDED.locked_until, \
+             expires_at = EXCLUDED.expires_at, created_at = NOW() \
+         WHERE idempotency_keys.expires_at <= NOW() \
+            OR (idempotency_keys.response_status IS NULL AND idempotency_keys.locked_until <= NOW() \
+                AND idempotency_keys.request_fingerprint = EXCLUDED.request_fingerprint) \
+         RETURNING tenant",
+    )
+    .bind(tenant)
+    .bind(key)
+    .bind(fingerprint)
+    .bind(env_secs("IDEMPOTENCY_LOCK_SECS", 60) as f64)
+    .bind(env_secs("IDEMPOTENCY_KEY_TTL_SECS", 24 * 3600) as f64)
+    .fetch_optional(db)
+    .await
+    .map_err(|_| AppError::Internal)?;
+
+    Ok(row.is_some())
+}
+
+/// SHA-256 (hex) of method, path and query, and body.
+fn request_fingerprint(method: &Method, uri: &Uri, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_str().as_bytes());
+    hasher.update(b"\n");
+    hasher.update(uri.path_and_query().map(|p| p.as_str()).unwrap_or_default().as_bytes());
+    hasher.update(b"\n");
+    hasher.update(body);
+    format!("{:x}", hasher.finalize())
+}
+
+fn env_secs(name: &str, default: i64) -> i64 {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(default)
+}
+
+/// Background job deleting expired idempotency keys (hourly).
+pub async fn run(db: Db) {
+    loop {
+        match sqlx::query("DELETE FROM idempotency_keys WHERE expires_at <= NOW()")
+            .execute(&db)
+            .await
+        {
+            Ok(result) if result.rows_affected() > 0 => {
+                tracing::info!(deleted = result.rows_affected(), "idempotency_keys_purged")
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!(error = %e, "idempotency_key_purge_failed"),
+        }
+
+        tokio::time::sleep(std::time::Duration::from_secs(3600)).await;
+    }
+}
@@ -0,0 +1,119 @@
+//! gRPC token introspection: other services (accounts-api) send the credential a caller
+//! presented and get back the business it belongs to.
+
+use chrono::Utc;
+use sqlx::Row;
+use tonic::{Request, Response, Status};
+use uuid::Uuid;
+
+use crate::auth::{decode_access_token, hash_api_key};
+use crate::db::Db;
+
+pub mod proto {
+    tonic::include_proto!("rails.users.v1");
+}
+
+use proto::auth_service_server::AuthService;
+use proto::introspect_request::Credential;
+use proto::{IntrospectRequest, IntrospectResponse};
+
+#[derive(Clone)]
+pub struct AuthGrpcService {
+    db: Db,
+}
+
+impl AuthGrpcService {
+    pub fn new(db: Db) -> Self {
+        Self { db }
+    }
+
+    /// Access tokens are valid while the user is active in the business owning the token's
+    /// environment, the same check as the REST extractor.
+    async fn introspect_access_token(&self, token: &str) -> Result<IntrospectResponse, Status> {
+        let Ok(claims) = decode_access_token(token) else {
+            return Ok(IntrospectResponse::default());
+        };
+        let Some(environment_id) = claims.environment_id else {
+            return Ok(IntrospectResponse::default());
+        };
+
+        let rec = sqlx::query(
+            "SELECT u.business_id, e.type FROM users u \
+             JOIN environments e ON e.business_id = u.business_id \
+             WHERE u.id = $1 AND u.status = 'active' AND e.id = $2 AND e.status = 'active' \
+             LIMIT 1"
+        )
+        .bind(claims.user_id)
+        .bind(environment_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|_| Status::internal("introspection lookup failed"))?;
+
+        let Some(rec) = rec else {
+            return Ok(IntrospectResponse::default());
+        };
+
+        Ok(IntrospectResponse {
+            active: true,
+            business_id: rec.get::<Uuid, _>("business_id").to_string(),
+            user_id: claims.user_id.to_string(),
+            api_key_id: String::new(),
+            environment_id: environment_id.to_string(),
+            environment: rec.get("type"),
+        })
+    }
+
+    async fn introspect_api_key(&self, api_key_plain: &str) -> Result<IntrospectResponse, Status> {
+        let key_hash = hash_api_key(api_key_plain).map_err(|_| Status::internal("failed to hash API key"))?;
+
+        let rec = sqlx::query(
+            "SELECT k.id, k.business_id, k.environment_id, e.type FROM api_keys k \
+             LEFT JOIN environments e ON e.id = k.environment_id \
+             WHERE k.key_hash = $1 AND k.status = 'active' AND k.revoked_at IS NULL"
+        )
+        .bind(&key_hash)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|_| Status::internal("introspection lookup failed"))?;
+
+        let Some(rec) = rec else {
+            return Ok(IntrospectResponse::default());
+        };
+
+        let api_key_id: Uuid = rec.get("id");
+        let environment_id: Option<Uuid> = rec.get("environment_id");
+
+        let _ = sqlx::query("UPDATE api_keys SET last_used_at = $1 WHERE id = $2")
+            .bind(Utc::now())
+            .bind(api_key_id)
+            .execute(&self.db)
+            .await;
+
+        Ok(IntrospectResponse {
+            active: true,
+            business_id: rec.get::<Uuid, _>("business_id").to_string(),
+            user_id: String::new(),
+            api_key_id: api_key_id.to_string(),
+            environment_id: environment_id.map(|id| id.to_string()).unwrap_or_default(),
+            environment: rec.get::<Option<String>, _>("type").unwrap_or_default(),
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl AuthService for AuthGrpcService {
+    async fn introspect(
+        &self,
+        request: Request<IntrospectRequest>,
+    ) -> Result<Response<IntrospectResponse>, Status> {
+        let response = match request.into_inner().credential {
+            Some(Credential::AccessToken(token)) if !token.trim().is_empty() => {
+                self.introspect_access_token(token.trim()).await?
+            }
+            Some(Credential::ApiKey(key)) if !key.trim().is_empty() => self.introspect_api_key(key.trim()).await?,
+            _ => return Err(Status::invalid_argument("access_token or api_key is required")),
+        };
+
+        Ok(Response::new(response))
+    }
+}
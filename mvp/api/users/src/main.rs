@@ -6,6 +6,10 @@ mod error;
 mod routes;
 mod auth;
 mod grpc;
+mod idempotency;
+mod introspection;
+mod pagination;
+mod rate_limit;
 
 use tracing_subscriber::prelude::*;
 use crate::routes::register_routes;
@@ -53,7 +57,11 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("Loaded configuration");
     tracing::info!("  DATABASE_URL: {}", mask_url(&config.database_url));
     tracing::info!("  SERVER_ADDR: {}", config.server_addr);
+    tracing::info!("  GRPC_ADDR: {}", config.grpc_addr);
     tracing::info!("  ACCOUNTS_GRPC_URL: {}", config.accounts_grpc_url);
+    if config.dev_jwt_secret {
+        tracing::warn!("JWT_SECRET is not set; signing access tokens with the development secret");
+    }
     
     tracing::info!("Connecting to database...");
     let db = db::init(&config.database_url).await
@@ -85,7 +93,15 @@ async fn main() -> anyhow::Result<()> {
     let grpc = grpc::init(&config).await?;
     tracing::info!("gRPC clients initialized");
     
-    let app = register_routes(db.clone(), grpc.clone());
+    // Background job: delete expired idempotency keys
+    tokio::spawn(idempotency::run(db.clone()));
+
+    // Per-caller token buckets (RATE_LIMIT_*_PER_MINUTE, overridden per business);
+    // overrides are reloaded every minute
+    let rate_limiter = rate_limit::RateLimiter::from_env();
+    tokio::spawn(rate_limit::run(db.clone(), rate_limiter.clone()));
+
+    let app = register_routes(db.clone(), grpc.clone(), rate_limiter);
     let addr: std::net::SocketAddr = config.server_addr.parse()
         .map_err(|e| anyhow::anyhow!("Failed to parse SERVER_ADDR '{}': {}", config.server_addr, e))?;
     
@@ -102,7 +118,26 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("  POST /api/v1/auth/refresh");
     tracing::info!("  POST /api/v1/auth/revoke");
     
-    serve(listener, app).await?;
+    let grpc_addr: std::net::SocketAddr = config.grpc_addr.parse()
+        .map_err(|e| anyhow::anyhow!("Failed to parse gRPC address '{}': {}", config.grpc_addr, e))?;
+    let auth_service = introspection::AuthGrpcService::new(db.clone());
+    tracing::info!("gRPC AuthService listening on {}", grpc_addr);
+
+    let http_task = async move {
+        serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>())
+            .await
+            .map_err(|e| anyhow::anyhow!("HTTP server error: {}", e))
+    };
+
+    let grpc_task = async move {
+        tonic::transport::Server::builder()
+            .add_service(introspection::proto::auth_service_server::AuthServiceServer::new(auth_service))
+            .serve(grpc_addr)
+            .await
+            .map_err(|e| anyhow::anyhow!("gRPC server error: {}", e))
+    };
+
+    tokio::try_join!(http_task, grpc_task)?;
     Ok(())
 }
 
@@ -0,0 +1,104 @@
+//! Opaque keyset cursors for listings ordered by `(created_at, id)`
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::engine::Engine;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::error::AppError;
+
+/// Which side of the cursor row a page lies on, in listing order (newest first)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorDirection {
+    After,
+    Before,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub direction: CursorDirection,
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl Cursor {
+    pub fn after(created_at: DateTime<Utc>, id: Uuid) -> Self {
+        Cursor { direction: CursorDirection::After, created_at, id }
+    }
+
+    pub fn before(created_at: DateTime<Utc>, id: Uuid) -> Self {
+        Cursor { direction: CursorDirection::Before, created_at, id }
+    }
+
+    pub fn encode(&self) -> String {
+        let direction = match self.direction {
+            CursorDirection::After => 'a',
+            CursorDirection::Before => 'b',
+        };
+        URL_SAFE_NO_PAD.encode(format!("{}|{}|{}", direction, self.created_at.timestamp_micros(), self.id))
+    }
+
+    pub fn decode(token: &str) -> Result<Self, AppError> {
+        let invalid = || AppError::BadRequest("invalid cursor".to_string());
+
+        let raw = URL_SAFE_NO_PAD.decode(token.trim()).map_err(|_| invalid())?;
+        let raw = String::from_utf8(raw).map_err(|_| invalid())?;
+        let mut parts = raw.splitn(3, '|');
+
+        let direction = match parts.next() {
+            Some("a") => CursorDirection::After,
+            Some("b") => CursorDirection::Before,
+            _ => return Err(invalid()),
+        };
+        let created_at = parts
+            .next()
+            .and_then(|micros| micros.parse::<i64>().ok())
+            .and_then(DateTime::from_timestamp_micros)
+            .ok_or_else(invalid)?;
+        let id = parts
+            .next()
+            .and_then(|id| Uuid::parse_str(id).ok())
+            .ok_or_else(invalid)?;
+
+        Ok(Cursor { direction, created_at, id })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(micros: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp_micros(micros).unwrap()
+    }
+
+    #[test]
+    fn cursors_round_trip_with_microsecond_precision() {
+        let created_at = at(1_760_000_000_123_456);
+        for original in [Cursor::after(created_at, Uuid::new_v4()), Cursor::before(created_at, Uuid::new_v4())] {
+            let token = original.encode();
+
+            assert!(token.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_'));
+            assert_eq!(Cursor::decode(&token).unwrap(), original);
+            assert_eq!(Cursor::decode(&format!(" {} ", token)).unwrap(), original);
+        }
+    }
+
+    #[test]
+    fn rejects_tampered_cursors() {
+        let id = Uuid::new_v4();
+        let tokens = [
+            "not base64!".to_string(),
+            URL_SAFE_NO_PAD.encode(format!("x|1|{}", id)),
+            URL_SAFE_NO_PAD.encode(format!("a|soon|{}", id)),
+            URL_SAFE_NO_PAD.encode("a|1|not-a-uuid"),
+            URL_SAFE_NO_PAD.encode("a|1"),
+            URL_SAFE_NO_PAD.encode(format!("a|{}|{}", i64::MAX, id)),
+            URL_SAFE_NO_PAD.encode([0xff, 0xfe]),
+        ];
+
+        for token in tokens {
+            assert!(matches!(Cursor::decode(&token), Err(AppError::BadRequest(_))), "{}", token);
+        }
+    }
+}
@@ -0,0 +1,464 @@
+//! Token-bucket rate limiting per caller and route class, with per-business overrides.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use axum::extract::{ConnectInfo, FromRequestParts, Request, State};
+use axum::http::{header, request::Parts, HeaderMap, HeaderValue, Method};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::auth::AuthContext;
+use crate::db::Db;
+use crate::error::AppError;
+use crate::routes::AppState;
+
+/// How often business overrides are reloaded and idle buckets dropped
+const RELOAD_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Most buckets kept between reloads. Auth routes and failed authentication get a bucket per
+/// client IP, so without a cap a flood from many addresses grows the map until the next reload.
+const MAX_BUCKETS: usize = 100_000;
+
+/// Requests that share a budget. On authenticated routes safe requests (GET, HEAD) count
+/// as reads.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RouteClass {
+    /// Login, token refresh/revoke and business registration, counted per client IP
+    Auth,
+    Read,
+    Write,
+}
+
+impl RouteClass {
+    const ALL: [RouteClass; 3] = [RouteClass::Auth, RouteClass::Read, RouteClass::Write];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RouteClass::Auth => "auth",
+            RouteClass::Read => "read",
+            RouteClass::Write => "write",
+        }
+    }
+
+    /// Default requests per minute (RATE_LIMIT_<CLASS>_PER_MINUTE); the burst is the same
+    fn default_budget(&self) -> Budget {
+        let (name, default) = match self {
+            RouteClass::Auth => ("RATE_LIMIT_AUTH_PER_MINUTE", 20),
+            RouteClass::Read => ("RATE_LIMIT_READ_PER_MINUTE", 600),
+            RouteClass::Write => ("RATE_LIMIT_WRITE_PER_MINUTE", 120),
+        };
+        let per_minute = std::env::var(name)
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(default);
+
+        Budget { per_minute, burst: per_minute }
+    }
+}
+
+/// Token bucket shape: holds up to `burst` requests, refilled at `per_minute`.
+#[derive(Clone, Copy, Debug)]
+struct Budget {
+    per_minute: u32,
+    burst: u32,
+}
+
+impl Budget {
+    fn refill_per_sec(&self) -> f64 {
+        f64::from(self.per_minute) / 60.0
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+    budget: Budget,
+}
+
+impl Bucket {
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.budget.refill_per_sec()).min(f64::from(self.budget.burst));
+        self.refilled_at = now;
+    }
+
+    fn is_full(&self, now: Instant) -> bool {
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens + elapsed * self.budget.refill_per_sec() >= f64::from(self.budget.burst)
+    }
+}
+
+struct Outcome {
+    allowed: bool,
+    budget: Budget,
+    remaining: u32,
+    reset_secs: u64,
+    retry_after_secs: u64,
+}
+
+impl Outcome {
+    fn apply(&self, headers: &mut HeaderMap) {
+        headers.insert("ratelimit-limit", HeaderValue::from(self.budget.burst));
+        headers.insert("ratelimit-remaining", HeaderValue::from(self.remaining));
+        headers.insert("ratelimit-reset", HeaderValue::from(self.reset_secs));
+        if let Ok(policy) = HeaderValue::from_str(&format!("{};w=60;burst={}", self.budget.per_minute, self.budget.burst)) {
+            headers.insert("ratelimit-policy", policy);
+        }
+        if !self.allowed {
+            headers.insert(header::RETRY_AFTER, HeaderValue::from(self.retry_after_secs));
+        }
+    }
+}
+
+/// In-memory buckets, so limits apply per instance. Budgets come from
+/// RATE_LIMIT_*_PER_MINUTE unless the business has a row in `rate_limits`.
+#[derive(Clone)]
+pub struct RateLimiter {
+    defaults: HashMap<RouteClass, Budget>,
+    overrides: Arc<RwLock<HashMap<(Uuid, RouteClass), Budget>>>,
+    buckets: Arc<Mutex<HashMap<String, Bucket>>>,
+    /// Proxies in front of the service that append to X-Forwarded-For (TRUSTED_PROXY_HOPS)
+    trusted_proxy_hops: usize,
+}
+
+impl RateLimiter {
+    pub fn from_env() -> Self {
+        Self {
+            defaults: RouteClass::ALL.into_iter().map(|class| (class, class.default_budget())).collect(),
+            overrides: Arc::new(RwLock::new(HashMap::new())),
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+            trusted_proxy_hops: std::env::var("TRUSTED_PROXY_HOPS")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(0),
+        }
+    }
+
+    fn budget(&self, business_id: Option<Uuid>, class: RouteClass) -> Budget {
+        business_id
+            .and_then(|id| {
+                self.overrides
+                    .read()
+                    .expect("rate limit overrides lock poisoned")
+                    .get(&(id, class))
+                    .copied()
+            })
+            .unwrap_or(self.defaults[&class])
+    }
+
+    /// Takes a request from every bucket, or from none if any of them is empty. The outcome
+    /// reported is the tightest one: the emptiest bucket, or the longest wait.
+    fn take(&self, limits: &[(String, Budget)]) -> Outcome {
+        self.take_at(limits, Instant::now())
+    }
+
+    fn take_at(&self, limits: &[(String, Budget)], now: Instant) -> Outcome {
+        let mut buckets = self.buckets.lock().expect("rate limit buckets lock poisoned");
+        if buckets.len() >= MAX_BUCKETS && limits.iter().any(|(key, _)| !buckets.contains_key(key)) {
+            make_room(&mut buckets, now);
+        }
+        for (key, budget) in limits {
+            let bucket = buckets.entry(key.clone()).or_insert_with(|| Bucket {
+                tokens: f64::from(budget.burst),
+                refilled_at: now,
+                budget: *budget,
+            });
+            bucket.budget = *budget;
+            bucket.refill(now);
+        }
+
+        let allowed = limits.iter().all(|(key, _)| buckets[key].tokens >= 1.0);
+        limits
+            .iter()
+            .map(|(key, budget)| {
+                let bucket = buckets.get_mut(key).expect("rate limit bucket created above");
+                if allowed {
+                    bucket.tokens -= 1.0;
+                }
+
+                let rate = budget.refill_per_sec();
+                Outcome {
+                    allowed,
+                    budget: *budget,
+                    remaining: bucket.tokens.floor() as u32,
+                    reset_secs: ((f64::from(budget.burst) - bucket.tokens) / rate).ceil() as u64,
+                    retry_after_secs: ((1.0 - bucket.tokens) / rate).ceil().max(1.0) as u64,
+                }
+            })
+            .reduce(|tightest, outcome| {
+                let tighter = if allowed {
+                    outcome.remaining < tightest.remaining
+                } else {
+                    outcome.retry_after_secs > tightest.retry_after_secs
+                };
+                if tighter {
+                    outcome
+                } else {
+                    tightest
+                }
+            })
+            .expect("at least one rate limit")
+    }
+
+    /// The peer address, or with TRUSTED_PROXY_HOPS set, the address the outermost trusted
+    /// proxy saw (entries further left in X-Forwarded-For can be forged by the client).
+    fn client_ip(&self, parts: &Parts) -> Option<IpAddr> {
+        if self.trusted_proxy_hops > 0 {
+            let forwarded: Vec<&str> = parts
+                .headers
+                .get_all("x-forwarded-for")
+                .iter()
+                .filter_map(|v| v.to_str().ok())
+                .flat_map(|v| v.split(','))
+                .map(str::trim)
+                .collect();
+            if let Some(ip) = forwarded
+                .len()
+                .checked_sub(self.trusted_proxy_hops)
+                .and_then(|i| forwarded[i].parse().ok())
+            {
+                return Some(ip);
+            }
+        }
+
+        parts.extensions.get::<ConnectInfo<SocketAddr>>().map(|ConnectInfo(addr)| addr.ip())
+    }
+
+    async fn reload(&self, db: &Db) -> Result<(), sqlx::Error> {
+        let rows = sqlx::query("SELECT organization_id, route_class, requests_per_minute, burst FROM rate_limits")
+            .fetch_all(db)
+            .await?;
+
+        let overrides = rows
+            .iter()
+            .filter_map(|row| {
+                let class_name: String = row.get("route_class");
+                let class = RouteClass::ALL.into_iter().find(|class| class.as_str() == class_name)?;
+                let budget = Budget {
+                    per_minute: u32::try_from(row.get::<i32, _>("requests_per_minute")).ok()?,
+                    burst: u32::try_from(row.get::<i32, _>("burst")).ok()?,
+                };
+                Some(((row.get::<Uuid, _>("organization_id"), class), budget))
+            })
+            .collect();
+
+        *self.overrides.write().expect("rate limit overrides lock poisoned") = overrides;
+        Ok(())
+    }
+
+    /// Drop buckets that have refilled completely; they are recreated full when needed.
+    fn evict_full(&self) {
+        let now = Instant::now();
+        self.buckets.lock().expect("rate limit buckets lock poisoned").retain(|_, bucket| !bucket.is_full(now));
+    }
+}
+
+/// Drop buckets that have refilled completely and, if the map is still at MAX_BUCKETS, the
+/// tenth that has gone longest without a request, so a full map is not scanned on every
+/// new caller.
+fn make_room(buckets: &mut HashMap<String, Bucket>, now: Instant) {
+    buckets.retain(|_, bucket| !bucket.is_full(now));
+    if buckets.len() < MAX_BUCKETS {
+        return;
+    }
+
+    let mut last_used: Vec<Instant> = buckets.values().map(|bucket| bucket.refilled_at).collect();
+    let (_, cutoff, _) = last_used.select_nth_unstable(MAX_BUCKETS / 10);
+    let cutoff = *cutoff;
+    buckets.retain(|_, bucket| bucket.refilled_at > cutoff);
+}
+
+/// Auth routes are counted per client IP. Other routes are counted per API key or user,
+/// within the business's budget for the route class; requests failing authentication are
+/// counted per client IP. Every response carries `RateLimit-*` headers; a request over
+/// budget gets 429 with `Retry-After`.
+pub async fn rate_limit_middleware(
+    State((state, class)): State<(AppState, RouteClass)>,
+    req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let class = if class != RouteClass::Auth && matches!(*req.method(), Method::GET | Method::HEAD) {
+        RouteClass::Read
+    } else {
+        class
+    };
+
+    let (mut parts, body) = req.into_parts();
+    let limiter = &state.rate_limiter;
+
+    let auth = if class == RouteClass::Auth {
+        None
+    } else {
+        Some(AuthContext::from_request_parts(&mut parts, &state).await)
+    };
+
+    let (caller, business_id) = match &auth {
+        Some(Ok(ctx)) => match (ctx.api_key_id, ctx.user_id) {
+            (Some(api_key_id), _) => (format!("key:{}", api_key_id), Some(ctx.business_id)),
+            (None, Some(user_id)) => (format!("user:{}", user_id), Some(ctx.business_id)),
+            (None, None) => (format!("business:{}", ctx.business_id), Some(ctx.business_id)),
+        },
+        Some(Err(_)) | None => match limiter.client_ip(&parts) {
+            Some(ip) => (format!("ip:{}", ip), None),
+            None => ("ip:unknown".to_string(), None),
+        },
+    };
+
+    // Each caller gets the business's budget, and all callers of the business share it too,
+    // so more API keys or users do not raise the business's limit
+    let budget = limiter.budget(business_id, class);
+    let mut limits = vec![(format!("{}:{}", class.as_str(), caller), budget)];
+    if let Some(business_id) = business_id {
+        let business = format!("{}:business:{}", class.as_str(), business_id);
+        if limits[0].0 != business {
+            limits.push((business, budget));
+        }
+    }
+    let outcome = limiter.take(&limits);
+
+    if !outcome.allowed {
+        tracing::warn!(caller = %caller, route_class = class.as_str(), "rate_limited");
+        let mut response = AppError::RateLimited(format!(
+            "too many {} requests; retry in {} seconds",
+            class.as_str(),
+            outcome.retry_after_secs
+        ))
+        .into_response();
+        outcome.apply(response.headers_mut());
+        return Ok(response);
+    }
+
+    let mut response = match auth {
+        Some(Err(e)) => e.into_response(),
+        Some(Ok(ctx)) => {
+            // Handlers reuse the context instead of authenticating again
+            parts.extensions.insert(ctx);
+            next.run(Request::from_parts(parts, body)).await
+        }
+        None => next.run(Request::from_parts(parts, body)).await,
+    };
+    outcome.apply(response.headers_mut());
+    Ok(response)
+}
+
+/// Background job reloading business overrides and dropping idle buckets (every minute).
+pub async fn run(db: Db, limiter: RateLimiter) {
+    loop {
+        if let Err(e) = limiter.reload(&db).await {
+            tracing::warn!(error = %e, "rate_limit_policy_reload_failed");
+        }
+        limiter.evict_full();
+
+        tokio::time::sleep(RELOAD_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(trusted_proxy_hops: usize) -> RateLimiter {
+        RateLimiter {
+            defaults: RouteClass::ALL.into_iter().map(|class| (class, Budget { per_minute: 60, burst: 2 })).collect(),
+            overrides: Arc::new(RwLock::new(HashMap::new())),
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+            trusted_proxy_hops,
+        }
+    }
+
+    fn parts(forwarded_for: &[&str]) -> Parts {
+        let mut builder = Request::builder().extension(ConnectInfo(SocketAddr::from(([10, 0, 0, 9], 4000))));
+        for value in forwarded_for {
+            builder = builder.header("x-forwarded-for", *value);
+        }
+        builder.body(()).unwrap().into_parts().0
+    }
+
+    #[test]
+    fn tokens_refill_and_rejections_carry_retry_after() {
+        let limiter = limiter(0);
+        let budget = Budget { per_minute: 60, burst: 2 };
+        let start = Instant::now();
+
+        assert!(limiter.take_at(&[("k".into(), budget)], start).allowed);
+        assert!(limiter.take_at(&[("k".into(), budget)], start).allowed);
+        let rejected = limiter.take_at(&[("k".into(), budget)], start);
+        assert!(!rejected.allowed);
+        let mut headers = HeaderMap::new();
+        rejected.apply(&mut headers);
+        assert_eq!(headers[header::RETRY_AFTER], "1");
+        assert_eq!(headers["ratelimit-remaining"], "0");
+
+        assert!(limiter.take_at(&[("k".into(), budget)], start + Duration::from_secs(1)).allowed);
+        assert!(!limiter.take_at(&[("k".into(), budget)], start + Duration::from_secs(1)).allowed);
+    }
+
+    #[test]
+    fn callers_share_the_business_budget() {
+        let limiter = limiter(0);
+        let budget = Budget { per_minute: 60, burst: 2 };
+        let start = Instant::now();
+        let limits = |caller: &str| [(caller.to_string(), budget), ("business".to_string(), budget)];
+
+        assert!(limiter.take_at(&limits("key:1"), start).allowed);
+        assert!(limiter.take_at(&limits("key:2"), start).allowed);
+
+        // key:1 has a token left but the business has none; nothing is taken
+        let rejected = limiter.take_at(&limits("key:1"), start);
+        assert!(!rejected.allowed);
+        assert_eq!(rejected.retry_after_secs, 1);
+
+        assert!(limiter.take_at(&limits("key:1"), start + Duration::from_secs(1)).allowed);
+        let buckets = limiter.buckets.lock().unwrap();
+        assert_eq!(buckets["key:1"].tokens, 1.0);
+        assert_eq!(buckets["business"].tokens, 0.0);
+    }
+
+    #[test]
+    fn business_overrides_replace_the_default_budget() {
+        let limiter = limiter(0);
+        let business_id = Uuid::new_v4();
+        limiter.overrides.write().unwrap().insert((business_id, RouteClass::Write), Budget { per_minute: 1000, burst: 50 });
+
+        assert_eq!(limiter.budget(Some(business_id), RouteClass::Write).burst, 50);
+        assert_eq!(limiter.budget(Some(business_id), RouteClass::Read).burst, 2);
+        assert_eq!(limiter.budget(None, RouteClass::Write).burst, 2);
+    }
+
+    #[test]
+    fn forged_forwarded_for_entries_are_skipped() {
+        let peer = Some(IpAddr::from([10, 0, 0, 9]));
+        assert_eq!(limiter(0).client_ip(&parts(&["203.0.113.7"])), peer);
+        assert_eq!(limiter(1).client_ip(&parts(&["198.51.100.1, 203.0.113.7"])), Some(IpAddr::from([203, 0, 113, 7])));
+        assert_eq!(limiter(2).client_ip(&parts(&["203.0.113.7"])), peer);
+        assert_eq!(limiter(1).client_ip(&parts(&["not-an-ip"])), peer);
+    }
+
+    #[test]
+    fn buckets_are_capped() {
+        let limiter = limiter(0);
+        let budget = Budget { per_minute: 60, burst: 2 };
+        let start = Instant::now();
+        {
+            let mut buckets = limiter.buckets.lock().unwrap();
+            for i in 0..MAX_BUCKETS {
+                let refilled_at = start + Duration::from_millis(i as u64);
+                buckets.insert(format!("ip:{}", i), Bucket { tokens: 0.0, refilled_at, budget });
+            }
+        }
+
+        let now = start + Duration::from_millis(MAX_BUCKETS as u64);
+        assert!(limiter.take_at(&[("ip:new".into(), budget)], now).allowed);
+
+        let buckets = limiter.buckets.lock().unwrap();
+        assert!(buckets.len() <= MAX_BUCKETS);
+        assert!(buckets.contains_key("ip:new"));
+        assert!(!buckets.contains_key("ip:0"));
+    }
+}
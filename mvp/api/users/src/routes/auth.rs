@@ -142,6 +142,11 @@ pub async fn login(
         .map(|row| row.get::<Uuid, _>("id"))
         .ok_or_else(|| AppError::Unauthorized)?;
 
+    let selected_environment_type = available_envs
+        .iter()
+        .find(|e| e.id == selected_environment_id)
+        .map(|e| e.r#type.clone());
+
     // 3. Generate JWT access token
     let jwt_id = Uuid::new_v4().to_string();
     let now = Utc::now();
@@ -152,9 +157,10 @@ pub async fn login(
         "exp": exp.timestamp(),
         "iat": now.timestamp(),
         "env": selected_environment_id.to_string(),
+        "env_type": selected_environment_type,
         "business_id": business_id.to_string(),
     });
-    let secret = std::env::var("JWT_SECRET").unwrap_or_else(|_| "dev_secret".to_string());
+    let secret = crate::config::jwt_secret().ok_or(AppError::Internal)?;
     let access_token = encode(&Header::default(), &claims, &EncodingKey::from_secret(secret.as_bytes()))
         .map_err(|_| AppError::Internal)?;
 
@@ -213,16 +219,20 @@ pub async fn refresh_token(
         return Err(AppError::Unauthorized);
     }
 
-    // Get business_id for JWT
+    // Get business_id and environment type for JWT
     let business_row = sqlx::query(
-        "SELECT business_id FROM users WHERE id = $1"
+        "SELECT u.business_id, e.type AS env_type FROM users u \
+         LEFT JOIN environments e ON e.id = $2 \
+         WHERE u.id = $1"
     )
     .bind(&user_id)
+    .bind(environment_id)
     .fetch_optional(&state.db)
     .await
     .map_err(|_| AppError::Internal)?
     .ok_or(AppError::Internal)?;
     let business_id: Uuid = business_row.get("business_id");
+    let env_type: Option<String> = business_row.get("env_type");
 
     // 2. Issue new JWT
     let jwt_id = Uuid::new_v4().to_string();
@@ -234,9 +244,10 @@ pub async fn refresh_token(
         "exp": exp.timestamp(),
         "iat": now.timestamp(),
         "env": environment_id.to_string(),
+        "env_type": env_type,
         "business_id": business_id.to_string(),
     });
-    let secret = std::env::var("JWT_SECRET").unwrap_or_else(|_| "dev_secret".to_string());
+    let secret = crate::config::jwt_secret().ok_or(AppError::Internal)?;
     let access_token = encode(&Header::default(), &claims, &EncodingKey::from_secret(secret.as_bytes()))
         .map_err(|_| AppError::Internal)?;
 
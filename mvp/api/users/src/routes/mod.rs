@@ -7,34 +7,40 @@ pub mod health;
 use axum::{Router, routing::{post, get}};
 use axum::body::Body;
 use axum::http::Request;
-use axum::middleware::{from_fn, Next};
+use axum::middleware::{from_fn, from_fn_with_state, Next};
 use axum::response::Response;
 use crate::db::Db;
 use crate::grpc::GrpcClients;
 use crate::error::AppError;
+use crate::idempotency::idempotency_middleware;
+use crate::rate_limit::{rate_limit_middleware, RateLimiter, RouteClass};
 use uuid::Uuid;
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: Db,
     pub grpc: GrpcClients,
+    pub rate_limiter: RateLimiter,
 }
 
-pub fn register_routes(db: Db, grpc: GrpcClients) -> Router {
-    let state = AppState { db, grpc };
+pub fn register_routes(db: Db, grpc: GrpcClients, rate_limiter: RateLimiter) -> Router {
+    let state = AppState { db, grpc, rate_limiter };
     let public = Router::new()
-        .route("/health", get(health::health_check))
         .route("/api/v1/business/register", post(business::register_business))
         .route("/api/v1/auth/login", post(auth::login))
         .route("/api/v1/auth/refresh", post(auth::refresh_token))
-        .route("/api/v1/auth/revoke", post(auth::revoke_token));
+        .route("/api/v1/auth/revoke", post(auth::revoke_token))
+        .route_layer(from_fn_with_state((state.clone(), RouteClass::Auth), rate_limit_middleware))
+        .route("/health", get(health::health_check));
 
     let protected = Router::new()
         .route("/api/v1/users", post(user::create_user).get(user::list_users))
         .route("/api/v1/api-keys", post(apikey::create_api_key))
         .route("/api/v1/api-keys", get(apikey::list_api_keys))
         .route("/api/v1/api-keys/:api_key_id/revoke", post(apikey::revoke_api_key))
-        .route("/api/v1/me", get(user::me));
+        .route("/api/v1/me", get(user::me))
+        .route_layer(from_fn_with_state(state.clone(), idempotency_middleware))
+        .route_layer(from_fn_with_state((state.clone(), RouteClass::Write), rate_limit_middleware));
 
     public
         .merge(protected)
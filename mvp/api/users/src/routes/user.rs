@@ -6,7 +6,8 @@ use crate::auth::AuthContext;
 use serde::{Deserialize, Serialize};
 use argon2::{Argon2, PasswordHasher};
 use argon2::password_hash::{SaltString, rand_core::OsRng};
-use sqlx::Row;
+use sqlx::{Postgres, QueryBuilder, Row};
+use crate::pagination::{Cursor, CursorDirection};
 
 #[derive(Deserialize)]
 pub struct CreateUserRequest {
@@ -73,14 +74,22 @@ pub struct ListUser {
 pub struct ListUsersQuery {
     pub page: Option<u32>,
     pub per_page: Option<u32>,
+    /// Opaque cursor from a previous response (takes precedence over `page`)
+    pub cursor: Option<String>,
 }
 
+/// Page-number fields are only present for `page` requests; cursor requests skip the count
 #[derive(Serialize)]
 pub struct PaginationMeta {
-    pub page: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
     pub per_page: u32,
-    pub total_count: i64,
-    pub total_pages: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_count: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_pages: Option<u32>,
+    pub next_cursor: Option<String>,
+    pub prev_cursor: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -365,75 +374,86 @@ pub async fn list_users(
         .filter(|s| s == "sandbox" || s == "production");
 
     // Parse and validate pagination params with defaults
-    let page = query.page.unwrap_or(1).max(1);
     let per_page = query.per_page.unwrap_or(10).min(100).max(1);
-    let offset = (page - 1) * per_page;
-
-    // Build query with optional environment type filter
+    let cursor = query
+        .cursor
+        .as_deref()
+        .filter(|c| !c.trim().is_empty())
+        .map(Cursor::decode)
+        .transpose()?;
+
+    // Scope with optional environment type filter
     // If X-Environment header is provided, filter by both environment_id AND environment.type
     // This ensures we only get users from the correct environment type (sandbox/production)
-    let (count_query, list_query) = if let Some(ref env_type) = environment_type {
-        // Filter by business_id, environment_id, AND environment.type
-        (
-            "SELECT COUNT(*) as count FROM users u 
-             INNER JOIN environments e ON u.environment_id = e.id 
-             WHERE u.business_id = $1 AND u.environment_id = $2 AND e.type = $3 AND u.status = 'active'",
-            "SELECT u.id, u.first_name, u.last_name, u.email, u.role, u.status, u.created_at, u.updated_at 
-             FROM users u 
-             INNER JOIN environments e ON u.environment_id = e.id 
-             WHERE u.business_id = $1 AND u.environment_id = $2 AND e.type = $3 AND u.status = 'active' 
-             ORDER BY u.created_at DESC, u.id DESC LIMIT $4 OFFSET $5"
-        )
-    } else {
-        // Fallback to original behavior: filter by environment_id only
-        (
-            "SELECT COUNT(*) as count FROM users WHERE business_id = $1 AND environment_id = $2 AND status = 'active'",
-            "SELECT id, first_name, last_name, email, role, status, created_at, updated_at FROM users WHERE business_id = $1 AND environment_id = $2 AND status = 'active' ORDER BY created_at DESC, id DESC LIMIT $3 OFFSET $4"
-        )
+    let push_scope = |builder: &mut QueryBuilder<'_, Postgres>| {
+        if let Some(ref env_type) = environment_type {
+            builder.push(" FROM users u INNER JOIN environments e ON u.environment_id = e.id WHERE e.type = ");
+            builder.push_bind(env_type.clone());
+            builder.push(" AND");
+        } else {
+            builder.push(" FROM users u WHERE");
+        }
+        builder.push(" u.business_id = ");
+        builder.push_bind(business_id);
+        builder.push(" AND u.environment_id = ");
+        builder.push_bind(environment_id);
+        builder.push(" AND u.status = 'active'");
     };
 
-    // Get total count
-    let count_row = if let Some(ref env_type) = environment_type {
-        sqlx::query(count_query)
-            .bind(&business_id)
-            .bind(&environment_id)
-            .bind(env_type)
-            .fetch_one(&state.db)
-            .await
-            .map_err(|_| AppError::Internal)?
-    } else {
-        sqlx::query(count_query)
-            .bind(&business_id)
-            .bind(&environment_id)
-            .fetch_one(&state.db)
-            .await
-            .map_err(|_| AppError::Internal)?
+    let mut list_query = QueryBuilder::<Postgres>::new(
+        "SELECT u.id, u.first_name, u.last_name, u.email, u.role, u.status, u.created_at, u.updated_at",
+    );
+    push_scope(&mut list_query);
+
+    // Page mode counts and uses OFFSET; cursor mode seeks on (created_at, id) without a count
+    let (page, total_count) = match cursor {
+        None => {
+            let page = query.page.unwrap_or(1).max(1);
+
+            let mut count_query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) as count");
+            push_scope(&mut count_query);
+            let count_row = count_query
+                .build()
+                .fetch_one(&state.db)
+                .await
+                .map_err(|_| AppError::Internal)?;
+
+            list_query.push(" ORDER BY u.created_at DESC, u.id DESC LIMIT ");
+            list_query.push_bind(per_page as i64);
+            list_query.push(" OFFSET ");
+            list_query.push_bind(((page - 1) * per_page) as i64);
+            (Some(page), Some(count_row.get::<i64, _>("count")))
+        }
+        Some(cursor) => {
+            // Pages before the cursor are scanned oldest first and reversed below
+            list_query.push(match cursor.direction {
+                CursorDirection::After => " AND (u.created_at, u.id) < (",
+                CursorDirection::Before => " AND (u.created_at, u.id) > (",
+            });
+            list_query.push_bind(cursor.created_at);
+            list_query.push(", ");
+            list_query.push_bind(cursor.id);
+            list_query.push(match cursor.direction {
+                CursorDirection::After => ") ORDER BY u.created_at DESC, u.id DESC LIMIT ",
+                CursorDirection::Before => ") ORDER BY u.created_at ASC, u.id ASC LIMIT ",
+            });
+            list_query.push_bind(per_page as i64 + 1);
+            (None, None)
+        }
     };
 
-    let total_count: i64 = count_row.get("count");
-    let total_pages = ((total_count as f64) / (per_page as f64)).ceil() as u32;
-
     // Fetch paginated results with deterministic ordering
-    let rows = if let Some(ref env_type) = environment_type {
-        sqlx::query(list_query)
-            .bind(&business_id)
-            .bind(&environment_id)
-            .bind(env_type)
-            .bind(per_page as i64)
-            .bind(offset as i64)
-            .fetch_all(&state.db)
-            .await
-            .map_err(|_| AppError::Internal)?
-    } else {
-        sqlx::query(list_query)
-            .bind(&business_id)
-            .bind(&environment_id)
-            .bind(per_page as i64)
-            .bind(offset as i64)
-            .fetch_all(&state.db)
-            .await
-            .map_err(|_| AppError::Internal)?
-    };
+    let mut rows = list_query
+        .build()
+        .fetch_all(&state.db)
+        .await
+        .map_err(|_| AppError::Internal)?;
+
+    let has_more = rows.len() > per_page as usize;
+    rows.truncate(per_page as usize);
+    if cursor.is_some_and(|c| c.direction == CursorDirection::Before) {
+        rows.reverse();
+    }
 
     let users: Vec<ListUser> = rows
         .into_iter()
@@ -449,6 +469,22 @@ pub async fn list_users(
         })
         .collect();
 
+    let total_pages = total_count.map(|count| ((count as f64) / (per_page as f64)).ceil() as u32);
+    let (more_after, more_before) = match (cursor, page) {
+        (Some(cursor), _) if cursor.direction == CursorDirection::After => (has_more, true),
+        (Some(_), _) => (true, has_more),
+        (None, Some(page)) => (page < total_pages.unwrap_or(0), page > 1),
+        (None, None) => (false, false),
+    };
+    let next_cursor = users
+        .last()
+        .filter(|_| more_after)
+        .map(|user| Cursor::after(user.created_at, user.id).encode());
+    let prev_cursor = users
+        .first()
+        .filter(|_| more_before)
+        .map(|user| Cursor::before(user.created_at, user.id).encode());
+
     Ok(Json(ListUsersResponse {
         data: users,
         pagination: PaginationMeta {
@@ -456,6 +492,8 @@ pub async fn list_users(
             per_page,
             total_count,
             total_pages,
+            next_cursor,
+            prev_cursor,
         },
     }))
 }